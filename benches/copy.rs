@@ -0,0 +1,45 @@
+//! Compares `copy` with `copy_str`, which skips re-validating UTF-8 in the
+//! writer. Run with `cargo bench --bench copy`.
+
+use bytestreams::{copy, copy_str, SliceReader, StdWriter, Utf8Reader, Utf8Writer};
+use std::{
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+const ITERATIONS: u32 = 50;
+
+fn input() -> Vec<u8> {
+    "Hello, wörld! ☃\n".repeat(1 << 16).into_bytes()
+}
+
+/// Run `f` `ITERATIONS` times after a warm-up run, and return the fastest
+/// time.
+fn bench<T, F: FnMut() -> T>(mut f: F) -> Duration {
+    black_box(f());
+    (0..ITERATIONS)
+        .map(|_| {
+            let start = Instant::now();
+            black_box(f());
+            start.elapsed()
+        })
+        .min()
+        .unwrap()
+}
+
+fn main() {
+    let input = input();
+    let copy_time = bench(|| {
+        let mut reader = Utf8Reader::new(SliceReader::new(&input));
+        let mut writer = Utf8Writer::new(StdWriter::new(Vec::with_capacity(input.len())));
+        copy(&mut reader, &mut writer).unwrap()
+    });
+    let copy_str_time = bench(|| {
+        let mut reader = Utf8Reader::new(SliceReader::new(&input));
+        let mut writer = Utf8Writer::new(StdWriter::new(Vec::with_capacity(input.len())));
+        copy_str(&mut reader, &mut writer).unwrap()
+    });
+    println!("input:    {} bytes", input.len());
+    println!("copy:     {:?}", copy_time);
+    println!("copy_str: {:?}", copy_str_time);
+}
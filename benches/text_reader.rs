@@ -0,0 +1,52 @@
+//! Measures `TextReader` on plain ASCII text, with and without the fast
+//! path which passes runs of printable ASCII through without stepping the
+//! state machine. Run with `cargo bench --bench text_reader`.
+
+use bytestreams::{SliceReader, TextReaderBuilder};
+use std::{
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+const ITERATIONS: u32 = 20;
+
+fn input() -> Vec<u8> {
+    "The quick brown fox jumps over the lazy dog, again and again.\n"
+        .repeat(1 << 14)
+        .into_bytes()
+}
+
+/// Run `f` `ITERATIONS` times after a warm-up run, and return the fastest
+/// time.
+fn bench<T, F: FnMut() -> T>(mut f: F) -> Duration {
+    black_box(f());
+    (0..ITERATIONS)
+        .map(|_| {
+            let start = Instant::now();
+            black_box(f());
+            start.elapsed()
+        })
+        .min()
+        .unwrap()
+}
+
+fn main() {
+    let input = input();
+    let fast = bench(|| {
+        let mut reader = TextReaderBuilder::new().build(SliceReader::new(&input));
+        let mut s = String::new();
+        bytestreams::default_read_to_string(&mut reader, &mut s).unwrap()
+    });
+    // Forbidding a printable ASCII character which doesn't occur in the
+    // input disables the fast path without changing the output.
+    let slow = bench(|| {
+        let mut reader = TextReaderBuilder::new()
+            .forbid('~')
+            .build(SliceReader::new(&input));
+        let mut s = String::new();
+        bytestreams::default_read_to_string(&mut reader, &mut s).unwrap()
+    });
+    println!("input:           {} bytes", input.len());
+    println!("with fast path:  {:?}", fast);
+    println!("state machine:   {:?}", slow);
+}
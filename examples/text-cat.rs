@@ -1,17 +1,8 @@
-use bytestreams::{
-    Read, StdReader, StdWriter, TextReader, TextWriter, Write, NORMALIZATION_BUFFER_SIZE,
-};
+use bytestreams::{copy, text_stdin, text_stdout};
 
 fn main() -> anyhow::Result<()> {
-    let mut reader = TextReader::new(StdReader::new(std::io::stdin()));
-    let mut stdout = TextWriter::new(StdWriter::new(std::io::stdout()));
-    let mut buf = [0; NORMALIZATION_BUFFER_SIZE];
-    loop {
-        let outcome = reader.read_outcome(&mut buf)?;
-        stdout.write_all(&buf[..outcome.size])?;
-        stdout.flush(outcome.status)?;
-        if outcome.status.is_end() {
-            return Ok(());
-        }
-    }
+    let mut reader = text_stdin();
+    let mut stdout = text_stdout();
+    copy(&mut reader, &mut stdout)?;
+    Ok(())
 }
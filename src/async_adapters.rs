@@ -0,0 +1,595 @@
+//! Adapters between this crate's `Read` and `Write` and the `futures-io`
+//! `AsyncRead` and `AsyncWrite` traits.
+//!
+//! The layers in this crate are synchronous, so to run them in an async
+//! task, an `AsyncRead` or `AsyncWrite` is wrapped in a `FromAsyncRead` or
+//! `FromAsyncWrite` at the bottom of the stack of layers, and the top of the
+//! stack is wrapped in an `IntoAsyncRead` or `IntoAsyncWrite`. When the top
+//! is polled, it makes the task's context available to the bottom, for the
+//! duration of the call, so that when the underlying stream isn't ready, the
+//! bottom can register the task to be woken and report
+//! `Readiness::Pending`, which the top translates into `Poll::Pending`.
+//! The layers in between see an ordinary non-blocking stream, and hold
+//! partial sequences across `Readiness::Pending`.
+//!
+//! Outside of a poll of an `IntoAsyncRead` or `IntoAsyncWrite`,
+//! `FromAsyncRead` and `FromAsyncWrite` block the current thread until the
+//! underlying stream is ready.
+//!
+//! [async-std] and [smol] use the `futures-io` traits for their streams,
+//! so their types, such as `async_std::net::TcpStream` and
+//! `smol::Async<T>`, can be used with these adapters directly. The
+//! `async-std` and `smol` features are aliases for the `futures-io`
+//! feature, so that dependents can name the ecosystem they use.
+//!
+//! [async-std]: https://docs.rs/async-std
+//! [smol]: https://docs.rs/smol
+
+use crate::{Read, ReadOutcome, Readiness, Status, Write};
+use futures_io::{AsyncRead, AsyncWrite};
+use std::{
+    cell::RefCell,
+    io,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll, Wake, Waker},
+    thread::{self, Thread},
+};
+
+/// The context of the task currently polling an `IntoAsyncRead` or
+/// `IntoAsyncWrite` on this thread.
+struct TaskState {
+    waker: Waker,
+
+    /// Set when a `FromAsyncWrite` couldn't pass all its data on.
+    blocked: bool,
+}
+
+thread_local! {
+    static TASK: RefCell<Option<TaskState>> = const { RefCell::new(None) };
+}
+
+/// Call `f` with `cx` made available to `FromAsyncRead` and
+/// `FromAsyncWrite`, and return its result, along with whether a
+/// `FromAsyncWrite` was left with data it couldn't pass on.
+fn with_task_context<R>(cx: &mut Context<'_>, f: impl FnOnce() -> R) -> (R, bool) {
+    let state = TaskState {
+        waker: cx.waker().clone(),
+        blocked: false,
+    };
+    let outer = TASK.with(|task| task.replace(Some(state)));
+    let result = f();
+    let state = TASK.with(|task| task.replace(outer));
+    (result, state.is_some_and(|state| state.blocked))
+}
+
+/// Poll `f` once with the current task's context, returning `None` if it's
+/// pending, or, outside of a task, block until it's ready.
+fn poll_in_task<T>(mut f: impl FnMut(&mut Context<'_>) -> Poll<T>) -> Option<T> {
+    let waker = TASK.with(|task| task.borrow().as_ref().map(|state| state.waker.clone()));
+    match waker {
+        Some(waker) => match f(&mut Context::from_waker(&waker)) {
+            Poll::Ready(t) => Some(t),
+            Poll::Pending => None,
+        },
+        None => {
+            let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+            let mut cx = Context::from_waker(&waker);
+            loop {
+                if let Poll::Ready(t) = f(&mut cx) {
+                    return Some(t);
+                }
+                thread::park();
+            }
+        }
+    }
+}
+
+/// Read from `inner` with `cx` made available to a `FromAsyncRead` below,
+/// translating `Readiness::Pending` into `Poll::Pending`.
+pub(crate) fn poll_read_outcome<R: Read>(
+    inner: &mut R,
+    cx: &mut Context<'_>,
+    buf: &mut [u8],
+) -> Poll<io::Result<ReadOutcome>> {
+    match with_task_context(cx, || inner.read_outcome(buf)).0 {
+        Ok(outcome) if outcome.status == Status::Open(Readiness::Pending) => {
+            if outcome.size == 0 {
+                Poll::Pending
+            } else {
+                Poll::Ready(Ok(ReadOutcome::ready(outcome.size)))
+            }
+        }
+        result => Poll::Ready(result),
+    }
+}
+
+/// Like `poll_read_outcome`, but read through lulls, since `AsyncRead` has
+/// no way to report them.
+pub(crate) fn poll_read_through_lulls<R: Read>(
+    inner: &mut R,
+    cx: &mut Context<'_>,
+    buf: &mut [u8],
+) -> Poll<io::Result<usize>> {
+    loop {
+        match poll_read_outcome(inner, cx, buf) {
+            Poll::Ready(Ok(outcome))
+                if outcome.size == 0 && !outcome.status.is_end() && !buf.is_empty() => {}
+            poll => return poll.map_ok(|outcome| outcome.size),
+        }
+    }
+}
+
+/// Record that a `FromAsyncWrite` has data it couldn't pass on.
+fn mark_blocked() {
+    TASK.with(|task| {
+        if let Some(state) = task.borrow_mut().as_mut() {
+            state.blocked = true;
+        }
+    })
+}
+
+/// A waker which unparks a blocked thread.
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    #[inline]
+    fn wake(self: Arc<Self>) {
+        self.0.unpark()
+    }
+}
+
+/// A `Read` implementation which reads from an `AsyncRead`.
+///
+/// When polled via an `IntoAsyncRead`, reads which would block return
+/// `Readiness::Pending`; otherwise, they block.
+pub struct FromAsyncRead<Inner: AsyncRead + Unpin> {
+    inner: Inner,
+}
+
+impl<Inner: AsyncRead + Unpin> FromAsyncRead<Inner> {
+    /// Construct a new `FromAsyncRead` which reads from `inner`.
+    #[inline]
+    pub fn new(inner: Inner) -> Self {
+        Self { inner }
+    }
+
+    /// Consume this `FromAsyncRead` and return the underlying stream.
+    #[inline]
+    pub fn into_inner(self) -> Inner {
+        self.inner
+    }
+
+    /// Gets a reference to the underlying stream.
+    #[inline]
+    pub fn get_ref(&self) -> &Inner {
+        &self.inner
+    }
+
+    /// Gets a mutable reference to the underlying stream.
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut Inner {
+        &mut self.inner
+    }
+}
+
+impl<Inner: AsyncRead + Unpin> Read for FromAsyncRead<Inner> {
+    fn read_outcome(&mut self, buf: &mut [u8]) -> io::Result<ReadOutcome> {
+        let inner = &mut self.inner;
+        match poll_in_task(|cx| Pin::new(&mut *inner).poll_read(cx, buf)) {
+            Some(Ok(0)) if !buf.is_empty() => Ok(ReadOutcome::end(0)),
+            Some(Ok(size)) => Ok(ReadOutcome::ready(size)),
+            Some(Err(e)) => Err(e),
+            None => Ok(ReadOutcome::pending(0)),
+        }
+    }
+}
+
+/// A `Write` implementation which writes to an `AsyncWrite`.
+///
+/// When polled via an `IntoAsyncWrite`, data which can't be written without
+/// blocking is buffered, and the `IntoAsyncWrite` waits for it to be written
+/// before accepting more. Otherwise, writes block.
+///
+/// `flush` with `Readiness::Ready` writes out buffered data, with
+/// `Readiness::Lull` or `Readiness::Pending` it also flushes the underlying
+/// stream, and with `Status::End` it closes the underlying stream.
+pub struct FromAsyncWrite<Inner: AsyncWrite + Unpin> {
+    inner: Inner,
+
+    /// Data accepted but not yet written, because the underlying stream
+    /// wasn't ready.
+    pending: Vec<u8>,
+
+    /// The end of the stream has been declared, and the underlying stream
+    /// is being closed.
+    closing: bool,
+
+    /// The underlying stream has been closed.
+    closed: bool,
+}
+
+impl<Inner: AsyncWrite + Unpin> FromAsyncWrite<Inner> {
+    /// Construct a new `FromAsyncWrite` which writes to `inner`.
+    #[inline]
+    pub fn new(inner: Inner) -> Self {
+        Self {
+            inner,
+            pending: Vec::new(),
+            closing: false,
+            closed: false,
+        }
+    }
+
+    /// Consume this `FromAsyncWrite` and return the underlying stream. Any
+    /// buffered data which hasn't been written is discarded.
+    #[inline]
+    pub fn into_inner(self) -> Inner {
+        self.inner
+    }
+
+    /// Write out as much of `self.pending` as possible, and return true if
+    /// it's all written.
+    fn drain(&mut self) -> io::Result<bool> {
+        while !self.pending.is_empty() {
+            let (inner, pending) = (&mut self.inner, &self.pending);
+            match poll_in_task(|cx| Pin::new(&mut *inner).poll_write(cx, pending)) {
+                Some(Ok(0)) => return Err(io::ErrorKind::WriteZero.into()),
+                Some(Ok(size)) => drop(self.pending.drain(..size)),
+                Some(Err(e)) => return Err(e),
+                None => return Ok(false),
+            }
+        }
+        Ok(true)
+    }
+}
+
+impl<Inner: AsyncWrite + Unpin> Write for FromAsyncWrite<Inner> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.drain()? {
+            let inner = &mut self.inner;
+            if let Some(result) = poll_in_task(|cx| Pin::new(&mut *inner).poll_write(cx, buf)) {
+                return result;
+            }
+        }
+        self.pending.extend_from_slice(buf);
+        mark_blocked();
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self, status: Status) -> io::Result<()> {
+        self.closing |= status.is_end();
+        if !self.drain()? {
+            mark_blocked();
+            return Ok(());
+        }
+        let inner = &mut self.inner;
+        let result = if self.closed || (!self.closing && status == Status::ready()) {
+            return Ok(());
+        } else if self.closing {
+            let result = poll_in_task(|cx| Pin::new(&mut *inner).poll_close(cx));
+            self.closed = matches!(result, Some(Ok(())));
+            result
+        } else {
+            poll_in_task(|cx| Pin::new(&mut *inner).poll_flush(cx))
+        };
+        result.unwrap_or_else(|| {
+            mark_blocked();
+            Ok(())
+        })
+    }
+
+    #[inline]
+    fn abandon(&mut self) {
+        self.pending.clear();
+    }
+}
+
+/// An `AsyncRead` implementation which reads from a `Read`, typically a
+/// stack of layers over a `FromAsyncRead`.
+///
+/// `Readiness::Pending` is reported as `Poll::Pending`, and lulls are
+/// read through, since `AsyncRead` has no way to report them. Reads which
+/// block, such as from a `StdReader` in blocking mode, block the task.
+pub struct IntoAsyncRead<Inner: Read + Unpin> {
+    inner: Inner,
+}
+
+impl<Inner: Read + Unpin> IntoAsyncRead<Inner> {
+    /// Construct a new `IntoAsyncRead` which reads from `inner`.
+    #[inline]
+    pub fn new(inner: Inner) -> Self {
+        Self { inner }
+    }
+
+    /// Gets a reference to the underlying reader.
+    #[inline]
+    pub fn get_ref(&self) -> &Inner {
+        &self.inner
+    }
+
+    /// Gets a mutable reference to the underlying reader.
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut Inner {
+        &mut self.inner
+    }
+
+    /// Consume this `IntoAsyncRead` and return the underlying reader.
+    #[inline]
+    pub fn into_inner(self) -> Inner {
+        self.inner
+    }
+}
+
+impl<Inner: Read + Unpin> AsyncRead for IntoAsyncRead<Inner> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        poll_read_through_lulls(&mut self.get_mut().inner, cx, buf)
+    }
+}
+
+/// An `AsyncWrite` implementation which writes to a `Write`, typically a
+/// stack of layers over a `FromAsyncWrite`.
+///
+/// `poll_flush` flushes with `Readiness::Pending`, so that buffered data is
+/// written out without declaring a lull, and `poll_close` flushes with
+/// `Status::End`. Writes which block, such as to a `StdWriter`, block the
+/// task.
+pub struct IntoAsyncWrite<Inner: Write + Unpin> {
+    inner: Inner,
+
+    /// A `FromAsyncWrite` below was left with data it couldn't pass on.
+    blocked: bool,
+
+    /// `Status::End` has been declared.
+    ended: bool,
+}
+
+impl<Inner: Write + Unpin> IntoAsyncWrite<Inner> {
+    /// Construct a new `IntoAsyncWrite` which writes to `inner`.
+    #[inline]
+    pub fn new(inner: Inner) -> Self {
+        Self {
+            inner,
+            blocked: false,
+            ended: false,
+        }
+    }
+
+    /// Gets a reference to the underlying writer.
+    #[inline]
+    pub fn get_ref(&self) -> &Inner {
+        &self.inner
+    }
+
+    /// Gets a mutable reference to the underlying writer.
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut Inner {
+        &mut self.inner
+    }
+
+    /// Consume this `IntoAsyncWrite` and return the underlying writer.
+    #[inline]
+    pub fn into_inner(self) -> Inner {
+        self.inner
+    }
+
+    /// Call `f` with `cx` made available to a `FromAsyncWrite` below, after
+    /// first waiting for any data it's holding to be written.
+    fn poll_with<T>(
+        &mut self,
+        cx: &mut Context<'_>,
+        f: impl FnOnce(&mut Inner) -> io::Result<T>,
+    ) -> Poll<io::Result<T>> {
+        let inner = &mut self.inner;
+        if self.blocked {
+            let (result, blocked) = with_task_context(cx, || inner.flush(Status::ready()));
+            self.blocked = blocked;
+            if let Err(e) = result {
+                return Poll::Ready(Err(e));
+            }
+            if blocked {
+                return Poll::Pending;
+            }
+        }
+        let (result, blocked) = with_task_context(cx, || f(inner));
+        self.blocked = blocked;
+        Poll::Ready(result)
+    }
+}
+
+impl<Inner: Write + Unpin> AsyncWrite for IntoAsyncWrite<Inner> {
+    #[inline]
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        self.get_mut().poll_with(cx, |inner| inner.write(buf))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        match this.poll_with(cx, |inner| inner.flush(Status::Open(Readiness::Pending))) {
+            Poll::Ready(Ok(())) if this.blocked => Poll::Pending,
+            poll => poll,
+        }
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        let status = if this.ended {
+            Status::ready()
+        } else {
+            Status::End
+        };
+        match this.poll_with(cx, |inner| inner.flush(status)) {
+            Poll::Ready(Ok(())) => {
+                this.ended = true;
+                if this.blocked {
+                    Poll::Pending
+                } else {
+                    Poll::Ready(Ok(()))
+                }
+            }
+            poll => poll,
+        }
+    }
+}
+
+/// An `AsyncRead` and `AsyncWrite` which is pending on every other call,
+/// waking the task immediately, and reads and writes at most 3 bytes at a
+/// time.
+#[cfg(test)]
+struct Stuttering {
+    input: Vec<u8>,
+    output: Vec<u8>,
+    ready: bool,
+    closed: bool,
+}
+
+#[cfg(test)]
+impl Stuttering {
+    fn new(input: &[u8]) -> Self {
+        Self {
+            input: input.to_vec(),
+            output: Vec::new(),
+            ready: false,
+            closed: false,
+        }
+    }
+
+    fn stutter(&mut self, cx: &mut Context<'_>) -> Poll<()> {
+        self.ready = !self.ready;
+        if self.ready {
+            Poll::Ready(())
+        } else {
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        }
+    }
+}
+
+#[cfg(test)]
+impl AsyncRead for Stuttering {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        if this.stutter(cx).is_pending() {
+            return Poll::Pending;
+        }
+        let size = buf.len().min(this.input.len()).min(3);
+        buf[..size].copy_from_slice(&this.input[..size]);
+        this.input.drain(..size);
+        Poll::Ready(Ok(size))
+    }
+}
+
+#[cfg(test)]
+impl AsyncWrite for Stuttering {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        assert!(!this.closed);
+        if this.stutter(cx).is_pending() {
+            return Poll::Pending;
+        }
+        let size = buf.len().min(3);
+        this.output.extend_from_slice(&buf[..size]);
+        Poll::Ready(Ok(size))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.get_mut().stutter(cx).map(Ok)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        if this.stutter(cx).is_pending() {
+            return Poll::Pending;
+        }
+        this.closed = true;
+        Poll::Ready(Ok(()))
+    }
+}
+
+/// Poll `f` until it's ready, counting the number of times it's pending.
+#[cfg(test)]
+fn poll_to_completion<T>(
+    pendings: &mut usize,
+    mut f: impl FnMut(&mut Context<'_>) -> Poll<T>,
+) -> T {
+    struct NoopWaker;
+    impl Wake for NoopWaker {
+        fn wake(self: Arc<Self>) {}
+    }
+    let waker = Waker::from(Arc::new(NoopWaker));
+    let mut cx = Context::from_waker(&waker);
+    loop {
+        match f(&mut cx) {
+            Poll::Ready(t) => return t,
+            Poll::Pending => *pendings += 1,
+        }
+    }
+}
+
+#[test]
+fn test_from_async_read_blocking() -> anyhow::Result<()> {
+    let mut reader = FromAsyncRead::new(Stuttering::new(b"hello world"));
+    let mut s = String::new();
+    crate::Read::read_to_string(&mut reader, &mut s)?;
+    assert_eq!(s, "hello world");
+    Ok(())
+}
+
+#[cfg(feature = "text")]
+#[test]
+fn test_into_async_read() -> anyhow::Result<()> {
+    let input = FromAsyncRead::new(Stuttering::new(b"hello\nworld\n"));
+    let mut reader = IntoAsyncRead::new(crate::TextReader::new(input));
+    let mut pendings = 0;
+    let mut output = Vec::new();
+    loop {
+        let mut buf = [0_u8; 4];
+        let size = poll_to_completion(&mut pendings, |cx| {
+            Pin::new(&mut reader).poll_read(cx, &mut buf)
+        })?;
+        if size == 0 {
+            break;
+        }
+        output.extend_from_slice(&buf[..size]);
+    }
+    assert_eq!(output, b"hello\nworld\n");
+    Ok(())
+}
+
+#[cfg(feature = "text")]
+#[test]
+fn test_into_async_write() -> anyhow::Result<()> {
+    let output = FromAsyncWrite::new(Stuttering::new(b""));
+    let mut writer = IntoAsyncWrite::new(crate::TextWriter::new(output));
+    let mut pendings = 0;
+    let mut input: &[u8] = b"hello\nworld\n";
+    while !input.is_empty() {
+        let size = poll_to_completion(&mut pendings, |cx| {
+            Pin::new(&mut writer).poll_write(cx, &input[..input.len().min(4)])
+        })?;
+        input = &input[size..];
+    }
+    poll_to_completion(&mut pendings, |cx| Pin::new(&mut writer).poll_flush(cx))?;
+    poll_to_completion(&mut pendings, |cx| Pin::new(&mut writer).poll_close(cx))?;
+    let inner = writer.into_inner().close_into_inner()?.into_inner();
+    assert_eq!(inner.output, b"hello\nworld\n");
+    assert!(inner.closed);
+    assert!(pendings > 0);
+    Ok(())
+}
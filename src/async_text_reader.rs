@@ -0,0 +1,134 @@
+use crate::{
+    async_adapters::{poll_read_outcome, poll_read_through_lulls},
+    FromAsyncRead, ReadOutcome, TextReader, TextReaderBuilder,
+};
+use futures_io::AsyncRead;
+use std::{
+    io,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+/// An async counterpart to `TextReader`, which reads from an `AsyncRead`.
+///
+/// The translation is done by the same state machine as `TextReader`, which
+/// holds partial sequences, such as a '\r' which may be followed by a '\n',
+/// a partial escape sequence, or a scalar value which may be followed by
+/// combining marks, across `Poll::Pending` until more data arrives, so the
+/// output doesn't depend on how the input is split up.
+///
+/// Use `TextReaderBuilder::build_async` to configure the options.
+pub struct AsyncTextReader<Inner: AsyncRead + Unpin> {
+    inner: TextReader<FromAsyncRead<Inner>>,
+}
+
+impl<Inner: AsyncRead + Unpin> AsyncTextReader<Inner> {
+    /// Construct a new instance of `AsyncTextReader` wrapping `inner`.
+    #[inline]
+    pub fn new(inner: Inner) -> Self {
+        TextReaderBuilder::new().build_async(inner)
+    }
+
+    #[inline]
+    pub(crate) fn from_text_reader(inner: TextReader<FromAsyncRead<Inner>>) -> Self {
+        Self { inner }
+    }
+
+    /// Read translated data into `buf`, like `Read::read_outcome`. Rather
+    /// than reporting `Readiness::Pending` with no data, this returns
+    /// `Poll::Pending`, and arranges for the task to be woken when more
+    /// data may be available.
+    #[inline]
+    pub fn poll_read_outcome(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<ReadOutcome>> {
+        poll_read_outcome(&mut self.get_mut().inner, cx, buf)
+    }
+
+    /// Consume this `AsyncTextReader` and return the underlying stream
+    /// object. Any data which has been read from the underlying stream but
+    /// not yet consumed is discarded.
+    #[inline]
+    pub fn into_inner(self) -> Inner {
+        self.inner.into_inner().into_inner()
+    }
+
+    /// Gets a reference to the underlying reader.
+    #[inline]
+    pub fn get_ref(&self) -> &Inner {
+        self.inner.get_ref().get_ref()
+    }
+
+    /// Gets a mutable reference to the underlying reader.
+    ///
+    /// It is inadvisable to directly read from the underlying reader, as
+    /// data buffered by this reader would then be out of order with it.
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut Inner {
+        self.inner.get_mut().get_mut()
+    }
+
+    /// Return a [`Stream`] over the lines of this stream, so that async
+    /// code can iterate over sanitized lines with `StreamExt::next`.
+    ///
+    /// [`Stream`]: https://docs.rs/futures-core/latest/futures_core/stream/trait.Stream.html
+    #[cfg(feature = "futures-core")]
+    #[inline]
+    pub fn into_lines_stream(self) -> crate::LinesStream<Inner> {
+        crate::LinesStream::new(self)
+    }
+
+    /// Gets a reference to the `TextReader` doing the translation, for
+    /// its counts of the sanitizations performed.
+    #[inline]
+    pub fn text_reader(&self) -> &TextReader<FromAsyncRead<Inner>> {
+        &self.inner
+    }
+}
+
+/// Lulls are read through, since `AsyncRead` has no way to report them.
+impl<Inner: AsyncRead + Unpin> AsyncRead for AsyncTextReader<Inner> {
+    #[inline]
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        poll_read_through_lulls(&mut self.get_mut().inner, cx, buf)
+    }
+}
+
+#[test]
+fn test_split_sequences() -> io::Result<()> {
+    use crate::async_utf8_reader::{read_to_end_polling, Chunked};
+
+    let mut reader = AsyncTextReader::new(Chunked::new(&[
+        b"a\r",
+        b"\nb\x1b[",
+        b"1mc",
+        b"e",
+        b"\xcc\x81\n",
+    ]));
+    let (output, pendings) = read_to_end_polling(&mut reader)?;
+    assert_eq!(output, "a\nbc\u{e9}\n".as_bytes());
+    assert!(pendings > 0);
+    assert_eq!(reader.text_reader().converted_crlfs(), 1);
+    assert_eq!(reader.text_reader().replacements(), 0);
+    Ok(())
+}
+
+#[test]
+fn test_adapters_match() -> io::Result<()> {
+    use crate::async_utf8_reader::{read_to_end_polling, Chunked};
+
+    // The same translation, through the adapters.
+    let mut reader =
+        crate::IntoAsyncRead::new(TextReader::new(FromAsyncRead::new(Chunked::new(&[
+            b"a\r", b"\nb\n",
+        ]))));
+    let (output, _) = read_to_end_polling(&mut reader)?;
+    assert_eq!(output, "a\nb\n".as_bytes());
+    Ok(())
+}
@@ -0,0 +1,167 @@
+use crate::{
+    async_adapters::{poll_read_outcome, poll_read_through_lulls},
+    FromAsyncRead, ReadOutcome, Utf8Reader, Utf8ReaderBuilder,
+};
+use futures_io::AsyncRead;
+use std::{
+    io,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+/// An async counterpart to `Utf8Reader`, which reads from an `AsyncRead`.
+///
+/// The translation is done by the same state machine as `Utf8Reader`, so
+/// the output is the same, and incomplete scalar value encodings are held
+/// across `Poll::Pending` until the rest of them arrives.
+///
+/// Use `Utf8ReaderBuilder::build_async` to configure the options.
+pub struct AsyncUtf8Reader<Inner: AsyncRead + Unpin> {
+    inner: Utf8Reader<FromAsyncRead<Inner>>,
+}
+
+impl<Inner: AsyncRead + Unpin> AsyncUtf8Reader<Inner> {
+    /// Construct a new instance of `AsyncUtf8Reader` wrapping `inner`.
+    #[inline]
+    pub fn new(inner: Inner) -> Self {
+        Utf8ReaderBuilder::new().build_async(inner)
+    }
+
+    #[inline]
+    pub(crate) fn from_utf8_reader(inner: Utf8Reader<FromAsyncRead<Inner>>) -> Self {
+        Self { inner }
+    }
+
+    /// Read translated data into `buf`, like `Read::read_outcome`. Rather
+    /// than reporting `Readiness::Pending` with no data, this returns
+    /// `Poll::Pending`, and arranges for the task to be woken when more
+    /// data may be available.
+    #[inline]
+    pub fn poll_read_outcome(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<ReadOutcome>> {
+        poll_read_outcome(&mut self.get_mut().inner, cx, buf)
+    }
+
+    /// Consume this `AsyncUtf8Reader` and return the underlying stream
+    /// object. Any data which has been read from the underlying stream but
+    /// not yet consumed is discarded.
+    #[inline]
+    pub fn into_inner(self) -> Inner {
+        self.inner.into_inner().into_inner()
+    }
+
+    /// Gets a reference to the underlying reader.
+    #[inline]
+    pub fn get_ref(&self) -> &Inner {
+        self.inner.get_ref().get_ref()
+    }
+
+    /// Gets a mutable reference to the underlying reader.
+    ///
+    /// It is inadvisable to directly read from the underlying reader, as
+    /// data buffered by this reader would then be out of order with it.
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut Inner {
+        self.inner.get_mut().get_mut()
+    }
+
+    /// Return the number of invalid sequences replaced so far.
+    #[inline]
+    pub fn replacements(&self) -> u64 {
+        self.inner.replacements()
+    }
+}
+
+/// Lulls are read through, since `AsyncRead` has no way to report them.
+impl<Inner: AsyncRead + Unpin> AsyncRead for AsyncUtf8Reader<Inner> {
+    #[inline]
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        poll_read_through_lulls(&mut self.get_mut().inner, cx, buf)
+    }
+}
+
+/// Poll `reader` to its end, returning the data and the number of times it
+/// was pending.
+#[cfg(test)]
+pub(crate) fn read_to_end_polling<R: AsyncRead + Unpin>(
+    reader: &mut R,
+) -> io::Result<(Vec<u8>, usize)> {
+    use std::{sync::Arc, task::Wake};
+
+    struct NoopWaker;
+    impl Wake for NoopWaker {
+        fn wake(self: Arc<Self>) {}
+    }
+    let waker = Arc::new(NoopWaker).into();
+    let mut cx = Context::from_waker(&waker);
+    let mut output = Vec::new();
+    let mut pendings = 0;
+    loop {
+        let mut buf = [0_u8; 8];
+        match Pin::new(&mut *reader).poll_read(&mut cx, &mut buf) {
+            Poll::Ready(Ok(0)) => return Ok((output, pendings)),
+            Poll::Ready(Ok(size)) => output.extend_from_slice(&buf[..size]),
+            Poll::Ready(Err(e)) => return Err(e),
+            Poll::Pending => pendings += 1,
+        }
+    }
+}
+
+/// An `AsyncRead` which yields the given chunks, with `Poll::Pending`
+/// between each of them.
+#[cfg(test)]
+pub(crate) struct Chunked {
+    chunks: std::collections::VecDeque<&'static [u8]>,
+    ready: bool,
+}
+
+#[cfg(test)]
+impl Chunked {
+    pub(crate) fn new(chunks: &[&'static [u8]]) -> Self {
+        Self {
+            chunks: chunks.iter().copied().collect(),
+            ready: true,
+        }
+    }
+}
+
+#[cfg(test)]
+impl AsyncRead for Chunked {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        if !std::mem::replace(&mut this.ready, false) {
+            this.ready = true;
+            cx.waker().wake_by_ref();
+            return Poll::Pending;
+        }
+        match this.chunks.pop_front() {
+            Some(chunk) => {
+                assert!(chunk.len() <= buf.len());
+                buf[..chunk.len()].copy_from_slice(chunk);
+                Poll::Ready(Ok(chunk.len()))
+            }
+            None => Poll::Ready(Ok(0)),
+        }
+    }
+}
+
+#[test]
+fn test_split_encoding() -> io::Result<()> {
+    let mut reader = AsyncUtf8Reader::new(Chunked::new(&[b"a\xc3", b"\xa9b\xe2\x82", b"\xac"]));
+    let (output, pendings) = read_to_end_polling(&mut reader)?;
+    assert_eq!(output, "aéb€".as_bytes());
+    assert!(pendings > 0);
+    assert_eq!(reader.replacements(), 0);
+    Ok(())
+}
@@ -0,0 +1,240 @@
+use crate::{Read, ReadOutcome, Status};
+use std::{cmp::min, io};
+
+/// The size of the buffer used to hold encoded input.
+const INPUT_SIZE: usize = 8192;
+
+/// A `Read` implementation which decodes base64 read from an inner `Read`.
+///
+/// This uses the standard alphabet from [RFC 4648]. Whitespace, such as
+/// the line breaks in MIME-style armoring, is ignored, and trailing `=`
+/// padding is optional. Groups of characters may be split across reads of
+/// the inner stream arbitrarily. Lulls and the end of the stream are
+/// passed through once all the data read before them has been decoded.
+///
+/// [RFC 4648]: https://www.rfc-editor.org/rfc/rfc4648
+pub struct Base64Reader<Inner: Read> {
+    /// The wrapped byte stream.
+    inner: Inner,
+
+    /// Scratch space for reading encoded input.
+    input: Box<[u8]>,
+
+    /// Decoded data waiting to be read.
+    decoded: Vec<u8>,
+
+    /// The position within `decoded` of the next byte to read.
+    pos: usize,
+
+    /// The status to report once `decoded` is drained.
+    status: Status,
+
+    /// The accumulated bits of an incomplete group.
+    bits: u32,
+
+    /// The number of characters accumulated in `bits`.
+    count: u8,
+
+    /// We've seen `=` padding, so only more padding may follow.
+    padded: bool,
+
+    /// The stream has ended and all the data has been read.
+    ended: bool,
+}
+
+impl<Inner: Read> Base64Reader<Inner> {
+    /// Construct a new instance of `Base64Reader` wrapping `inner`.
+    #[inline]
+    pub fn new(inner: Inner) -> Self {
+        Self {
+            inner,
+            input: vec![0; INPUT_SIZE].into_boxed_slice(),
+            decoded: Vec::new(),
+            pos: 0,
+            status: Status::ready(),
+            bits: 0,
+            count: 0,
+            padded: false,
+            ended: false,
+        }
+    }
+
+    /// Read from `inner` until we have some decoded data or a status to
+    /// report.
+    fn fill_decoded(&mut self) -> io::Result<()> {
+        self.decoded.clear();
+        self.pos = 0;
+        loop {
+            let outcome = self.inner.read_outcome(&mut self.input)?;
+            for i in 0..outcome.size {
+                self.decode(self.input[i])?;
+            }
+            if outcome.status.is_end() {
+                self.finish()?;
+            }
+            self.status = outcome.status;
+            if !self.decoded.is_empty() || self.status != Status::ready() {
+                return Ok(());
+            }
+        }
+    }
+
+    fn decode(&mut self, c: u8) -> io::Result<()> {
+        let value = match c {
+            b'A'..=b'Z' => c - b'A',
+            b'a'..=b'z' => c - b'a' + 26,
+            b'0'..=b'9' => c - b'0' + 52,
+            b'+' => 62,
+            b'/' => 63,
+            b' ' | b'\t' | b'\r' | b'\n' => return Ok(()),
+            b'=' => {
+                if !self.padded {
+                    self.finish()?;
+                    self.padded = true;
+                }
+                return Ok(());
+            }
+            _ => return Err(invalid_data("invalid base64 character")),
+        };
+        if self.padded {
+            return Err(invalid_data("base64 data after padding"));
+        }
+        self.bits = (self.bits << 6) | u32::from(value);
+        self.count += 1;
+        if self.count == 4 {
+            self.decoded
+                .extend_from_slice(&self.bits.to_be_bytes()[1..]);
+            self.bits = 0;
+            self.count = 0;
+        }
+        Ok(())
+    }
+
+    /// Decode the final, incomplete group, if any.
+    fn finish(&mut self) -> io::Result<()> {
+        match self.count {
+            0 => {}
+            1 => return Err(invalid_data("truncated base64 data")),
+            count => {
+                let bits = self.bits << (6 * (4 - count));
+                let len = usize::from(count) - 1;
+                self.decoded
+                    .extend_from_slice(&bits.to_be_bytes()[1..1 + len]);
+            }
+        }
+        self.bits = 0;
+        self.count = 0;
+        Ok(())
+    }
+}
+
+impl<Inner: Read> Read for Base64Reader<Inner> {
+    fn read_outcome(&mut self, buf: &mut [u8]) -> io::Result<ReadOutcome> {
+        if self.ended {
+            return Ok(ReadOutcome::end(0));
+        }
+        if buf.is_empty() {
+            return Ok(ReadOutcome::ready(0));
+        }
+        if self.pos == self.decoded.len() {
+            self.fill_decoded()?;
+        }
+
+        let size = min(buf.len(), self.decoded.len() - self.pos);
+        buf[..size].copy_from_slice(&self.decoded[self.pos..self.pos + size]);
+        self.pos += size;
+        if self.pos < self.decoded.len() {
+            return Ok(ReadOutcome::ready(size));
+        }
+        self.ended = self.status.is_end();
+        Ok(ReadOutcome {
+            size,
+            status: self.status,
+        })
+    }
+}
+
+impl<Inner: Read> io::Read for Base64Reader<Inner> {
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        Read::read(self, buf)
+    }
+
+    #[inline]
+    fn read_vectored(&mut self, bufs: &mut [io::IoSliceMut<'_>]) -> io::Result<usize> {
+        Read::read_vectored(self, bufs)
+    }
+
+    #[cfg(feature = "nightly")]
+    #[inline]
+    fn is_read_vectored(&self) -> bool {
+        Read::is_read_vectored(self)
+    }
+
+    #[inline]
+    fn read_to_end(&mut self, buf: &mut Vec<u8>) -> io::Result<usize> {
+        Read::read_to_end(self, buf)
+    }
+
+    #[inline]
+    fn read_to_string(&mut self, buf: &mut String) -> io::Result<usize> {
+        Read::read_to_string(self, buf)
+    }
+
+    #[inline]
+    fn read_exact(&mut self, buf: &mut [u8]) -> io::Result<()> {
+        Read::read_exact(self, buf)
+    }
+}
+
+fn invalid_data(msg: &'static str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+#[cfg(test)]
+fn decode(input: &[u8]) -> io::Result<Vec<u8>> {
+    let mut reader = Base64Reader::new(crate::SliceReader::new(input));
+    let mut v = Vec::new();
+    reader.read_to_end(&mut v)?;
+    Ok(v)
+}
+
+#[test]
+fn test_base64_reader() {
+    assert_eq!(decode(b"").unwrap(), b"");
+    assert_eq!(decode(b"Zg==").unwrap(), b"f");
+    assert_eq!(decode(b"Zm8=").unwrap(), b"fo");
+    assert_eq!(decode(b"Zm9v").unwrap(), b"foo");
+    assert_eq!(decode(b"Zm9vYg==").unwrap(), b"foob");
+    assert_eq!(decode(b"Zm9vYmE=").unwrap(), b"fooba");
+    assert_eq!(decode(b"Zm9vYmFy").unwrap(), b"foobar");
+    assert_eq!(decode(b"Zm9vYmE").unwrap(), b"fooba");
+    assert_eq!(decode(b"Zm9v\r\nYmFy\n").unwrap(), b"foobar");
+    assert_eq!(decode(b"//79").unwrap(), b"\xff\xfe\xfd");
+}
+
+#[test]
+fn test_base64_reader_invalid() {
+    assert!(decode(b"Zm9v!").is_err());
+    assert!(decode(b"Z").is_err());
+    assert!(decode(b"Zg==Zg==").is_err());
+}
+
+#[test]
+fn test_base64_reader_lull() {
+    let (reader, mut writer) = crate::pipe();
+    let mut reader = Base64Reader::new(reader);
+    let mut buf = [0; 16];
+
+    crate::Write::write_all(&mut writer, b"Zm9vY").unwrap();
+    crate::Write::flush(&mut writer, Status::Open(crate::Readiness::Lull)).unwrap();
+    let outcome = reader.read_outcome(&mut buf).unwrap();
+    assert_eq!(&buf[..outcome.size], b"foo");
+    assert_eq!(outcome.status, Status::Open(crate::Readiness::Lull));
+
+    crate::Write::write_all(&mut writer, b"mFy").unwrap();
+    crate::Write::flush(&mut writer, Status::End).unwrap();
+    let outcome = reader.read_outcome(&mut buf).unwrap();
+    assert_eq!(&buf[..outcome.size], b"bar");
+    assert!(outcome.status.is_end());
+}
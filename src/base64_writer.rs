@@ -0,0 +1,138 @@
+use crate::{Close, Status, Write};
+use std::io;
+
+/// The standard base64 alphabet from [RFC 4648].
+///
+/// [RFC 4648]: https://www.rfc-editor.org/rfc/rfc4648
+const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// A `Write` implementation which encodes data written to it as base64,
+/// and writes the encoded text to an inner `Write`.
+///
+/// Bytes which don't yet form a complete three-byte group are held until
+/// more data is written, so arbitrary write boundaries produce the same
+/// output as a single write. The final group is padded with `=` when the
+/// stream is flushed with `Status::End`.
+///
+/// The output is written with `write_all_utf8`, so the inner stream may be
+/// a `TextWriter`.
+pub struct Base64Writer<Inner: Write> {
+    /// The wrapped byte stream.
+    inner: Inner,
+
+    /// Bytes of an incomplete group.
+    partial: [u8; 3],
+
+    /// The number of bytes in `partial`.
+    len: usize,
+
+    /// Scratch space for encoded output.
+    encoded: String,
+}
+
+impl<Inner: Write> Base64Writer<Inner> {
+    /// Construct a new instance of `Base64Writer` wrapping `inner`.
+    #[inline]
+    pub fn new(inner: Inner) -> Self {
+        Self {
+            inner,
+            partial: [0; 3],
+            len: 0,
+            encoded: String::new(),
+        }
+    }
+
+    /// Flush and close the underlying stream and return the underlying
+    /// stream object.
+    pub fn close_into_inner(mut self) -> io::Result<Inner> {
+        self.flush(Status::End)?;
+        Ok(self.inner)
+    }
+
+    fn encode_group(&mut self, group: [u8; 3], len: usize) {
+        let bits = u32::from_be_bytes([0, group[0], group[1], group[2]]);
+        for i in 0..4 {
+            if i <= len {
+                let index = (bits >> (18 - 6 * i)) & 0x3f;
+                self.encoded.push(char::from(ALPHABET[index as usize]));
+            } else {
+                self.encoded.push('=');
+            }
+        }
+    }
+}
+
+impl<Inner: Write> Close for Base64Writer<Inner> {
+    type Output = Inner;
+
+    #[inline]
+    fn close_into_inner(self) -> io::Result<Inner> {
+        Base64Writer::close_into_inner(self)
+    }
+}
+
+impl<Inner: Write> Write for Base64Writer<Inner> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.encoded.clear();
+        for &b in buf {
+            self.partial[self.len] = b;
+            self.len += 1;
+            if self.len == 3 {
+                self.encode_group(self.partial, 3);
+                self.len = 0;
+            }
+        }
+        self.inner.write_all_utf8(&self.encoded)?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self, status: Status) -> io::Result<()> {
+        if status.is_end() && self.len != 0 {
+            self.encoded.clear();
+            self.partial[self.len..].iter_mut().for_each(|b| *b = 0);
+            self.encode_group(self.partial, self.len);
+            self.len = 0;
+            self.inner.write_all_utf8(&self.encoded)?;
+        }
+        self.inner.flush(status)
+    }
+
+    #[inline]
+    fn abandon(&mut self) {
+        self.len = 0;
+        self.inner.abandon()
+    }
+}
+
+#[cfg(test)]
+fn encode(input: &[u8]) -> String {
+    let mut v = Vec::new();
+    let mut writer = Base64Writer::new(crate::StdWriter::new(&mut v));
+    for &b in input {
+        writer.write_all(&[b]).unwrap();
+    }
+    writer.close_into_inner().unwrap();
+    String::from_utf8(v).unwrap()
+}
+
+#[test]
+fn test_base64_writer() {
+    assert_eq!(encode(b""), "");
+    assert_eq!(encode(b"f"), "Zg==");
+    assert_eq!(encode(b"fo"), "Zm8=");
+    assert_eq!(encode(b"foo"), "Zm9v");
+    assert_eq!(encode(b"foob"), "Zm9vYg==");
+    assert_eq!(encode(b"fooba"), "Zm9vYmE=");
+    assert_eq!(encode(b"foobar"), "Zm9vYmFy");
+    assert_eq!(encode(b"\xff\xfe\xfd"), "//79");
+}
+
+#[test]
+fn test_base64_round_trip() {
+    let data = (0..=255).collect::<Vec<u8>>();
+    let encoded = encode(&data);
+    let mut reader = crate::Base64Reader::new(crate::SliceReader::new(encoded.as_bytes()));
+    let mut v = Vec::new();
+    crate::Read::read_to_end(&mut reader, &mut v).unwrap();
+    assert_eq!(v, data);
+}
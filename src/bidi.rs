@@ -0,0 +1,108 @@
+//! Defines `BidiPolicy` and `BidiBalance`.
+
+/// What a `TextReader` or `TextWriter` does with the bidirectional
+/// embedding, override, and isolate controls: U+202A (LRE), U+202B (RLE),
+/// U+202C (PDF), U+202D (LRO), U+202E (RLO), U+2066 (LRI), U+2067 (RLI),
+/// U+2068 (FSI), and U+2069 (PDI).
+///
+/// These can make text display in a different order than it's processed
+/// in, which is used by [Trojan Source] attacks to hide code in source
+/// files. The implicit directional marks U+200E (LRM), U+200F (RLM), and
+/// U+061C (ALM) don't affect other text this way, and aren't affected by
+/// this policy; they're handled by the `InvisiblePolicy`.
+///
+/// [Trojan Source]: https://trojansource.codes/
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BidiPolicy {
+    /// Pass the controls through. This is the default.
+    Allow,
+
+    /// Remove the controls.
+    Strip,
+
+    /// Replace the controls with U+FFFD, or the configured replacement.
+    Replace,
+
+    /// Fail with [`Error::BidiControl`].
+    ///
+    /// [`Error::BidiControl`]: crate::Error::BidiControl
+    Reject,
+
+    /// Permit the controls only if each line's embeddings, overrides, and
+    /// isolates are properly nested and terminated by the end of the line,
+    /// and fail with [`Error::BidiControl`] otherwise.
+    ///
+    /// [`Error::BidiControl`]: crate::Error::BidiControl
+    RequireBalanced,
+}
+
+impl Default for BidiPolicy {
+    #[inline]
+    fn default() -> Self {
+        Self::Allow
+    }
+}
+
+/// Test whether `c` is a bidirectional embedding, override, or isolate
+/// control.
+#[inline]
+pub(crate) fn is_bidi_control(c: char) -> bool {
+    matches!(c, '\u{202a}'..='\u{202e}' | '\u{2066}'..='\u{2069}')
+}
+
+/// The maximum nesting depth of embeddings, overrides, and isolates, from
+/// [UAX #9](https://unicode.org/reports/tr9/#BD2).
+const MAX_DEPTH: usize = 125;
+
+/// Tracks the nesting of bidirectional controls within a line, for
+/// `BidiPolicy::RequireBalanced`.
+#[derive(Default)]
+pub(crate) struct BidiBalance {
+    /// For each open embedding or override, false, and for each open
+    /// isolate, true.
+    stack: Vec<bool>,
+}
+
+impl BidiBalance {
+    /// Process the next scalar value, and return false if it leaves the
+    /// controls unbalanced.
+    pub(crate) fn step(&mut self, c: char) -> bool {
+        match c {
+            '\u{202a}' | '\u{202b}' | '\u{202d}' | '\u{202e}' => self.push(false),
+            '\u{2066}' | '\u{2067}' | '\u{2068}' => self.push(true),
+            '\u{202c}' => self.stack.pop() == Some(false),
+            '\u{2069}' => self.stack.pop() == Some(true),
+            '\n' => self.end_line(),
+            _ => true,
+        }
+    }
+
+    /// Declare the end of a line, and return false if any controls are
+    /// left open.
+    pub(crate) fn end_line(&mut self) -> bool {
+        let balanced = self.stack.is_empty();
+        self.stack.clear();
+        balanced
+    }
+
+    fn push(&mut self, isolate: bool) -> bool {
+        self.stack.push(isolate);
+        self.stack.len() <= MAX_DEPTH
+    }
+}
+
+#[test]
+fn test_bidi_balance() {
+    let balanced = |s: &str| {
+        let mut balance = BidiBalance::default();
+        s.chars().all(|c| balance.step(c))
+    };
+    assert!(balanced("abc\n"));
+    assert!(balanced("a\u{202e}b\u{202c}c\n"));
+    assert!(balanced("a\u{2067}b\u{202b}c\u{202c}\u{2069}\n"));
+    assert!(!balanced("a\u{202e}b\nc\n"));
+    assert!(!balanced("a\u{2067}b\u{202c}\n"));
+    assert!(!balanced("a\u{202c}\n"));
+    assert!(!balanced("a\u{2069}\n"));
+    assert!(!balanced(&"\u{2066}".repeat(MAX_DEPTH + 1)));
+}
@@ -0,0 +1,34 @@
+//! Defines `BomPolicy`.
+
+/// What a `TextReader` does with U+FEFF (BOM).
+///
+/// At the beginning of a stream, U+FEFF is a byte-order mark, which some
+/// producers add to indicate the encoding. Elsewhere, it's the deprecated
+/// ZERO WIDTH NO-BREAK SPACE, though it most often appears when files with
+/// byte-order marks are concatenated.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BomPolicy {
+    /// Strip U+FEFF everywhere in the stream. This is the default.
+    StripAll,
+
+    /// Strip U+FEFF at the beginning of the stream, and pass it through
+    /// elsewhere.
+    StripLeading,
+
+    /// Strip U+FEFF at the beginning of the stream, and fail with
+    /// [`Error::InteriorBom`] if it appears elsewhere, since that likely
+    /// indicates corruption.
+    ///
+    /// [`Error::InteriorBom`]: crate::Error::InteriorBom
+    RejectInterior,
+
+    /// Pass U+FEFF through everywhere.
+    Preserve,
+}
+
+impl Default for BomPolicy {
+    #[inline]
+    fn default() -> Self {
+        Self::StripAll
+    }
+}
@@ -0,0 +1,528 @@
+use crate::{
+    unicode::{MAX_UTF8_SIZE, NORMALIZATION_BUFFER_SIZE},
+    Read, ReadBuf, ReadBuffered, ReadOutcome, Readiness, Status,
+};
+#[cfg(unix)]
+use std::os::unix::io::{AsFd, AsRawFd, BorrowedFd, RawFd};
+#[cfg(windows)]
+use std::os::windows::io::{AsHandle, AsRawHandle, BorrowedHandle, RawHandle};
+use std::{cmp::min, io, mem, str};
+
+/// The default capacity of a `BufferedReader`'s buffer.
+const DEFAULT_CAPACITY: usize = 8192;
+
+/// A `Read` implementation which adds buffering to an inner `Read`, and
+/// implements `ReadBuffered` so that consumers can inspect the buffer
+/// contents without copying.
+///
+/// Each buffer fill performs a single read from the inner stream, so status
+/// information is preserved: a lull or end reported by the inner stream is
+/// reported once the data preceding it has been consumed.
+pub struct BufferedReader<Inner: Read> {
+    /// The wrapped byte stream.
+    inner: Inner,
+
+    /// The buffer.
+    buf: Box<[u8]>,
+
+    /// The position in `buf` of the next byte to be consumed.
+    pos: usize,
+
+    /// The number of valid bytes in `buf`.
+    filled: usize,
+
+    /// The status reported by the inner stream after the data in `buf`.
+    status: Status,
+}
+
+impl<Inner: Read> BufferedReader<Inner> {
+    /// Construct a new instance of `BufferedReader` wrapping `inner`, with a
+    /// default buffer capacity.
+    #[inline]
+    pub fn new(inner: Inner) -> Self {
+        Self::with_capacity(DEFAULT_CAPACITY, inner)
+    }
+
+    /// Construct a new instance of `BufferedReader` wrapping `inner`, with
+    /// the given buffer capacity.
+    ///
+    /// When wrapping a `TextReader`, capacities smaller than
+    /// [`NORMALIZATION_BUFFER_SIZE`](crate::NORMALIZATION_BUFFER_SIZE)
+    /// cause the `TextReader` to stage its output internally.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is zero.
+    #[inline]
+    pub fn with_capacity(capacity: usize, inner: Inner) -> Self {
+        assert_ne!(capacity, 0, "buffer capacity must be non-zero");
+        Self {
+            inner,
+            buf: vec![0; capacity].into_boxed_slice(),
+            pos: 0,
+            filled: 0,
+            status: Status::ready(),
+        }
+    }
+
+    /// Return the contents of the buffer which have not yet been consumed.
+    #[inline]
+    pub fn buffer(&self) -> &[u8] {
+        &self.buf[self.pos..self.filled]
+    }
+
+    /// Return the capacity of the buffer.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.buf.len()
+    }
+
+    /// Return up to `n` upcoming bytes without consuming them, reading from
+    /// the inner stream as needed, for lookahead such as detecting magic
+    /// numbers.
+    ///
+    /// Fewer than `n` bytes are returned if the inner stream reports a lull,
+    /// that reading would block, or the end of the stream first, or if `n`
+    /// exceeds the capacity of the buffer.
+    ///
+    /// Inner streams such as `Utf8Reader` and `TextReader` need room for a
+    /// whole scalar value in each read, so if the unconsumed data leaves
+    /// less room than that, the buffer is grown to make room for at least
+    /// [`NORMALIZATION_BUFFER_SIZE`] more bytes.
+    pub fn peek(&mut self, n: usize) -> io::Result<&[u8]> {
+        let n = min(n, self.buf.len());
+        while self.filled - self.pos < n
+            && (self.status == Status::ready()
+                || (self.pos == self.filled && !self.status.is_end()))
+        {
+            if self.pos != 0 {
+                self.buf.copy_within(self.pos..self.filled, 0);
+                self.filled -= self.pos;
+                self.pos = 0;
+            }
+            if self.buf.len() - self.filled < MAX_UTF8_SIZE {
+                let mut buf = mem::take(&mut self.buf).into_vec();
+                buf.resize(self.filled + NORMALIZATION_BUFFER_SIZE, 0);
+                self.buf = buf.into_boxed_slice();
+            }
+            match self.inner.read_outcome(&mut self.buf[self.filled..]) {
+                Ok(outcome) => {
+                    self.filled += outcome.size;
+                    self.status = outcome.status;
+                    if self.status == Status::Open(Readiness::Pending) {
+                        break;
+                    }
+                }
+                Err(ref e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(&self.buf[self.pos..min(self.filled, self.pos + n)])
+    }
+
+    /// Like `peek`, but returns the upcoming data as a `str`, up to the
+    /// first invalid or incomplete UTF-8 sequence.
+    pub fn peek_str(&mut self, n: usize) -> io::Result<&str> {
+        let bytes = self.peek(n)?;
+        let valid = match str::from_utf8(bytes) {
+            Ok(_) => bytes.len(),
+            Err(error) => error.valid_up_to(),
+        };
+        Ok(str::from_utf8(&bytes[..valid]).unwrap())
+    }
+}
+
+impl<Inner: Read> ReadBuffered for BufferedReader<Inner> {
+    fn fill_buf_outcome(&mut self) -> io::Result<(&[u8], Status)> {
+        if self.pos == self.filled && !self.status.is_end() {
+            let outcome = self.inner.read_outcome(&mut self.buf)?;
+            self.pos = 0;
+            self.filled = outcome.size;
+            self.status = outcome.status;
+        }
+        Ok((&self.buf[self.pos..self.filled], self.status))
+    }
+
+    #[inline]
+    fn consume(&mut self, amt: usize) {
+        self.pos = min(self.pos + amt, self.filled);
+    }
+
+    fn read_line(&mut self, buf: &mut String) -> io::Result<ReadOutcome> {
+        let mut bytes = mem::take(buf).into_bytes();
+        let start_len = bytes.len();
+        let result = self.read_until(b'\n', &mut bytes);
+        match (str::from_utf8(&bytes[start_len..]), &result) {
+            (Ok(_), _) => {}
+            // A lull, or a read which would block, split a UTF-8 sequence.
+            // `read_until` drained the buffer, so put the incomplete bytes
+            // back at its start, to be decoded once the rest arrives.
+            (Err(error), Ok(outcome))
+                if error.error_len().is_none() && !outcome.status.is_end() =>
+            {
+                let tail = bytes.split_off(start_len + error.valid_up_to());
+                if self.buf.len() < tail.len() {
+                    self.buf = vec![0; MAX_UTF8_SIZE].into_boxed_slice();
+                }
+                self.buf[..tail.len()].copy_from_slice(&tail);
+                self.pos = 0;
+                self.filled = tail.len();
+                self.status = Status::ready();
+                *buf = String::from_utf8(bytes).unwrap();
+                return Ok(ReadOutcome {
+                    size: outcome.size - tail.len(),
+                    status: outcome.status,
+                });
+            }
+            (Err(_), _) => {
+                bytes.truncate(start_len);
+                *buf = String::from_utf8(bytes).unwrap();
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "stream did not contain valid UTF-8",
+                ));
+            }
+        }
+        *buf = String::from_utf8(bytes).unwrap();
+        result
+    }
+}
+
+impl<Inner: Read> Read for BufferedReader<Inner> {
+    fn read_outcome(&mut self, buf: &mut [u8]) -> io::Result<ReadOutcome> {
+        // If the buffer is empty and the read is at least as big as it,
+        // bypass the buffer.
+        if self.pos == self.filled && buf.len() >= self.buf.len() {
+            if self.status.is_end() {
+                return Ok(ReadOutcome::end(0));
+            }
+            let outcome = self.inner.read_outcome(buf)?;
+            self.status = outcome.status;
+            return Ok(outcome);
+        }
+
+        let (data, status) = self.fill_buf_outcome()?;
+        let size = min(data.len(), buf.len());
+        buf[..size].copy_from_slice(&data[..size]);
+        let drained = size == data.len();
+        self.consume(size);
+
+        Ok(if drained {
+            ReadOutcome { size, status }
+        } else {
+            ReadOutcome::ready(size)
+        })
+    }
+
+    fn read_buf_outcome(&mut self, buf: &mut ReadBuf<'_>) -> io::Result<ReadOutcome> {
+        // As in `read_outcome`, bypass the buffer for large reads.
+        if self.pos == self.filled && buf.remaining() >= self.buf.len() {
+            if self.status.is_end() {
+                return Ok(ReadOutcome::end(0));
+            }
+            let outcome = self.inner.read_buf_outcome(buf)?;
+            self.status = outcome.status;
+            return Ok(outcome);
+        }
+
+        let (data, status) = self.fill_buf_outcome()?;
+        let size = min(data.len(), buf.remaining());
+        buf.put_slice(&data[..size]);
+        let drained = size == data.len();
+        self.consume(size);
+
+        Ok(if drained {
+            ReadOutcome { size, status }
+        } else {
+            ReadOutcome::ready(size)
+        })
+    }
+
+    #[inline]
+    fn remaining_hint(&self) -> (u64, Option<u64>) {
+        let buffered = (self.filled - self.pos) as u64;
+        if self.status.is_end() {
+            return (buffered, Some(buffered));
+        }
+        let (lower, upper) = self.inner.remaining_hint();
+        (
+            lower.saturating_add(buffered),
+            upper.and_then(|upper| upper.checked_add(buffered)),
+        )
+    }
+}
+
+impl<Inner: Read> io::Read for BufferedReader<Inner> {
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        Read::read(self, buf)
+    }
+
+    #[inline]
+    fn read_vectored(&mut self, bufs: &mut [io::IoSliceMut<'_>]) -> io::Result<usize> {
+        Read::read_vectored(self, bufs)
+    }
+
+    #[cfg(feature = "nightly")]
+    #[inline]
+    fn is_read_vectored(&self) -> bool {
+        Read::is_read_vectored(self)
+    }
+
+    #[inline]
+    fn read_to_end(&mut self, buf: &mut Vec<u8>) -> io::Result<usize> {
+        Read::read_to_end(self, buf)
+    }
+
+    #[inline]
+    fn read_to_string(&mut self, buf: &mut String) -> io::Result<usize> {
+        Read::read_to_string(self, buf)
+    }
+
+    #[inline]
+    fn read_exact(&mut self, buf: &mut [u8]) -> io::Result<()> {
+        Read::read_exact(self, buf)
+    }
+}
+
+/// Since `BufRead` has no way to report a lull, lulls are waited through.
+/// If the inner stream reports `Readiness::Pending` with no data,
+/// `fill_buf` fails with `io::ErrorKind::WouldBlock`.
+impl<Inner: Read> io::BufRead for BufferedReader<Inner> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        while self.pos == self.filled && !self.status.is_end() {
+            let outcome = self.inner.read_outcome(&mut self.buf)?;
+            self.pos = 0;
+            self.filled = outcome.size;
+            self.status = outcome.status;
+            if self.filled == 0 && self.status == Status::Open(Readiness::Pending) {
+                return Err(io::Error::new(
+                    io::ErrorKind::WouldBlock,
+                    "reading would block",
+                ));
+            }
+        }
+        Ok(&self.buf[self.pos..self.filled])
+    }
+
+    #[inline]
+    fn consume(&mut self, amt: usize) {
+        ReadBuffered::consume(self, amt)
+    }
+}
+
+#[cfg(unix)]
+impl<Inner: Read + AsRawFd> AsRawFd for BufferedReader<Inner> {
+    #[inline]
+    fn as_raw_fd(&self) -> RawFd {
+        self.inner.as_raw_fd()
+    }
+}
+
+#[cfg(unix)]
+impl<Inner: Read + AsFd> AsFd for BufferedReader<Inner> {
+    #[inline]
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.inner.as_fd()
+    }
+}
+
+#[cfg(windows)]
+impl<Inner: Read + AsRawHandle> AsRawHandle for BufferedReader<Inner> {
+    #[inline]
+    fn as_raw_handle(&self) -> RawHandle {
+        self.inner.as_raw_handle()
+    }
+}
+
+#[cfg(windows)]
+impl<Inner: Read + AsHandle> AsHandle for BufferedReader<Inner> {
+    #[inline]
+    fn as_handle(&self) -> BorrowedHandle<'_> {
+        self.inner.as_handle()
+    }
+}
+
+#[test]
+fn test_buffered_reader() {
+    let mut reader = BufferedReader::new(crate::SliceReader::new(b"hello world"));
+    let (data, status) = reader.fill_buf_outcome().unwrap();
+    assert_eq!(data, b"hello world");
+    assert!(status.is_end());
+    reader.consume(6);
+    assert_eq!(reader.buffer(), b"world");
+    let mut s = String::new();
+    reader.read_to_string(&mut s).unwrap();
+    assert_eq!(s, "world");
+    let (data, status) = reader.fill_buf_outcome().unwrap();
+    assert!(data.is_empty());
+    assert!(status.is_end());
+}
+
+#[test]
+fn test_buffered_reader_small_reads() {
+    let mut reader = BufferedReader::with_capacity(4, crate::SliceReader::new(b"hello world"));
+    let mut s = String::new();
+    reader.read_to_string(&mut s).unwrap();
+    assert_eq!(s, "hello world");
+}
+
+#[test]
+fn test_read_until() {
+    use crate::testing::{ScriptedReader, Step};
+
+    let mut reader = BufferedReader::new(ScriptedReader::new(vec![
+        Step::Data(b"hello\nwor".to_vec()),
+        Step::Lull,
+        Step::Data(b"ld\n".to_vec()),
+    ]));
+    let mut v = Vec::new();
+    let outcome = reader.read_until(b'\n', &mut v).unwrap();
+    assert_eq!(v, b"hello\n");
+    assert_eq!(outcome.size, 6);
+    assert_eq!(outcome.status, Status::ready());
+
+    v.clear();
+    let outcome = reader.read_until(b'\n', &mut v).unwrap();
+    assert_eq!(v, b"wor");
+    assert_eq!(outcome.size, 3);
+    assert_eq!(outcome.status, Status::Open(crate::Readiness::Lull));
+
+    let outcome = reader.read_until(b'\n', &mut v).unwrap();
+    assert_eq!(v, b"world\n");
+    assert_eq!(outcome.size, 3);
+    let outcome = reader.read_until(b'\n', &mut v).unwrap();
+    assert_eq!(outcome.size, 0);
+    assert!(outcome.status.is_end());
+}
+
+#[test]
+fn test_read_line() {
+    let mut reader = BufferedReader::new(crate::SliceReader::new(b"hello\nworld"));
+    let mut s = String::new();
+    reader.read_line(&mut s).unwrap();
+    assert_eq!(s, "hello\n");
+    let outcome = reader.read_line(&mut s).unwrap();
+    assert_eq!(s, "hello\nworld");
+    assert!(outcome.status.is_end());
+
+    let mut reader = BufferedReader::new(crate::SliceReader::new(b"caf\xe9\n"));
+    let mut s = String::from("x");
+    assert_eq!(
+        reader.read_line(&mut s).unwrap_err().kind(),
+        io::ErrorKind::InvalidData
+    );
+    assert_eq!(s, "x");
+}
+
+#[test]
+fn test_read_line_split_sequence() {
+    use crate::{
+        testing::{ScriptedReader, Step},
+        Readiness,
+    };
+
+    for pause in [Step::Lull, Step::Pending] {
+        let mut reader = BufferedReader::new(ScriptedReader::new(vec![
+            Step::Data(b"price: \xe2\x82".to_vec()),
+            pause.clone(),
+            Step::Data(b"\xac5\nnext\n".to_vec()),
+        ]));
+        let mut s = String::new();
+        let outcome = reader.read_line(&mut s).unwrap();
+        assert_eq!(s, "price: ");
+        assert_eq!(outcome.size, 7);
+        assert_ne!(outcome.status, Status::ready());
+        let outcome = reader.read_line(&mut s).unwrap();
+        assert_eq!(s, "price: \u{20ac}5\n");
+        assert_eq!(outcome.size, 5);
+        assert_eq!(outcome.status, Status::Open(Readiness::Ready));
+        s.clear();
+        reader.read_line(&mut s).unwrap();
+        assert_eq!(s, "next\n");
+    }
+
+    // An incomplete sequence at the end of the stream is invalid.
+    let mut reader = BufferedReader::new(crate::SliceReader::new(b"ab\xe2\x82"));
+    let mut s = String::new();
+    assert_eq!(
+        reader.read_line(&mut s).unwrap_err().kind(),
+        io::ErrorKind::InvalidData
+    );
+    assert_eq!(s, "");
+}
+
+#[test]
+fn test_peek() {
+    use crate::testing::{ScriptedReader, Step};
+
+    let mut reader = BufferedReader::with_capacity(
+        16,
+        ScriptedReader::new(vec![
+            Step::Data(b"#".to_vec()),
+            Step::Data(b"!/bin/sh\n".to_vec()),
+            Step::Lull,
+            Step::Data(b"echo".to_vec()),
+        ]),
+    );
+    assert_eq!(reader.peek(2).unwrap(), b"#!");
+    assert_eq!(reader.peek_str(32).unwrap(), "#!/bin/sh\n");
+
+    let mut s = String::new();
+    reader.read_line(&mut s).unwrap();
+    assert_eq!(s, "#!/bin/sh\n");
+    assert_eq!(reader.peek(4).unwrap(), b"echo");
+    s.clear();
+    Read::read_to_string(&mut reader, &mut s).unwrap();
+    assert_eq!(s, "echo");
+}
+
+#[test]
+fn test_peek_pending() {
+    use crate::testing::{ScriptedReader, Step};
+
+    let mut reader = BufferedReader::new(ScriptedReader::new(vec![
+        Step::Pending,
+        Step::Data(b"hello".to_vec()),
+    ]));
+    assert_eq!(reader.peek(5).unwrap(), b"");
+    assert_eq!(reader.peek(5).unwrap(), b"hello");
+}
+
+#[cfg(feature = "text")]
+#[test]
+fn test_peek_text_reader() {
+    use crate::{
+        testing::{ScriptedReader, Step},
+        TextReader, Utf8Reader,
+    };
+
+    // After the first read, less room is left than a scalar value needs,
+    // so the buffer has to grow.
+    let steps = vec![
+        Step::Data("h\u{e9}llo".as_bytes().to_vec()),
+        Step::Data(" w\u{f6}rld\n".as_bytes().to_vec()),
+    ];
+    let mut reader =
+        BufferedReader::with_capacity(8, Utf8Reader::new(ScriptedReader::new(steps.clone())));
+    assert_eq!(reader.peek_str(8).unwrap(), "h\u{e9}llo w");
+    let mut reader = BufferedReader::with_capacity(8, TextReader::new(ScriptedReader::new(steps)));
+    assert_eq!(reader.peek_str(8).unwrap(), "h\u{e9}llo w");
+    let mut s = String::new();
+    Read::read_to_string(&mut reader, &mut s).unwrap();
+    assert_eq!(s, "h\u{e9}llo w\u{f6}rld\n");
+}
+
+#[test]
+fn test_buf_read() {
+    use crate::testing::{ScriptedReader, Step};
+    use io::BufRead;
+
+    let reader = BufferedReader::new(ScriptedReader::new(vec![
+        Step::Data(b"hello\nwor".to_vec()),
+        Step::Lull,
+        Step::Data(b"ld\n".to_vec()),
+    ]));
+    let lines = reader.lines().collect::<io::Result<Vec<_>>>().unwrap();
+    assert_eq!(lines, vec!["hello", "world"]);
+}
@@ -0,0 +1,255 @@
+use crate::{Close, Readiness, Status, Write};
+#[cfg(unix)]
+use std::os::unix::io::{AsFd, AsRawFd, BorrowedFd, RawFd};
+#[cfg(windows)]
+use std::os::windows::io::{AsHandle, AsRawHandle, BorrowedHandle, RawHandle};
+use std::{io, str};
+
+/// The default capacity of a `BufferedWriter`'s buffer.
+const DEFAULT_CAPACITY: usize = 8192;
+
+/// A `Write` implementation which adds buffering to an inner `Write`.
+///
+/// Data is accumulated in the buffer and only written to the inner stream
+/// when the buffer fills, or when flushed with a status of
+/// `Status::Open(Readiness::Lull)` or `Status::End`. Flushes with
+/// `Status::Open(Readiness::Ready)` leave the buffer in place.
+///
+/// When the buffer fills in the middle of a UTF-8 scalar value encoding,
+/// the incomplete encoding is held back until the next write, so that
+/// `BufferedWriter` can wrap `Utf8Writer` and `TextWriter`.
+///
+/// Buffered data which hasn't been written when the `BufferedWriter` is
+/// dropped is discarded, and the inner stream is abandoned so that it isn't
+/// mistaken for complete; use `flush` or `close_into_inner` to write it.
+pub struct BufferedWriter<Inner: Write> {
+    /// The wrapped byte stream, or `None` once it has been taken by
+    /// `close_into_inner`.
+    inner: Option<Inner>,
+
+    /// The buffer.
+    buf: Vec<u8>,
+
+    /// The capacity of `buf`.
+    capacity: usize,
+}
+
+impl<Inner: Write> BufferedWriter<Inner> {
+    /// Construct a new instance of `BufferedWriter` wrapping `inner`, with a
+    /// default buffer capacity.
+    #[inline]
+    pub fn new(inner: Inner) -> Self {
+        Self::with_capacity(DEFAULT_CAPACITY, inner)
+    }
+
+    /// Construct a new instance of `BufferedWriter` wrapping `inner`, with
+    /// the given buffer capacity.
+    #[inline]
+    pub fn with_capacity(capacity: usize, inner: Inner) -> Self {
+        Self {
+            inner: Some(inner),
+            buf: Vec::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    /// Return the contents of the buffer which have not yet been written to
+    /// the inner stream.
+    #[inline]
+    pub fn buffer(&self) -> &[u8] {
+        &self.buf
+    }
+
+    /// Return the capacity of the buffer.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Flush and close the underlying stream and return the underlying
+    /// stream object.
+    pub fn close_into_inner(mut self) -> io::Result<Inner> {
+        self.flush(Status::End)?;
+        Ok(self.inner.take().unwrap())
+    }
+
+    /// Write the contents of the buffer to the inner stream. If `partial` is
+    /// true, an incomplete UTF-8 encoding at the end of the buffer is held
+    /// back.
+    fn write_buffer(&mut self, partial: bool) -> io::Result<()> {
+        let len = match str::from_utf8(&self.buf) {
+            Ok(s) => {
+                let result = self.inner.as_mut().unwrap().write_all_utf8(s);
+                self.buf.clear();
+                return result;
+            }
+            Err(error) if partial && error.error_len().is_none() => error.valid_up_to(),
+            Err(_) => self.buf.len(),
+        };
+
+        let result = match str::from_utf8(&self.buf[..len]) {
+            Ok(s) => self.inner.as_mut().unwrap().write_all_utf8(s),
+            Err(_) => self.inner.as_mut().unwrap().write_all(&self.buf[..len]),
+        };
+        self.buf.drain(..len);
+        result
+    }
+}
+
+impl<Inner: Write> Close for BufferedWriter<Inner> {
+    type Output = Inner;
+
+    #[inline]
+    fn close_into_inner(self) -> io::Result<Inner> {
+        BufferedWriter::close_into_inner(self)
+    }
+}
+
+impl<Inner: Write> Write for BufferedWriter<Inner> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.buf.len() + buf.len() > self.capacity {
+            self.write_buffer(true)?;
+        }
+        if self.buf.is_empty() && buf.len() >= self.capacity {
+            self.inner.as_mut().unwrap().write(buf)
+        } else {
+            self.buf.extend_from_slice(buf);
+            Ok(buf.len())
+        }
+    }
+
+    fn flush(&mut self, status: Status) -> io::Result<()> {
+        match status {
+            Status::Open(Readiness::Ready) | Status::Open(Readiness::Pending) => Ok(()),
+            Status::Open(Readiness::Lull) | Status::End => {
+                self.write_buffer(false)?;
+                self.inner.as_mut().unwrap().flush(status)
+            }
+        }
+    }
+
+    fn abandon(&mut self) {
+        self.buf.clear();
+        self.inner.as_mut().unwrap().abandon()
+    }
+
+    fn write_all_utf8(&mut self, s: &str) -> io::Result<()> {
+        if self.buf.len() + s.len() > self.capacity {
+            self.write_buffer(true)?;
+        }
+        if self.buf.is_empty() && s.len() >= self.capacity {
+            self.inner.as_mut().unwrap().write_all_utf8(s)
+        } else {
+            self.buf.extend_from_slice(s.as_bytes());
+            Ok(())
+        }
+    }
+
+    fn write_outcome(&mut self, buf: &[u8], status: Status) -> io::Result<()> {
+        match status {
+            Status::Open(Readiness::Ready) | Status::Open(Readiness::Pending) => {
+                self.write_all(buf)
+            }
+            Status::Open(Readiness::Lull) | Status::End => {
+                if self.buf.is_empty() {
+                    return self.inner.as_mut().unwrap().write_outcome(buf, status);
+                }
+                // Deliver the buffered data, the new data, and the status to
+                // the inner stream together.
+                self.buf.extend_from_slice(buf);
+                let result = self
+                    .inner
+                    .as_mut()
+                    .unwrap()
+                    .write_outcome(&self.buf, status);
+                self.buf.clear();
+                result
+            }
+        }
+    }
+}
+
+impl<Inner: Write> Drop for BufferedWriter<Inner> {
+    fn drop(&mut self) {
+        if !self.buf.is_empty() && self.inner.is_some() {
+            self.abandon();
+        }
+    }
+}
+
+#[cfg(unix)]
+impl<Inner: Write + AsRawFd> AsRawFd for BufferedWriter<Inner> {
+    #[inline]
+    fn as_raw_fd(&self) -> RawFd {
+        self.inner.as_ref().unwrap().as_raw_fd()
+    }
+}
+
+#[cfg(unix)]
+impl<Inner: Write + AsFd> AsFd for BufferedWriter<Inner> {
+    #[inline]
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.inner.as_ref().unwrap().as_fd()
+    }
+}
+
+#[cfg(windows)]
+impl<Inner: Write + AsRawHandle> AsRawHandle for BufferedWriter<Inner> {
+    #[inline]
+    fn as_raw_handle(&self) -> RawHandle {
+        self.inner.as_ref().unwrap().as_raw_handle()
+    }
+}
+
+#[cfg(windows)]
+impl<Inner: Write + AsHandle> AsHandle for BufferedWriter<Inner> {
+    #[inline]
+    fn as_handle(&self) -> BorrowedHandle<'_> {
+        self.inner.as_ref().unwrap().as_handle()
+    }
+}
+
+#[test]
+fn test_buffered_writer() {
+    let mut writer = BufferedWriter::new(crate::StdWriter::new(Vec::<u8>::new()));
+    writer.write_all(b"hello ").unwrap();
+    writer.write_all_utf8("world\n").unwrap();
+    writer.flush(Status::ready()).unwrap();
+    assert_eq!(writer.buffer(), b"hello world\n");
+    writer.flush(Status::Open(Readiness::Lull)).unwrap();
+    assert!(writer.buffer().is_empty());
+    let inner = writer.close_into_inner().unwrap();
+    assert_eq!(inner.get_ref(), b"hello world\n");
+}
+
+#[test]
+fn test_buffered_writer_split_utf8() {
+    let mut v = Vec::new();
+    let mut writer =
+        BufferedWriter::with_capacity(4, crate::Utf8Writer::new(crate::StdWriter::new(&mut v)));
+    for b in "hellö wörld\n".as_bytes() {
+        writer.write_all(&[*b]).unwrap();
+    }
+    writer.close_into_inner().unwrap();
+    assert_eq!(v, "hellö wörld\n".as_bytes());
+}
+
+#[cfg(feature = "text")]
+#[test]
+fn test_buffered_writer_drop() {
+    use crate::{DropPolicy, TextWriterBuilder};
+
+    // Dropping buffered data abandons the inner stream, so a `TextWriter`
+    // which panics when dropped without a trailing newline doesn't.
+    let mut writer = BufferedWriter::with_capacity(
+        4,
+        TextWriterBuilder::new()
+            .drop_policy(DropPolicy::Panic)
+            .build(crate::StdWriter::new(Vec::<u8>::new()))
+            .unwrap(),
+    );
+    writer.write_all(b"hello").unwrap();
+    writer.write_all(b" ").unwrap();
+    assert_eq!(writer.buffer(), b" ");
+    drop(writer);
+}
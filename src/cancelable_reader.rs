@@ -0,0 +1,140 @@
+use crate::{CancellationToken, Error, Read, ReadOutcome};
+use std::io;
+
+/// A `Read` implementation which passes data through from an inner `Read`
+/// unmodified, until its `CancellationToken` is cancelled.
+///
+/// The token is checked before each read of the inner stream, so loops such
+/// as `read_to_end` stop promptly after the token is cancelled, failing
+/// with `Error::Canceled`. That error is reported with
+/// `io::ErrorKind::Other` rather than `io::ErrorKind::Interrupted`, since
+/// loops retry reads which are interrupted. A read which is blocked in the
+/// inner stream isn't woken by cancellation, so for prompt shutdown the
+/// inner stream should report lulls or `Readiness::Pending`, or be wrapped
+/// in a `TimeoutReader`.
+pub struct CancelableReader<Inner: Read> {
+    /// The wrapped byte stream.
+    inner: Inner,
+
+    /// The token to check before each read.
+    token: CancellationToken,
+}
+
+impl<Inner: Read> CancelableReader<Inner> {
+    /// Construct a new instance of `CancelableReader` wrapping `inner`,
+    /// which fails once `token` is cancelled.
+    #[inline]
+    pub fn new(inner: Inner, token: CancellationToken) -> Self {
+        Self { inner, token }
+    }
+
+    /// Gets a reference to the token.
+    #[inline]
+    pub fn token(&self) -> &CancellationToken {
+        &self.token
+    }
+
+    /// Gets a reference to the underlying reader.
+    #[inline]
+    pub fn get_ref(&self) -> &Inner {
+        &self.inner
+    }
+
+    /// Gets a mutable reference to the underlying reader.
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut Inner {
+        &mut self.inner
+    }
+
+    /// Consume this `CancelableReader` and return the underlying stream.
+    #[inline]
+    pub fn into_inner(self) -> Inner {
+        self.inner
+    }
+}
+
+impl<Inner: Read> Read for CancelableReader<Inner> {
+    #[inline]
+    fn read_outcome(&mut self, buf: &mut [u8]) -> io::Result<ReadOutcome> {
+        if self.token.is_canceled() {
+            return Err(Error::Canceled.into());
+        }
+        self.inner.read_outcome(buf)
+    }
+
+    #[inline]
+    fn remaining_hint(&self) -> (u64, Option<u64>) {
+        // Cancellation only ends the stream early, so the upper bound holds.
+        let (_, upper) = self.inner.remaining_hint();
+        (0, upper)
+    }
+}
+
+impl<Inner: Read> io::Read for CancelableReader<Inner> {
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        Read::read(self, buf)
+    }
+
+    #[inline]
+    fn read_vectored(&mut self, bufs: &mut [io::IoSliceMut<'_>]) -> io::Result<usize> {
+        Read::read_vectored(self, bufs)
+    }
+
+    #[cfg(feature = "nightly")]
+    #[inline]
+    fn is_read_vectored(&self) -> bool {
+        Read::is_read_vectored(self)
+    }
+
+    #[inline]
+    fn read_to_end(&mut self, buf: &mut Vec<u8>) -> io::Result<usize> {
+        Read::read_to_end(self, buf)
+    }
+
+    #[inline]
+    fn read_to_string(&mut self, buf: &mut String) -> io::Result<usize> {
+        Read::read_to_string(self, buf)
+    }
+
+    #[inline]
+    fn read_exact(&mut self, buf: &mut [u8]) -> io::Result<()> {
+        Read::read_exact(self, buf)
+    }
+}
+
+#[test]
+fn test_cancelable_reader() {
+    use crate::{Readiness, Status};
+
+    let token = CancellationToken::new();
+    let mut reader = CancelableReader::new(crate::SliceReader::new(b"hello world"), token.clone());
+    let mut buf = [0; 5];
+    let outcome = reader.read_outcome(&mut buf).unwrap();
+    assert_eq!(&buf[..outcome.size], b"hello");
+    assert_eq!(outcome.status, Status::Open(Readiness::Ready));
+
+    token.cancel();
+    let mut rest = Vec::new();
+    let err = reader.read_to_end(&mut rest).unwrap_err();
+    assert_eq!(Error::from_io_error(&err), Some(&Error::Canceled));
+    assert!(rest.is_empty());
+}
+
+#[test]
+fn test_cancel_from_another_thread() {
+    // An endless stream of lulls, as from an idle interactive source.
+    struct Idle;
+    impl Read for Idle {
+        fn read_outcome(&mut self, _buf: &mut [u8]) -> io::Result<ReadOutcome> {
+            Ok(ReadOutcome::lull(0))
+        }
+    }
+
+    let token = CancellationToken::new();
+    let mut reader = CancelableReader::new(Idle, token.clone());
+    let canceler = std::thread::spawn(move || token.cancel());
+    let err = reader.read_to_end(&mut Vec::new()).unwrap_err();
+    assert_eq!(Error::from_io_error(&err), Some(&Error::Canceled));
+    canceler.join().unwrap();
+}
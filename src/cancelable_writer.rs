@@ -0,0 +1,110 @@
+use crate::{CancellationToken, Close, Error, Status, Write};
+use std::io;
+
+/// A `Write` implementation which passes data through to an inner `Write`
+/// unmodified, until its `CancellationToken` is cancelled.
+///
+/// The token is checked before each write and flush of the inner stream, so
+/// loops such as `write_all` stop promptly after the token is cancelled,
+/// failing with `Error::Canceled`, which is reported with
+/// `io::ErrorKind::Other`. The inner stream isn't abandoned, so that after
+/// cancellation it can be retrieved with `into_inner` and closed gracefully.
+pub struct CancelableWriter<Inner: Write> {
+    /// The wrapped byte stream.
+    inner: Inner,
+
+    /// The token to check before each write and flush.
+    token: CancellationToken,
+}
+
+impl<Inner: Write> CancelableWriter<Inner> {
+    /// Construct a new instance of `CancelableWriter` wrapping `inner`,
+    /// which fails once `token` is cancelled.
+    #[inline]
+    pub fn new(inner: Inner, token: CancellationToken) -> Self {
+        Self { inner, token }
+    }
+
+    /// Gets a reference to the token.
+    #[inline]
+    pub fn token(&self) -> &CancellationToken {
+        &self.token
+    }
+
+    /// Gets a reference to the underlying writer.
+    #[inline]
+    pub fn get_ref(&self) -> &Inner {
+        &self.inner
+    }
+
+    /// Gets a mutable reference to the underlying writer.
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut Inner {
+        &mut self.inner
+    }
+
+    /// Consume this `CancelableWriter` and return the underlying stream,
+    /// without flushing or closing it.
+    #[inline]
+    pub fn into_inner(self) -> Inner {
+        self.inner
+    }
+
+    /// Flush and close the underlying stream and return the underlying
+    /// stream object.
+    pub fn close_into_inner(mut self) -> io::Result<Inner> {
+        self.flush(Status::End)?;
+        Ok(self.inner)
+    }
+
+    fn check(&self) -> io::Result<()> {
+        if self.token.is_canceled() {
+            Err(Error::Canceled.into())
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl<Inner: Write> Close for CancelableWriter<Inner> {
+    type Output = Inner;
+
+    #[inline]
+    fn close_into_inner(self) -> io::Result<Inner> {
+        CancelableWriter::close_into_inner(self)
+    }
+}
+
+impl<Inner: Write> Write for CancelableWriter<Inner> {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.check()?;
+        self.inner.write(buf)
+    }
+
+    #[inline]
+    fn flush(&mut self, status: Status) -> io::Result<()> {
+        self.check()?;
+        self.inner.flush(status)
+    }
+
+    #[inline]
+    fn abandon(&mut self) {
+        self.inner.abandon()
+    }
+}
+
+#[test]
+fn test_cancelable_writer() {
+    let token = CancellationToken::new();
+    let mut writer = CancelableWriter::new(crate::StdWriter::new(Vec::<u8>::new()), token.clone());
+    writer.write_all(b"hello\n").unwrap();
+    token.cancel();
+    let err = writer.write_all(b"world\n").unwrap_err();
+    assert_eq!(Error::from_io_error(&err), Some(&Error::Canceled));
+    assert!(writer.flush(Status::Open(crate::Readiness::Lull)).is_err());
+
+    // The inner stream can still be closed.
+    let inner = writer.into_inner().close_into_inner().unwrap();
+    assert_eq!(inner, b"hello\n");
+}
@@ -0,0 +1,58 @@
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+/// A flag shared between a controlling thread and the `CancelableReader`s
+/// and `CancelableWriter`s it may want to stop, for graceful shutdown of
+/// long-running pipelines.
+///
+/// Clones share the same flag, so cancelling any of them cancels all of
+/// them. Once cancelled, a token stays cancelled.
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken {
+    canceled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    /// Construct a new, uncancelled, `CancellationToken`.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancel the streams using this token, and any clones of it.
+    #[inline]
+    pub fn cancel(&self) {
+        self.canceled.store(true, Ordering::Release);
+    }
+
+    /// Test whether this token has been cancelled.
+    #[inline]
+    pub fn is_canceled(&self) -> bool {
+        self.canceled.load(Ordering::Acquire)
+    }
+}
+
+impl From<Arc<AtomicBool>> for CancellationToken {
+    /// Use an existing flag, such as one set by a signal handler, as a
+    /// token. Storing `true` to it cancels the token.
+    #[inline]
+    fn from(canceled: Arc<AtomicBool>) -> Self {
+        Self { canceled }
+    }
+}
+
+#[test]
+fn test_cancellation_token() {
+    let token = CancellationToken::new();
+    let clone = token.clone();
+    assert!(!token.is_canceled());
+    std::thread::spawn(move || clone.cancel()).join().unwrap();
+    assert!(token.is_canceled());
+
+    let flag = Arc::new(AtomicBool::new(false));
+    let token = CancellationToken::from(Arc::clone(&flag));
+    flag.store(true, Ordering::SeqCst);
+    assert!(token.is_canceled());
+}
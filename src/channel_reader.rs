@@ -0,0 +1,156 @@
+use crate::{Read, ReadOutcome};
+use std::{
+    cmp::min,
+    io,
+    sync::mpsc::{Receiver, TryRecvError},
+};
+
+/// A `Read` implementation which reads chunks of data from a
+/// [`std::sync::mpsc::Receiver`].
+///
+/// When the channel has no more chunks waiting, the read which drains the
+/// last chunk reports a lull, and the next read waits for another chunk.
+/// Once all the senders have been dropped, the stream ends.
+///
+/// This allows producer threads to feed a reader such as `TextReader`
+/// without an OS pipe.
+pub struct ChannelReader<T: AsRef<[u8]> = Vec<u8>> {
+    /// The channel to receive chunks from.
+    receiver: Receiver<T>,
+
+    /// The current chunk, if any.
+    chunk: Option<T>,
+
+    /// The position within `chunk` of the next byte to read.
+    pos: usize,
+
+    /// All senders have been dropped.
+    ended: bool,
+}
+
+impl<T: AsRef<[u8]>> ChannelReader<T> {
+    /// Construct a new instance of `ChannelReader` which reads from
+    /// `receiver`.
+    #[inline]
+    pub fn new(receiver: Receiver<T>) -> Self {
+        Self {
+            receiver,
+            chunk: None,
+            pos: 0,
+            ended: false,
+        }
+    }
+}
+
+impl<T: AsRef<[u8]>> Read for ChannelReader<T> {
+    fn read_outcome(&mut self, buf: &mut [u8]) -> io::Result<ReadOutcome> {
+        if self.ended {
+            return Ok(ReadOutcome::end(0));
+        }
+        if buf.is_empty() {
+            return Ok(ReadOutcome::ready(0));
+        }
+
+        if self.chunk.is_none() {
+            match self.receiver.recv() {
+                Ok(chunk) => self.chunk = Some(chunk),
+                Err(_) => {
+                    self.ended = true;
+                    return Ok(ReadOutcome::end(0));
+                }
+            }
+        }
+
+        let chunk = self.chunk.as_ref().unwrap().as_ref();
+        let size = min(buf.len(), chunk.len() - self.pos);
+        buf[..size].copy_from_slice(&chunk[self.pos..self.pos + size]);
+        self.pos += size;
+        if self.pos < chunk.len() {
+            return Ok(ReadOutcome::ready(size));
+        }
+
+        // We've drained the current chunk; see if there's another waiting.
+        self.pos = 0;
+        self.chunk = None;
+        match self.receiver.try_recv() {
+            Ok(chunk) => {
+                self.chunk = Some(chunk);
+                Ok(ReadOutcome::ready(size))
+            }
+            Err(TryRecvError::Empty) => Ok(ReadOutcome::lull(size)),
+            Err(TryRecvError::Disconnected) => {
+                self.ended = true;
+                Ok(ReadOutcome::end(size))
+            }
+        }
+    }
+}
+
+impl<T: AsRef<[u8]>> io::Read for ChannelReader<T> {
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        Read::read(self, buf)
+    }
+
+    #[inline]
+    fn read_vectored(&mut self, bufs: &mut [io::IoSliceMut<'_>]) -> io::Result<usize> {
+        Read::read_vectored(self, bufs)
+    }
+
+    #[cfg(feature = "nightly")]
+    #[inline]
+    fn is_read_vectored(&self) -> bool {
+        Read::is_read_vectored(self)
+    }
+
+    #[inline]
+    fn read_to_end(&mut self, buf: &mut Vec<u8>) -> io::Result<usize> {
+        Read::read_to_end(self, buf)
+    }
+
+    #[inline]
+    fn read_to_string(&mut self, buf: &mut String) -> io::Result<usize> {
+        Read::read_to_string(self, buf)
+    }
+
+    #[inline]
+    fn read_exact(&mut self, buf: &mut [u8]) -> io::Result<()> {
+        Read::read_exact(self, buf)
+    }
+}
+
+#[test]
+fn test_channel_reader() {
+    let (sender, receiver) = std::sync::mpsc::channel();
+    let mut reader = ChannelReader::new(receiver);
+    sender.send(b"hello".to_vec()).unwrap();
+    sender.send(b" world".to_vec()).unwrap();
+
+    let mut buf = [0; 16];
+    let outcome = reader.read_outcome(&mut buf).unwrap();
+    assert_eq!(&buf[..outcome.size], b"hello");
+    assert_eq!(outcome.status, crate::Status::ready());
+    let outcome = reader.read_outcome(&mut buf).unwrap();
+    assert_eq!(&buf[..outcome.size], b" world");
+    assert_eq!(outcome.status, crate::Status::Open(crate::Readiness::Lull));
+
+    sender.send(b"!".to_vec()).unwrap();
+    drop(sender);
+    let outcome = reader.read_outcome(&mut buf).unwrap();
+    assert_eq!(&buf[..outcome.size], b"!");
+    assert!(outcome.status.is_end());
+}
+
+#[test]
+fn test_channel_reader_text() {
+    let (sender, receiver) = std::sync::mpsc::channel::<&'static [u8]>();
+    let thread = std::thread::spawn(move || {
+        sender.send(b"hello\r\n").unwrap();
+        sender.send(b"\xffworld").unwrap();
+    });
+    let mut reader = crate::Utf8Reader::new(ChannelReader::new(receiver));
+    let mut s = String::new();
+    reader.read_to_string(&mut s).unwrap();
+    thread.join().unwrap();
+    assert_eq!(s, "hello\r\n\u{fffd}world");
+}
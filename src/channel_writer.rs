@@ -0,0 +1,151 @@
+use crate::{Error, Readiness, Status, Write};
+use std::{io, mem, sync::mpsc::Sender};
+
+/// The size at which a `ChannelWriter` sends its buffer without waiting for
+/// a flush.
+const CHUNK_SIZE: usize = 8192;
+
+/// A `Write` implementation which sends chunks of data over a
+/// [`std::sync::mpsc::Sender`].
+///
+/// Written data is buffered, and sent as a chunk when the buffer fills or
+/// when flushed with a status of `Status::Open(Readiness::Lull)` or
+/// `Status::End`. Flushing with `Status::End` also drops the sender, so that
+/// a receiving `ChannelReader` sees the end of the stream once all other
+/// senders have been dropped.
+pub struct ChannelWriter {
+    /// The channel to send chunks to, or `None` if the stream has ended.
+    sender: Option<Sender<Vec<u8>>>,
+
+    /// Data which has been written but not yet sent.
+    buf: Vec<u8>,
+}
+
+impl ChannelWriter {
+    /// Construct a new instance of `ChannelWriter` which sends to `sender`.
+    #[inline]
+    pub fn new(sender: Sender<Vec<u8>>) -> Self {
+        Self {
+            sender: Some(sender),
+            buf: Vec::new(),
+        }
+    }
+
+    fn send_buffer(&mut self) -> io::Result<()> {
+        if self.buf.is_empty() {
+            return Ok(());
+        }
+        let sender = self.sender.as_ref().ok_or_else(stream_already_ended)?;
+        if sender.send(mem::take(&mut self.buf)).is_err() {
+            self.abandon();
+            return Err(io::Error::new(
+                io::ErrorKind::BrokenPipe,
+                "channel receiver has been dropped",
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl Write for ChannelWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.sender.is_none() {
+            return Err(stream_already_ended());
+        }
+        self.buf.extend_from_slice(buf);
+        if self.buf.len() >= CHUNK_SIZE {
+            self.send_buffer()?;
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self, status: Status) -> io::Result<()> {
+        if self.sender.is_none() {
+            return Err(stream_already_ended());
+        }
+        match status {
+            Status::Open(Readiness::Ready) | Status::Open(Readiness::Pending) => Ok(()),
+            Status::Open(Readiness::Lull) => self.send_buffer(),
+            Status::End => {
+                let result = self.send_buffer();
+                self.sender = None;
+                result
+            }
+        }
+    }
+
+    fn abandon(&mut self) {
+        self.buf.clear();
+        self.sender = None;
+    }
+
+    fn write_outcome(&mut self, buf: &[u8], status: Status) -> io::Result<()> {
+        if self.sender.is_none() {
+            return Err(stream_already_ended());
+        }
+        // Don't send the buffer when it fills, so that the data is sent as a
+        // single chunk along with the status.
+        self.buf.extend_from_slice(buf);
+        match status {
+            Status::Open(Readiness::Ready) | Status::Open(Readiness::Pending) => {
+                if self.buf.len() >= CHUNK_SIZE {
+                    self.send_buffer()?;
+                }
+                Ok(())
+            }
+            Status::Open(Readiness::Lull) | Status::End => self.flush(status),
+        }
+    }
+}
+
+fn stream_already_ended() -> io::Error {
+    Error::StreamEnded.into()
+}
+
+#[test]
+fn test_channel_writer() {
+    let (sender, receiver) = std::sync::mpsc::channel();
+    let mut writer = ChannelWriter::new(sender);
+    writer.write_all(b"hello").unwrap();
+    writer.write_all(b" world\n").unwrap();
+    assert!(receiver.try_recv().is_err());
+    writer.flush(Status::Open(Readiness::Lull)).unwrap();
+    assert_eq!(receiver.try_recv().unwrap(), b"hello world\n");
+    writer.write_all(b"!\n").unwrap();
+    writer.flush(Status::End).unwrap();
+    assert_eq!(receiver.recv().unwrap(), b"!\n");
+    assert!(receiver.recv().is_err());
+}
+
+#[test]
+fn test_channel_writer_to_reader() {
+    let (sender, receiver) = std::sync::mpsc::channel();
+    let thread = std::thread::spawn(move || {
+        let mut writer = crate::Utf8Writer::new(ChannelWriter::new(sender));
+        for _ in 0..100 {
+            writer.write_all_utf8("hello world\n").unwrap();
+            writer.flush(Status::Open(Readiness::Lull)).unwrap();
+        }
+        writer.close_into_inner().unwrap();
+    });
+    let mut reader = crate::ChannelReader::new(receiver);
+    let mut s = String::new();
+    crate::Read::read_to_string(&mut reader, &mut s).unwrap();
+    thread.join().unwrap();
+    assert_eq!(s, "hello world\n".repeat(100));
+}
+
+#[test]
+fn test_channel_writer_write_outcome() {
+    let (sender, receiver) = std::sync::mpsc::channel();
+    let mut writer = ChannelWriter::new(sender);
+    let message = vec![b'x'; CHUNK_SIZE * 2];
+    writer
+        .write_outcome(&message, Status::Open(Readiness::Lull))
+        .unwrap();
+    assert_eq!(receiver.try_recv().unwrap(), message);
+    assert!(receiver.try_recv().is_err());
+    writer.write_outcome(b"bye\n", Status::End).unwrap();
+    assert_eq!(receiver.recv().unwrap(), b"bye\n");
+    assert!(receiver.recv().is_err());
+}
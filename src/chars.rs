@@ -0,0 +1,91 @@
+use crate::{ReadOutcome, ReadStr};
+use std::io;
+
+/// The size of the buffer used for reading from the underlying stream.
+const CHUNK_SIZE: usize = 1024;
+
+/// An iterator over the scalar values of a [`ReadStr`], returned from
+/// [`ReadStr::chars`].
+///
+/// Scalar values whose encodings are split across reads of the underlying
+/// byte stream are reassembled by the `ReadStr` implementation, such as
+/// `Utf8Reader`, so each item is a complete `char`. Lulls are not reported.
+pub struct Chars<Inner: ReadStr> {
+    /// The wrapped text stream.
+    inner: Inner,
+
+    /// Temporary storage for reading from the underlying stream.
+    chunk: String,
+
+    /// The position within `chunk` of the next scalar value to produce.
+    pos: usize,
+
+    /// The length of the valid data in `chunk`.
+    len: usize,
+
+    /// The underlying stream has ended.
+    ended: bool,
+}
+
+impl<Inner: ReadStr> Chars<Inner> {
+    #[inline]
+    pub(crate) fn new(inner: Inner) -> Self {
+        Self {
+            inner,
+            chunk: "\0".repeat(CHUNK_SIZE),
+            pos: 0,
+            len: 0,
+            ended: false,
+        }
+    }
+
+    /// Consume this `Chars` and return the underlying stream object. Any
+    /// data which has been read from the underlying stream but not yet
+    /// produced is discarded.
+    #[inline]
+    pub fn into_inner(self) -> Inner {
+        self.inner
+    }
+}
+
+impl<Inner: ReadStr> Iterator for Chars<Inner> {
+    type Item = io::Result<char>;
+
+    fn next(&mut self) -> Option<io::Result<char>> {
+        while self.pos == self.len {
+            if self.ended {
+                return None;
+            }
+            match self.inner.read_str(&mut self.chunk) {
+                Ok(ReadOutcome { size, status }) => {
+                    self.pos = 0;
+                    self.len = size;
+                    self.ended = status.is_end();
+                }
+                Err(ref e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Some(Err(e)),
+            }
+        }
+
+        let c = self.chunk[self.pos..self.len].chars().next().unwrap();
+        self.pos += c.len_utf8();
+        Some(Ok(c))
+    }
+}
+
+#[test]
+fn test_chars() {
+    use crate::testing::{ScriptedReader, Step};
+
+    let reader = crate::Utf8Reader::new(ScriptedReader::new(vec![
+        Step::Data(b"a\xc3".to_vec()),
+        Step::Lull,
+        Step::Data(b"\xa9\xf0\x9f".to_vec()),
+        Step::Interrupted,
+        Step::Data(b"\x98\x80\xff".to_vec()),
+    ]));
+    let chars = Chars::new(reader)
+        .collect::<io::Result<Vec<char>>>()
+        .unwrap();
+    assert_eq!(chars, ['a', '\u{e9}', '\u{1f600}', '\u{fffd}']);
+}
@@ -0,0 +1,35 @@
+use crate::Write;
+use std::io;
+
+/// A trait for writers which can be closed, returning the underlying stream
+/// object, so that generic code can finalize a stack of writers and recover
+/// the objects inside it.
+///
+/// Closing a writer declares the end of the stream through all the layers
+/// beneath it, so a stack of writers only needs to be closed once, at the
+/// outermost layer.
+///
+/// This is implemented by the writer wrappers which have an inherent
+/// `close_into_inner` method, and forwards to it.
+pub trait Close: Write {
+    /// The type returned by `close_into_inner`, usually the underlying
+    /// stream object.
+    type Output;
+
+    /// Flush and close the underlying stream and return the underlying
+    /// stream object.
+    fn close_into_inner(self) -> io::Result<Self::Output>;
+}
+
+#[cfg(test)]
+fn close<W: Close>(writer: W) -> io::Result<W::Output> {
+    writer.close_into_inner()
+}
+
+#[test]
+fn test_close() {
+    let mut writer = crate::BufferedWriter::new(crate::StdWriter::new(Vec::new()));
+    writer.write_all(b"hello\n").unwrap();
+    let inner = close(writer).unwrap();
+    assert_eq!(inner.get_ref(), b"hello\n");
+}
@@ -0,0 +1,111 @@
+use crate::{Read, ReadOutcome, ReadStr, Readiness, Status, Write, NORMALIZATION_BUFFER_SIZE};
+use std::io;
+
+/// The size of the buffer used by `copy` and `copy_str`. It's at least
+/// `NORMALIZATION_BUFFER_SIZE` so that text readers can fill it.
+const COPY_BUFFER_SIZE: usize = if NORMALIZATION_BUFFER_SIZE > 8192 {
+    NORMALIZATION_BUFFER_SIZE
+} else {
+    8192
+};
+
+/// Like [`std::io::copy`], but forwards the `Status` of `reader` to
+/// `writer`: lulls are flushed through, and when `reader` ends, `writer` is
+/// ended too.
+///
+/// This returns the total number of bytes copied and the final status,
+/// which is `Status::End` once the stream is complete, or
+/// `Status::Open(Readiness::Pending)` if `reader` is non-blocking and has no
+/// data available, in which case `copy` may be called again when it does.
+pub fn copy<R: Read + ?Sized, W: Write + ?Sized>(
+    reader: &mut R,
+    writer: &mut W,
+) -> io::Result<(u64, Status)> {
+    let mut buf = [0; COPY_BUFFER_SIZE];
+    let mut total = 0;
+    loop {
+        let ReadOutcome { size, status } = match reader.read_outcome(&mut buf) {
+            Ok(outcome) => outcome,
+            Err(ref e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+        writer.write_outcome(&buf[..size], status)?;
+        total += size as u64;
+        match status {
+            Status::Open(Readiness::Ready) | Status::Open(Readiness::Lull) => (),
+            Status::Open(Readiness::Pending) | Status::End => return Ok((total, status)),
+        }
+    }
+}
+
+/// Like [`copy`], but reads from a `ReadStr` and writes with
+/// `write_all_utf8`, so that writers such as `Utf8Writer` and `TextWriter`
+/// needn't validate the data again.
+pub fn copy_str<R: ReadStr + ?Sized, W: Write + ?Sized>(
+    reader: &mut R,
+    writer: &mut W,
+) -> io::Result<(u64, Status)> {
+    let mut buf = "\0".repeat(COPY_BUFFER_SIZE);
+    let mut total = 0;
+    loop {
+        let ReadOutcome { size, status } = match reader.read_str(&mut buf) {
+            Ok(outcome) => outcome,
+            Err(ref e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+        writer.write_all_utf8(&buf[..size])?;
+        writer.flush(status)?;
+        total += size as u64;
+        match status {
+            Status::Open(Readiness::Ready) | Status::Open(Readiness::Lull) => (),
+            Status::Open(Readiness::Pending) | Status::End => return Ok((total, status)),
+        }
+    }
+}
+
+#[test]
+fn test_copy() {
+    use crate::testing::{ScriptedReader, Step};
+
+    let mut reader = ScriptedReader::new(vec![
+        Step::Data(b"hello ".to_vec()),
+        Step::Lull,
+        Step::Interrupted,
+        Step::Data(b"world\n".to_vec()),
+    ]);
+    let (mut output, mut writer) = crate::pipe();
+    assert_eq!(copy(&mut reader, &mut writer).unwrap(), (12, Status::End));
+
+    let mut s = String::new();
+    output.read_to_string(&mut s).unwrap();
+    assert_eq!(s, "hello world\n");
+}
+
+#[test]
+fn test_copy_pending() {
+    use crate::testing::{ScriptedReader, Step};
+
+    let mut reader = ScriptedReader::new(vec![
+        Step::Data(b"hello".to_vec()),
+        Step::Pending,
+        Step::Data(b"!".to_vec()),
+    ]);
+    let mut writer = crate::StdWriter::new(Vec::new());
+    assert_eq!(
+        copy(&mut reader, &mut writer).unwrap(),
+        (5, Status::Open(Readiness::Pending))
+    );
+    assert_eq!(copy(&mut reader, &mut writer).unwrap(), (1, Status::End));
+    assert_eq!(writer.get_ref(), b"hello!");
+}
+
+#[test]
+fn test_copy_str() {
+    let mut reader = crate::Utf8Reader::new(crate::SliceReader::new("héllo\n".as_bytes()));
+    let mut writer = crate::Utf8Writer::new(crate::StdWriter::new(Vec::new()));
+    assert_eq!(
+        copy_str(&mut reader, &mut writer).unwrap(),
+        (7, Status::End)
+    );
+    assert_eq!(writer.get_ref().get_ref(), "héllo\n".as_bytes());
+}
@@ -0,0 +1,133 @@
+use crate::{Read, ReadBuf, ReadOutcome};
+use std::io;
+
+/// A `Read` implementation which passes data through from an inner `Read`
+/// unmodified, keeping counts of the bytes, scalar values, and newlines
+/// read, in the manner of `wc`.
+///
+/// The scalar value count is only meaningful when the inner stream produces
+/// valid UTF-8, such as when wrapping a `Utf8Reader` or `TextReader`.
+pub struct CountingReader<Inner: Read> {
+    /// The wrapped byte stream.
+    inner: Inner,
+
+    /// The number of bytes read.
+    bytes: u64,
+
+    /// The number of scalar values read.
+    chars: u64,
+
+    /// The number of '\n' bytes read.
+    lines: u64,
+}
+
+impl<Inner: Read> CountingReader<Inner> {
+    /// Construct a new instance of `CountingReader` wrapping `inner`.
+    #[inline]
+    pub fn new(inner: Inner) -> Self {
+        Self {
+            inner,
+            bytes: 0,
+            chars: 0,
+            lines: 0,
+        }
+    }
+
+    /// Return the number of bytes read so far.
+    #[inline]
+    pub fn bytes(&self) -> u64 {
+        self.bytes
+    }
+
+    /// Return the number of Unicode scalar values read so far.
+    #[inline]
+    pub fn chars(&self) -> u64 {
+        self.chars
+    }
+
+    /// Return the number of newlines read so far.
+    #[inline]
+    pub fn lines(&self) -> u64 {
+        self.lines
+    }
+
+    fn count(&mut self, buf: &[u8]) {
+        self.bytes += buf.len() as u64;
+        for b in buf {
+            // Count every byte that isn't a UTF-8 continuation byte.
+            if (b & 0xc0) != 0x80 {
+                self.chars += 1;
+            }
+            if *b == b'\n' {
+                self.lines += 1;
+            }
+        }
+    }
+}
+
+impl<Inner: Read> Read for CountingReader<Inner> {
+    #[inline]
+    fn read_outcome(&mut self, buf: &mut [u8]) -> io::Result<ReadOutcome> {
+        let outcome = self.inner.read_outcome(buf)?;
+        self.count(&buf[..outcome.size]);
+        Ok(outcome)
+    }
+
+    #[inline]
+    fn read_buf_outcome(&mut self, buf: &mut ReadBuf<'_>) -> io::Result<ReadOutcome> {
+        let start = buf.filled().len();
+        let outcome = self.inner.read_buf_outcome(buf)?;
+        self.count(&buf.filled()[start..]);
+        Ok(outcome)
+    }
+
+    #[inline]
+    fn remaining_hint(&self) -> (u64, Option<u64>) {
+        self.inner.remaining_hint()
+    }
+}
+
+impl<Inner: Read> io::Read for CountingReader<Inner> {
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        Read::read(self, buf)
+    }
+
+    #[inline]
+    fn read_vectored(&mut self, bufs: &mut [io::IoSliceMut<'_>]) -> io::Result<usize> {
+        Read::read_vectored(self, bufs)
+    }
+
+    #[cfg(feature = "nightly")]
+    #[inline]
+    fn is_read_vectored(&self) -> bool {
+        Read::is_read_vectored(self)
+    }
+
+    #[inline]
+    fn read_to_end(&mut self, buf: &mut Vec<u8>) -> io::Result<usize> {
+        Read::read_to_end(self, buf)
+    }
+
+    #[inline]
+    fn read_to_string(&mut self, buf: &mut String) -> io::Result<usize> {
+        Read::read_to_string(self, buf)
+    }
+
+    #[inline]
+    fn read_exact(&mut self, buf: &mut [u8]) -> io::Result<()> {
+        Read::read_exact(self, buf)
+    }
+}
+
+#[test]
+fn test_counting_reader() {
+    let mut reader = CountingReader::new(crate::Utf8Reader::new(crate::SliceReader::new(
+        "hello\nwörld\n\u{1f4a9}".as_bytes(),
+    )));
+    let mut s = String::new();
+    reader.read_to_string(&mut s).unwrap();
+    assert_eq!(reader.bytes(), 17);
+    assert_eq!(reader.chars(), 13);
+    assert_eq!(reader.lines(), 2);
+}
@@ -0,0 +1,158 @@
+use crate::{Close, Readiness, Status, Write};
+use std::io;
+
+/// A `Write` implementation which passes data through to an inner `Write`
+/// unmodified, keeping counts of the bytes and newlines written and the
+/// number of lulls and ends flushed.
+pub struct CountingWriter<Inner: Write> {
+    /// The wrapped byte stream.
+    inner: Inner,
+
+    /// The counts so far.
+    counts: WriteCounts,
+}
+
+/// Statistics collected by a `CountingWriter`.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct WriteCounts {
+    /// The number of bytes written.
+    pub bytes: u64,
+
+    /// The number of '\n' bytes written.
+    pub lines: u64,
+
+    /// The number of flushes with `Status::Open(Readiness::Lull)`.
+    pub lulls: u64,
+
+    /// The number of flushes with `Status::End`.
+    pub ends: u64,
+}
+
+impl<Inner: Write> CountingWriter<Inner> {
+    /// Construct a new instance of `CountingWriter` wrapping `inner`.
+    #[inline]
+    pub fn new(inner: Inner) -> Self {
+        Self {
+            inner,
+            counts: WriteCounts::default(),
+        }
+    }
+
+    /// Return the number of bytes written so far.
+    #[inline]
+    pub fn bytes(&self) -> u64 {
+        self.counts.bytes
+    }
+
+    /// Return the number of newlines written so far.
+    #[inline]
+    pub fn lines(&self) -> u64 {
+        self.counts.lines
+    }
+
+    /// Return the number of lulls flushed so far.
+    #[inline]
+    pub fn lulls(&self) -> u64 {
+        self.counts.lulls
+    }
+
+    /// Return the number of ends flushed so far.
+    #[inline]
+    pub fn ends(&self) -> u64 {
+        self.counts.ends
+    }
+
+    /// Return all the counts collected so far.
+    #[inline]
+    pub fn counts(&self) -> WriteCounts {
+        self.counts
+    }
+
+    /// Flush and close the underlying stream and return the underlying
+    /// stream object, along with the final counts.
+    pub fn close_into_inner(mut self) -> io::Result<(Inner, WriteCounts)> {
+        self.flush(Status::End)?;
+        Ok((self.inner, self.counts))
+    }
+
+    fn count(&mut self, buf: &[u8]) {
+        self.counts.bytes += buf.len() as u64;
+        self.counts.lines += buf.iter().filter(|b| **b == b'\n').count() as u64;
+    }
+
+    fn count_status(&mut self, status: Status) {
+        match status {
+            Status::Open(Readiness::Ready) | Status::Open(Readiness::Pending) => (),
+            Status::Open(Readiness::Lull) => self.counts.lulls += 1,
+            Status::End => self.counts.ends += 1,
+        }
+    }
+}
+
+impl<Inner: Write> Close for CountingWriter<Inner> {
+    type Output = (Inner, WriteCounts);
+
+    #[inline]
+    fn close_into_inner(self) -> io::Result<(Inner, WriteCounts)> {
+        CountingWriter::close_into_inner(self)
+    }
+}
+
+impl<Inner: Write> Write for CountingWriter<Inner> {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let size = self.inner.write(buf)?;
+        self.count(&buf[..size]);
+        Ok(size)
+    }
+
+    #[inline]
+    fn flush(&mut self, status: Status) -> io::Result<()> {
+        self.inner.flush(status)?;
+        self.count_status(status);
+        Ok(())
+    }
+
+    #[inline]
+    fn abandon(&mut self) {
+        self.inner.abandon()
+    }
+
+    #[inline]
+    fn write_all_utf8(&mut self, s: &str) -> io::Result<()> {
+        self.inner.write_all_utf8(s)?;
+        self.count(s.as_bytes());
+        Ok(())
+    }
+
+    #[inline]
+    fn write_outcome(&mut self, buf: &[u8], status: Status) -> io::Result<()> {
+        self.inner.write_outcome(buf, status)?;
+        self.count(buf);
+        self.count_status(status);
+        Ok(())
+    }
+}
+
+#[test]
+fn test_counting_writer() {
+    let mut writer = CountingWriter::new(crate::StdWriter::new(Vec::<u8>::new()));
+    writer.write_all(b"hello\n").unwrap();
+    writer.flush(Status::Open(Readiness::Lull)).unwrap();
+    writer.write_all_utf8("wörld\n").unwrap();
+    assert_eq!(writer.bytes(), 13);
+    assert_eq!(writer.lines(), 2);
+    assert_eq!(writer.lulls(), 1);
+    assert_eq!(writer.ends(), 0);
+    let (inner, counts) = writer.close_into_inner().unwrap();
+    assert_eq!(inner.get_ref(), "hello\nwörld\n".as_bytes());
+    assert_eq!(
+        counts,
+        WriteCounts {
+            bytes: 13,
+            lines: 2,
+            lulls: 1,
+            ends: 1,
+        }
+    );
+}
@@ -0,0 +1,185 @@
+use crate::{Error, Read, ReadBuf, ReadOutcome, Status, Write};
+use std::{
+    cmp::min,
+    io::{self, Seek, SeekFrom},
+};
+
+/// A seekable in-memory stream, similar to [`std::io::Cursor`], which
+/// implements `Read`, `Write`, and [`std::io::Seek`].
+///
+/// Reads report `Status::End` whenever they reach the end of the data.
+/// Writes after the writer has been flushed with `Status::End` fail until
+/// the cursor is seeked; seeking repositions the stream and clears any
+/// ended state, so that it may be read or written again at the new
+/// position. Wrappers which implement `Seek`, such as `Utf8Reader` and
+/// `TextReader`, follow the same convention, and discard any data they have
+/// buffered.
+pub struct Cursor<T> {
+    /// The underlying cursor.
+    inner: io::Cursor<T>,
+
+    /// The writer has declared the end of the stream.
+    ended: bool,
+}
+
+impl<T> Cursor<T> {
+    /// Construct a new `Cursor` wrapping `inner`, starting at position 0.
+    #[inline]
+    pub fn new(inner: T) -> Self {
+        Self {
+            inner: io::Cursor::new(inner),
+            ended: false,
+        }
+    }
+
+    /// Consume this cursor, returning the underlying value.
+    #[inline]
+    pub fn into_inner(self) -> T {
+        self.inner.into_inner()
+    }
+
+    /// Gets a reference to the underlying value.
+    #[inline]
+    pub fn get_ref(&self) -> &T {
+        self.inner.get_ref()
+    }
+
+    /// Gets a mutable reference to the underlying value.
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut T {
+        self.inner.get_mut()
+    }
+
+    /// Return the current position of this cursor.
+    #[inline]
+    pub fn position(&self) -> u64 {
+        self.inner.position()
+    }
+
+    /// Set the position of this cursor. Like `seek`, this clears any ended
+    /// state.
+    #[inline]
+    pub fn set_position(&mut self, pos: u64) {
+        self.ended = false;
+        self.inner.set_position(pos)
+    }
+}
+
+impl<T: AsRef<[u8]>> Read for Cursor<T> {
+    #[inline]
+    fn read_outcome(&mut self, buf: &mut [u8]) -> io::Result<ReadOutcome> {
+        let size = io::Read::read(&mut self.inner, buf)?;
+        let len = self.inner.get_ref().as_ref().len() as u64;
+        Ok(ReadOutcome::ready_or_not(size, self.inner.position() < len))
+    }
+
+    #[inline]
+    fn read_buf_outcome(&mut self, buf: &mut ReadBuf<'_>) -> io::Result<ReadOutcome> {
+        let data = self.inner.get_ref().as_ref();
+        let len = data.len() as u64;
+        let start = min(self.inner.position(), len) as usize;
+        let size = min(buf.remaining(), data.len() - start);
+        buf.put_slice(&data[start..start + size]);
+        self.inner.set_position((start + size) as u64);
+        Ok(ReadOutcome::ready_or_not(size, self.inner.position() < len))
+    }
+
+    #[inline]
+    fn remaining_hint(&self) -> (u64, Option<u64>) {
+        let len = self.inner.get_ref().as_ref().len() as u64;
+        let remaining = len.saturating_sub(self.inner.position());
+        (remaining, Some(remaining))
+    }
+}
+
+impl<T: AsRef<[u8]>> io::Read for Cursor<T> {
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        Read::read(self, buf)
+    }
+
+    #[inline]
+    fn read_vectored(&mut self, bufs: &mut [io::IoSliceMut<'_>]) -> io::Result<usize> {
+        Read::read_vectored(self, bufs)
+    }
+
+    #[cfg(feature = "nightly")]
+    #[inline]
+    fn is_read_vectored(&self) -> bool {
+        Read::is_read_vectored(self)
+    }
+
+    #[inline]
+    fn read_to_end(&mut self, buf: &mut Vec<u8>) -> io::Result<usize> {
+        Read::read_to_end(self, buf)
+    }
+
+    #[inline]
+    fn read_to_string(&mut self, buf: &mut String) -> io::Result<usize> {
+        Read::read_to_string(self, buf)
+    }
+
+    #[inline]
+    fn read_exact(&mut self, buf: &mut [u8]) -> io::Result<()> {
+        Read::read_exact(self, buf)
+    }
+}
+
+impl<T> Write for Cursor<T>
+where
+    io::Cursor<T>: io::Write,
+{
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.ended {
+            return Err(stream_already_ended());
+        }
+        io::Write::write(&mut self.inner, buf)
+    }
+
+    #[inline]
+    fn flush(&mut self, status: Status) -> io::Result<()> {
+        if self.ended {
+            return Err(stream_already_ended());
+        }
+        if status.is_end() {
+            self.ended = true;
+        }
+        Ok(())
+    }
+
+    #[inline]
+    fn abandon(&mut self) {
+        self.ended = true;
+    }
+}
+
+impl<T: AsRef<[u8]>> Seek for Cursor<T> {
+    #[inline]
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let pos = self.inner.seek(pos)?;
+        self.ended = false;
+        Ok(pos)
+    }
+}
+
+fn stream_already_ended() -> io::Error {
+    Error::StreamEnded.into()
+}
+
+#[test]
+fn test_cursor() {
+    let mut cursor = Cursor::new(Vec::new());
+    cursor.write_all(b"hello world").unwrap();
+    cursor.flush(Status::End).unwrap();
+    assert!(cursor.write_all(b"!").is_err());
+
+    cursor.seek(SeekFrom::Start(6)).unwrap();
+    let mut s = String::new();
+    Read::read_to_string(&mut cursor, &mut s).unwrap();
+    assert_eq!(s, "world");
+
+    cursor.seek(SeekFrom::End(0)).unwrap();
+    cursor.write_all(b"!").unwrap();
+    assert_eq!(cursor.get_ref(), b"hello world!");
+}
@@ -0,0 +1,184 @@
+use std::{error, fmt, io};
+
+/// The specific conditions which streams in this crate report as errors.
+///
+/// These are returned wrapped in an `io::Error`; use
+/// [`Error::from_io_error`] to recover them, so that they can be matched on.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Error {
+    /// Invalid UTF-8 was encountered. The offset of the invalid sequence in
+    /// the input stream is included when it's known.
+    InvalidUtf8 {
+        /// The byte offset of the invalid sequence.
+        offset: Option<u64>,
+    },
+
+    /// More invalid UTF-8 sequences were encountered than the configured
+    /// maximum number of replacements.
+    TooManyReplacements {
+        /// The byte offset of the invalid sequence which exceeded the limit.
+        offset: u64,
+    },
+
+    /// A scalar value which isn't permitted in a text stream, such as a
+    /// control code or U+FEFF (BOM), was written to a text stream.
+    InvalidScalarValue,
+
+    /// A control code was encountered with a policy which rejects it, such
+    /// as `TabPolicy::Reject`, `NulPolicy::Reject`, or
+    /// `FormFeedPolicy::Reject`.
+    ControlCode {
+        /// The control code.
+        scalar: char,
+    },
+
+    /// A U+FEFF (BOM) was read after the beginning of a stream, with
+    /// `BomPolicy::RejectInterior`.
+    InteriorBom,
+
+    /// A scalar value in the configured `ForbiddenCharacters` was
+    /// encountered.
+    ForbiddenCharacter {
+        /// The forbidden scalar value.
+        scalar: char,
+    },
+
+    /// A word exceeded the configured UTS #39 restriction level.
+    RestrictionLevelExceeded,
+
+    /// A bidirectional control was encountered with `BidiPolicy::Reject`,
+    /// or bidirectional controls were unbalanced with
+    /// `BidiPolicy::RequireBalanced`.
+    BidiControl,
+
+    /// An invisible formatting character was encountered with
+    /// `InvisiblePolicy::Reject`.
+    InvisibleCharacter {
+        /// The invisible scalar value.
+        scalar: char,
+    },
+
+    /// A U+0085 (NEL), U+2028 (LINE SEPARATOR), or U+2029 (PARAGRAPH
+    /// SEPARATOR) was encountered with `LineTerminatorPolicy::Reject`.
+    LineTerminator {
+        /// The line terminator scalar value.
+        scalar: char,
+    },
+
+    /// Data written or read at the beginning of a stream or after a lull
+    /// began with a Unicode Normalization Form non-starter.
+    NonStarter,
+
+    /// A text stream was ended without a trailing newline.
+    MissingNewline,
+
+    /// A text stream had a lull which wasn't preceded by a newline.
+    LullWithoutNewline,
+
+    /// A stream was written to or flushed after it was ended.
+    StreamEnded,
+
+    /// A writer's output limit was exceeded.
+    OutputLimitExceeded,
+
+    /// A stream was used after its `CancellationToken` was cancelled.
+    Canceled,
+}
+
+impl Error {
+    /// If `err` wraps an `Error`, return it.
+    #[inline]
+    pub fn from_io_error(err: &io::Error) -> Option<&Self> {
+        err.get_ref().and_then(|inner| inner.downcast_ref())
+    }
+
+    /// Return the `io::ErrorKind` this error is reported with.
+    pub fn kind(&self) -> io::ErrorKind {
+        match self {
+            Self::InvalidUtf8 { .. } | Self::TooManyReplacements { .. } => {
+                io::ErrorKind::InvalidData
+            }
+            _ => io::ErrorKind::Other,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidUtf8 { offset: None } => write!(f, "invalid UTF-8"),
+            Self::InvalidUtf8 {
+                offset: Some(offset),
+            } => write!(f, "invalid UTF-8 at byte offset {}", offset),
+            Self::TooManyReplacements { offset } => write!(
+                f,
+                "too many invalid UTF-8 sequences, at byte offset {}",
+                offset
+            ),
+            Self::InvalidScalarValue => {
+                write!(f, "invalid Unicode scalar value written to text stream")
+            }
+            Self::ControlCode { scalar } => write!(
+                f,
+                "disallowed control code U+{:04X} in text stream",
+                u32::from(*scalar)
+            ),
+            Self::InteriorBom => write!(f, "U+FEFF (BOM) after the beginning of text stream"),
+            Self::ForbiddenCharacter { scalar } => write!(
+                f,
+                "forbidden character U+{:04X} in text stream",
+                u32::from(*scalar)
+            ),
+            Self::RestrictionLevelExceeded => {
+                write!(f, "text exceeds the configured restriction level")
+            }
+            Self::BidiControl => write!(
+                f,
+                "disallowed or unbalanced bidirectional control in text stream"
+            ),
+            Self::InvisibleCharacter { scalar } => write!(
+                f,
+                "invisible character U+{:04X} in text stream",
+                u32::from(*scalar)
+            ),
+            Self::LineTerminator { scalar } => write!(
+                f,
+                "line terminator U+{:04X} in text stream",
+                u32::from(*scalar)
+            ),
+            Self::NonStarter => write!(
+                f,
+                "text data must begin with a Unicode Normalization Form starter"
+            ),
+            Self::MissingNewline => write!(f, "output text stream must end with newline"),
+            Self::LullWithoutNewline => {
+                write!(f, "output text stream lull must be preceded by newline")
+            }
+            Self::StreamEnded => write!(f, "stream has already ended"),
+            Self::OutputLimitExceeded => write!(f, "output limit exceeded"),
+            Self::Canceled => write!(f, "operation was canceled"),
+        }
+    }
+}
+
+impl error::Error for Error {}
+
+impl From<Error> for io::Error {
+    #[inline]
+    fn from(err: Error) -> Self {
+        io::Error::new(err.kind(), err)
+    }
+}
+
+#[test]
+fn test_from_io_error() {
+    let err = io::Error::from(Error::StreamEnded);
+    assert_eq!(err.kind(), io::ErrorKind::Other);
+    assert_eq!(err.to_string(), "stream has already ended");
+    assert_eq!(Error::from_io_error(&err), Some(&Error::StreamEnded));
+    assert_eq!(
+        Error::from_io_error(&io::Error::other("something else")),
+        None
+    );
+}
@@ -0,0 +1,128 @@
+use crate::{escape_state::EscapeState, unicode::REPL, Read, ReadOutcome, Status};
+use std::io;
+
+/// A `Read` implementation which removes terminal escape sequences from a
+/// byte stream read from an inner `Read`.
+///
+/// This recognizes the same escape sequences that `TextReader` strips, but
+/// performs no other processing: control codes, invalid UTF-8, and
+/// unnormalized text are passed through unmodified. This is useful for
+/// de-fanging captured terminal output when the rest of `TextReader`'s
+/// work isn't wanted.
+///
+/// As in `TextReader`, a lull or the end of the stream terminates any
+/// escape sequence in progress.
+pub struct EscapeFilterReader<Inner: Read> {
+    /// The wrapped byte stream.
+    inner: Inner,
+
+    /// Escape-sequence state machine.
+    escape: EscapeState,
+}
+
+impl<Inner: Read> EscapeFilterReader<Inner> {
+    /// Construct a new instance of `EscapeFilterReader` wrapping `inner`.
+    #[inline]
+    pub fn new(inner: Inner) -> Self {
+        Self {
+            inner,
+            escape: EscapeState::Ground,
+        }
+    }
+}
+
+impl<Inner: Read> Read for EscapeFilterReader<Inner> {
+    fn read_outcome(&mut self, buf: &mut [u8]) -> io::Result<ReadOutcome> {
+        loop {
+            let outcome = self.inner.read_outcome(buf)?;
+
+            // Filter in place. Non-ASCII bytes never continue an escape
+            // sequence, except within an OSC, so treat them all as a
+            // non-control non-ASCII character.
+            let mut size = 0;
+            for i in 0..outcome.size {
+                let b = buf[i];
+                let c = if b.is_ascii() { char::from(b) } else { REPL };
+                if !self.escape.step(c) {
+                    buf[size] = b;
+                    size += 1;
+                }
+            }
+
+            if outcome.status.is_boundary() {
+                self.escape = EscapeState::Ground;
+            }
+            if size != 0 || outcome.size == 0 || outcome.status != Status::ready() {
+                return Ok(ReadOutcome {
+                    size,
+                    status: outcome.status,
+                });
+            }
+        }
+    }
+}
+
+impl<Inner: Read> io::Read for EscapeFilterReader<Inner> {
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        Read::read(self, buf)
+    }
+
+    #[inline]
+    fn read_vectored(&mut self, bufs: &mut [io::IoSliceMut<'_>]) -> io::Result<usize> {
+        Read::read_vectored(self, bufs)
+    }
+
+    #[cfg(feature = "nightly")]
+    #[inline]
+    fn is_read_vectored(&self) -> bool {
+        Read::is_read_vectored(self)
+    }
+
+    #[inline]
+    fn read_to_end(&mut self, buf: &mut Vec<u8>) -> io::Result<usize> {
+        Read::read_to_end(self, buf)
+    }
+
+    #[inline]
+    fn read_to_string(&mut self, buf: &mut String) -> io::Result<usize> {
+        Read::read_to_string(self, buf)
+    }
+
+    #[inline]
+    fn read_exact(&mut self, buf: &mut [u8]) -> io::Result<()> {
+        Read::read_exact(self, buf)
+    }
+}
+
+#[cfg(test)]
+fn filter(bytes: &[u8]) -> Vec<u8> {
+    let mut reader = EscapeFilterReader::new(crate::SliceReader::new(bytes));
+    let mut v = Vec::new();
+    reader.read_to_end(&mut v).unwrap();
+    v
+}
+
+#[test]
+fn test_escape_filter_reader() {
+    assert_eq!(filter(b""), b"");
+    assert_eq!(filter(b"hello\r\n\xff\x00"), b"hello\r\n\xff\x00");
+    assert_eq!(filter(b"\x1b@hello\x1b@world\x1b@"), b"helloworld");
+    assert_eq!(filter(b"\x1b[1;31mred\x1b[0m"), b"red");
+    assert_eq!(filter(b"\x1b]0;title\x07hello"), b"hello");
+    assert_eq!(filter(b"\x1b]0;t\xc3\xaftle\x1b\\hello"), b"hello");
+    assert_eq!(filter(b"\x1b[[Ahello"), b"hello");
+    assert_eq!(filter(b"\x1b\xc3\xa9"), b"\xc3\xa9");
+}
+
+#[test]
+fn test_escape_filter_reader_split() {
+    let (reader, mut writer) = crate::pipe();
+    let mut reader = EscapeFilterReader::new(reader);
+    crate::Write::write_all(&mut writer, b"hello\x1b[1").unwrap();
+    crate::Write::write_all(&mut writer, b";31mworld").unwrap();
+    crate::Write::flush(&mut writer, Status::End).unwrap();
+    let mut s = String::new();
+    reader.read_to_string(&mut s).unwrap();
+    assert_eq!(s, "helloworld");
+}
@@ -0,0 +1,70 @@
+//! Defines `EscapeState`.
+
+use crate::unicode::{DEL, ESC};
+
+/// A state machine which recognizes terminal escape sequences, shared by
+/// `TextReader` and `EscapeFilterReader`.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) enum EscapeState {
+    // Not within an escape sequence.
+    Ground,
+
+    // After a '\x1b'.
+    Esc,
+
+    // Immediately after a "\x1b[".
+    CsiStart,
+
+    // Within a sequence started by "\x1b[".
+    Csi,
+
+    // Within a sequence started by "\x1b]".
+    Osc,
+
+    // After a "\x1b[[".
+    Linux,
+}
+
+impl EscapeState {
+    /// Advance the state machine with `c`. Returns `true` if `c` is part of
+    /// an escape sequence and should be discarded, or `false` if it should
+    /// be processed as regular content, in which case the state is
+    /// `Ground`.
+    pub(crate) fn step(&mut self, c: char) -> bool {
+        match (*self, c) {
+            (Self::Ground, ESC) => *self = Self::Esc,
+            (Self::Ground, _) => return false,
+
+            (Self::Esc, '[') => *self = Self::CsiStart,
+            (Self::Esc, ']') => *self = Self::Osc,
+            (Self::Esc, c) if ('@'..='~').contains(&c) => *self = Self::Ground,
+            (Self::Esc, _) => {
+                *self = Self::Ground;
+                return false;
+            }
+
+            (Self::CsiStart, '[') => *self = Self::Linux,
+            (Self::CsiStart, c) | (Self::Csi, c) if (' '..='?').contains(&c) => *self = Self::Csi,
+            (Self::CsiStart, c) | (Self::Csi, c) if ('@'..='~').contains(&c) => {
+                *self = Self::Ground
+            }
+            (Self::CsiStart, _) | (Self::Csi, _) => {
+                *self = Self::Ground;
+                return false;
+            }
+
+            // An ESC within an OSC starts an ST ("\x1b\\") terminator, which
+            // completes as a two-byte escape sequence.
+            (Self::Osc, ESC) => *self = Self::Esc,
+            (Self::Osc, c) if !c.is_control() || c == '\n' || c == '\t' => (),
+            (Self::Osc, _) => *self = Self::Ground,
+
+            (Self::Linux, c) if ('\0'..=DEL).contains(&c) => *self = Self::Ground,
+            (Self::Linux, _) => {
+                *self = Self::Ground;
+                return false;
+            }
+        }
+        true
+    }
+}
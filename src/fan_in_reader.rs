@@ -0,0 +1,214 @@
+use crate::{Read, ReadOutcome, Readiness, Status};
+use std::{
+    cmp::min,
+    io,
+    sync::mpsc::{sync_channel, Receiver},
+    thread,
+};
+
+/// The size of the chunks read from each source.
+const CHUNK_SIZE: usize = 8192;
+
+/// The number of chunks each source may read ahead of the consumer.
+const CHUNKS_IN_FLIGHT: usize = 4;
+
+/// A chunk of data read from one of the sources.
+type Message = (usize, io::Result<(Vec<u8>, Status)>);
+
+/// A `Read` implementation which merges multiple `Read` sources, such as a
+/// child process' stdout and stderr, yielding data from whichever source
+/// produces it first.
+///
+/// Each source is read on its own thread. Chunks from different sources are
+/// interleaved but never split, apart from by the consumer's own buffer
+/// size. The stream ends once all of the sources have ended. If a source
+/// fails with an error, the error is reported by the next read and that
+/// source is treated as having ended.
+pub struct FanInReader<F: FnMut(usize, &[u8]) = fn(usize, &[u8])> {
+    /// The channel to receive chunks from the source threads.
+    receiver: Receiver<Message>,
+
+    /// A callback to call with the index of the source of each chunk.
+    callback: F,
+
+    /// The current chunk and its status.
+    chunk: Option<(Vec<u8>, Status)>,
+
+    /// The position within `chunk` of the next byte to read.
+    pos: usize,
+
+    /// The number of sources which haven't ended yet.
+    remaining: usize,
+}
+
+impl FanInReader {
+    /// Construct a new instance of `FanInReader` which reads from `sources`.
+    #[inline]
+    pub fn new(sources: Vec<Box<dyn Read + Send>>) -> Self {
+        Self::with_tags(sources, |_, _| ())
+    }
+}
+
+impl<F: FnMut(usize, &[u8])> FanInReader<F> {
+    /// Like `new`, but calls `callback` with the index within `sources` of
+    /// the source of each chunk, and the chunk's data, before the chunk is
+    /// read.
+    pub fn with_tags(sources: Vec<Box<dyn Read + Send>>, callback: F) -> Self {
+        let (sender, receiver) = sync_channel(CHUNKS_IN_FLIGHT * sources.len());
+        let remaining = sources.len();
+        for (index, mut source) in sources.into_iter().enumerate() {
+            let sender = sender.clone();
+            thread::spawn(move || loop {
+                let mut buf = vec![0; CHUNK_SIZE];
+                let message = match source.read_outcome(&mut buf) {
+                    Ok(outcome) => {
+                        buf.truncate(outcome.size);
+                        Ok((buf, outcome.status))
+                    }
+                    Err(ref e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                    Err(e) => Err(e),
+                };
+                let last = match &message {
+                    Ok((_, status)) => status.is_end(),
+                    Err(_) => true,
+                };
+                if sender.send((index, message)).is_err() || last {
+                    return;
+                }
+            });
+        }
+        Self {
+            receiver,
+            callback,
+            chunk: None,
+            pos: 0,
+            remaining,
+        }
+    }
+}
+
+impl<F: FnMut(usize, &[u8])> Read for FanInReader<F> {
+    fn read_outcome(&mut self, buf: &mut [u8]) -> io::Result<ReadOutcome> {
+        if self.chunk.is_none() {
+            if self.remaining == 0 {
+                return Ok(ReadOutcome::end(0));
+            }
+            if buf.is_empty() {
+                return Ok(ReadOutcome::ready(0));
+            }
+            let (index, message) = match self.receiver.recv() {
+                Ok(message) => message,
+                Err(_) => {
+                    // All of the source threads have exited.
+                    self.remaining = 0;
+                    return Ok(ReadOutcome::end(0));
+                }
+            };
+            match message {
+                Ok((data, status)) => {
+                    if status.is_end() {
+                        self.remaining -= 1;
+                    }
+                    (self.callback)(index, &data);
+                    self.chunk = Some((data, status));
+                }
+                Err(e) => {
+                    self.remaining -= 1;
+                    return Err(e);
+                }
+            }
+        }
+
+        let (data, status) = self.chunk.as_ref().unwrap();
+        let size = min(buf.len(), data.len() - self.pos);
+        buf[..size].copy_from_slice(&data[self.pos..self.pos + size]);
+        self.pos += size;
+        if self.pos < data.len() {
+            return Ok(ReadOutcome::ready(size));
+        }
+
+        // We've drained the current chunk.
+        let status = *status;
+        self.pos = 0;
+        self.chunk = None;
+        if self.remaining == 0 {
+            Ok(ReadOutcome::end(size))
+        } else if status == Status::Open(Readiness::Ready) {
+            Ok(ReadOutcome::ready(size))
+        } else {
+            // A source which has ended is a lull for the merged stream.
+            Ok(ReadOutcome::lull(size))
+        }
+    }
+}
+
+impl<F: FnMut(usize, &[u8])> io::Read for FanInReader<F> {
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        Read::read(self, buf)
+    }
+
+    #[inline]
+    fn read_vectored(&mut self, bufs: &mut [io::IoSliceMut<'_>]) -> io::Result<usize> {
+        Read::read_vectored(self, bufs)
+    }
+
+    #[cfg(feature = "nightly")]
+    #[inline]
+    fn is_read_vectored(&self) -> bool {
+        Read::is_read_vectored(self)
+    }
+
+    #[inline]
+    fn read_to_end(&mut self, buf: &mut Vec<u8>) -> io::Result<usize> {
+        Read::read_to_end(self, buf)
+    }
+
+    #[inline]
+    fn read_to_string(&mut self, buf: &mut String) -> io::Result<usize> {
+        Read::read_to_string(self, buf)
+    }
+
+    #[inline]
+    fn read_exact(&mut self, buf: &mut [u8]) -> io::Result<()> {
+        Read::read_exact(self, buf)
+    }
+}
+
+#[test]
+fn test_fan_in_reader() {
+    let mut reader = FanInReader::new(vec![
+        Box::new(crate::SliceReader::new(b"hello\n")),
+        Box::new(crate::SliceReader::new(b"world\n")),
+    ]);
+    let mut s = String::new();
+    reader.read_to_string(&mut s).unwrap();
+    let mut lines = s.lines().collect::<Vec<_>>();
+    lines.sort_unstable();
+    assert_eq!(lines, ["hello", "world"]);
+}
+
+#[test]
+fn test_fan_in_reader_tags() {
+    let mut tagged = [Vec::new(), Vec::new()];
+    let mut reader = FanInReader::with_tags(
+        vec![
+            Box::new(crate::SliceReader::new(b"hello\n")),
+            Box::new(crate::SliceReader::new(b"world\n")),
+        ],
+        |index, data| tagged[index].extend_from_slice(data),
+    );
+    let mut s = String::new();
+    reader.read_to_string(&mut s).unwrap();
+    drop(reader);
+    assert_eq!(tagged[0], b"hello\n");
+    assert_eq!(tagged[1], b"world\n");
+}
+
+#[test]
+fn test_fan_in_reader_empty() {
+    let mut reader = FanInReader::new(Vec::new());
+    let mut s = String::new();
+    reader.read_to_string(&mut s).unwrap();
+    assert!(s.is_empty());
+}
@@ -0,0 +1,107 @@
+//! Defines `ForbiddenCharacters` and `ForbiddenCharacterPolicy`.
+
+use std::collections::BTreeSet;
+
+/// A set of scalar values which `TextReader` and `TextWriter` don't permit
+/// in text.
+///
+/// The default set is the [Forbidden Characters] of UAX #15, whose
+/// decompositions were changed by corrigenda, so that they normalize
+/// differently in different versions of Unicode. Applications can add
+/// their own.
+///
+/// Scalar values are checked as they appear in the input, before
+/// normalization.
+///
+/// [Forbidden Characters]: https://unicode.org/reports/tr15/#Forbidding_Characters
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ForbiddenCharacters {
+    set: BTreeSet<char>,
+}
+
+impl ForbiddenCharacters {
+    /// Construct a set containing the UAX #15 Forbidden Characters.
+    pub fn tr15() -> Self {
+        Self {
+            set: [
+                // http://www.unicode.org/versions/corrigendum3.html
+                '\u{f951}',
+                // http://www.unicode.org/versions/corrigendum4.html
+                '\u{2f868}',
+                '\u{2f874}',
+                '\u{2f91f}',
+                '\u{2f95f}',
+                '\u{2f9bf}',
+            ]
+            .iter()
+            .copied()
+            .collect(),
+        }
+    }
+
+    /// Construct an empty set.
+    #[inline]
+    pub fn empty() -> Self {
+        Self {
+            set: BTreeSet::new(),
+        }
+    }
+
+    /// Add `c` to the set. Returns true if it wasn't already present.
+    #[inline]
+    pub fn insert(&mut self, c: char) -> bool {
+        self.set.insert(c)
+    }
+
+    /// Remove `c` from the set. Returns true if it was present.
+    #[inline]
+    pub fn remove(&mut self, c: char) -> bool {
+        self.set.remove(&c)
+    }
+
+    /// Test whether `c` is in the set.
+    #[inline]
+    pub fn contains(&self, c: char) -> bool {
+        !self.set.is_empty() && self.set.contains(&c)
+    }
+
+    /// Test whether the set contains any printable ASCII, ' ' through '~'.
+    #[inline]
+    pub(crate) fn contains_printable_ascii(&self) -> bool {
+        self.set.range(' '..='~').next().is_some()
+    }
+}
+
+impl Default for ForbiddenCharacters {
+    #[inline]
+    fn default() -> Self {
+        Self::tr15()
+    }
+}
+
+/// What a `TextReader` or `TextWriter` does when it encounters a scalar
+/// value in its `ForbiddenCharacters`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ForbiddenCharacterPolicy {
+    /// Fail with [`Error::ForbiddenCharacter`]. This is the default for
+    /// `TextWriter`.
+    ///
+    /// [`Error::ForbiddenCharacter`]: crate::Error::ForbiddenCharacter
+    Reject,
+
+    /// Replace the scalar value with U+FFFD, or the configured replacement.
+    /// This is the default for `TextReader`.
+    Replace,
+}
+
+#[test]
+fn test_forbidden_characters() {
+    let mut set = ForbiddenCharacters::default();
+    assert!(set.contains('\u{f951}'));
+    assert!(!set.contains('a'));
+    assert!(set.insert('a'));
+    assert!(set.contains('a'));
+    assert!(set.remove('\u{f951}'));
+    assert!(!set.contains('\u{f951}'));
+    assert!(!ForbiddenCharacters::empty().contains('\u{f951}'));
+}
@@ -0,0 +1,22 @@
+//! Defines `FormFeedPolicy`.
+
+/// What a `TextReader` or `TextWriter` does with U+000C (FF).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FormFeedPolicy {
+    /// Convert FF to ' '. This is the default for `TextReader`.
+    Space,
+
+    /// Pass FF through, as a page separator, as used by groff and man
+    /// pages and in printer workflows.
+    Preserve,
+
+    /// Replace FF with U+FFFD, or the configured replacement, like other
+    /// control codes.
+    Replace,
+
+    /// Fail with [`Error::ControlCode`]. This is the default for
+    /// `TextWriter`.
+    ///
+    /// [`Error::ControlCode`]: crate::Error::ControlCode
+    Reject,
+}
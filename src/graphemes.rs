@@ -0,0 +1,119 @@
+use crate::{ReadOutcome, ReadStr};
+use std::io;
+use unicode_segmentation::UnicodeSegmentation;
+
+/// The size of the buffer used for reading from the underlying stream.
+const CHUNK_SIZE: usize = 1024;
+
+/// An iterator over the extended grapheme clusters of a [`ReadStr`],
+/// returned from [`ReadStr::graphemes`].
+///
+/// Clusters are never split, even when the underlying reads split them;
+/// the last cluster in each read is held back until more data arrives, as
+/// it may be extended. At a lull or the end of the stream, all clusters
+/// read so far are produced.
+pub struct Graphemes<Inner: ReadStr> {
+    /// The wrapped text stream.
+    inner: Inner,
+
+    /// Text which has been read but not yet produced.
+    buffer: String,
+
+    /// The position within `buffer` of the next cluster to produce.
+    pos: usize,
+
+    /// The end of the prefix of `buffer` which contains complete clusters.
+    boundary: usize,
+
+    /// Temporary storage for reading from the underlying stream.
+    chunk: String,
+
+    /// The underlying stream has ended.
+    ended: bool,
+}
+
+impl<Inner: ReadStr> Graphemes<Inner> {
+    #[inline]
+    pub(crate) fn new(inner: Inner) -> Self {
+        Self {
+            inner,
+            buffer: String::new(),
+            pos: 0,
+            boundary: 0,
+            chunk: "\0".repeat(CHUNK_SIZE),
+            ended: false,
+        }
+    }
+
+    /// Consume this `Graphemes` and return the underlying stream object. Any
+    /// data which has been read from the underlying stream but not yet
+    /// produced is discarded.
+    #[inline]
+    pub fn into_inner(self) -> Inner {
+        self.inner
+    }
+}
+
+impl<Inner: ReadStr> Iterator for Graphemes<Inner> {
+    type Item = io::Result<String>;
+
+    fn next(&mut self) -> Option<io::Result<String>> {
+        while self.pos == self.boundary {
+            if self.ended {
+                return None;
+            }
+            match self.inner.read_str(&mut self.chunk) {
+                Ok(ReadOutcome { size, status }) => {
+                    self.buffer.drain(..self.pos);
+                    self.pos = 0;
+                    self.buffer.push_str(&self.chunk[..size]);
+                    self.boundary = if !status.is_boundary() {
+                        self.buffer
+                            .grapheme_indices(true)
+                            .next_back()
+                            .map_or(0, |(index, _)| index)
+                    } else {
+                        self.buffer.len()
+                    };
+                    self.ended = status.is_end();
+                }
+                Err(ref e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Some(Err(e)),
+            }
+        }
+
+        let grapheme = self.buffer[self.pos..self.boundary]
+            .graphemes(true)
+            .next()
+            .unwrap();
+        self.pos += grapheme.len();
+        Some(Ok(grapheme.to_owned()))
+    }
+}
+
+#[test]
+fn test_graphemes() {
+    use crate::testing::{ScriptedReader, Step};
+
+    // A regional-indicator flag, an "e" with a combining accent, and a
+    // ZWJ sequence, each split across reads.
+    let reader = crate::Utf8Reader::new(ScriptedReader::new(vec![
+        Step::Data("a\u{1f1fa}".as_bytes().to_vec()),
+        Step::Data("\u{1f1f8}e".as_bytes().to_vec()),
+        Step::Data("\u{301}\u{1f469}\u{200d}".as_bytes().to_vec()),
+        Step::Data("\u{1f4bb}!".as_bytes().to_vec()),
+    ]));
+    let graphemes = Graphemes::new(reader)
+        .collect::<io::Result<Vec<String>>>()
+        .unwrap();
+    assert_eq!(
+        graphemes,
+        [
+            "a",
+            "\u{1f1fa}\u{1f1f8}",
+            "e\u{301}",
+            "\u{1f469}\u{200d}\u{1f4bb}",
+            "!"
+        ]
+    );
+}
@@ -0,0 +1,316 @@
+use crate::{Read, ReadOutcome, Readiness, Status};
+use flate2::{Crc, Decompress, FlushDecompress};
+use std::io;
+
+/// The size of the buffer used to hold compressed input.
+const INPUT_SIZE: usize = 8192;
+
+const FHCRC: u8 = 0x02;
+const FEXTRA: u8 = 0x04;
+const FNAME: u8 = 0x08;
+const FCOMMENT: u8 = 0x10;
+
+/// Where we are within the gzip member.
+#[derive(Copy, Clone, Eq, PartialEq)]
+enum State {
+    Header,
+    Body,
+    Trailer,
+    Done,
+}
+
+/// A `Read` implementation which decompresses a gzip stream read from an
+/// inner `Read`.
+///
+/// The end of the compressed member is reported as `Status::End`. Lulls in
+/// the compressed input are reported as lulls in the decompressed output
+/// when no more output can be produced until more input arrives. Any data
+/// following the first member is ignored.
+///
+/// This allows compressed text logs to be piped straight into a
+/// `TextReader`.
+pub struct GzipReader<Inner: Read> {
+    /// The wrapped byte stream.
+    inner: Inner,
+
+    /// The deflate decompression state.
+    decompress: Decompress,
+
+    /// A checksum of the decompressed data.
+    crc: Crc,
+
+    /// Compressed data which has been read but not yet consumed.
+    input: Vec<u8>,
+
+    /// The position within `input` of the next byte to consume.
+    pos: usize,
+
+    /// The status reported by the last read from `inner`.
+    inner_status: Status,
+
+    /// Where we are within the gzip member.
+    state: State,
+}
+
+impl<Inner: Read> GzipReader<Inner> {
+    /// Construct a new instance of `GzipReader` wrapping `inner`.
+    #[inline]
+    pub fn new(inner: Inner) -> Self {
+        Self {
+            inner,
+            decompress: Decompress::new(false),
+            crc: Crc::new(),
+            input: Vec::new(),
+            pos: 0,
+            inner_status: Status::ready(),
+            state: State::Header,
+        }
+    }
+
+    /// Read more compressed data from `inner`, appending it to `input`.
+    fn fill_input(&mut self) -> io::Result<()> {
+        if self.pos != 0 {
+            self.input.drain(..self.pos);
+            self.pos = 0;
+        }
+        let len = self.input.len();
+        self.input.resize(len + INPUT_SIZE, 0);
+        let outcome = self.inner.read_outcome(&mut self.input[len..]);
+        let size = outcome.as_ref().map_or(0, |outcome| outcome.size);
+        self.input.truncate(len + size);
+        self.inner_status = outcome?.status;
+        Ok(())
+    }
+
+    fn read_header(&mut self) -> io::Result<bool> {
+        let input = &self.input[self.pos..];
+        match parse_header(input)? {
+            Some(len) => {
+                self.pos += len;
+                self.state = State::Body;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    fn read_trailer(&mut self) -> io::Result<bool> {
+        let input = &self.input[self.pos..];
+        if input.len() < 8 {
+            return Ok(false);
+        }
+        let crc = u32::from_le_bytes([input[0], input[1], input[2], input[3]]);
+        let isize = u32::from_le_bytes([input[4], input[5], input[6], input[7]]);
+        if crc != self.crc.sum() || isize != self.crc.amount() {
+            return Err(invalid_data("gzip checksum mismatch"));
+        }
+        self.pos += 8;
+        self.state = State::Done;
+        Ok(true)
+    }
+}
+
+impl<Inner: Read> Read for GzipReader<Inner> {
+    fn read_outcome(&mut self, buf: &mut [u8]) -> io::Result<ReadOutcome> {
+        loop {
+            let progress = match self.state {
+                State::Header => self.read_header()?,
+                State::Body => {
+                    let before_in = self.decompress.total_in();
+                    let before_out = self.decompress.total_out();
+                    let status = self
+                        .decompress
+                        .decompress(&self.input[self.pos..], buf, FlushDecompress::None)
+                        .map_err(|e| invalid_data(&e.to_string()))?;
+                    self.pos += (self.decompress.total_in() - before_in) as usize;
+                    let size = (self.decompress.total_out() - before_out) as usize;
+                    self.crc.update(&buf[..size]);
+
+                    if status == flate2::Status::StreamEnd {
+                        self.state = State::Trailer;
+                        if self.read_trailer()? {
+                            return Ok(ReadOutcome::end(size));
+                        }
+                    }
+                    if size != 0 {
+                        // If all the available input has been consumed and the
+                        // inner stream is in a lull, pass the lull along.
+                        if self.pos == self.input.len() && self.inner_status == lull() {
+                            self.inner_status = Status::ready();
+                            return Ok(ReadOutcome::lull(size));
+                        }
+                        return Ok(ReadOutcome::ready(size));
+                    }
+                    if buf.is_empty() {
+                        return Ok(ReadOutcome::ready(0));
+                    }
+                    status == flate2::Status::StreamEnd
+                }
+                State::Trailer => self.read_trailer()?,
+                State::Done => return Ok(ReadOutcome::end(0)),
+            };
+
+            if !progress {
+                // We need more input.
+                match self.inner_status {
+                    Status::Open(Readiness::Ready) => self.fill_input()?,
+                    Status::Open(Readiness::Lull) => {
+                        self.inner_status = Status::ready();
+                        return Ok(ReadOutcome::lull(0));
+                    }
+                    Status::Open(Readiness::Pending) => {
+                        self.inner_status = Status::ready();
+                        return Ok(ReadOutcome::pending(0));
+                    }
+                    Status::End => {
+                        return Err(io::Error::new(
+                            io::ErrorKind::UnexpectedEof,
+                            "gzip stream ended prematurely",
+                        ))
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl<Inner: Read> io::Read for GzipReader<Inner> {
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        Read::read(self, buf)
+    }
+
+    #[inline]
+    fn read_vectored(&mut self, bufs: &mut [io::IoSliceMut<'_>]) -> io::Result<usize> {
+        Read::read_vectored(self, bufs)
+    }
+
+    #[cfg(feature = "nightly")]
+    #[inline]
+    fn is_read_vectored(&self) -> bool {
+        Read::is_read_vectored(self)
+    }
+
+    #[inline]
+    fn read_to_end(&mut self, buf: &mut Vec<u8>) -> io::Result<usize> {
+        Read::read_to_end(self, buf)
+    }
+
+    #[inline]
+    fn read_to_string(&mut self, buf: &mut String) -> io::Result<usize> {
+        Read::read_to_string(self, buf)
+    }
+
+    #[inline]
+    fn read_exact(&mut self, buf: &mut [u8]) -> io::Result<()> {
+        Read::read_exact(self, buf)
+    }
+}
+
+/// Parse a gzip member header, as described in [RFC 1952], returning its
+/// length, or `None` if `input` doesn't yet contain the whole header.
+///
+/// [RFC 1952]: https://www.rfc-editor.org/rfc/rfc1952
+fn parse_header(input: &[u8]) -> io::Result<Option<usize>> {
+    if input.len() < 10 {
+        if input.iter().zip(&[0x1f, 0x8b, 8]).any(|(a, b)| a != b) {
+            return Err(invalid_data("invalid gzip header"));
+        }
+        return Ok(None);
+    }
+    if input[..3] != [0x1f, 0x8b, 8] {
+        return Err(invalid_data("invalid gzip header"));
+    }
+    let flags = input[3];
+    let mut len = 10;
+    if flags & FEXTRA != 0 {
+        if input.len() < len + 2 {
+            return Ok(None);
+        }
+        len += 2 + usize::from(u16::from_le_bytes([input[len], input[len + 1]]));
+    }
+    for flag in &[FNAME, FCOMMENT] {
+        if flags & flag != 0 {
+            match input
+                .get(len..)
+                .and_then(|rest| rest.iter().position(|b| *b == 0))
+            {
+                Some(nul) => len += nul + 1,
+                None => return Ok(None),
+            }
+        }
+    }
+    if flags & FHCRC != 0 {
+        len += 2;
+    }
+    Ok(if input.len() < len { None } else { Some(len) })
+}
+
+fn lull() -> Status {
+    Status::Open(Readiness::Lull)
+}
+
+fn invalid_data(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg.to_owned())
+}
+
+#[cfg(test)]
+fn compress(data: &[u8], builder: flate2::GzBuilder) -> Vec<u8> {
+    let mut encoder = builder.write(Vec::new(), flate2::Compression::default());
+    io::Write::write_all(&mut encoder, data).unwrap();
+    encoder.finish().unwrap()
+}
+
+#[test]
+fn test_gzip_reader() {
+    let compressed = compress(b"hello world\n", flate2::GzBuilder::new());
+    let mut reader = GzipReader::new(crate::SliceReader::new(&compressed));
+    let mut s = String::new();
+    reader.read_to_string(&mut s).unwrap();
+    assert_eq!(s, "hello world\n");
+}
+
+#[test]
+fn test_gzip_reader_header_fields() {
+    let data = "hello world\n".repeat(1000);
+    let builder = flate2::GzBuilder::new()
+        .filename("hello.txt")
+        .comment("greetings")
+        .extra(&b"extra"[..]);
+    let compressed = compress(data.as_bytes(), builder);
+    let mut reader = GzipReader::new(crate::SliceReader::new(&compressed));
+    let mut s = String::new();
+    reader.read_to_string(&mut s).unwrap();
+    assert_eq!(s, data);
+}
+
+#[test]
+fn test_gzip_reader_lull() {
+    let compressed = compress(b"hello world\n", flate2::GzBuilder::new());
+    let (reader, mut writer) = crate::pipe();
+    let mut reader = GzipReader::new(reader);
+    let mut buf = [0; 64];
+
+    crate::Write::write_all(&mut writer, &compressed[..5]).unwrap();
+    crate::Write::flush(&mut writer, lull()).unwrap();
+    let outcome = reader.read_outcome(&mut buf).unwrap();
+    assert_eq!(outcome.size, 0);
+    assert_eq!(outcome.status, lull());
+
+    crate::Write::write_all(&mut writer, &compressed[5..]).unwrap();
+    crate::Write::flush(&mut writer, Status::End).unwrap();
+    let mut s = String::new();
+    reader.read_to_string(&mut s).unwrap();
+    assert_eq!(s, "hello world\n");
+}
+
+#[test]
+fn test_gzip_reader_truncated() {
+    let compressed = compress(b"hello world\n", flate2::GzBuilder::new());
+    let mut reader = GzipReader::new(crate::SliceReader::new(&compressed[..compressed.len() - 4]));
+    let mut s = String::new();
+    assert_eq!(
+        reader.read_to_string(&mut s).unwrap_err().kind(),
+        io::ErrorKind::UnexpectedEof
+    );
+}
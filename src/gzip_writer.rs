@@ -0,0 +1,176 @@
+use crate::{Close, Readiness, Status, Write};
+use flate2::{Compress, Compression, Crc, FlushCompress};
+use std::io;
+
+/// The size of the buffer used to hold compressed output.
+const OUTPUT_SIZE: usize = 8192;
+
+/// A minimal gzip member header, as described in [RFC 1952], with no
+/// optional fields, no modification time, and an unknown OS.
+///
+/// [RFC 1952]: https://www.rfc-editor.org/rfc/rfc1952
+const HEADER: [u8; 10] = [0x1f, 0x8b, 8, 0, 0, 0, 0, 0, 0, 0xff];
+
+/// A `Write` implementation which compresses data written to it into a
+/// gzip stream, and writes it to an inner `Write`.
+///
+/// Flushing with `Status::Open(Readiness::Lull)` performs a deflate sync
+/// flush, so that all the data written so far can be decompressed by the
+/// reader before the lull is passed on. Flushing with `Status::End`
+/// finishes the gzip member.
+pub struct GzipWriter<Inner: Write> {
+    /// The wrapped byte stream.
+    inner: Inner,
+
+    /// The deflate compression state.
+    compress: Compress,
+
+    /// A checksum of the uncompressed data.
+    crc: Crc,
+
+    /// Compressed output waiting to be written to `inner`.
+    output: Vec<u8>,
+
+    /// The gzip header has been written.
+    started: bool,
+}
+
+impl<Inner: Write> GzipWriter<Inner> {
+    /// Construct a new instance of `GzipWriter` wrapping `inner`, with the
+    /// default compression level.
+    #[inline]
+    pub fn new(inner: Inner) -> Self {
+        Self::with_level(inner, Compression::default())
+    }
+
+    /// Construct a new instance of `GzipWriter` wrapping `inner`, with the
+    /// given compression `level`.
+    #[inline]
+    pub fn with_level(inner: Inner, level: Compression) -> Self {
+        Self {
+            inner,
+            compress: Compress::new(level, false),
+            crc: Crc::new(),
+            output: Vec::with_capacity(OUTPUT_SIZE),
+            started: false,
+        }
+    }
+
+    /// Flush and close the underlying stream and return the underlying
+    /// stream object.
+    pub fn close_into_inner(mut self) -> io::Result<Inner> {
+        self.flush(Status::End)?;
+        Ok(self.inner)
+    }
+
+    fn start(&mut self) -> io::Result<()> {
+        if !self.started {
+            self.started = true;
+            self.inner.write_all(&HEADER)?;
+        }
+        Ok(())
+    }
+
+    /// Compress `input`, writing the compressed output to `inner`, until
+    /// all of `input` is consumed and, if `flush` isn't `FlushCompress::None`,
+    /// the flush is complete.
+    fn compress(&mut self, mut input: &[u8], flush: FlushCompress) -> io::Result<()> {
+        loop {
+            let before_in = self.compress.total_in();
+            let status = self
+                .compress
+                .compress_vec(input, &mut self.output, flush)
+                .map_err(io::Error::other)?;
+            input = &input[(self.compress.total_in() - before_in) as usize..];
+
+            let full = self.output.len() == self.output.capacity();
+            if !self.output.is_empty() {
+                self.inner.write_all(&self.output)?;
+                self.output.clear();
+            }
+
+            let done = match flush {
+                FlushCompress::Finish => status == flate2::Status::StreamEnd,
+                _ => input.is_empty() && !full,
+            };
+            if done {
+                return Ok(());
+            }
+        }
+    }
+}
+
+impl<Inner: Write> Close for GzipWriter<Inner> {
+    type Output = Inner;
+
+    #[inline]
+    fn close_into_inner(self) -> io::Result<Inner> {
+        GzipWriter::close_into_inner(self)
+    }
+}
+
+impl<Inner: Write> Write for GzipWriter<Inner> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.start()?;
+        self.compress(buf, FlushCompress::None)?;
+        self.crc.update(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self, status: Status) -> io::Result<()> {
+        match status {
+            Status::Open(Readiness::Ready) | Status::Open(Readiness::Pending) => {}
+            Status::Open(Readiness::Lull) => {
+                self.start()?;
+                self.compress(&[], FlushCompress::Sync)?;
+            }
+            Status::End => {
+                self.start()?;
+                self.compress(&[], FlushCompress::Finish)?;
+                self.inner.write_all(&self.crc.sum().to_le_bytes())?;
+                self.inner.write_all(&self.crc.amount().to_le_bytes())?;
+            }
+        }
+        self.inner.flush(status)
+    }
+
+    #[inline]
+    fn abandon(&mut self) {
+        self.inner.abandon()
+    }
+}
+
+#[test]
+fn test_gzip_writer() {
+    let data = "hello world\n".repeat(1000);
+    let mut v = Vec::new();
+    let mut writer = GzipWriter::new(crate::StdWriter::new(&mut v));
+    writer.write_all(data.as_bytes()).unwrap();
+    writer.close_into_inner().unwrap();
+    assert!(v.len() < data.len());
+
+    let mut decoder = flate2::read::GzDecoder::new(&v[..]);
+    let mut s = String::new();
+    io::Read::read_to_string(&mut decoder, &mut s).unwrap();
+    assert_eq!(s, data);
+}
+
+#[test]
+fn test_gzip_writer_to_reader() {
+    let (reader, writer) = crate::pipe();
+    let mut writer = GzipWriter::new(writer);
+    let mut reader = crate::GzipReader::new(reader);
+
+    writer.write_all(b"hello ").unwrap();
+    writer.flush(Status::Open(Readiness::Lull)).unwrap();
+    let mut buf = [0; 64];
+    let outcome = crate::Read::read_outcome(&mut reader, &mut buf).unwrap();
+    assert_eq!(&buf[..outcome.size], b"hello ");
+    assert_eq!(outcome.status, Status::Open(Readiness::Lull));
+
+    writer.write_all(b"world\n").unwrap();
+    writer.close_into_inner().unwrap();
+    let mut s = String::new();
+    crate::Read::read_to_string(&mut reader, &mut s).unwrap();
+    assert_eq!(s, "world\n");
+}
@@ -0,0 +1,103 @@
+use crate::{Read, ReadOutcome};
+use digest::{Digest, Output};
+use std::io;
+
+/// A `Read` implementation which passes data through from an inner `Read`
+/// unmodified, updating a [`Digest`] with all the data read.
+///
+/// For example, this can compute a SHA-256 of the raw bytes of a stream
+/// while a `TextReader` layered on top sanitizes them.
+pub struct HashingReader<Inner: Read, D: Digest> {
+    /// The wrapped byte stream.
+    inner: Inner,
+
+    /// The hash state.
+    digest: D,
+}
+
+impl<Inner: Read, D: Digest> HashingReader<Inner, D> {
+    /// Construct a new instance of `HashingReader` wrapping `inner`, with a
+    /// new instance of `D`.
+    #[inline]
+    pub fn new(inner: Inner) -> Self {
+        Self::with_digest(inner, D::new())
+    }
+
+    /// Construct a new instance of `HashingReader` wrapping `inner`, with
+    /// the given `digest`.
+    #[inline]
+    pub fn with_digest(inner: Inner, digest: D) -> Self {
+        Self { inner, digest }
+    }
+
+    /// Gets a reference to the hash state.
+    #[inline]
+    pub fn digest(&self) -> &D {
+        &self.digest
+    }
+
+    /// Consume this `HashingReader` and return the hash of all the data read.
+    #[inline]
+    pub fn finalize(self) -> Output<D> {
+        self.digest.finalize()
+    }
+}
+
+impl<Inner: Read, D: Digest> Read for HashingReader<Inner, D> {
+    #[inline]
+    fn read_outcome(&mut self, buf: &mut [u8]) -> io::Result<ReadOutcome> {
+        let outcome = self.inner.read_outcome(buf)?;
+        self.digest.update(&buf[..outcome.size]);
+        Ok(outcome)
+    }
+
+    #[inline]
+    fn remaining_hint(&self) -> (u64, Option<u64>) {
+        self.inner.remaining_hint()
+    }
+}
+
+impl<Inner: Read, D: Digest> io::Read for HashingReader<Inner, D> {
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        Read::read(self, buf)
+    }
+
+    #[inline]
+    fn read_vectored(&mut self, bufs: &mut [io::IoSliceMut<'_>]) -> io::Result<usize> {
+        Read::read_vectored(self, bufs)
+    }
+
+    #[cfg(feature = "nightly")]
+    #[inline]
+    fn is_read_vectored(&self) -> bool {
+        Read::is_read_vectored(self)
+    }
+
+    #[inline]
+    fn read_to_end(&mut self, buf: &mut Vec<u8>) -> io::Result<usize> {
+        Read::read_to_end(self, buf)
+    }
+
+    #[inline]
+    fn read_to_string(&mut self, buf: &mut String) -> io::Result<usize> {
+        Read::read_to_string(self, buf)
+    }
+
+    #[inline]
+    fn read_exact(&mut self, buf: &mut [u8]) -> io::Result<()> {
+        Read::read_exact(self, buf)
+    }
+}
+
+#[test]
+fn test_hashing_reader() {
+    let mut reader = HashingReader::<_, sha2::Sha256>::new(crate::SliceReader::new(b"hello world"));
+    let mut s = String::new();
+    reader.read_to_string(&mut s).unwrap();
+    assert_eq!(s, "hello world");
+    assert_eq!(
+        reader.finalize()[..],
+        sha2::Sha256::digest(b"hello world")[..]
+    );
+}
@@ -0,0 +1,87 @@
+use crate::{Close, Status, Write};
+use digest::{Digest, Output};
+use std::io;
+
+/// A `Write` implementation which passes data through to an inner `Write`
+/// unmodified, updating a [`Digest`] with all the data written.
+pub struct HashingWriter<Inner: Write, D: Digest> {
+    /// The wrapped byte stream.
+    inner: Inner,
+
+    /// The hash state.
+    digest: D,
+}
+
+impl<Inner: Write, D: Digest> HashingWriter<Inner, D> {
+    /// Construct a new instance of `HashingWriter` wrapping `inner`, with a
+    /// new instance of `D`.
+    #[inline]
+    pub fn new(inner: Inner) -> Self {
+        Self::with_digest(inner, D::new())
+    }
+
+    /// Construct a new instance of `HashingWriter` wrapping `inner`, with
+    /// the given `digest`.
+    #[inline]
+    pub fn with_digest(inner: Inner, digest: D) -> Self {
+        Self { inner, digest }
+    }
+
+    /// Gets a reference to the hash state.
+    #[inline]
+    pub fn digest(&self) -> &D {
+        &self.digest
+    }
+
+    /// Flush and close the underlying stream and return the underlying
+    /// stream object, along with the hash of all the data written.
+    pub fn close_into_inner(mut self) -> io::Result<(Inner, Output<D>)> {
+        self.inner.flush(Status::End)?;
+        Ok((self.inner, self.digest.finalize()))
+    }
+}
+
+impl<Inner: Write, D: Digest> Close for HashingWriter<Inner, D> {
+    type Output = (Inner, Output<D>);
+
+    #[inline]
+    fn close_into_inner(self) -> io::Result<(Inner, Output<D>)> {
+        HashingWriter::close_into_inner(self)
+    }
+}
+
+impl<Inner: Write, D: Digest> Write for HashingWriter<Inner, D> {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let size = self.inner.write(buf)?;
+        self.digest.update(&buf[..size]);
+        Ok(size)
+    }
+
+    #[inline]
+    fn flush(&mut self, status: Status) -> io::Result<()> {
+        self.inner.flush(status)
+    }
+
+    #[inline]
+    fn abandon(&mut self) {
+        self.inner.abandon()
+    }
+
+    #[inline]
+    fn write_all_utf8(&mut self, s: &str) -> io::Result<()> {
+        self.inner.write_all_utf8(s)?;
+        self.digest.update(s.as_bytes());
+        Ok(())
+    }
+}
+
+#[test]
+fn test_hashing_writer() {
+    let mut writer = HashingWriter::<_, sha2::Sha256>::new(crate::StdWriter::new(Vec::<u8>::new()));
+    writer.write_all(b"hello ").unwrap();
+    writer.write_all_utf8("world").unwrap();
+    let (inner, hash) = writer.close_into_inner().unwrap();
+    assert_eq!(inner.get_ref(), b"hello world");
+    assert_eq!(hash[..], sha2::Sha256::digest(b"hello world")[..]);
+}
@@ -0,0 +1,158 @@
+use crate::{Close, Readiness, Status, Write};
+use std::{fmt::Write as _, io};
+
+/// The number of bytes displayed on each line.
+const LINE_SIZE: usize = 16;
+
+/// A `Write` implementation which renders data written to it as a classic
+/// offset/hex/ASCII dump, in the format of `hexdump -C`, and writes the
+/// dump to an inner `Write`.
+///
+/// For example, writing `hello world\n` and ending the stream produces:
+///
+/// ```text
+/// 00000000  68 65 6c 6c 6f 20 77 6f  72 6c 64 0a              |hello world.|
+/// 0000000c
+/// ```
+///
+/// Lines are written as they are completed. Flushing with
+/// `Status::Open(Readiness::Lull)` also writes any incomplete line, so that
+/// each lull in the stream starts a new line of the dump. The output is
+/// written with `write_all_utf8`, so the inner stream may be a
+/// `TextWriter`.
+pub struct HexDumpWriter<Inner: Write> {
+    /// The wrapped byte stream.
+    inner: Inner,
+
+    /// Bytes of the current, incomplete line.
+    line: Vec<u8>,
+
+    /// The offset in the stream of the start of `line`.
+    offset: u64,
+
+    /// Scratch space for rendered output.
+    dump: String,
+}
+
+impl<Inner: Write> HexDumpWriter<Inner> {
+    /// Construct a new instance of `HexDumpWriter` wrapping `inner`.
+    #[inline]
+    pub fn new(inner: Inner) -> Self {
+        Self {
+            inner,
+            line: Vec::with_capacity(LINE_SIZE),
+            offset: 0,
+            dump: String::new(),
+        }
+    }
+
+    /// Flush and close the underlying stream and return the underlying
+    /// stream object.
+    pub fn close_into_inner(mut self) -> io::Result<Inner> {
+        self.flush(Status::End)?;
+        Ok(self.inner)
+    }
+
+    /// Render the current line into `dump`.
+    fn render_line(&mut self) {
+        if self.line.is_empty() {
+            return;
+        }
+        write!(self.dump, "{:08x} ", self.offset).unwrap();
+        for i in 0..LINE_SIZE {
+            if i == LINE_SIZE / 2 {
+                self.dump.push(' ');
+            }
+            match self.line.get(i) {
+                Some(b) => write!(self.dump, " {:02x}", b).unwrap(),
+                None => self.dump.push_str("   "),
+            }
+        }
+        self.dump.push_str("  |");
+        for &b in &self.line {
+            self.dump.push(if (0x20..=0x7e).contains(&b) {
+                char::from(b)
+            } else {
+                '.'
+            });
+        }
+        self.dump.push_str("|\n");
+        self.offset += self.line.len() as u64;
+        self.line.clear();
+    }
+}
+
+impl<Inner: Write> Close for HexDumpWriter<Inner> {
+    type Output = Inner;
+
+    #[inline]
+    fn close_into_inner(self) -> io::Result<Inner> {
+        HexDumpWriter::close_into_inner(self)
+    }
+}
+
+impl<Inner: Write> Write for HexDumpWriter<Inner> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.dump.clear();
+        for &b in buf {
+            self.line.push(b);
+            if self.line.len() == LINE_SIZE {
+                self.render_line();
+            }
+        }
+        self.inner.write_all_utf8(&self.dump)?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self, status: Status) -> io::Result<()> {
+        self.dump.clear();
+        match status {
+            Status::Open(Readiness::Ready) | Status::Open(Readiness::Pending) => {}
+            Status::Open(Readiness::Lull) => self.render_line(),
+            Status::End => {
+                self.render_line();
+                writeln!(self.dump, "{:08x}", self.offset).unwrap();
+            }
+        }
+        self.inner.write_all_utf8(&self.dump)?;
+        self.inner.flush(status)
+    }
+
+    #[inline]
+    fn abandon(&mut self) {
+        self.line.clear();
+        self.inner.abandon()
+    }
+}
+
+#[test]
+fn test_hex_dump_writer() {
+    let mut v = Vec::new();
+    let mut writer = HexDumpWriter::new(crate::StdWriter::new(&mut v));
+    writer
+        .write_all(b"hello world\nhello\x00\xff world\n")
+        .unwrap();
+    writer.close_into_inner().unwrap();
+    assert_eq!(
+        String::from_utf8(v).unwrap(),
+        "00000000  68 65 6c 6c 6f 20 77 6f  72 6c 64 0a 68 65 6c 6c  |hello world.hell|\n\
+         00000010  6f 00 ff 20 77 6f 72 6c  64 0a                    |o.. world.|\n\
+         0000001a\n"
+    );
+}
+
+#[test]
+fn test_hex_dump_writer_lull() {
+    let mut v = Vec::new();
+    let mut writer = HexDumpWriter::new(crate::StdWriter::new(&mut v));
+    writer.write_all(b"hello").unwrap();
+    writer.flush(Status::Open(Readiness::Lull)).unwrap();
+    writer.write_all(b" world").unwrap();
+    writer.close_into_inner().unwrap();
+    assert_eq!(
+        String::from_utf8(v).unwrap(),
+        "00000000  68 65 6c 6c 6f                                    |hello|\n\
+         00000005  20 77 6f 72 6c 64                                 | world|\n\
+         0000000b\n"
+    );
+}
@@ -0,0 +1,191 @@
+use crate::{Read, ReadOutcome, Status};
+use std::{cmp::min, io};
+
+/// The size of the buffer used to hold encoded input.
+const INPUT_SIZE: usize = 8192;
+
+/// A `Read` implementation which decodes ASCII hex read from an inner
+/// `Read`.
+///
+/// Digits may be upper or lower case, and whitespace between them is
+/// ignored, so the output of tools which group and wrap hex can be decoded
+/// directly. Lulls and the end of the stream are passed through once all
+/// the data read before them has been decoded.
+pub struct HexReader<Inner: Read> {
+    /// The wrapped byte stream.
+    inner: Inner,
+
+    /// Scratch space for reading encoded input.
+    input: Box<[u8]>,
+
+    /// Decoded data waiting to be read.
+    decoded: Vec<u8>,
+
+    /// The position within `decoded` of the next byte to read.
+    pos: usize,
+
+    /// The status to report once `decoded` is drained.
+    status: Status,
+
+    /// The value of the first digit of an incomplete pair.
+    high: Option<u8>,
+
+    /// The stream has ended and all the data has been read.
+    ended: bool,
+}
+
+impl<Inner: Read> HexReader<Inner> {
+    /// Construct a new instance of `HexReader` wrapping `inner`.
+    #[inline]
+    pub fn new(inner: Inner) -> Self {
+        Self {
+            inner,
+            input: vec![0; INPUT_SIZE].into_boxed_slice(),
+            decoded: Vec::new(),
+            pos: 0,
+            status: Status::ready(),
+            high: None,
+            ended: false,
+        }
+    }
+
+    /// Read from `inner` until we have some decoded data or a status to
+    /// report.
+    fn fill_decoded(&mut self) -> io::Result<()> {
+        self.decoded.clear();
+        self.pos = 0;
+        loop {
+            let outcome = self.inner.read_outcome(&mut self.input)?;
+            for i in 0..outcome.size {
+                self.decode(self.input[i])?;
+            }
+            if outcome.status.is_end() && self.high.is_some() {
+                return Err(invalid_data("odd number of hex digits"));
+            }
+            self.status = outcome.status;
+            if !self.decoded.is_empty() || self.status != Status::ready() {
+                return Ok(());
+            }
+        }
+    }
+
+    fn decode(&mut self, c: u8) -> io::Result<()> {
+        let value = match c {
+            b'0'..=b'9' => c - b'0',
+            b'a'..=b'f' => c - b'a' + 10,
+            b'A'..=b'F' => c - b'A' + 10,
+            b' ' | b'\t' | b'\r' | b'\n' => return Ok(()),
+            _ => return Err(invalid_data("invalid hex digit")),
+        };
+        match self.high.take() {
+            Some(high) => self.decoded.push((high << 4) | value),
+            None => self.high = Some(value),
+        }
+        Ok(())
+    }
+}
+
+impl<Inner: Read> Read for HexReader<Inner> {
+    fn read_outcome(&mut self, buf: &mut [u8]) -> io::Result<ReadOutcome> {
+        if self.ended {
+            return Ok(ReadOutcome::end(0));
+        }
+        if buf.is_empty() {
+            return Ok(ReadOutcome::ready(0));
+        }
+        if self.pos == self.decoded.len() {
+            self.fill_decoded()?;
+        }
+
+        let size = min(buf.len(), self.decoded.len() - self.pos);
+        buf[..size].copy_from_slice(&self.decoded[self.pos..self.pos + size]);
+        self.pos += size;
+        if self.pos < self.decoded.len() {
+            return Ok(ReadOutcome::ready(size));
+        }
+        self.ended = self.status.is_end();
+        Ok(ReadOutcome {
+            size,
+            status: self.status,
+        })
+    }
+}
+
+impl<Inner: Read> io::Read for HexReader<Inner> {
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        Read::read(self, buf)
+    }
+
+    #[inline]
+    fn read_vectored(&mut self, bufs: &mut [io::IoSliceMut<'_>]) -> io::Result<usize> {
+        Read::read_vectored(self, bufs)
+    }
+
+    #[cfg(feature = "nightly")]
+    #[inline]
+    fn is_read_vectored(&self) -> bool {
+        Read::is_read_vectored(self)
+    }
+
+    #[inline]
+    fn read_to_end(&mut self, buf: &mut Vec<u8>) -> io::Result<usize> {
+        Read::read_to_end(self, buf)
+    }
+
+    #[inline]
+    fn read_to_string(&mut self, buf: &mut String) -> io::Result<usize> {
+        Read::read_to_string(self, buf)
+    }
+
+    #[inline]
+    fn read_exact(&mut self, buf: &mut [u8]) -> io::Result<()> {
+        Read::read_exact(self, buf)
+    }
+}
+
+fn invalid_data(msg: &'static str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+#[cfg(test)]
+fn decode(input: &[u8]) -> io::Result<Vec<u8>> {
+    let mut reader = HexReader::new(crate::SliceReader::new(input));
+    let mut v = Vec::new();
+    reader.read_to_end(&mut v)?;
+    Ok(v)
+}
+
+#[test]
+fn test_hex_reader() {
+    assert_eq!(decode(b"").unwrap(), b"");
+    assert_eq!(decode(b"68656c6c6f").unwrap(), b"hello");
+    assert_eq!(decode(b"68 65 6C\r\n6C 6F\n").unwrap(), b"hello");
+    assert_eq!(decode(b"00ff7F").unwrap(), b"\x00\xff\x7f");
+}
+
+#[test]
+fn test_hex_reader_invalid() {
+    assert!(decode(b"6g").is_err());
+    assert!(decode(b"686").is_err());
+    assert!(decode(b"0x68").is_err());
+}
+
+#[test]
+fn test_hex_reader_split_pair() {
+    let (reader, mut writer) = crate::pipe();
+    let mut reader = HexReader::new(reader);
+    let mut buf = [0; 16];
+
+    crate::Write::write_all(&mut writer, b"686").unwrap();
+    crate::Write::flush(&mut writer, Status::Open(crate::Readiness::Lull)).unwrap();
+    let outcome = reader.read_outcome(&mut buf).unwrap();
+    assert_eq!(&buf[..outcome.size], b"h");
+    assert_eq!(outcome.status, Status::Open(crate::Readiness::Lull));
+
+    crate::Write::write_all(&mut writer, b"9").unwrap();
+    crate::Write::flush(&mut writer, Status::End).unwrap();
+    let outcome = reader.read_outcome(&mut buf).unwrap();
+    assert_eq!(&buf[..outcome.size], b"i");
+    assert!(outcome.status.is_end());
+}
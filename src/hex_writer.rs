@@ -0,0 +1,91 @@
+use crate::{Close, Status, Write};
+use std::io;
+
+/// Lower-case hex digits.
+const DIGITS: &[u8; 16] = b"0123456789abcdef";
+
+/// A `Write` implementation which encodes data written to it as lower-case
+/// ASCII hex, and writes the encoded text to an inner `Write`.
+///
+/// The output is written with `write_all_utf8`, so the inner stream may be
+/// a `TextWriter`.
+pub struct HexWriter<Inner: Write> {
+    /// The wrapped byte stream.
+    inner: Inner,
+
+    /// Scratch space for encoded output.
+    encoded: String,
+}
+
+impl<Inner: Write> HexWriter<Inner> {
+    /// Construct a new instance of `HexWriter` wrapping `inner`.
+    #[inline]
+    pub fn new(inner: Inner) -> Self {
+        Self {
+            inner,
+            encoded: String::new(),
+        }
+    }
+
+    /// Flush and close the underlying stream and return the underlying
+    /// stream object.
+    pub fn close_into_inner(mut self) -> io::Result<Inner> {
+        self.flush(Status::End)?;
+        Ok(self.inner)
+    }
+}
+
+impl<Inner: Write> Close for HexWriter<Inner> {
+    type Output = Inner;
+
+    #[inline]
+    fn close_into_inner(self) -> io::Result<Inner> {
+        HexWriter::close_into_inner(self)
+    }
+}
+
+impl<Inner: Write> Write for HexWriter<Inner> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.encoded.clear();
+        for &b in buf {
+            self.encoded.push(char::from(DIGITS[usize::from(b >> 4)]));
+            self.encoded.push(char::from(DIGITS[usize::from(b & 0xf)]));
+        }
+        self.inner.write_all_utf8(&self.encoded)?;
+        Ok(buf.len())
+    }
+
+    #[inline]
+    fn flush(&mut self, status: Status) -> io::Result<()> {
+        self.inner.flush(status)
+    }
+
+    #[inline]
+    fn abandon(&mut self) {
+        self.inner.abandon()
+    }
+}
+
+#[test]
+fn test_hex_writer() {
+    let mut v = Vec::new();
+    let mut writer = HexWriter::new(crate::StdWriter::new(&mut v));
+    writer.write_all(b"hello").unwrap();
+    writer.write_all(b"\x00\xff\x7f").unwrap();
+    writer.close_into_inner().unwrap();
+    assert_eq!(v, b"68656c6c6f00ff7f");
+}
+
+#[test]
+fn test_hex_round_trip() {
+    let data = (0..=255).collect::<Vec<u8>>();
+    let mut v = Vec::new();
+    let mut writer = HexWriter::new(crate::StdWriter::new(&mut v));
+    writer.write_all(&data).unwrap();
+    writer.close_into_inner().unwrap();
+
+    let mut reader = crate::HexReader::new(crate::SliceReader::new(&v));
+    let mut decoded = Vec::new();
+    crate::Read::read_to_end(&mut reader, &mut decoded).unwrap();
+    assert_eq!(decoded, data);
+}
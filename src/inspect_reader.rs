@@ -0,0 +1,88 @@
+use crate::{Read, ReadOutcome, Status};
+use std::io;
+
+/// A `Read` implementation which passes data through from an inner `Read`
+/// unmodified, calling a user-supplied closure with each chunk and its
+/// `Status` as it goes by.
+///
+/// This is useful for adding debugging, auditing, or metrics hooks to an
+/// existing pipeline.
+pub struct InspectReader<Inner: Read, F: FnMut(&[u8], Status)> {
+    /// The wrapped byte stream.
+    inner: Inner,
+
+    /// The closure to call with each chunk.
+    callback: F,
+}
+
+impl<Inner: Read, F: FnMut(&[u8], Status)> InspectReader<Inner, F> {
+    /// Construct a new instance of `InspectReader` wrapping `inner`, which
+    /// calls `callback` with the data and status of each read.
+    #[inline]
+    pub fn new(inner: Inner, callback: F) -> Self {
+        Self { inner, callback }
+    }
+}
+
+impl<Inner: Read, F: FnMut(&[u8], Status)> Read for InspectReader<Inner, F> {
+    #[inline]
+    fn read_outcome(&mut self, buf: &mut [u8]) -> io::Result<ReadOutcome> {
+        let outcome = self.inner.read_outcome(buf)?;
+        (self.callback)(&buf[..outcome.size], outcome.status);
+        Ok(outcome)
+    }
+
+    #[inline]
+    fn remaining_hint(&self) -> (u64, Option<u64>) {
+        self.inner.remaining_hint()
+    }
+}
+
+impl<Inner: Read, F: FnMut(&[u8], Status)> io::Read for InspectReader<Inner, F> {
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        Read::read(self, buf)
+    }
+
+    #[inline]
+    fn read_vectored(&mut self, bufs: &mut [io::IoSliceMut<'_>]) -> io::Result<usize> {
+        Read::read_vectored(self, bufs)
+    }
+
+    #[cfg(feature = "nightly")]
+    #[inline]
+    fn is_read_vectored(&self) -> bool {
+        Read::is_read_vectored(self)
+    }
+
+    #[inline]
+    fn read_to_end(&mut self, buf: &mut Vec<u8>) -> io::Result<usize> {
+        Read::read_to_end(self, buf)
+    }
+
+    #[inline]
+    fn read_to_string(&mut self, buf: &mut String) -> io::Result<usize> {
+        Read::read_to_string(self, buf)
+    }
+
+    #[inline]
+    fn read_exact(&mut self, buf: &mut [u8]) -> io::Result<()> {
+        Read::read_exact(self, buf)
+    }
+}
+
+#[test]
+fn test_inspect_reader() {
+    let mut seen = Vec::new();
+    let mut ended = false;
+    let mut reader =
+        InspectReader::new(crate::SliceReader::new(b"hello world"), |chunk, status| {
+            seen.extend_from_slice(chunk);
+            ended |= status.is_end();
+        });
+    let mut s = String::new();
+    reader.read_to_string(&mut s).unwrap();
+    assert_eq!(s, "hello world");
+    assert_eq!(seen, b"hello world");
+    assert!(ended);
+}
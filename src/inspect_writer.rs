@@ -0,0 +1,93 @@
+use crate::{Close, Status, Write};
+use std::io;
+
+/// A `Write` implementation which passes data through to an inner `Write`
+/// unmodified, calling a user-supplied closure with each chunk and its
+/// `Status` as it goes by.
+///
+/// Data written with `write` and `write_all_utf8` is reported with a status
+/// of `Status::ready()`. Calls to `flush` are reported as an empty chunk with
+/// the given status.
+pub struct InspectWriter<Inner: Write, F: FnMut(&[u8], Status)> {
+    /// The wrapped byte stream.
+    inner: Inner,
+
+    /// The closure to call with each chunk.
+    callback: F,
+}
+
+impl<Inner: Write, F: FnMut(&[u8], Status)> InspectWriter<Inner, F> {
+    /// Construct a new instance of `InspectWriter` wrapping `inner`, which
+    /// calls `callback` with the data and status of each write and flush.
+    #[inline]
+    pub fn new(inner: Inner, callback: F) -> Self {
+        Self { inner, callback }
+    }
+
+    /// Flush and close the underlying stream and return the underlying
+    /// stream object.
+    pub fn close_into_inner(mut self) -> io::Result<Inner> {
+        self.flush(Status::End)?;
+        Ok(self.inner)
+    }
+}
+
+impl<Inner: Write, F: FnMut(&[u8], Status)> Close for InspectWriter<Inner, F> {
+    type Output = Inner;
+
+    #[inline]
+    fn close_into_inner(self) -> io::Result<Inner> {
+        InspectWriter::close_into_inner(self)
+    }
+}
+
+impl<Inner: Write, F: FnMut(&[u8], Status)> Write for InspectWriter<Inner, F> {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let size = self.inner.write(buf)?;
+        (self.callback)(&buf[..size], Status::ready());
+        Ok(size)
+    }
+
+    #[inline]
+    fn flush(&mut self, status: Status) -> io::Result<()> {
+        self.inner.flush(status)?;
+        (self.callback)(&[], status);
+        Ok(())
+    }
+
+    #[inline]
+    fn abandon(&mut self) {
+        self.inner.abandon()
+    }
+
+    #[inline]
+    fn write_all_utf8(&mut self, s: &str) -> io::Result<()> {
+        self.inner.write_all_utf8(s)?;
+        (self.callback)(s.as_bytes(), Status::ready());
+        Ok(())
+    }
+
+    #[inline]
+    fn write_outcome(&mut self, buf: &[u8], status: Status) -> io::Result<()> {
+        self.inner.write_outcome(buf, status)?;
+        (self.callback)(buf, status);
+        Ok(())
+    }
+}
+
+#[test]
+fn test_inspect_writer() {
+    let mut seen = Vec::new();
+    let mut statuses = Vec::new();
+    let mut writer =
+        InspectWriter::new(crate::StdWriter::new(Vec::<u8>::new()), |chunk, status| {
+            seen.extend_from_slice(chunk);
+            statuses.push(status);
+        });
+    writer.write_all(b"hello world\n").unwrap();
+    let inner = writer.close_into_inner().unwrap();
+    assert_eq!(inner.get_ref(), b"hello world\n");
+    assert_eq!(seen, b"hello world\n");
+    assert_eq!(statuses.last(), Some(&Status::End));
+}
@@ -0,0 +1,84 @@
+//! Defines `InvisiblePolicy`.
+
+/// What a `TextReader` or `TextWriter` does with invisible formatting
+/// characters: the [Default_Ignorable_Code_Point] scalar values, such as
+/// U+200B (ZERO WIDTH SPACE), U+200D (ZERO WIDTH JOINER), U+00AD (SOFT
+/// HYPHEN), U+2060 (WORD JOINER), variation selectors, and tag characters.
+///
+/// These don't display, or display only by affecting the characters around
+/// them, so they can make text which looks identical compare differently.
+///
+/// U+FEFF (BOM) is always stripped, and the bidirectional embedding,
+/// override, and isolate controls are handled by the `BidiPolicy` instead.
+///
+/// Some of these characters are meaningful in some contexts; in particular,
+/// U+200D and the variation selectors are used in emoji sequences, and
+/// U+200C and U+200D affect the rendering of several scripts.
+///
+/// [Default_Ignorable_Code_Point]: https://www.unicode.org/reports/tr44/#Default_Ignorable_Code_Point
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InvisiblePolicy {
+    /// Pass the characters through. This is the default.
+    Keep,
+
+    /// Remove the characters.
+    Strip,
+
+    /// Replace the characters with U+FFFD, or the configured replacement.
+    Replace,
+
+    /// Fail with [`Error::InvisibleCharacter`].
+    ///
+    /// [`Error::InvisibleCharacter`]: crate::Error::InvisibleCharacter
+    Reject,
+}
+
+impl Default for InvisiblePolicy {
+    #[inline]
+    fn default() -> Self {
+        Self::Keep
+    }
+}
+
+/// Test whether `c` is an invisible formatting character governed by
+/// `InvisiblePolicy`.
+pub(crate) fn is_invisible(c: char) -> bool {
+    matches!(
+        c,
+        '\u{ad}'
+            | '\u{34f}'
+            | '\u{61c}'
+            | '\u{115f}'..='\u{1160}'
+            | '\u{17b4}'..='\u{17b5}'
+            | '\u{180b}'..='\u{180f}'
+            | '\u{200b}'..='\u{200f}'
+            | '\u{2060}'..='\u{2065}'
+            | '\u{206a}'..='\u{206f}'
+            | '\u{3164}'
+            | '\u{fe00}'..='\u{fe0f}'
+            | '\u{ffa0}'
+            | '\u{fff0}'..='\u{fff8}'
+            | '\u{1bca0}'..='\u{1bca3}'
+            | '\u{1d173}'..='\u{1d17a}'
+            | '\u{e0000}'..='\u{e0fff}'
+    )
+}
+
+#[test]
+fn test_is_invisible() {
+    for c in [
+        '\u{ad}',
+        '\u{200b}',
+        '\u{200d}',
+        '\u{2060}',
+        '\u{fe0f}',
+        '\u{e0041}',
+    ] {
+        assert!(is_invisible(c), "U+{:04X}", u32::from(c));
+    }
+    for c in [
+        'a', ' ', '\u{a0}', '\u{feff}', '\u{202e}', '\u{2066}', '\u{fffd}',
+    ] {
+        assert!(!is_invisible(c), "U+{:04X}", u32::from(c));
+    }
+}
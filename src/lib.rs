@@ -2,36 +2,257 @@
 
 #![deny(missing_docs)]
 
+#[cfg(feature = "futures-io")]
+mod async_adapters;
+#[cfg(all(feature = "futures-io", feature = "text"))]
+mod async_text_reader;
+#[cfg(feature = "futures-io")]
+mod async_utf8_reader;
+mod base64_reader;
+mod base64_writer;
 #[cfg(feature = "text")]
-mod no_forbidden_characters;
+mod bidi;
+#[cfg(feature = "text")]
+mod bom_policy;
+mod buffered_reader;
+mod buffered_writer;
+mod cancelable_reader;
+mod cancelable_writer;
+mod cancellation_token;
+mod channel_reader;
+mod channel_writer;
+mod chars;
+mod close;
+mod copy;
+mod counting_reader;
+mod counting_writer;
+mod cursor;
+mod error;
+mod escape_filter_reader;
+mod escape_state;
+mod fan_in_reader;
+#[cfg(feature = "text")]
+mod forbidden_characters;
+#[cfg(feature = "text")]
+mod form_feed_policy;
+#[cfg(feature = "text")]
+mod graphemes;
+#[cfg(feature = "gzip")]
+mod gzip_reader;
+#[cfg(feature = "gzip")]
+mod gzip_writer;
+#[cfg(feature = "digest")]
+mod hashing_reader;
+#[cfg(feature = "digest")]
+mod hashing_writer;
+mod hex_dump_writer;
+mod hex_reader;
+mod hex_writer;
+mod inspect_reader;
+mod inspect_writer;
+#[cfg(feature = "text")]
+mod invisible;
+mod limit_writer;
+mod line_buffered_writer;
+#[cfg(feature = "text")]
+mod line_terminator_policy;
+mod lines;
+#[cfg(all(feature = "futures-io", feature = "futures-core", feature = "text"))]
+mod lines_stream;
+#[cfg(all(unix, feature = "mio"))]
+mod mio_source;
+#[cfg(feature = "text")]
+mod newline_policy;
+#[cfg(feature = "text")]
+mod non_starter_policy;
+#[cfg(feature = "text")]
+mod normalization_form;
+#[cfg(feature = "text")]
+mod nul_policy;
+mod offload_writer;
+mod pipe;
+mod positioned_reader;
+#[cfg(feature = "text")]
+mod problem_sequences;
+#[cfg(all(unix, feature = "pty"))]
+mod pty;
 #[cfg(feature = "text")]
 mod rc_char_queue;
 mod read;
+mod read_buf;
+mod read_str;
+#[cfg(feature = "security")]
+mod restriction;
+mod retry_reader;
+mod show_nonprinting_writer;
 mod slice_reader;
+mod split;
 mod status;
+#[cfg(windows)]
+mod std_named_pipe;
 mod std_reader;
+mod std_tcp_stream;
+#[cfg(unix)]
+mod std_unix_stream;
 mod std_writer;
+mod substitution;
+#[cfg(feature = "text")]
+mod tab_policy;
+pub mod testing;
+#[cfg(feature = "text")]
+mod text_duplexer;
 #[cfg(feature = "text")]
 mod text_reader;
 #[cfg(feature = "text")]
+mod text_stdio;
+#[cfg(feature = "text")]
 mod text_writer;
+mod throttle_reader;
+mod timeout_reader;
 mod unicode;
+#[cfg(all(target_os = "linux", feature = "io-uring"))]
+mod uring;
+#[cfg(all(target_os = "linux", feature = "io-uring"))]
+mod uring_reader;
+#[cfg(all(target_os = "linux", feature = "io-uring"))]
+mod uring_writer;
 mod utf8_reader;
 mod utf8_writer;
+#[cfg(windows)]
+mod windows_console;
+#[cfg(windows)]
+mod windows_console_reader;
+#[cfg(windows)]
+mod windows_console_writer;
+#[cfg(feature = "text")]
+mod words;
 mod write;
+#[cfg(feature = "zstd")]
+mod zstd_reader;
+#[cfg(feature = "zstd")]
+mod zstd_writer;
 
+#[cfg(feature = "futures-io")]
+pub use async_adapters::{FromAsyncRead, FromAsyncWrite, IntoAsyncRead, IntoAsyncWrite};
+#[cfg(all(feature = "futures-io", feature = "text"))]
+pub use async_text_reader::AsyncTextReader;
+#[cfg(feature = "futures-io")]
+pub use async_utf8_reader::AsyncUtf8Reader;
+pub use base64_reader::Base64Reader;
+pub use base64_writer::Base64Writer;
+#[cfg(feature = "text")]
+pub use bidi::BidiPolicy;
+#[cfg(feature = "text")]
+pub use bom_policy::BomPolicy;
+pub use buffered_reader::BufferedReader;
+pub use buffered_writer::BufferedWriter;
+pub use cancelable_reader::CancelableReader;
+pub use cancelable_writer::CancelableWriter;
+pub use cancellation_token::CancellationToken;
+pub use channel_reader::ChannelReader;
+pub use channel_writer::ChannelWriter;
+pub use chars::Chars;
+pub use close::Close;
+pub use copy::{copy, copy_str};
+pub use counting_reader::CountingReader;
+pub use counting_writer::{CountingWriter, WriteCounts};
+pub use cursor::Cursor;
+pub use error::Error;
+pub use escape_filter_reader::EscapeFilterReader;
+pub use fan_in_reader::FanInReader;
+#[cfg(feature = "text")]
+pub use forbidden_characters::{ForbiddenCharacterPolicy, ForbiddenCharacters};
+#[cfg(feature = "text")]
+pub use form_feed_policy::FormFeedPolicy;
+#[cfg(feature = "text")]
+pub use graphemes::Graphemes;
+#[cfg(feature = "gzip")]
+pub use gzip_reader::GzipReader;
+#[cfg(feature = "gzip")]
+pub use gzip_writer::GzipWriter;
+#[cfg(feature = "digest")]
+pub use hashing_reader::HashingReader;
+#[cfg(feature = "digest")]
+pub use hashing_writer::HashingWriter;
+pub use hex_dump_writer::HexDumpWriter;
+pub use hex_reader::HexReader;
+pub use hex_writer::HexWriter;
+pub use inspect_reader::InspectReader;
+pub use inspect_writer::InspectWriter;
+#[cfg(feature = "text")]
+pub use invisible::InvisiblePolicy;
+pub use limit_writer::LimitWriter;
+pub use line_buffered_writer::LineBufferedWriter;
+#[cfg(feature = "text")]
+pub use line_terminator_policy::LineTerminatorPolicy;
+pub use lines::{Line, Lines};
+#[cfg(all(feature = "futures-io", feature = "futures-core", feature = "text"))]
+pub use lines_stream::LinesStream;
+#[cfg(feature = "text")]
+pub use newline_policy::NewlinePolicy;
+#[cfg(feature = "text")]
+pub use non_starter_policy::NonStarterPolicy;
+#[cfg(feature = "text")]
+pub use normalization_form::NormalizationForm;
+#[cfg(feature = "text")]
+pub use nul_policy::NulPolicy;
+pub use offload_writer::OffloadWriter;
+pub use pipe::{pipe, PipeReader, PipeWriter};
+pub use positioned_reader::{Position, PositionedReader};
+#[cfg(all(unix, feature = "pty"))]
+pub use pty::{open_pty, PtyMaster};
 pub use read::{
-    default_read_exact, default_read_to_end, default_read_to_string, Read, ReadOutcome,
+    default_read_buf_outcome, default_read_exact, default_read_exact_timeout, default_read_to_end,
+    default_read_to_end_limit, default_read_to_string, Read, ReadBuffered, ReadOutcome,
 };
+pub use read_buf::ReadBuf;
+pub use read_str::ReadStr;
+#[cfg(feature = "security")]
+pub use restriction::RestrictionPolicy;
+pub use retry_reader::{RetryPolicy, RetryReader};
+pub use show_nonprinting_writer::ShowNonprintingWriter;
 pub use slice_reader::SliceReader;
+pub use split::{split, ReadHalf, WriteHalf};
 pub use status::{Readiness, Status};
-pub use std_reader::StdReader;
+#[cfg(windows)]
+pub use std_named_pipe::StdNamedPipe;
+pub use std_reader::{StdReader, StdReaderMode, StdReaderOptions};
+pub use std_tcp_stream::StdTcpStream;
+#[cfg(unix)]
+pub use std_unix_stream::StdUnixStream;
 pub use std_writer::StdWriter;
+pub use substitution::{Substitution, SubstitutionKind};
+#[cfg(feature = "text")]
+pub use tab_policy::TabPolicy;
 #[cfg(feature = "text")]
-pub use text_reader::TextReader;
+pub use text_duplexer::TextDuplexer;
 #[cfg(feature = "text")]
-pub use text_writer::TextWriter;
+pub use text_reader::{TextReader, TextReaderBuilder};
+#[cfg(feature = "text")]
+pub use text_stdio::{text_stdin, text_stdout, TextStdin, TextStdout};
+#[cfg(feature = "text")]
+pub use text_writer::{DropPolicy, TextWriter, TextWriterBuilder};
+pub use throttle_reader::ThrottleReader;
+pub use timeout_reader::{TimeoutAction, TimeoutReader};
 pub use unicode::NORMALIZATION_BUFFER_SIZE;
-pub use utf8_reader::Utf8Reader;
+#[cfg(feature = "security")]
+pub use unicode_security::RestrictionLevel;
+#[cfg(all(target_os = "linux", feature = "io-uring"))]
+pub use uring_reader::UringReader;
+#[cfg(all(target_os = "linux", feature = "io-uring"))]
+pub use uring_writer::UringWriter;
+pub use utf8_reader::{Utf8Reader, Utf8ReaderBuilder};
 pub use utf8_writer::Utf8Writer;
+#[cfg(windows)]
+pub use windows_console_reader::WindowsConsoleReader;
+#[cfg(windows)]
+pub use windows_console_writer::WindowsConsoleWriter;
+#[cfg(feature = "text")]
+pub use words::Words;
+#[cfg(feature = "nightly")]
+pub use write::default_write_all_vectored;
 pub use write::{default_write_all, default_write_vectored, Write};
+#[cfg(feature = "zstd")]
+pub use zstd_reader::ZstdReader;
+#[cfg(feature = "zstd")]
+pub use zstd_writer::ZstdWriter;
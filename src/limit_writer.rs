@@ -0,0 +1,151 @@
+use crate::{Close, Error, Status, Write};
+use std::io;
+
+/// A `Write` implementation which passes data through to an inner `Write`,
+/// enforcing a limit on the total number of bytes written.
+///
+/// This is useful for bounding the size of log output, or the expansion of
+/// attacker-controlled input, such as from U+FFFD replacement.
+///
+/// By default, an attempt to exceed the limit is reported as an error and
+/// the inner stream is abandoned. With `truncating`, data beyond the limit
+/// is silently discarded instead.
+pub struct LimitWriter<Inner: Write> {
+    /// The wrapped byte stream.
+    inner: Inner,
+
+    /// The number of bytes which may still be written.
+    remaining: u64,
+
+    /// When enabled, data beyond the limit is discarded rather than
+    /// reported as an error.
+    truncate: bool,
+}
+
+impl<Inner: Write> LimitWriter<Inner> {
+    /// Construct a new instance of `LimitWriter` wrapping `inner`, which
+    /// fails if more than `limit` bytes are written.
+    #[inline]
+    pub fn new(inner: Inner, limit: u64) -> Self {
+        Self {
+            inner,
+            remaining: limit,
+            truncate: false,
+        }
+    }
+
+    /// Construct a new instance of `LimitWriter` wrapping `inner`, which
+    /// discards any data beyond the first `limit` bytes.
+    ///
+    /// When truncating data written with `write_all_utf8`, the output is
+    /// truncated at a scalar value boundary, so that it remains valid UTF-8.
+    #[inline]
+    pub fn truncating(inner: Inner, limit: u64) -> Self {
+        Self {
+            inner,
+            remaining: limit,
+            truncate: true,
+        }
+    }
+
+    /// Return the number of bytes which may still be written before the
+    /// limit is reached.
+    #[inline]
+    pub fn remaining(&self) -> u64 {
+        self.remaining
+    }
+
+    /// Flush and close the underlying stream and return the underlying
+    /// stream object.
+    pub fn close_into_inner(mut self) -> io::Result<Inner> {
+        self.inner.flush(Status::End)?;
+        Ok(self.inner)
+    }
+
+    /// Return the number of bytes of a `len`-byte buffer which may be
+    /// written, or fail if the limit would be exceeded in non-truncating
+    /// mode.
+    fn allowance(&mut self, len: usize) -> io::Result<usize> {
+        if len as u64 <= self.remaining {
+            Ok(len)
+        } else if self.truncate {
+            Ok(self.remaining as usize)
+        } else {
+            self.abandon();
+            Err(limit_exceeded())
+        }
+    }
+}
+
+impl<Inner: Write> Close for LimitWriter<Inner> {
+    type Output = Inner;
+
+    #[inline]
+    fn close_into_inner(self) -> io::Result<Inner> {
+        LimitWriter::close_into_inner(self)
+    }
+}
+
+impl<Inner: Write> Write for LimitWriter<Inner> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let allowed = self.allowance(buf.len())?;
+        if allowed == 0 {
+            // In truncating mode, report the discarded bytes as written.
+            return Ok(buf.len());
+        }
+        let size = self.inner.write(&buf[..allowed])?;
+        self.remaining -= size as u64;
+        Ok(if size == allowed { buf.len() } else { size })
+    }
+
+    #[inline]
+    fn flush(&mut self, status: Status) -> io::Result<()> {
+        self.inner.flush(status)
+    }
+
+    #[inline]
+    fn abandon(&mut self) {
+        self.inner.abandon()
+    }
+
+    fn write_all_utf8(&mut self, s: &str) -> io::Result<()> {
+        let mut allowed = self.allowance(s.len())?;
+        while !s.is_char_boundary(allowed) {
+            allowed -= 1;
+        }
+        self.inner.write_all_utf8(&s[..allowed])?;
+        self.remaining -= allowed as u64;
+        Ok(())
+    }
+}
+
+fn limit_exceeded() -> io::Error {
+    Error::OutputLimitExceeded.into()
+}
+
+#[test]
+fn test_limit_writer() {
+    let mut writer = LimitWriter::new(crate::StdWriter::new(Vec::<u8>::new()), 8);
+    writer.write_all(b"hello").unwrap();
+    assert_eq!(writer.remaining(), 3);
+    assert!(writer.write_all(b" world").is_err());
+}
+
+#[test]
+fn test_truncating_limit_writer() {
+    let mut writer = LimitWriter::truncating(crate::StdWriter::new(Vec::<u8>::new()), 8);
+    writer.write_all(b"hello").unwrap();
+    writer.write_all(b" world").unwrap();
+    writer.write_all_utf8("!").unwrap();
+    let inner = writer.close_into_inner().unwrap();
+    assert_eq!(inner.get_ref(), b"hello wo");
+}
+
+#[test]
+fn test_truncating_limit_writer_utf8() {
+    let mut writer = LimitWriter::truncating(crate::StdWriter::new(Vec::<u8>::new()), 6);
+    writer.write_all_utf8("hellö").unwrap();
+    writer.write_all_utf8("ö").unwrap();
+    let inner = writer.close_into_inner().unwrap();
+    assert_eq!(inner.get_ref(), "hellö".as_bytes());
+}
@@ -0,0 +1,149 @@
+use crate::{BufferedWriter, Close, Readiness, Status, Write};
+use std::io;
+#[cfg(unix)]
+use std::os::unix::io::{AsFd, AsRawFd, BorrowedFd, RawFd};
+#[cfg(windows)]
+use std::os::windows::io::{AsHandle, AsRawHandle, BorrowedHandle, RawHandle};
+
+/// A `Write` implementation which adds buffering to an inner `Write`, in
+/// the manner of [`std::io::LineWriter`].
+///
+/// This is like `BufferedWriter`, but whenever a '\n' is written, everything
+/// up to and including it is written to the inner stream, which is then
+/// flushed with a status of `Status::Open(Readiness::Lull)`. This gives
+/// prompt output for interactive programs without manual flushing.
+pub struct LineBufferedWriter<Inner: Write> {
+    /// The buffered wrapped byte stream.
+    inner: BufferedWriter<Inner>,
+}
+
+impl<Inner: Write> LineBufferedWriter<Inner> {
+    /// Construct a new instance of `LineBufferedWriter` wrapping `inner`,
+    /// with a default buffer capacity.
+    #[inline]
+    pub fn new(inner: Inner) -> Self {
+        Self {
+            inner: BufferedWriter::new(inner),
+        }
+    }
+
+    /// Construct a new instance of `LineBufferedWriter` wrapping `inner`,
+    /// with the given buffer capacity.
+    #[inline]
+    pub fn with_capacity(capacity: usize, inner: Inner) -> Self {
+        Self {
+            inner: BufferedWriter::with_capacity(capacity, inner),
+        }
+    }
+
+    /// Return the contents of the buffer which have not yet been written to
+    /// the inner stream.
+    #[inline]
+    pub fn buffer(&self) -> &[u8] {
+        self.inner.buffer()
+    }
+
+    /// Flush and close the underlying stream and return the underlying
+    /// stream object.
+    pub fn close_into_inner(self) -> io::Result<Inner> {
+        self.inner.close_into_inner()
+    }
+}
+
+impl<Inner: Write> Close for LineBufferedWriter<Inner> {
+    type Output = Inner;
+
+    #[inline]
+    fn close_into_inner(self) -> io::Result<Inner> {
+        LineBufferedWriter::close_into_inner(self)
+    }
+}
+
+impl<Inner: Write> Write for LineBufferedWriter<Inner> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match buf.iter().rposition(|b| *b == b'\n') {
+            Some(last_nl) => {
+                self.inner.write_all(&buf[..=last_nl])?;
+                self.inner.flush(Status::Open(Readiness::Lull))?;
+                Ok(last_nl + 1)
+            }
+            None => self.inner.write(buf),
+        }
+    }
+
+    #[inline]
+    fn flush(&mut self, status: Status) -> io::Result<()> {
+        self.inner.flush(status)
+    }
+
+    #[inline]
+    fn abandon(&mut self) {
+        self.inner.abandon()
+    }
+
+    fn write_all_utf8(&mut self, s: &str) -> io::Result<()> {
+        match s.rfind('\n') {
+            Some(last_nl) => {
+                self.inner.write_all_utf8(&s[..=last_nl])?;
+                self.inner.flush(Status::Open(Readiness::Lull))?;
+                self.inner.write_all_utf8(&s[last_nl + 1..])
+            }
+            None => self.inner.write_all_utf8(s),
+        }
+    }
+}
+
+#[cfg(unix)]
+impl<Inner: Write + AsRawFd> AsRawFd for LineBufferedWriter<Inner> {
+    #[inline]
+    fn as_raw_fd(&self) -> RawFd {
+        self.inner.as_raw_fd()
+    }
+}
+
+#[cfg(unix)]
+impl<Inner: Write + AsFd> AsFd for LineBufferedWriter<Inner> {
+    #[inline]
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.inner.as_fd()
+    }
+}
+
+#[cfg(windows)]
+impl<Inner: Write + AsRawHandle> AsRawHandle for LineBufferedWriter<Inner> {
+    #[inline]
+    fn as_raw_handle(&self) -> RawHandle {
+        self.inner.as_raw_handle()
+    }
+}
+
+#[cfg(windows)]
+impl<Inner: Write + AsHandle> AsHandle for LineBufferedWriter<Inner> {
+    #[inline]
+    fn as_handle(&self) -> BorrowedHandle<'_> {
+        self.inner.as_handle()
+    }
+}
+
+#[test]
+fn test_line_buffered_writer() {
+    let mut v = Vec::new();
+    let mut lulls = 0;
+    let mut writer = LineBufferedWriter::new(crate::InspectWriter::new(
+        crate::StdWriter::new(&mut v),
+        |_, status| {
+            if status == Status::Open(Readiness::Lull) {
+                lulls += 1;
+            }
+        },
+    ));
+    writer.write_all(b"hello").unwrap();
+    assert_eq!(writer.buffer(), b"hello");
+    writer.write_all(b" world\nhow").unwrap();
+    assert_eq!(writer.buffer(), b"how");
+    writer.write_all_utf8(" are you\n").unwrap();
+    assert!(writer.buffer().is_empty());
+    writer.close_into_inner().unwrap();
+    assert_eq!(lulls, 2);
+    assert_eq!(v, b"hello world\nhow are you\n");
+}
@@ -0,0 +1,39 @@
+//! Defines `LineTerminatorPolicy`.
+
+/// What a `TextReader` or `TextWriter` does with the Unicode line
+/// terminators other than '\n' and "\r\n": U+0085 (NEL), U+2028 (LINE
+/// SEPARATOR), and U+2029 (PARAGRAPH SEPARATOR).
+///
+/// [UAX #14] treats all of these as mandatory line breaks, so consumers
+/// which only recognize '\n' may see different lines than a display does.
+///
+/// [UAX #14]: https://unicode.org/reports/tr14/#BK
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LineTerminatorPolicy {
+    /// Treat U+0085 like other control codes, which `TextReader` replaces
+    /// and `TextWriter` rejects, and pass U+2028 and U+2029 through. This
+    /// is the default.
+    Keep,
+
+    /// Convert all of them to '\n'.
+    Normalize,
+
+    /// Fail with [`Error::LineTerminator`].
+    ///
+    /// [`Error::LineTerminator`]: crate::Error::LineTerminator
+    Reject,
+}
+
+impl Default for LineTerminatorPolicy {
+    #[inline]
+    fn default() -> Self {
+        Self::Keep
+    }
+}
+
+/// Test whether `c` is one of the line terminators governed by
+/// `LineTerminatorPolicy`.
+#[inline]
+pub(crate) fn is_unicode_line_terminator(c: char) -> bool {
+    matches!(c, '\u{85}' | '\u{2028}' | '\u{2029}')
+}
@@ -0,0 +1,163 @@
+use crate::{ReadOutcome, ReadStr, Readiness, Status};
+use std::{io, mem};
+
+/// The size of the buffer used for reading from the underlying stream.
+const CHUNK_SIZE: usize = 1024;
+
+/// An item produced by [`Lines`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Line {
+    /// A complete line, without its terminating '\n' or "\r\n".
+    Complete(String),
+
+    /// The underlying stream reported a lull, or that reading would block.
+    /// Any partial line read so far is retained, and is included in the next
+    /// `Complete` line.
+    Pending,
+}
+
+/// An iterator over the lines of a [`ReadStr`], returned from
+/// [`ReadStr::lines`].
+///
+/// When used with a `TextReader`, lines have the text guarantees already
+/// applied, and every line is complete, since input text streams always end
+/// with a '\n'. With other readers, a final line without a '\n' is produced
+/// as a `Complete` line.
+pub struct Lines<Inner: ReadStr> {
+    /// The wrapped text stream.
+    inner: Inner,
+
+    /// Text which has been read but not yet produced.
+    buffer: String,
+
+    /// The length of the prefix of `buffer` known not to contain a '\n'.
+    scanned: usize,
+
+    /// Temporary storage for reading from the underlying stream.
+    chunk: String,
+
+    /// A lull has been read but not yet reported.
+    lull: bool,
+
+    /// The underlying stream has ended.
+    ended: bool,
+}
+
+impl<Inner: ReadStr> Lines<Inner> {
+    #[inline]
+    pub(crate) fn new(inner: Inner) -> Self {
+        Self {
+            inner,
+            buffer: String::new(),
+            scanned: 0,
+            chunk: "\0".repeat(CHUNK_SIZE),
+            lull: false,
+            ended: false,
+        }
+    }
+
+    /// Consume this `Lines` and return the underlying stream object. Any
+    /// data which has been read from the underlying stream but not yet
+    /// produced is discarded.
+    #[inline]
+    pub fn into_inner(self) -> Inner {
+        self.inner
+    }
+
+    fn take_line(&mut self, end: usize, next: usize) -> Line {
+        self.scanned = 0;
+        Line::Complete(take_line(&mut self.buffer, end, next))
+    }
+}
+
+/// Remove the text up to `next` from the front of `buffer`, and return the
+/// line up to `end` without any trailing '\r'.
+pub(crate) fn take_line(buffer: &mut String, end: usize, next: usize) -> String {
+    let mut line = buffer.split_off(next);
+    mem::swap(&mut line, buffer);
+    line.truncate(end);
+    if line.ends_with('\r') {
+        line.pop();
+    }
+    line
+}
+
+impl<Inner: ReadStr> Iterator for Lines<Inner> {
+    type Item = io::Result<Line>;
+
+    fn next(&mut self) -> Option<io::Result<Line>> {
+        loop {
+            if let Some(pos) = self.buffer[self.scanned..].find('\n') {
+                let end = self.scanned + pos;
+                return Some(Ok(self.take_line(end, end + 1)));
+            }
+            self.scanned = self.buffer.len();
+
+            if self.lull {
+                self.lull = false;
+                return Some(Ok(Line::Pending));
+            }
+
+            if self.ended {
+                if self.buffer.is_empty() {
+                    return None;
+                }
+                let end = self.buffer.len();
+                return Some(Ok(self.take_line(end, end)));
+            }
+
+            match self.inner.read_str(&mut self.chunk) {
+                Ok(ReadOutcome { size, status }) => {
+                    self.buffer.push_str(&self.chunk[..size]);
+                    match status {
+                        Status::Open(Readiness::Ready) => {}
+                        Status::Open(Readiness::Lull) | Status::Open(Readiness::Pending) => {
+                            self.lull = true
+                        }
+                        Status::End => self.ended = true,
+                    }
+                }
+                Err(ref e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Some(Err(e)),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+fn collect_lines<Inner: ReadStr>(inner: Inner) -> Vec<Line> {
+    Lines::new(inner).map(Result::unwrap).collect()
+}
+
+#[test]
+fn test_lines() {
+    let reader = crate::Utf8Reader::new(crate::SliceReader::new(b"hello\r\n\nworld"));
+    assert_eq!(
+        collect_lines(reader),
+        [
+            Line::Complete("hello".to_owned()),
+            Line::Complete("".to_owned()),
+            Line::Complete("world".to_owned()),
+        ]
+    );
+}
+
+#[cfg(feature = "text")]
+#[test]
+fn test_lines_pending() {
+    use crate::testing::{ScriptedReader, Step};
+
+    let reader = crate::TextReader::new(ScriptedReader::new(vec![
+        Step::Data(b"hello\nwor".to_vec()),
+        Step::Lull,
+        Step::Data(b"ld".to_vec()),
+    ]));
+    assert_eq!(
+        collect_lines(reader),
+        [
+            Line::Complete("hello".to_owned()),
+            Line::Pending,
+            Line::Complete("world".to_owned()),
+        ]
+    );
+}
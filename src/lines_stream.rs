@@ -0,0 +1,128 @@
+use crate::{lines::take_line, AsyncTextReader, Status};
+use futures_core::Stream;
+use futures_io::AsyncRead;
+use std::{
+    io,
+    pin::Pin,
+    str,
+    task::{Context, Poll},
+};
+
+/// The size of the buffer used for reading from the underlying stream.
+const CHUNK_SIZE: usize = 1024;
+
+/// A [`Stream`] over the lines of an [`AsyncTextReader`], returned from
+/// [`AsyncTextReader::into_lines_stream`].
+///
+/// Lines have the text guarantees already applied, and are produced without
+/// their terminating '\n'. Lulls are read through, with any partial line
+/// retained until the rest of it arrives.
+///
+/// [`Stream`]: https://docs.rs/futures-core/latest/futures_core/stream/trait.Stream.html
+pub struct LinesStream<Inner: AsyncRead + Unpin> {
+    /// The wrapped text stream.
+    inner: AsyncTextReader<Inner>,
+
+    /// Text which has been read but not yet produced.
+    buffer: String,
+
+    /// The length of the prefix of `buffer` known not to contain a '\n'.
+    scanned: usize,
+
+    /// Temporary storage for reading from the underlying stream.
+    chunk: Box<[u8]>,
+
+    /// The underlying stream has ended.
+    ended: bool,
+}
+
+impl<Inner: AsyncRead + Unpin> LinesStream<Inner> {
+    #[inline]
+    pub(crate) fn new(inner: AsyncTextReader<Inner>) -> Self {
+        Self {
+            inner,
+            buffer: String::new(),
+            scanned: 0,
+            chunk: vec![0; CHUNK_SIZE].into_boxed_slice(),
+            ended: false,
+        }
+    }
+
+    /// Consume this `LinesStream` and return the underlying stream object.
+    /// Any data which has been read from the underlying stream but not yet
+    /// produced is discarded.
+    #[inline]
+    pub fn into_inner(self) -> AsyncTextReader<Inner> {
+        self.inner
+    }
+}
+
+impl<Inner: AsyncRead + Unpin> Stream for LinesStream<Inner> {
+    type Item = io::Result<String>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        loop {
+            if let Some(pos) = this.buffer[this.scanned..].find('\n') {
+                let end = this.scanned + pos;
+                this.scanned = 0;
+                return Poll::Ready(Some(Ok(take_line(&mut this.buffer, end, end + 1))));
+            }
+            this.scanned = this.buffer.len();
+
+            if this.ended {
+                if this.buffer.is_empty() {
+                    return Poll::Ready(None);
+                }
+                let end = this.buffer.len();
+                this.scanned = 0;
+                return Poll::Ready(Some(Ok(take_line(&mut this.buffer, end, end))));
+            }
+
+            match Pin::new(&mut this.inner).poll_read_outcome(cx, &mut this.chunk) {
+                Poll::Ready(Ok(outcome)) => {
+                    // `TextReader` doesn't split scalar values in buffers
+                    // this size.
+                    this.buffer
+                        .push_str(str::from_utf8(&this.chunk[..outcome.size]).unwrap());
+                    this.ended = outcome.status == Status::End;
+                }
+                Poll::Ready(Err(e)) if e.kind() == io::ErrorKind::Interrupted => {}
+                Poll::Ready(Err(e)) => return Poll::Ready(Some(Err(e))),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
+#[test]
+fn test_lines_stream() {
+    use crate::async_utf8_reader::Chunked;
+    use std::{sync::Arc, task::Wake};
+
+    struct NoopWaker;
+    impl Wake for NoopWaker {
+        fn wake(self: Arc<Self>) {}
+    }
+    let waker = Arc::new(NoopWaker).into();
+    let mut cx = Context::from_waker(&waker);
+
+    let reader = AsyncTextReader::new(Chunked::new(&[
+        b"hello\r",
+        b"\nwor",
+        b"ld\x1b[1m!\ne",
+        b"\xcc\x81",
+    ]));
+    let mut lines = reader.into_lines_stream();
+    let mut output = Vec::new();
+    let mut pendings = 0;
+    loop {
+        match Pin::new(&mut lines).poll_next(&mut cx) {
+            Poll::Ready(Some(line)) => output.push(line.unwrap()),
+            Poll::Ready(None) => break,
+            Poll::Pending => pendings += 1,
+        }
+    }
+    assert_eq!(output, ["hello", "world!", "\u{e9}"]);
+    assert!(pendings > 0);
+}
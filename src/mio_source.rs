@@ -0,0 +1,110 @@
+//! Implementations of `mio::event::Source` for the fd-backed types and the
+//! layers over them, so that a stack of layers can be registered with a
+//! `mio::Poll` directly.
+//!
+//! mio's readiness events are edge-triggered: once an event is delivered,
+//! another isn't delivered until the fd becomes readable again after a
+//! read would block. Layers may also hold data which has already been read
+//! from the fd. So after a readable event, keep reading until a read
+//! reports `Readiness::Pending`, which means the fd has been drained and
+//! the next event re-arms it; `Readiness::Ready` and `Readiness::Lull` say
+//! nothing about the fd, and `Status::End` means there's nothing more to
+//! wait for. The fd must be in non-blocking mode, and a `StdReader` over it
+//! must be constructed with `StdReaderOptions::nonblocking`.
+//!
+//! Similarly, after a writable event, keep writing until a write fails with
+//! `io::ErrorKind::WouldBlock`.
+
+use crate::{
+    BufferedReader, BufferedWriter, LineBufferedWriter, Read, StdReader, StdTcpStream,
+    StdUnixStream, StdWriter, Utf8Reader, Utf8Writer, Write,
+};
+use mio::{event::Source, unix::SourceFd, Interest, Registry, Token};
+use std::{io, os::unix::io::AsRawFd};
+
+macro_rules! impl_source {
+    ($([$($generics:tt)*] $ty:ty),* $(,)?) => {$(
+        impl<$($generics)*> Source for $ty {
+            #[inline]
+            fn register(
+                &mut self,
+                registry: &Registry,
+                token: Token,
+                interests: Interest,
+            ) -> io::Result<()> {
+                SourceFd(&self.as_raw_fd()).register(registry, token, interests)
+            }
+
+            #[inline]
+            fn reregister(
+                &mut self,
+                registry: &Registry,
+                token: Token,
+                interests: Interest,
+            ) -> io::Result<()> {
+                SourceFd(&self.as_raw_fd()).reregister(registry, token, interests)
+            }
+
+            #[inline]
+            fn deregister(&mut self, registry: &Registry) -> io::Result<()> {
+                SourceFd(&self.as_raw_fd()).deregister(registry)
+            }
+        }
+    )*};
+}
+
+impl_source!(
+    [Inner: io::Read + AsRawFd] StdReader<Inner>,
+    [Inner: io::Write + AsRawFd] StdWriter<Inner>,
+    [] StdTcpStream,
+    [] StdUnixStream,
+    [Inner: Read + AsRawFd] BufferedReader<Inner>,
+    [Inner: Write + AsRawFd] BufferedWriter<Inner>,
+    [Inner: Write + AsRawFd] LineBufferedWriter<Inner>,
+    [Inner: Read + AsRawFd] Utf8Reader<Inner>,
+    [Inner: Write + AsRawFd] Utf8Writer<Inner>,
+);
+
+#[cfg(feature = "text")]
+impl_source!(
+    [Inner: Read + AsRawFd] crate::TextReader<Inner>,
+    [Inner: Write + AsRawFd] crate::TextWriter<Inner>,
+);
+
+#[cfg(feature = "pty")]
+impl_source!([] crate::PtyMaster);
+
+#[cfg(feature = "text")]
+#[test]
+fn test_poll_text_reader() -> io::Result<()> {
+    use crate::{Readiness, Status, StdReaderOptions, TextReader};
+    use mio::{Events, Poll};
+    use std::{io::Write as _, os::unix::net::UnixStream, str};
+
+    let (mut client, server) = UnixStream::pair()?;
+    server.set_nonblocking(true)?;
+    let mut reader = TextReader::new(StdReaderOptions::new().nonblocking(true).build(server));
+    let mut poll = Poll::new()?;
+    let mut events = Events::with_capacity(4);
+    poll.registry()
+        .register(&mut reader, Token(0), Interest::READABLE)?;
+
+    let mut output = String::new();
+    let mut buf = [0_u8; crate::NORMALIZATION_BUFFER_SIZE];
+    for chunk in [&b"hello\r"[..], b"\nwor", b"ld\n"] {
+        client.write_all(chunk)?;
+        poll.poll(&mut events, None)?;
+        assert_eq!(events.iter().next().unwrap().token(), Token(0));
+        loop {
+            let outcome = reader.read_outcome(&mut buf)?;
+            output.push_str(str::from_utf8(&buf[..outcome.size]).unwrap());
+            if outcome.status == Status::Open(Readiness::Pending) {
+                break;
+            }
+        }
+    }
+    assert_eq!(output, "hello\nworld\n");
+
+    poll.registry().deregister(&mut reader)?;
+    Ok(())
+}
@@ -0,0 +1,35 @@
+//! Defines `NewlinePolicy`.
+
+/// How a `TextReader` translates line endings involving '\r'.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NewlinePolicy {
+    /// Convert "\r\n" to '\n', and replace a '\r' not followed by '\n' like
+    /// other control codes. This is the default.
+    ConvertCrlf,
+
+    /// Convert "\r\n" to '\n', and also convert a '\r' not followed by '\n'
+    /// to '\n', for input from classic Mac OS and devices which end lines
+    /// with '\r' alone.
+    ///
+    /// A '\r' is converted as soon as it's seen, and a '\n' immediately
+    /// following it is then dropped, so "\r\n" is still a single newline
+    /// even when a lull separates the '\r' from the '\n'.
+    ConvertCr,
+
+    /// Leave "\r\n" intact, and replace a '\r' not followed by '\n' like
+    /// other control codes, for round-tripping files whose line endings
+    /// must not change, such as in patch tools. UTF-8 repair,
+    /// normalization, and control-code filtering are still applied.
+    ///
+    /// The output is then no longer plain text in the sense of the rest of
+    /// this crate, since it may contain '\r'. As with `ConvertCrlf`, a
+    /// '\r' followed by a lull is replaced.
+    PreserveCrlf,
+}
+
+impl Default for NewlinePolicy {
+    #[inline]
+    fn default() -> Self {
+        Self::ConvertCrlf
+    }
+}
@@ -0,0 +1,39 @@
+//! Defines `NonStarterPolicy`.
+
+/// What a `TextReader` or `TextWriter` does when the data at the beginning
+/// of a stream, or after a lull, begins with a Unicode Normalization Form
+/// non-starter, such as a combining mark.
+///
+/// In interactive streams, lulls can occur in the middle of a line, so a
+/// combining mark typed after a lull may be a legitimate part of the text
+/// before it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NonStarterPolicy {
+    /// Fail with [`Error::NonStarter`]. This is the default for
+    /// `TextWriter`.
+    ///
+    /// [`Error::NonStarter`]: crate::Error::NonStarter
+    Reject,
+
+    /// Replace the non-starter with U+FFFD, or the configured replacement.
+    /// This is the default for `TextReader`.
+    Replace,
+
+    /// Hold back the last normalization segment before a lull, from its
+    /// starter onward, until more data arrives, so that any non-starters
+    /// which follow are combined with it and normalized in order. If there
+    /// is no such segment, because the data before the lull ended with a
+    /// control code such as '\n', or because the stream is just starting,
+    /// the non-starter is replaced, as with `Replace`.
+    ///
+    /// `TextReader` holds the segment back at lulls and at
+    /// `Readiness::Pending`. `TextWriter` always holds back the last segment
+    /// of each write until the next write; with this policy, it also keeps
+    /// it across flushes with `Readiness::Pending`, releasing it only at a
+    /// lull or the end of the stream.
+    ///
+    /// This delays the last few scalar values before a lull, which is
+    /// visible on interactive streams that lull in the middle of a line,
+    /// such as after a prompt.
+    Buffer,
+}
@@ -0,0 +1,76 @@
+//! Defines `NormalizationForm` and the `Normalize` iterator.
+
+use unicode_normalization::{Decompositions, Recompositions, StreamSafe, UnicodeNormalization};
+
+/// The [Unicode Normalization Forms] which `TextReader` and `TextWriter`
+/// can transform text to.
+///
+/// [Unicode Normalization Forms]: https://unicode.org/reports/tr15/#Norm_Forms
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NormalizationForm {
+    /// Normalization Form C, canonical composition. This is the default,
+    /// and is what most consumers expect.
+    Nfc,
+
+    /// Normalization Form D, canonical decomposition, as used by some
+    /// filesystems, such as HFS+ on macOS.
+    Nfd,
+
+    /// Normalization Form KC, compatibility composition, which folds
+    /// compatibility variants such as ligatures and full-width forms, for
+    /// consumers comparing identifiers.
+    Nfkc,
+
+    /// Normalization Form KD, compatibility decomposition.
+    Nfkd,
+
+    /// No normalization, and no Stream-Safe Text Process, so that the
+    /// input's scalar values are preserved exactly, for consumers such as
+    /// diff and patch tools. Control codes, escape sequences, newlines, and
+    /// `ForbiddenCharacters` are still sanitized, and leading non-starters are still handled
+    /// according to the `NonStarterPolicy`.
+    Passthrough,
+}
+
+impl Default for NormalizationForm {
+    #[inline]
+    fn default() -> Self {
+        Self::Nfc
+    }
+}
+
+/// An iterator which applies the Stream-Safe Text Process (UAX15-D4) and
+/// then transforms to a `NormalizationForm`, or passes scalar values through
+/// unchanged for `NormalizationForm::Passthrough`.
+pub(crate) enum Normalize<Inner: Iterator<Item = char>> {
+    Nfc(Recompositions<StreamSafe<Inner>>),
+    Nfd(Decompositions<StreamSafe<Inner>>),
+    Nfkc(Recompositions<StreamSafe<Inner>>),
+    Nfkd(Decompositions<StreamSafe<Inner>>),
+    Passthrough(Inner),
+}
+
+impl<Inner: Iterator<Item = char>> Normalize<Inner> {
+    pub(crate) fn new(inner: Inner, form: NormalizationForm) -> Self {
+        match form {
+            NormalizationForm::Nfc => Self::Nfc(inner.stream_safe().nfc()),
+            NormalizationForm::Nfd => Self::Nfd(inner.stream_safe().nfd()),
+            NormalizationForm::Nfkc => Self::Nfkc(inner.stream_safe().nfkc()),
+            NormalizationForm::Nfkd => Self::Nfkd(inner.stream_safe().nfkd()),
+            NormalizationForm::Passthrough => Self::Passthrough(inner),
+        }
+    }
+}
+
+impl<Inner: Iterator<Item = char>> Iterator for Normalize<Inner> {
+    type Item = char;
+
+    #[inline]
+    fn next(&mut self) -> Option<char> {
+        match self {
+            Self::Nfc(iter) | Self::Nfkc(iter) => iter.next(),
+            Self::Nfd(iter) | Self::Nfkd(iter) => iter.next(),
+            Self::Passthrough(iter) => iter.next(),
+        }
+    }
+}
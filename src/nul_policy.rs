@@ -0,0 +1,21 @@
+//! Defines `NulPolicy`.
+
+/// What a `TextReader` or `TextWriter` does with U+0000 (NUL).
+///
+/// NUL in text usually means the data is actually binary, so consumers
+/// may prefer to stop rather than receive replacement characters.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NulPolicy {
+    /// Fail with [`Error::ControlCode`]. This is the default for
+    /// `TextWriter`.
+    ///
+    /// [`Error::ControlCode`]: crate::Error::ControlCode
+    Reject,
+
+    /// Replace NUL with U+FFFD, or the configured replacement, like other
+    /// control codes. This is the default for `TextReader`.
+    Replace,
+
+    /// Remove NUL.
+    Strip,
+}
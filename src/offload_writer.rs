@@ -0,0 +1,307 @@
+use crate::{Close, Error, Readiness, Status, Write};
+use std::{
+    io, mem,
+    panic::resume_unwind,
+    sync::{
+        mpsc::{sync_channel, Receiver, SyncSender},
+        Arc, Mutex,
+    },
+    thread::{self, JoinHandle},
+};
+
+/// The size at which an `OffloadWriter` sends its buffer to the worker
+/// without waiting for a flush.
+const CHUNK_SIZE: usize = 8192;
+
+/// The default number of chunks which may be queued for the worker before
+/// writes block.
+const DEFAULT_CAPACITY: usize = 16;
+
+/// A request sent to the worker thread.
+enum Message {
+    /// Write the data, and then flush with the status.
+    Write(Vec<u8>, Status),
+
+    /// Abandon the inner stream.
+    Abandon,
+}
+
+/// A `Write` implementation which moves an inner `Write`, and all the work
+/// it does, to a worker thread.
+///
+/// This is useful for wrapping a `TextWriter`, so that latency-sensitive
+/// producers, such as a REPL loop, aren't blocked by the normalization of a
+/// large paste, or by slow output.
+///
+/// Written data is buffered, and sent to the worker as a chunk when the
+/// buffer fills or when flushed. Only a bounded number of chunks may be
+/// queued, after which writes block until the worker catches up, so a fast
+/// producer can't run arbitrarily far ahead of the output.
+///
+/// Since the inner stream is written to asynchronously, errors from it,
+/// such as a `TextWriter` rejecting a control code, are reported by a later
+/// write or flush, and by the flush with `Status::End` at the latest, which
+/// waits for the worker to finish. After an error, the inner stream is
+/// abandoned.
+///
+/// If an `OffloadWriter` is dropped without being ended, it waits for the
+/// worker to write out what has been sent, and then drops the inner stream
+/// on the current thread, so that the inner stream's own drop behavior,
+/// such as `TextWriter`'s `DropPolicy`, applies as usual.
+pub struct OffloadWriter<Inner: Write + Send + 'static> {
+    /// The channel to the worker, or `None` if the stream has ended.
+    sender: Option<SyncSender<Message>>,
+
+    /// The worker thread, which returns the inner stream when it finishes.
+    worker: Option<JoinHandle<Inner>>,
+
+    /// The first error reported by the inner stream.
+    error: Arc<Mutex<Option<io::Error>>>,
+
+    /// Data which has been written but not yet sent.
+    buf: Vec<u8>,
+
+    /// The inner stream, once the worker has finished.
+    inner: Option<Inner>,
+}
+
+impl<Inner: Write + Send + 'static> OffloadWriter<Inner> {
+    /// Construct a new instance of `OffloadWriter` which moves `inner` to a
+    /// new worker thread.
+    #[inline]
+    pub fn new(inner: Inner) -> Self {
+        Self::with_capacity(inner, DEFAULT_CAPACITY)
+    }
+
+    /// Like `new`, but allow up to `capacity` chunks to be queued for the
+    /// worker before writes block.
+    pub fn with_capacity(inner: Inner, capacity: usize) -> Self {
+        let (sender, receiver) = sync_channel(capacity);
+        let error = Arc::new(Mutex::new(None));
+        let worker_error = Arc::clone(&error);
+        let worker = thread::spawn(move || run(inner, receiver, &worker_error));
+        Self {
+            sender: Some(sender),
+            worker: Some(worker),
+            error,
+            buf: Vec::new(),
+            inner: None,
+        }
+    }
+
+    /// Flush and close the underlying stream, waiting for the worker to
+    /// finish, and return the underlying stream object.
+    pub fn close_into_inner(mut self) -> io::Result<Inner> {
+        self.flush(Status::End)?;
+        Ok(self.inner.take().unwrap())
+    }
+
+    /// Send the buffered data to the worker, to be followed by a flush with
+    /// `status`.
+    fn send(&mut self, status: Status) -> io::Result<()> {
+        let sender = self.sender.as_ref().ok_or_else(stream_already_ended)?;
+        let message = Message::Write(mem::take(&mut self.buf), status);
+        if sender.send(message).is_err() {
+            // The worker has stopped, which it only does after an error.
+            return Err(self.fail());
+        }
+        if let Some(err) = self.take_error() {
+            self.finish();
+            return Err(err);
+        }
+        Ok(())
+    }
+
+    /// Wait for the worker to stop after an error, and return the error.
+    fn fail(&mut self) -> io::Error {
+        self.finish();
+        self.take_error().unwrap_or_else(|| {
+            io::Error::new(io::ErrorKind::BrokenPipe, "offload worker has stopped")
+        })
+    }
+
+    /// Take the error reported by the inner stream, if any.
+    fn take_error(&self) -> Option<io::Error> {
+        self.error.lock().unwrap().take()
+    }
+
+    /// Close the channel and wait for the worker to finish.
+    fn finish(&mut self) {
+        self.sender = None;
+        if let Some(worker) = self.worker.take() {
+            match worker.join() {
+                Ok(inner) => self.inner = Some(inner),
+                Err(payload) if !thread::panicking() => resume_unwind(payload),
+                Err(_) => {}
+            }
+        }
+    }
+}
+
+impl<Inner: Write + Send + 'static> Close for OffloadWriter<Inner> {
+    type Output = Inner;
+
+    #[inline]
+    fn close_into_inner(self) -> io::Result<Inner> {
+        OffloadWriter::close_into_inner(self)
+    }
+}
+
+impl<Inner: Write + Send + 'static> Write for OffloadWriter<Inner> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.sender.is_none() {
+            return Err(stream_already_ended());
+        }
+        self.buf.extend_from_slice(buf);
+        if self.buf.len() >= CHUNK_SIZE {
+            self.send(Status::ready())?;
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self, status: Status) -> io::Result<()> {
+        if status == Status::ready() && self.buf.is_empty() && self.sender.is_some() {
+            return Ok(());
+        }
+        self.send(status)?;
+        if status.is_end() {
+            self.finish();
+            if let Some(err) = self.take_error() {
+                return Err(err);
+            }
+        }
+        Ok(())
+    }
+
+    fn abandon(&mut self) {
+        self.buf.clear();
+        if let Some(sender) = self.sender.take() {
+            let _ = sender.send(Message::Abandon);
+        }
+        // Let the worker finish on its own.
+        self.worker = None;
+    }
+
+    fn write_outcome(&mut self, buf: &[u8], status: Status) -> io::Result<()> {
+        if self.sender.is_none() {
+            return Err(stream_already_ended());
+        }
+        // Send the data as a single chunk along with the status.
+        self.buf.extend_from_slice(buf);
+        match status {
+            Status::Open(Readiness::Ready) if self.buf.len() < CHUNK_SIZE => Ok(()),
+            _ => self.flush(status),
+        }
+    }
+}
+
+impl<Inner: Write + Send + 'static> Drop for OffloadWriter<Inner> {
+    fn drop(&mut self) {
+        if self.sender.is_some() {
+            if !self.buf.is_empty() {
+                let _ = self.send(Status::ready());
+            }
+            self.finish();
+        }
+    }
+}
+
+/// The body of the worker thread.
+fn run<Inner: Write>(
+    mut inner: Inner,
+    receiver: Receiver<Message>,
+    error: &Mutex<Option<io::Error>>,
+) -> Inner {
+    for message in receiver {
+        match message {
+            Message::Write(data, status) => {
+                if let Err(err) = inner.write_outcome(&data, status) {
+                    inner.abandon();
+                    *error.lock().unwrap() = Some(err);
+                    break;
+                }
+            }
+            Message::Abandon => {
+                inner.abandon();
+                break;
+            }
+        }
+    }
+    inner
+}
+
+fn stream_already_ended() -> io::Error {
+    Error::StreamEnded.into()
+}
+
+#[cfg(feature = "text")]
+#[test]
+fn test_offload_writer() {
+    use crate::{StdWriter, TextWriter};
+
+    let mut writer = OffloadWriter::new(TextWriter::new(StdWriter::new(Vec::<u8>::new())));
+    writer.write_all(b"hello e\xcc\x81\n").unwrap();
+    writer.flush(Status::Open(Readiness::Lull)).unwrap();
+    writer.write_all_utf8(&"paste\n".repeat(10000)).unwrap();
+    let inner = writer.close_into_inner().unwrap();
+    assert_eq!(
+        inner.get_ref().get_ref(),
+        &format!("hello \u{e9}\n{}", "paste\n".repeat(10000)).into_bytes()
+    );
+}
+
+#[cfg(feature = "text")]
+#[test]
+fn test_offload_writer_error() {
+    use crate::{StdWriter, TextWriter};
+
+    let mut writer = OffloadWriter::new(TextWriter::new(StdWriter::new(Vec::<u8>::new())));
+    writer.write_all_utf8("hello\x07\n").unwrap();
+    let err = writer.flush(Status::End).unwrap_err();
+    assert_eq!(Error::from_io_error(&err), Some(&Error::InvalidScalarValue));
+    assert!(writer.write_all(b"more\n").is_err());
+}
+
+#[test]
+fn test_offload_writer_backpressure() {
+    use std::sync::mpsc::channel;
+
+    // A writer which reports each write and blocks until it's released.
+    struct Gate(std::sync::mpsc::Sender<()>, Arc<Mutex<()>>);
+    impl Write for Gate {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.send(()).unwrap();
+            let _guard = self.1.lock().unwrap();
+            Ok(buf.len())
+        }
+        fn flush(&mut self, _status: Status) -> io::Result<()> {
+            Ok(())
+        }
+        fn abandon(&mut self) {}
+    }
+
+    let (sender, receiver) = channel();
+    let gate = Arc::new(Mutex::new(()));
+    let guard = gate.lock().unwrap();
+    let mut writer = OffloadWriter::with_capacity(Gate(sender, Arc::clone(&gate)), 1);
+
+    // The worker takes the first chunk and blocks; one more can be queued.
+    writer
+        .write_outcome(b"one", Status::Open(Readiness::Lull))
+        .unwrap();
+    receiver.recv().unwrap();
+    writer
+        .write_outcome(b"two", Status::Open(Readiness::Lull))
+        .unwrap();
+    let producer = thread::spawn(move || {
+        writer
+            .write_outcome(b"three", Status::Open(Readiness::Lull))
+            .unwrap();
+        writer.close_into_inner().unwrap();
+    });
+    thread::sleep(std::time::Duration::from_millis(20));
+    assert!(!producer.is_finished());
+    drop(guard);
+    producer.join().unwrap();
+    assert_eq!(receiver.iter().count(), 2);
+}
@@ -0,0 +1,312 @@
+//! In-memory pipes.
+
+use crate::{Error, Read, ReadOutcome, Readiness, Status, Write};
+use std::{
+    cmp::min,
+    collections::VecDeque,
+    io,
+    sync::{Arc, Condvar, Mutex, MutexGuard},
+};
+
+/// Create a connected in-memory pipe, returning its reading and writing
+/// ends.
+///
+/// Data written to the `PipeWriter` is read from the `PipeReader`. When the
+/// writer is flushed with `Status::Open(Readiness::Lull)`, the reader reports
+/// a lull once it has read all the data written before the flush. When the
+/// writer is flushed with `Status::End` or dropped, the reader reports
+/// `Status::End` once it has read all the data.
+///
+/// Reads on an empty pipe wait until the writer writes more data, declares
+/// a lull, or ends the stream. Writes never wait; the pipe's buffer grows as
+/// needed.
+pub fn pipe() -> (PipeReader, PipeWriter) {
+    let shared = Arc::new(Shared {
+        state: Mutex::new(State {
+            buf: VecDeque::new(),
+            read_total: 0,
+            written_total: 0,
+            lulls: VecDeque::new(),
+            ended: false,
+            reader_gone: false,
+        }),
+        cond: Condvar::new(),
+    });
+    (
+        PipeReader {
+            shared: Arc::clone(&shared),
+        },
+        PipeWriter { shared },
+    )
+}
+
+/// The reading end of a pipe created by [`pipe`].
+pub struct PipeReader {
+    shared: Arc<Shared>,
+}
+
+/// The writing end of a pipe created by [`pipe`].
+pub struct PipeWriter {
+    shared: Arc<Shared>,
+}
+
+struct Shared {
+    state: Mutex<State>,
+    cond: Condvar,
+}
+
+struct State {
+    /// Data which has been written but not yet read.
+    buf: VecDeque<u8>,
+
+    /// The total number of bytes read.
+    read_total: u64,
+
+    /// The total number of bytes written.
+    written_total: u64,
+
+    /// The values of `written_total` at each lull which has not yet been
+    /// reported to the reader.
+    lulls: VecDeque<u64>,
+
+    /// The writer has ended the stream, or has been dropped.
+    ended: bool,
+
+    /// The reader has been dropped.
+    reader_gone: bool,
+}
+
+impl Shared {
+    fn lock(&self) -> MutexGuard<'_, State> {
+        // The state is always consistent, so recover from poisoning.
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Read for PipeReader {
+    fn read_outcome(&mut self, buf: &mut [u8]) -> io::Result<ReadOutcome> {
+        let mut state = self.shared.lock();
+        if buf.is_empty() {
+            return Ok(ReadOutcome::ready_or_not(
+                0,
+                !state.ended || !state.buf.is_empty(),
+            ));
+        }
+
+        while state.buf.is_empty() && !state.ended && state.lulls.front() != Some(&state.read_total)
+        {
+            state = self
+                .shared
+                .cond
+                .wait(state)
+                .unwrap_or_else(|e| e.into_inner());
+        }
+
+        // Don't read past the next lull.
+        let mut size = min(buf.len(), state.buf.len());
+        if let Some(lull) = state.lulls.front() {
+            size = min(size, (lull - state.read_total) as usize);
+        }
+        for (dst, src) in buf.iter_mut().zip(state.buf.drain(..size)) {
+            *dst = src;
+        }
+        state.read_total += size as u64;
+
+        if state.lulls.front() == Some(&state.read_total) {
+            state.lulls.pop_front();
+            Ok(ReadOutcome::lull(size))
+        } else if state.ended && state.buf.is_empty() {
+            Ok(ReadOutcome::end(size))
+        } else {
+            Ok(ReadOutcome::ready(size))
+        }
+    }
+}
+
+impl io::Read for PipeReader {
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        Read::read(self, buf)
+    }
+
+    #[inline]
+    fn read_vectored(&mut self, bufs: &mut [io::IoSliceMut<'_>]) -> io::Result<usize> {
+        Read::read_vectored(self, bufs)
+    }
+
+    #[cfg(feature = "nightly")]
+    #[inline]
+    fn is_read_vectored(&self) -> bool {
+        Read::is_read_vectored(self)
+    }
+
+    #[inline]
+    fn read_to_end(&mut self, buf: &mut Vec<u8>) -> io::Result<usize> {
+        Read::read_to_end(self, buf)
+    }
+
+    #[inline]
+    fn read_to_string(&mut self, buf: &mut String) -> io::Result<usize> {
+        Read::read_to_string(self, buf)
+    }
+
+    #[inline]
+    fn read_exact(&mut self, buf: &mut [u8]) -> io::Result<()> {
+        Read::read_exact(self, buf)
+    }
+}
+
+impl Drop for PipeReader {
+    fn drop(&mut self) {
+        self.shared.lock().reader_gone = true;
+    }
+}
+
+impl State {
+    fn write(&mut self, buf: &[u8]) -> io::Result<()> {
+        if self.ended {
+            return Err(stream_already_ended());
+        }
+        if self.reader_gone {
+            return Err(io::Error::new(
+                io::ErrorKind::BrokenPipe,
+                "pipe reader has been dropped",
+            ));
+        }
+        self.buf.extend(buf);
+        self.written_total += buf.len() as u64;
+        Ok(())
+    }
+
+    fn flush(&mut self, status: Status) -> io::Result<()> {
+        if self.ended {
+            return Err(stream_already_ended());
+        }
+        match status {
+            Status::Open(Readiness::Ready) | Status::Open(Readiness::Pending) => {}
+            Status::Open(Readiness::Lull) => {
+                let written_total = self.written_total;
+                if self.lulls.back() != Some(&written_total) {
+                    self.lulls.push_back(written_total);
+                }
+            }
+            Status::End => self.ended = true,
+        }
+        Ok(())
+    }
+}
+
+impl Write for PipeWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.shared.lock().write(buf)?;
+        self.shared.cond.notify_one();
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self, status: Status) -> io::Result<()> {
+        self.shared.lock().flush(status)?;
+        self.shared.cond.notify_one();
+        Ok(())
+    }
+
+    fn write_outcome(&mut self, buf: &[u8], status: Status) -> io::Result<()> {
+        // Hold the lock across both, so that the reader doesn't see the data
+        // without the status.
+        let mut state = self.shared.lock();
+        state.write(buf)?;
+        state.flush(status)?;
+        self.shared.cond.notify_one();
+        Ok(())
+    }
+
+    fn abandon(&mut self) {
+        let mut state = self.shared.lock();
+        state.ended = true;
+        self.shared.cond.notify_one();
+    }
+}
+
+impl Drop for PipeWriter {
+    fn drop(&mut self) {
+        self.abandon()
+    }
+}
+
+fn stream_already_ended() -> io::Error {
+    Error::StreamEnded.into()
+}
+
+#[test]
+fn test_pipe() {
+    let (mut reader, mut writer) = pipe();
+    writer.write_all(b"hello").unwrap();
+    writer.flush(Status::Open(Readiness::Lull)).unwrap();
+    writer.write_all(b" world").unwrap();
+    writer.flush(Status::End).unwrap();
+
+    let mut buf = [0; 16];
+    let outcome = reader.read_outcome(&mut buf).unwrap();
+    assert_eq!(&buf[..outcome.size], b"hello");
+    assert_eq!(outcome.status, Status::Open(Readiness::Lull));
+    let outcome = reader.read_outcome(&mut buf).unwrap();
+    assert_eq!(&buf[..outcome.size], b" world");
+    assert_eq!(outcome.status, Status::End);
+}
+
+#[test]
+fn test_pipe_threads() {
+    let (mut reader, mut writer) = pipe();
+    let thread = std::thread::spawn(move || {
+        for _ in 0..100 {
+            writer.write_all(b"hello world\n").unwrap();
+        }
+    });
+    let mut s = String::new();
+    reader.read_to_string(&mut s).unwrap();
+    thread.join().unwrap();
+    assert_eq!(s, "hello world\n".repeat(100));
+}
+
+#[test]
+fn test_pipe_reader_dropped() {
+    let (reader, mut writer) = pipe();
+    drop(reader);
+    assert_eq!(
+        writer.write(b"hello").unwrap_err().kind(),
+        io::ErrorKind::BrokenPipe
+    );
+}
+
+#[test]
+fn test_pipe_write_outcome() {
+    let (mut reader, mut writer) = pipe();
+    writer
+        .write_outcome(b"hello", Status::Open(Readiness::Lull))
+        .unwrap();
+    writer.write_outcome(b" world", Status::End).unwrap();
+
+    let mut buf = [0; 16];
+    let outcome = reader.read_outcome(&mut buf).unwrap();
+    assert_eq!(&buf[..outcome.size], b"hello");
+    assert_eq!(outcome.status, Status::Open(Readiness::Lull));
+    let outcome = reader.read_outcome(&mut buf).unwrap();
+    assert_eq!(&buf[..outcome.size], b" world");
+    assert_eq!(outcome.status, Status::End);
+}
+
+#[test]
+fn test_pipe_end_and_lull() {
+    let (mut reader, mut writer) = pipe();
+    writer.write_all(b"hello").unwrap();
+    writer.lull().unwrap();
+    writer.write_all(b" world").unwrap();
+    writer.end().unwrap();
+
+    let mut buf = [0; 16];
+    let outcome = reader.read_outcome(&mut buf).unwrap();
+    assert_eq!(&buf[..outcome.size], b"hello");
+    assert_eq!(outcome.status, Status::Open(Readiness::Lull));
+    let outcome = reader.read_outcome(&mut buf).unwrap();
+    assert_eq!(&buf[..outcome.size], b" world");
+    assert_eq!(outcome.status, Status::End);
+}
@@ -0,0 +1,173 @@
+use crate::{Read, ReadOutcome, ReadStr};
+use std::io;
+
+/// A location within a stream, as tracked by a `PositionedReader`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Position {
+    /// The number of bytes before this position.
+    pub byte: u64,
+
+    /// The number of scalar values before this position.
+    pub char: u64,
+
+    /// The line number of this position, starting at 1.
+    pub line: u64,
+
+    /// The column number of this position, in scalar values, starting at 1.
+    pub column: u64,
+}
+
+impl Default for Position {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            byte: 0,
+            char: 0,
+            line: 1,
+            column: 1,
+        }
+    }
+}
+
+/// A `Read` implementation which passes data through from an inner `Read`
+/// unmodified, tracking the position, as a byte offset, scalar value
+/// offset, line, and column, just past the data read so far, so that
+/// parsers can report precise locations in error messages.
+///
+/// Scalar value offsets and columns are only meaningful when the inner
+/// stream produces valid UTF-8, such as when wrapping a `Utf8Reader` or
+/// `TextReader`.
+pub struct PositionedReader<Inner: Read> {
+    /// The wrapped byte stream.
+    inner: Inner,
+
+    /// The position just past the data read so far.
+    position: Position,
+}
+
+impl<Inner: Read> PositionedReader<Inner> {
+    /// Construct a new instance of `PositionedReader` wrapping `inner`.
+    #[inline]
+    pub fn new(inner: Inner) -> Self {
+        Self {
+            inner,
+            position: Position::default(),
+        }
+    }
+
+    /// Return the position just past the data read so far.
+    #[inline]
+    pub fn position(&self) -> Position {
+        self.position
+    }
+
+    /// Consume this `PositionedReader` and return the underlying stream
+    /// object.
+    #[inline]
+    pub fn into_inner(self) -> Inner {
+        self.inner
+    }
+
+    fn advance(&mut self, buf: &[u8]) {
+        let position = &mut self.position;
+        position.byte += buf.len() as u64;
+        for b in buf {
+            // Count every byte that isn't a UTF-8 continuation byte.
+            if (b & 0xc0) != 0x80 {
+                position.char += 1;
+                if *b == b'\n' {
+                    position.line += 1;
+                    position.column = 1;
+                } else {
+                    position.column += 1;
+                }
+            }
+        }
+    }
+}
+
+impl<Inner: Read> Read for PositionedReader<Inner> {
+    #[inline]
+    fn read_outcome(&mut self, buf: &mut [u8]) -> io::Result<ReadOutcome> {
+        let outcome = self.inner.read_outcome(buf)?;
+        self.advance(&buf[..outcome.size]);
+        Ok(outcome)
+    }
+
+    #[inline]
+    fn remaining_hint(&self) -> (u64, Option<u64>) {
+        self.inner.remaining_hint()
+    }
+}
+
+impl<Inner: Read + ReadStr> ReadStr for PositionedReader<Inner> {
+    #[inline]
+    fn read_str(&mut self, buf: &mut str) -> io::Result<ReadOutcome> {
+        let outcome = self.inner.read_str(buf)?;
+        self.advance(&buf.as_bytes()[..outcome.size]);
+        Ok(outcome)
+    }
+}
+
+impl<Inner: Read> io::Read for PositionedReader<Inner> {
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        Read::read(self, buf)
+    }
+
+    #[inline]
+    fn read_vectored(&mut self, bufs: &mut [io::IoSliceMut<'_>]) -> io::Result<usize> {
+        Read::read_vectored(self, bufs)
+    }
+
+    #[cfg(feature = "nightly")]
+    #[inline]
+    fn is_read_vectored(&self) -> bool {
+        Read::is_read_vectored(self)
+    }
+
+    #[inline]
+    fn read_to_end(&mut self, buf: &mut Vec<u8>) -> io::Result<usize> {
+        Read::read_to_end(self, buf)
+    }
+
+    #[inline]
+    fn read_to_string(&mut self, buf: &mut String) -> io::Result<usize> {
+        Read::read_to_string(self, buf)
+    }
+
+    #[inline]
+    fn read_exact(&mut self, buf: &mut [u8]) -> io::Result<()> {
+        Read::read_exact(self, buf)
+    }
+}
+
+#[test]
+fn test_positioned_reader() {
+    let mut reader = PositionedReader::new(crate::SliceReader::new("ab\ncaf\u{e9}\nx".as_bytes()));
+    assert_eq!(reader.position(), Position::default());
+
+    let mut buf = [0; 4];
+    Read::read_exact(&mut reader, &mut buf).unwrap();
+    assert_eq!(
+        reader.position(),
+        Position {
+            byte: 4,
+            char: 4,
+            line: 2,
+            column: 2,
+        }
+    );
+
+    let mut s = String::new();
+    Read::read_to_string(&mut reader, &mut s).unwrap();
+    assert_eq!(
+        reader.position(),
+        Position {
+            byte: 10,
+            char: 9,
+            line: 3,
+            column: 2,
+        }
+    );
+}
@@ -1,107 +1,91 @@
 use unicode_normalization::char::canonical_combining_class;
 
-/// An iterator over `char`s which detects occurrences of
-/// [Forbidden Characters].
+/// A detector for the [Corrigendum #5 problem sequences]: a starter,
+/// one or more intervening non-starters, and a starter which a
+/// pre-Corrigendum #5 implementation would have composed with the first
+/// starter across the intervening non-starters.
 ///
-/// [Forbidden Characters]: https://unicode.org/reports/tr15/#Forbidding_Characters
-pub(crate) struct NoForbiddenCharacters<Inner: Iterator<Item = char>> {
-    inner: Inner,
-    buffer: Vec<char>,
-    pos: usize,
-}
+/// The normalization implementation is post-Corrigendum #5, so these
+/// sequences are normalized correctly; this detects them in normalized
+/// output so that they can be counted. It's fed one scalar value at a time
+/// and keeps its state between calls, so sequences are detected even when
+/// they straddle reads.
+///
+/// [Corrigendum #5 problem sequences]: https://unicode.org/reports/tr15/#Corrigendum_5_Sequences
+#[derive(Default)]
+pub(crate) struct ProblemSequences {
+    /// The category of the last starter, if it can begin a problem sequence.
+    first: Option<C5>,
 
-impl<Inner: Iterator<Item = char>> NoForbiddenCharacters<Inner> {
-    pub(crate) fn new(inner: Inner) -> Self {
-        Self {
-            inner,
-            buffer: Vec::new(),
-            pos: 0,
-        }
-    }
+    /// Whether any non-starters have followed `first`.
+    intervening: bool,
 }
 
-impl<Inner: Iterator<Item = char>> Iterator for NoForbiddenCharacters<Inner> {
-    type Item = Option<char>;
-
-    fn next(&mut self) -> Option<Self::Item> {
-        if !self.buffer.is_empty() {
-            let c = self.buffer[self.pos];
-            self.pos += 1;
-            if self.pos == self.buffer.len() {
-                self.buffer.clear();
+impl ProblemSequences {
+    /// Process the next scalar value, and return true if it completes a
+    /// problem sequence.
+    pub(crate) fn step(&mut self, c: char) -> bool {
+        if let Some(c5) = self.first {
+            // Intervening Character(s)
+            if canonical_combining_class(c) != 0 {
+                self.intervening = true;
+                return false;
             }
-            return Some(Some(c));
-        }
 
-        self.inner.next().map(|c| match c {
-            // http://www.unicode.org/versions/corrigendum3.html
-            '\u{f951}' => None,
-            // http://www.unicode.org/versions/corrigendum4.html
-            '\u{2f868}' | '\u{2f874}' | '\u{2f91f}' | '\u{2f95f}' | '\u{2f9bf}' => None,
-            _ => match categorize_c5(c) {
-                None => Some(c),
-                Some(c5) => {
-                    self.buffer.push(c);
-                    loop {
-                        match self.inner.next() {
-                            None => {
-                                self.pos = 1;
-                                break Some(self.buffer[0]);
-                            }
-                            Some(c) => match (c, c5) {
-                                // Intervening Character(s)
-                                (c, _) if canonical_combining_class(c) != 0 => self.buffer.push(c),
-
-                                // Normalized equivalents to [:HangulSyllableType=LV:].
-                                ('\u{1161}'..='\u{1175}', _)
-                                    if self.buffer.len() == 1
-                                        && ('\u{1100}'..='\u{1112}').contains(&self.buffer[0]) =>
-                                {
-                                    self.buffer.push(c)
-                                }
+            // Normalized equivalents to [:HangulSyllableType=LV:].
+            if !self.intervening
+                && c5 == C5::HangulChoseongKiyeokHieuh
+                && ('\u{1161}'..='\u{1175}').contains(&c)
+            {
+                self.first = Some(C5::HangulSyllableTypeLV);
+                return false;
+            }
 
-                                // Last Character
-                                ('\u{9be}', C5::BangaliVowelSignE)
-                                | ('\u{9d7}', C5::BangaliVowelSignE)
-                                | ('\u{b3e}', C5::OriyaVowelSignE)
-                                | ('\u{b56}', C5::OriyaVowelSignE)
-                                | ('\u{b57}', C5::OriyaVowelSignE)
-                                | ('\u{bbe}', C5::TamilVowelSignE)
-                                | ('\u{bd7}', C5::TamilVowelSignE)
-                                | ('\u{bbe}', C5::TamilVowelSignEE)
-                                | ('\u{bd7}', C5::TamilLetterO)
-                                | ('\u{cc2}', C5::KannadaVowelSignE)
-                                | ('\u{cd5}', C5::KannadaVowelSignE)
-                                | ('\u{cd6}', C5::KannadaVowelSignE)
-                                | ('\u{cd5}', C5::KannadaVowelSignIO)
-                                | ('\u{d3e}', C5::MalayalamVowelSignEE)
-                                | ('\u{d3e}', C5::MalayalamVowelSignE)
-                                | ('\u{d57}', C5::MalayalamVowelSignE)
-                                | ('\u{102e}', C5::MyanmarLetterU)
-                                | ('\u{dcf}', C5::SinhalaVowelSignKombuva)
-                                | ('\u{ddf}', C5::SinhalaVowelSignKombuva)
-                                | ('\u{1161}'..='\u{1175}', C5::HangulChoseongKiyeokHieuh)
-                                | ('\u{11a8}'..='\u{aac2}', C5::HangulSyllableTypeLV) => {
-                                    self.buffer.clear();
-                                    break None;
-                                }
+            let found = self.intervening && is_last_character(c, c5);
+            self.reset(c);
+            return found;
+        }
+        self.reset(c);
+        false
+    }
 
-                                _ => {
-                                    self.buffer.push(c);
-                                    self.pos = 1;
-                                    break Some(self.buffer[0]);
-                                }
-                            },
-                        }
-                    }
-                }
-            },
-        })
+    /// Forget any sequence in progress, and start over at `c`.
+    fn reset(&mut self, c: char) {
+        self.first = categorize_c5(c);
+        self.intervening = false;
     }
 }
 
+/// Test whether `c` is a Last Character for sequences beginning with `c5`.
+fn is_last_character(c: char, c5: C5) -> bool {
+    matches!(
+        (c, c5),
+        ('\u{9be}', C5::BangaliVowelSignE)
+            | ('\u{9d7}', C5::BangaliVowelSignE)
+            | ('\u{b3e}', C5::OriyaVowelSignE)
+            | ('\u{b56}', C5::OriyaVowelSignE)
+            | ('\u{b57}', C5::OriyaVowelSignE)
+            | ('\u{bbe}', C5::TamilVowelSignE)
+            | ('\u{bd7}', C5::TamilVowelSignE)
+            | ('\u{bbe}', C5::TamilVowelSignEE)
+            | ('\u{bd7}', C5::TamilLetterO)
+            | ('\u{cc2}', C5::KannadaVowelSignE)
+            | ('\u{cd5}', C5::KannadaVowelSignE)
+            | ('\u{cd6}', C5::KannadaVowelSignE)
+            | ('\u{cd5}', C5::KannadaVowelSignIO)
+            | ('\u{d3e}', C5::MalayalamVowelSignEE)
+            | ('\u{d3e}', C5::MalayalamVowelSignE)
+            | ('\u{d57}', C5::MalayalamVowelSignE)
+            | ('\u{102e}', C5::MyanmarLetterU)
+            | ('\u{dcf}', C5::SinhalaVowelSignKombuva)
+            | ('\u{ddf}', C5::SinhalaVowelSignKombuva)
+            | ('\u{1161}'..='\u{1175}', C5::HangulChoseongKiyeokHieuh)
+            | ('\u{11a8}'..='\u{11c2}', C5::HangulSyllableTypeLV)
+    )
+}
+
 // Table 10. Problem Sequences
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, PartialEq, Eq)]
 enum C5 {
     BangaliVowelSignE,
     OriyaVowelSignE,
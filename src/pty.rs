@@ -0,0 +1,266 @@
+use crate::{Error, Read, ReadOutcome, Readiness, Status, Write};
+use std::{
+    ffi::CStr,
+    fs::{File, OpenOptions},
+    io::{self, Write as _},
+    os::unix::{
+        fs::OpenOptionsExt,
+        io::{AsFd, AsRawFd, BorrowedFd, FromRawFd, RawFd},
+    },
+};
+
+/// Open a new pseudo-terminal pair, returning a `PtyMaster` for the
+/// controlling side and a `File` for the terminal side, which can be
+/// passed to a child process as its stdin, stdout, and stderr.
+pub fn open_pty() -> io::Result<(PtyMaster, File)> {
+    unsafe {
+        let fd = libc::posix_openpt(libc::O_RDWR | libc::O_NOCTTY);
+        if fd < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let master = File::from_raw_fd(fd);
+        if libc::fcntl(fd, libc::F_SETFD, libc::FD_CLOEXEC) != 0
+            || libc::grantpt(fd) != 0
+            || libc::unlockpt(fd) != 0
+        {
+            return Err(io::Error::last_os_error());
+        }
+        let path = terminal_path(fd)?;
+        let terminal = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .custom_flags(libc::O_NOCTTY)
+            .open(path)?;
+        Ok((PtyMaster::new(master), terminal))
+    }
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+unsafe fn terminal_path(fd: RawFd) -> io::Result<String> {
+    let mut buf = [0 as libc::c_char; 128];
+    let err = libc::ptsname_r(fd, buf.as_mut_ptr(), buf.len());
+    if err != 0 {
+        return Err(io::Error::from_raw_os_error(err));
+    }
+    Ok(CStr::from_ptr(buf.as_ptr()).to_string_lossy().into_owned())
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+unsafe fn terminal_path(fd: RawFd) -> io::Result<String> {
+    let name = libc::ptsname(fd);
+    if name.is_null() {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(CStr::from_ptr(name).to_string_lossy().into_owned())
+}
+
+/// The controlling side of a pseudo-terminal, implementing `Read` for the
+/// output of the program running in the terminal, and `Write` for its
+/// input.
+///
+/// Reads report a lull after each chunk ending in a newline, as
+/// `StdReader::line_by_line` does, so that output can be processed line by
+/// line as the program produces it. Once all handles to the terminal side
+/// have been closed, such as when the child process exits, reads report the
+/// end of the stream.
+///
+/// Flushing the output with `Status::End` sends the terminal's end-of-file
+/// character, so that the program sees the end of its input.
+pub struct PtyMaster {
+    /// The master file descriptor.
+    inner: File,
+
+    /// The terminal side has been closed.
+    read_ended: bool,
+
+    /// We've sent the end-of-file character.
+    write_ended: bool,
+}
+
+impl PtyMaster {
+    fn new(inner: File) -> Self {
+        Self {
+            inner,
+            read_ended: false,
+            write_ended: false,
+        }
+    }
+
+    /// Gets a reference to the underlying master file descriptor.
+    #[inline]
+    pub fn get_ref(&self) -> &File {
+        &self.inner
+    }
+
+    /// Consume this `PtyMaster` and return the underlying master file
+    /// descriptor.
+    #[inline]
+    pub fn into_inner(self) -> File {
+        self.inner
+    }
+
+    /// Set the size of the terminal, in rows and columns.
+    pub fn resize(&self, rows: u16, cols: u16) -> io::Result<()> {
+        let size = libc::winsize {
+            ws_row: rows,
+            ws_col: cols,
+            ws_xpixel: 0,
+            ws_ypixel: 0,
+        };
+        if unsafe { libc::ioctl(self.inner.as_raw_fd(), libc::TIOCSWINSZ, &size) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(())
+    }
+
+    fn eof_char(&self) -> io::Result<u8> {
+        let mut termios = std::mem::MaybeUninit::<libc::termios>::uninit();
+        if unsafe { libc::tcgetattr(self.inner.as_raw_fd(), termios.as_mut_ptr()) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(unsafe { termios.assume_init() }.c_cc[libc::VEOF])
+    }
+}
+
+impl Read for PtyMaster {
+    fn read_outcome(&mut self, buf: &mut [u8]) -> io::Result<ReadOutcome> {
+        if self.read_ended {
+            return Ok(ReadOutcome::end(0));
+        }
+        match io::Read::read(&mut self.inner, buf) {
+            Ok(0) if !buf.is_empty() => {
+                self.read_ended = true;
+                Ok(ReadOutcome::end(0))
+            }
+            Ok(size) if size != 0 && buf[size - 1] == b'\n' => Ok(ReadOutcome::lull(size)),
+            Ok(size) => Ok(ReadOutcome::ready(size)),
+            // Linux reports `EIO` once the terminal side has been closed.
+            Err(ref e) if e.raw_os_error() == Some(libc::EIO) => {
+                self.read_ended = true;
+                Ok(ReadOutcome::end(0))
+            }
+            Err(ref e) if e.kind() == io::ErrorKind::WouldBlock => Ok(ReadOutcome::pending(0)),
+            Err(ref e) if e.kind() == io::ErrorKind::Interrupted => Ok(ReadOutcome::ready(0)),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+impl io::Read for PtyMaster {
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        Read::read(self, buf)
+    }
+
+    #[inline]
+    fn read_vectored(&mut self, bufs: &mut [io::IoSliceMut<'_>]) -> io::Result<usize> {
+        Read::read_vectored(self, bufs)
+    }
+
+    #[cfg(feature = "nightly")]
+    #[inline]
+    fn is_read_vectored(&self) -> bool {
+        Read::is_read_vectored(self)
+    }
+
+    #[inline]
+    fn read_to_end(&mut self, buf: &mut Vec<u8>) -> io::Result<usize> {
+        Read::read_to_end(self, buf)
+    }
+
+    #[inline]
+    fn read_to_string(&mut self, buf: &mut String) -> io::Result<usize> {
+        Read::read_to_string(self, buf)
+    }
+
+    #[inline]
+    fn read_exact(&mut self, buf: &mut [u8]) -> io::Result<()> {
+        Read::read_exact(self, buf)
+    }
+}
+
+impl Write for PtyMaster {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.write_ended {
+            return Err(stream_already_ended());
+        }
+        self.inner.write(buf)
+    }
+
+    fn flush(&mut self, status: Status) -> io::Result<()> {
+        if self.write_ended {
+            return Err(stream_already_ended());
+        }
+        match status {
+            Status::Open(Readiness::Ready) | Status::Open(Readiness::Pending) => Ok(()),
+            Status::Open(Readiness::Lull) => self.inner.flush(),
+            Status::End => {
+                self.write_ended = true;
+                let eof = self.eof_char()?;
+                self.inner.write_all(&[eof])
+            }
+        }
+    }
+
+    #[inline]
+    fn abandon(&mut self) {
+        self.write_ended = true;
+    }
+}
+
+impl AsRawFd for PtyMaster {
+    #[inline]
+    fn as_raw_fd(&self) -> RawFd {
+        self.inner.as_raw_fd()
+    }
+}
+
+impl AsFd for PtyMaster {
+    #[inline]
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.inner.as_fd()
+    }
+}
+
+fn stream_already_ended() -> io::Error {
+    Error::StreamEnded.into()
+}
+
+#[test]
+fn test_pty_output() {
+    let (mut master, mut terminal) = open_pty().unwrap();
+    terminal.write_all(b"hello\n").unwrap();
+    let mut buf = [0; 64];
+    let outcome = master.read_outcome(&mut buf).unwrap();
+    // The terminal translates "\n" to "\r\n" by default.
+    assert_eq!(&buf[..outcome.size], b"hello\r\n");
+    assert_eq!(outcome.status, Status::Open(Readiness::Lull));
+
+    drop(terminal);
+    let outcome = master.read_outcome(&mut buf).unwrap();
+    assert_eq!(outcome.size, 0);
+    assert!(outcome.status.is_end());
+}
+
+#[test]
+fn test_pty_text() {
+    let (master, mut terminal) = open_pty().unwrap();
+    terminal.write_all(b"\x1b[1mhello\x1b[0m\n").unwrap();
+    drop(terminal);
+    let mut reader = crate::TextReader::new(master);
+    let mut s = String::new();
+    reader.read_to_string(&mut s).unwrap();
+    assert_eq!(s, "hello\n");
+}
+
+#[test]
+fn test_pty_input() {
+    let (mut master, terminal) = open_pty().unwrap();
+    master.resize(24, 80).unwrap();
+    master.write_all(b"hello\n").unwrap();
+    master.flush(Status::End).unwrap();
+    let mut s = String::new();
+    io::Read::read_to_string(&mut &terminal, &mut s).unwrap();
+    assert_eq!(s, "hello\n");
+}
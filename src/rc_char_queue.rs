@@ -1,5 +1,6 @@
 //! Defines `RcCharQueue` and `RcCharQueueIter`.
 
+use crate::unicode::is_normalization_form_starter;
 use std::{cell::RefCell, collections::vec_deque::VecDeque, rc::Rc};
 
 /// A queue of `char`s held by an `Rc<RefCell<...>>` so that we can insert
@@ -19,6 +20,18 @@ impl RcCharQueue {
         self.queue.borrow_mut().push_back(c)
     }
 
+    /// Push each of `bytes`, which must be ASCII, as a `char`.
+    pub(crate) fn push_ascii(&mut self, bytes: &[u8]) {
+        debug_assert!(bytes.is_ascii());
+        self.queue
+            .borrow_mut()
+            .extend(bytes.iter().map(|b| char::from(*b)))
+    }
+
+    pub(crate) fn pop(&mut self) -> Option<char> {
+        self.queue.borrow_mut().pop_front()
+    }
+
     pub(crate) fn len(&self) -> usize {
         self.queue.borrow().len()
     }
@@ -27,6 +40,24 @@ impl RcCharQueue {
         self.queue.borrow().is_empty()
     }
 
+    pub(crate) fn clear(&mut self) {
+        self.queue.borrow_mut().clear()
+    }
+
+    /// Remove and return the scalar values from the last normalization-form
+    /// starter to the end of the queue, unless that starter is a control
+    /// code, which nothing can combine with.
+    pub(crate) fn split_off_last_segment(&mut self) -> Vec<char> {
+        let mut queue = self.queue.borrow_mut();
+        match queue
+            .iter()
+            .rposition(|c| is_normalization_form_starter(*c))
+        {
+            Some(index) if !queue[index].is_control() => queue.drain(index..).collect(),
+            _ => Vec::new(),
+        }
+    }
+
     pub(crate) fn iter(&self) -> RcCharQueueIter {
         RcCharQueueIter::new(Rc::clone(&self.queue))
     }
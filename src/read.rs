@@ -1,5 +1,10 @@
-use crate::{unicode::NORMALIZATION_BUFFER_SIZE, Readiness, Status};
-use std::io::{self, IoSliceMut};
+use crate::{unicode::NORMALIZATION_BUFFER_SIZE, ReadBuf, Readiness, Status};
+use std::{
+    convert::TryFrom,
+    io::{self, IoSliceMut},
+    mem, str,
+    time::{Duration, Instant},
+};
 
 /// A superset of [`std::io::Read`], with `read_outcome` and
 /// `read_vectored_outcome` which return more information and zero is not
@@ -13,6 +18,16 @@ pub trait Read {
         default_read_vectored_outcome(self, bufs)
     }
 
+    /// Like `read_outcome`, but reads into a [`ReadBuf`], which may be
+    /// partially uninitialized, and appends to its filled part.
+    ///
+    /// The `size` of the outcome is the number of bytes filled by this call.
+    /// The default implementation zero-fills any uninitialized part of the
+    /// buffer and calls `read_outcome`.
+    fn read_buf_outcome(&mut self, buf: &mut ReadBuf<'_>) -> io::Result<ReadOutcome> {
+        default_read_buf_outcome(self, buf)
+    }
+
     /// Like [`std::io::Read::read`].
     fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
         default_read(self, buf)
@@ -25,22 +40,269 @@ pub trait Read {
 
     /// Like [`std::io::Read::is_read_vectored`].
     #[cfg(feature = "nightly")]
-    fn is_read_vectored(&self) -> bool;
+    fn is_read_vectored(&self) -> bool {
+        false
+    }
 
     /// Like [`std::io::Read::read_to_end`] (but sometimes more efficient).
+    ///
+    /// If a read reports `Readiness::Pending`, this fails with
+    /// `io::ErrorKind::WouldBlock`, leaving the data read so far in `buf`.
     fn read_to_end(&mut self, buf: &mut Vec<u8>) -> io::Result<usize> {
         default_read_to_end(self, buf)
     }
 
     /// Like [`std::io::Read::read_to_string`] (but sometimes more efficient).
+    ///
+    /// If a read reports `Readiness::Pending`, this fails with
+    /// `io::ErrorKind::WouldBlock`, leaving the data read so far in `buf`.
     fn read_to_string(&mut self, buf: &mut String) -> io::Result<usize> {
         default_read_to_string(self, buf)
     }
 
-    /// Like [`std::io::Read::read_exact`].
+    /// Like [`std::io::Read::read_exact`]. If a read reports
+    /// `Readiness::Pending` before `buf` is filled, this fails with
+    /// `io::ErrorKind::WouldBlock`.
     fn read_exact(&mut self, buf: &mut [u8]) -> io::Result<()> {
         default_read_exact(self, buf)
     }
+
+    /// Like `read_exact`, but fail with `io::ErrorKind::TimedOut` if `buf`
+    /// isn't filled within `timeout`.
+    ///
+    /// The timeout is checked between reads, so it can't interrupt a read
+    /// which blocks; to bound those, use a `TimeoutReader`. As with
+    /// `read_exact`, a read reporting `Readiness::Pending` fails with
+    /// `io::ErrorKind::WouldBlock`.
+    fn read_exact_timeout(&mut self, buf: &mut [u8], timeout: Duration) -> io::Result<()> {
+        default_read_exact_timeout(self, buf, timeout)
+    }
+
+    /// Like `read_to_end`, but rather than reading more than `limit` bytes,
+    /// fail with `io::ErrorKind::InvalidData`, leaving `buf` as it was, so
+    /// that hostile inputs can't exhaust memory. As with `read_to_end`, a
+    /// read reporting `Readiness::Pending` fails with
+    /// `io::ErrorKind::WouldBlock`, leaving the data read so far in `buf`.
+    fn read_to_end_limit(&mut self, buf: &mut Vec<u8>, limit: usize) -> io::Result<usize> {
+        default_read_to_end_limit(self, buf, limit)
+    }
+
+    /// Return a lower bound and an optional upper bound on the number of
+    /// bytes remaining in the stream, in the manner of
+    /// [`Iterator::size_hint`].
+    ///
+    /// This is only a hint; streams may produce more or fewer bytes than
+    /// indicated. It's used by `read_to_end` to pre-allocate, and may be
+    /// used by consumers to display progress. The default returns
+    /// `(0, None)`.
+    #[inline]
+    fn remaining_hint(&self) -> (u64, Option<u64>) {
+        (0, None)
+    }
+}
+
+impl<R: Read + ?Sized> Read for &mut R {
+    #[inline]
+    fn read_outcome(&mut self, buf: &mut [u8]) -> io::Result<ReadOutcome> {
+        (**self).read_outcome(buf)
+    }
+
+    #[inline]
+    fn read_vectored_outcome(&mut self, bufs: &mut [IoSliceMut<'_>]) -> io::Result<ReadOutcome> {
+        (**self).read_vectored_outcome(bufs)
+    }
+
+    #[inline]
+    fn read_buf_outcome(&mut self, buf: &mut ReadBuf<'_>) -> io::Result<ReadOutcome> {
+        (**self).read_buf_outcome(buf)
+    }
+
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        (**self).read(buf)
+    }
+
+    #[inline]
+    fn read_vectored(&mut self, bufs: &mut [IoSliceMut<'_>]) -> io::Result<usize> {
+        (**self).read_vectored(bufs)
+    }
+
+    #[cfg(feature = "nightly")]
+    #[inline]
+    fn is_read_vectored(&self) -> bool {
+        (**self).is_read_vectored()
+    }
+
+    #[inline]
+    fn read_to_end(&mut self, buf: &mut Vec<u8>) -> io::Result<usize> {
+        (**self).read_to_end(buf)
+    }
+
+    #[inline]
+    fn read_to_string(&mut self, buf: &mut String) -> io::Result<usize> {
+        (**self).read_to_string(buf)
+    }
+
+    #[inline]
+    fn read_exact(&mut self, buf: &mut [u8]) -> io::Result<()> {
+        (**self).read_exact(buf)
+    }
+
+    #[inline]
+    fn read_exact_timeout(&mut self, buf: &mut [u8], timeout: Duration) -> io::Result<()> {
+        (**self).read_exact_timeout(buf, timeout)
+    }
+
+    #[inline]
+    fn read_to_end_limit(&mut self, buf: &mut Vec<u8>, limit: usize) -> io::Result<usize> {
+        (**self).read_to_end_limit(buf, limit)
+    }
+
+    #[inline]
+    fn remaining_hint(&self) -> (u64, Option<u64>) {
+        (**self).remaining_hint()
+    }
+}
+
+impl<R: Read + ?Sized> Read for Box<R> {
+    #[inline]
+    fn read_outcome(&mut self, buf: &mut [u8]) -> io::Result<ReadOutcome> {
+        (**self).read_outcome(buf)
+    }
+
+    #[inline]
+    fn read_vectored_outcome(&mut self, bufs: &mut [IoSliceMut<'_>]) -> io::Result<ReadOutcome> {
+        (**self).read_vectored_outcome(bufs)
+    }
+
+    #[inline]
+    fn read_buf_outcome(&mut self, buf: &mut ReadBuf<'_>) -> io::Result<ReadOutcome> {
+        (**self).read_buf_outcome(buf)
+    }
+
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        (**self).read(buf)
+    }
+
+    #[inline]
+    fn read_vectored(&mut self, bufs: &mut [IoSliceMut<'_>]) -> io::Result<usize> {
+        (**self).read_vectored(bufs)
+    }
+
+    #[cfg(feature = "nightly")]
+    #[inline]
+    fn is_read_vectored(&self) -> bool {
+        (**self).is_read_vectored()
+    }
+
+    #[inline]
+    fn read_to_end(&mut self, buf: &mut Vec<u8>) -> io::Result<usize> {
+        (**self).read_to_end(buf)
+    }
+
+    #[inline]
+    fn read_to_string(&mut self, buf: &mut String) -> io::Result<usize> {
+        (**self).read_to_string(buf)
+    }
+
+    #[inline]
+    fn read_exact(&mut self, buf: &mut [u8]) -> io::Result<()> {
+        (**self).read_exact(buf)
+    }
+
+    #[inline]
+    fn read_exact_timeout(&mut self, buf: &mut [u8], timeout: Duration) -> io::Result<()> {
+        (**self).read_exact_timeout(buf, timeout)
+    }
+
+    #[inline]
+    fn read_to_end_limit(&mut self, buf: &mut Vec<u8>, limit: usize) -> io::Result<usize> {
+        (**self).read_to_end_limit(buf, limit)
+    }
+
+    #[inline]
+    fn remaining_hint(&self) -> (u64, Option<u64>) {
+        (**self).remaining_hint()
+    }
+}
+
+/// A superset of [`std::io::BufRead`], with `fill_buf_outcome` which also
+/// returns the status of the stream after the returned data.
+pub trait ReadBuffered: Read {
+    /// Like [`std::io::BufRead::fill_buf`], but also returns the `Status` of
+    /// the stream following the returned data.
+    ///
+    /// Once the returned data is fully consumed, the status applies. If the
+    /// status is `Status::End`, subsequent calls return an empty slice.
+    fn fill_buf_outcome(&mut self) -> io::Result<(&[u8], Status)>;
+
+    /// Like [`std::io::BufRead::consume`].
+    fn consume(&mut self, amt: usize);
+
+    /// Like [`std::io::BufRead::read_until`], but returns a `ReadOutcome`,
+    /// and stops at a lull, so that a lull in the middle of a line is
+    /// reported rather than waited through.
+    ///
+    /// The `size` of the outcome is the number of bytes appended to `buf`,
+    /// including the delimiter if it was found, and the `status` is the
+    /// status of the stream following them. If the status is `Ready`, the
+    /// delimiter was found.
+    fn read_until(&mut self, delim: u8, buf: &mut Vec<u8>) -> io::Result<ReadOutcome> {
+        let mut size = 0;
+        loop {
+            let (available, status) = match self.fill_buf_outcome() {
+                Ok(result) => result,
+                Err(ref e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            };
+            match available.iter().position(|b| *b == delim) {
+                Some(i) => {
+                    buf.extend_from_slice(&available[..=i]);
+                    let drained = i + 1 == available.len();
+                    self.consume(i + 1);
+                    size += i + 1;
+                    return Ok(if drained {
+                        ReadOutcome { size, status }
+                    } else {
+                        ReadOutcome::ready(size)
+                    });
+                }
+                None => {
+                    let len = available.len();
+                    buf.extend_from_slice(available);
+                    self.consume(len);
+                    size += len;
+                    if status != Status::ready() {
+                        return Ok(ReadOutcome { size, status });
+                    }
+                }
+            }
+        }
+    }
+
+    /// Like [`std::io::BufRead::read_line`], but returns a `ReadOutcome`,
+    /// and stops at a lull, as [`ReadBuffered::read_until`] does.
+    ///
+    /// If the data read is not valid UTF-8, it's discarded and an error
+    /// with `io::ErrorKind::InvalidData` is returned. Implementations which
+    /// can, such as `BufferedReader`, hold an incomplete UTF-8 sequence
+    /// before a lull until the rest of it is read, rather than failing.
+    fn read_line(&mut self, buf: &mut String) -> io::Result<ReadOutcome> {
+        let mut bytes = mem::take(buf).into_bytes();
+        let start_len = bytes.len();
+        let result = self.read_until(b'\n', &mut bytes);
+        if str::from_utf8(&bytes[start_len..]).is_err() {
+            bytes.truncate(start_len);
+            *buf = String::from_utf8(bytes).unwrap();
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "stream did not contain valid UTF-8",
+            ));
+        }
+        *buf = String::from_utf8(bytes).unwrap();
+        result
+    }
 }
 
 /// Information returned after a successful read.
@@ -89,6 +351,15 @@ impl ReadOutcome {
             status: Status::Open(Readiness::Lull),
         }
     }
+
+    /// Data was read on a stream which would now block.
+    #[inline]
+    pub fn pending(size: usize) -> Self {
+        Self {
+            size,
+            status: Status::Open(Readiness::Pending),
+        }
+    }
 }
 
 /// Default implementation of `Read::read`.
@@ -116,47 +387,69 @@ pub fn default_read_vectored_outcome<Inner: Read + ?Sized>(
     inner.read_outcome(buf)
 }
 
+/// Default implementation of `Read::read_buf_outcome`.
+pub fn default_read_buf_outcome<Inner: Read + ?Sized>(
+    inner: &mut Inner,
+    buf: &mut ReadBuf<'_>,
+) -> io::Result<ReadOutcome> {
+    let outcome = inner.read_outcome(buf.initialize_unfilled())?;
+    buf.add_filled(outcome.size);
+    Ok(outcome)
+}
+
+/// The most space which `reserve_for_hint` reserves, since a stream's
+/// `remaining_hint` may overestimate.
+const MAX_HINT_RESERVATION: usize = 64 * 1024 * 1024;
+
+/// Reserve space in `buf` for the lower bound of `inner`'s `remaining_hint`,
+/// up to `limit`. If the allocation fails, `buf` is left to grow as data
+/// arrives instead.
+fn reserve_for_hint<Inner: Read + ?Sized>(inner: &Inner, buf: &mut Vec<u8>, limit: usize) {
+    let lower = usize::try_from(inner.remaining_hint().0).unwrap_or(usize::MAX);
+    let _ = buf.try_reserve(lower.min(limit).min(MAX_HINT_RESERVATION));
+}
+
 /// Default implementation of `Read::read_to_end`.
 pub fn default_read_to_end<Inner: Read + ?Sized>(
     inner: &mut Inner,
     buf: &mut Vec<u8>,
 ) -> io::Result<usize> {
     let start_len = buf.len();
+    reserve_for_hint(inner, buf, usize::MAX);
     let buffer_size = 1024;
-    let mut read_len = buffer_size;
+
+    // The number of bytes in the spare capacity of `buf` which have been
+    // initialized by previous reads, so that they needn't be zeroed again.
+    let mut initialized = 0;
     loop {
-        let read_pos = buf.len();
+        if buf.capacity() - buf.len() < NORMALIZATION_BUFFER_SIZE {
+            buf.reserve(buffer_size.max(NORMALIZATION_BUFFER_SIZE));
+            initialized = 0;
+        }
 
-        // Allocate space in the buffer. This needlessly zeros out the
-        // memory, however the current way to avoid it is to be part of the
-        // standard library so that we can make assumptions about the
-        // compiler not exploiting undefined behavior.
-        // https://github.com/rust-lang/rust/issues/42788 for details.
-        buf.resize(read_pos + read_len, 0);
+        let mut read_buf = ReadBuf::uninit(buf.spare_capacity_mut());
+        unsafe { read_buf.assume_init(initialized) };
+        let result = inner.read_buf_outcome(&mut read_buf);
+        let size = read_buf.filled().len();
+        initialized = read_buf.initialized_len() - size;
 
-        match inner.read_outcome(&mut buf[read_pos..]) {
-            Ok(ReadOutcome { size, status }) => {
-                buf.resize(read_pos + size, 0);
-                match status {
-                    Status::Open(_) => {
-                        read_len -= size;
-                        if read_len < NORMALIZATION_BUFFER_SIZE {
-                            read_len += buffer_size;
-                        }
-                    }
-                    Status::End => return Ok(buf.len() - start_len),
-                }
-            }
-            Err(ref e) if e.kind() == io::ErrorKind::Interrupted => {}
+        // Safety: `ReadBuf` guarantees that its filled bytes are initialized.
+        unsafe { buf.set_len(buf.len() + size) };
+
+        match result {
+            Ok(ReadOutcome { size: _, status }) => match status {
+                Status::End => return Ok(buf.len() - start_len),
+                // Keep the data read so far, as `std::io::Read::read_to_end`
+                // does, so that the caller can resume after polling.
+                Status::Open(Readiness::Pending) => return Err(would_block()),
+                Status::Open(_) => (),
+            },
+            Err(ref e) if e.kind() == io::ErrorKind::Interrupted => (),
             Err(e) => {
-                buf.resize(start_len, 0);
+                buf.truncate(start_len);
                 return Err(e);
             }
         }
-
-        if read_len == 0 {
-            read_len = buffer_size;
-        }
     }
 }
 
@@ -169,10 +462,14 @@ pub fn default_read_to_string<Inner: Read + ?Sized>(
     // rather than reading directly into `buf`'s buffer, but similarly
     // avoids issues of undefined behavior for now.
     let mut vec = Vec::new();
-    let size = inner.read_to_end(&mut vec)?;
-    let new = String::from_utf8(vec).map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
-    buf.push_str(&new);
-    Ok(size)
+    // Keep the data read before an error, such as `WouldBlock`, as
+    // `read_to_end` does.
+    let result = inner.read_to_end(&mut vec);
+    match String::from_utf8(vec) {
+        Ok(new) => buf.push_str(&new),
+        Err(err) => return result.and(Err(io::Error::other(err))),
+    }
+    result
 }
 
 /// Default implementation of `Read::read_exact`.
@@ -185,8 +482,12 @@ pub fn default_read_exact<Inner: Read + ?Sized>(
             Ok(ReadOutcome { size, status }) => {
                 let t = buf;
                 buf = &mut t[size..];
-                if status.is_end() {
-                    break;
+                match status {
+                    Status::End => break,
+                    Status::Open(Readiness::Pending) if !buf.is_empty() => {
+                        return Err(would_block())
+                    }
+                    Status::Open(_) => (),
                 }
             }
             Err(e) => return Err(e),
@@ -203,8 +504,99 @@ pub fn default_read_exact<Inner: Read + ?Sized>(
     }
 }
 
+/// Default implementation of `Read::read_exact_timeout`.
+pub fn default_read_exact_timeout<Inner: Read + ?Sized>(
+    inner: &mut Inner,
+    mut buf: &mut [u8],
+    timeout: Duration,
+) -> io::Result<()> {
+    // A timeout too long to represent is effectively no timeout.
+    let deadline = Instant::now().checked_add(timeout);
+    while !buf.is_empty() {
+        match inner.read_outcome(buf) {
+            Ok(ReadOutcome { size, status }) => {
+                let t = buf;
+                buf = &mut t[size..];
+                match status {
+                    Status::End => break,
+                    Status::Open(Readiness::Pending) if !buf.is_empty() => {
+                        return Err(would_block())
+                    }
+                    Status::Open(_) => (),
+                }
+            }
+            Err(ref e) if e.kind() == io::ErrorKind::Interrupted => (),
+            Err(e) => return Err(e),
+        }
+        if !buf.is_empty() && deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+            return Err(io::Error::new(
+                io::ErrorKind::TimedOut,
+                "timed out before filling whole buffer",
+            ));
+        }
+    }
+
+    if buf.is_empty() {
+        Ok(())
+    } else {
+        Err(io::Error::new(
+            io::ErrorKind::UnexpectedEof,
+            "failed to fill whole buffer",
+        ))
+    }
+}
+
+/// Default implementation of `Read::read_to_end_limit`.
+pub fn default_read_to_end_limit<Inner: Read + ?Sized>(
+    inner: &mut Inner,
+    buf: &mut Vec<u8>,
+    limit: usize,
+) -> io::Result<usize> {
+    let start_len = buf.len();
+    reserve_for_hint(inner, buf, limit);
+
+    // Read into a separate buffer, so that `buf` never grows past `limit`.
+    let mut chunk = [0_u8; 4096];
+    loop {
+        match inner.read_outcome(&mut chunk) {
+            Ok(ReadOutcome { size, status }) => {
+                if buf.len() - start_len + size > limit {
+                    buf.truncate(start_len);
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("stream is longer than the limit of {} bytes", limit),
+                    ));
+                }
+                buf.extend_from_slice(&chunk[..size]);
+                match status {
+                    Status::End => return Ok(buf.len() - start_len),
+                    // Keep the data read so far, as `default_read_to_end`
+                    // does.
+                    Status::Open(Readiness::Pending) => return Err(would_block()),
+                    Status::Open(_) => (),
+                }
+            }
+            Err(ref e) if e.kind() == io::ErrorKind::Interrupted => (),
+            Err(e) => {
+                buf.truncate(start_len);
+                return Err(e);
+            }
+        }
+    }
+}
+
+/// The error reported by `std::io::Read`-style functions when a read
+/// reports `Readiness::Pending`, so that callers poll rather than retry.
+fn would_block() -> io::Error {
+    io::Error::new(io::ErrorKind::WouldBlock, "read would block")
+}
+
 fn outcome_to_usize(outcome: ReadOutcome) -> io::Result<usize> {
     match outcome {
+        ReadOutcome {
+            size: 0,
+            status: Status::Open(Readiness::Pending),
+        } => Err(would_block()),
         ReadOutcome {
             size: 0,
             status: Status::Open(_),
@@ -215,3 +607,196 @@ fn outcome_to_usize(outcome: ReadOutcome) -> io::Result<usize> {
         ReadOutcome { size, status: _ } => Ok(size),
     }
 }
+
+#[cfg(test)]
+fn read_all<R: Read>(mut reader: R) -> String {
+    let mut s = String::new();
+    reader.read_to_string(&mut s).unwrap();
+    s
+}
+
+#[test]
+fn test_read_by_reference() {
+    let mut reader = crate::Utf8Reader::new(crate::SliceReader::new(b"hello\n"));
+    assert_eq!(read_all(&mut reader), "hello\n");
+    assert_eq!(
+        read_all(Box::new(crate::SliceReader::new(b"world\n"))),
+        "world\n"
+    );
+}
+
+#[test]
+fn test_dyn_read() {
+    let mut readers: Vec<Box<dyn Read>> = vec![
+        Box::new(crate::SliceReader::new(b"hello ")),
+        Box::new(crate::Utf8Reader::new(crate::SliceReader::new(b"world\n"))),
+    ];
+    let mut s = String::new();
+    for reader in &mut readers {
+        reader.read_to_string(&mut s).unwrap();
+    }
+    assert_eq!(s, "hello world\n");
+    assert_eq!(read_all(readers.remove(0)), "");
+}
+
+#[test]
+fn test_read_buf_outcome() {
+    let mut reader = crate::Utf8Reader::new(crate::SliceReader::new(b"hello\n"));
+    let mut storage = [std::mem::MaybeUninit::uninit(); 16];
+    let mut buf = ReadBuf::uninit(&mut storage);
+    let outcome = reader.read_buf_outcome(&mut buf).unwrap();
+    assert_eq!(outcome.size, 6);
+    assert_eq!(buf.filled(), b"hello\n");
+}
+
+#[test]
+fn test_read_exact_timeout() {
+    use crate::testing::{ScriptedReader, Step};
+
+    let mut reader = ScriptedReader::new(vec![
+        Step::Data(b"hel".to_vec()),
+        Step::Lull,
+        Step::Data(b"lo".to_vec()),
+    ]);
+    let mut buf = [0; 5];
+    reader
+        .read_exact_timeout(&mut buf, Duration::from_secs(3600))
+        .unwrap();
+    assert_eq!(&buf, b"hello");
+
+    let mut reader = ScriptedReader::new(vec![
+        Step::Data(b"hel".to_vec()),
+        Step::Lull,
+        Step::Data(b"lo".to_vec()),
+    ]);
+    let err = reader
+        .read_exact_timeout(&mut buf, Duration::ZERO)
+        .unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::TimedOut);
+
+    let mut reader = crate::SliceReader::new(b"hel");
+    let err = reader
+        .read_exact_timeout(&mut buf, Duration::from_secs(3600))
+        .unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+}
+
+#[test]
+fn test_read_to_end_limit() {
+    let mut buf = b"> ".to_vec();
+    let mut reader = crate::SliceReader::new(b"hello world");
+    assert_eq!(reader.read_to_end_limit(&mut buf, 11).unwrap(), 11);
+    assert_eq!(buf, b"> hello world");
+
+    let mut buf = b"> ".to_vec();
+    let mut reader = crate::SliceReader::new(b"hello world");
+    let err = reader.read_to_end_limit(&mut buf, 10).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    assert_eq!(buf, b"> ");
+}
+
+#[test]
+fn test_read_to_end_bogus_hint() {
+    // A reader whose `remaining_hint` vastly overestimates.
+    struct Overestimate<'a>(crate::SliceReader<'a>);
+
+    impl Read for Overestimate<'_> {
+        fn read_outcome(&mut self, buf: &mut [u8]) -> io::Result<ReadOutcome> {
+            self.0.read_outcome(buf)
+        }
+
+        fn remaining_hint(&self) -> (u64, Option<u64>) {
+            (u64::MAX, None)
+        }
+    }
+
+    let mut buf = Vec::new();
+    let mut reader = Overestimate(crate::SliceReader::new(b"hello world"));
+    assert_eq!(reader.read_to_end(&mut buf).unwrap(), 11);
+    assert_eq!(buf, b"hello world");
+    assert!(buf.capacity() <= MAX_HINT_RESERVATION + NORMALIZATION_BUFFER_SIZE);
+
+    let mut buf = Vec::new();
+    let mut reader = Overestimate(crate::SliceReader::new(b"hello world"));
+    assert_eq!(reader.read_to_end_limit(&mut buf, 64).unwrap(), 11);
+    assert_eq!(buf, b"hello world");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_pending_would_block() {
+    use crate::{
+        testing::{ScriptedReader, Step},
+        StdReaderOptions,
+    };
+    use std::os::unix::net::UnixStream;
+
+    // A non-blocking source with nothing to read.
+    let (_client, server) = UnixStream::pair().unwrap();
+    server.set_nonblocking(true).unwrap();
+    let mut reader = StdReaderOptions::new().nonblocking(true).build(server);
+    let mut buf = [0; 4];
+    let would_block = io::ErrorKind::WouldBlock;
+    assert_eq!(
+        Read::read(&mut reader, &mut buf).unwrap_err().kind(),
+        would_block
+    );
+    let mut utf8_reader = crate::Utf8Reader::new(&mut reader);
+    assert_eq!(
+        io::Read::read(&mut utf8_reader, &mut buf)
+            .unwrap_err()
+            .kind(),
+        would_block
+    );
+    let mut v = Vec::new();
+    assert_eq!(
+        Read::read_to_end(&mut reader, &mut v).unwrap_err().kind(),
+        would_block
+    );
+    let mut s = String::new();
+    assert_eq!(
+        Read::read_to_string(&mut reader, &mut s)
+            .unwrap_err()
+            .kind(),
+        would_block
+    );
+    assert_eq!(
+        Read::read_exact(&mut reader, &mut buf).unwrap_err().kind(),
+        would_block
+    );
+    assert_eq!(
+        reader
+            .read_exact_timeout(&mut buf, Duration::from_secs(3600))
+            .unwrap_err()
+            .kind(),
+        would_block
+    );
+    assert_eq!(
+        reader.read_to_end_limit(&mut v, 16).unwrap_err().kind(),
+        would_block
+    );
+
+    // Data read before the source would block is kept.
+    let mut reader = ScriptedReader::new(vec![
+        Step::Data(b"hello".to_vec()),
+        Step::Pending,
+        Step::Data(b" world".to_vec()),
+    ]);
+    let mut v = Vec::new();
+    assert_eq!(reader.read_to_end(&mut v).unwrap_err().kind(), would_block);
+    assert_eq!(reader.read_to_end(&mut v).unwrap(), 6);
+    assert_eq!(v, b"hello world");
+
+    let mut reader = ScriptedReader::new(vec![
+        Step::Data(b"hello".to_vec()),
+        Step::Pending,
+        Step::Data(b" world".to_vec()),
+    ]);
+    let mut s = String::new();
+    assert_eq!(
+        reader.read_to_string(&mut s).unwrap_err().kind(),
+        would_block
+    );
+    assert_eq!(reader.read_to_string(&mut s).unwrap(), 6);
+    assert_eq!(s, "hello world");
+}
@@ -0,0 +1,157 @@
+use std::{cmp::min, mem::MaybeUninit, ptr, slice};
+
+/// A buffer for reading into, which may be partially uninitialized, for use
+/// with [`Read::read_buf_outcome`](crate::Read::read_buf_outcome).
+///
+/// A `ReadBuf` tracks how much of the buffer has been filled with data, and
+/// how much has been initialized, so that reading into a large buffer, such
+/// as the spare capacity of a `Vec`, doesn't require zero-filling it first.
+pub struct ReadBuf<'buf> {
+    /// The underlying buffer.
+    buf: &'buf mut [MaybeUninit<u8>],
+
+    /// The number of bytes at the start of `buf` which have been filled.
+    filled: usize,
+
+    /// The number of bytes at the start of `buf` which are initialized.
+    initialized: usize,
+}
+
+impl<'buf> ReadBuf<'buf> {
+    /// Construct a new `ReadBuf` wrapping `buf`, which is fully initialized.
+    #[inline]
+    pub fn new(buf: &'buf mut [u8]) -> Self {
+        let initialized = buf.len();
+        let buf = unsafe { &mut *(buf as *mut [u8] as *mut [MaybeUninit<u8>]) };
+        Self {
+            buf,
+            filled: 0,
+            initialized,
+        }
+    }
+
+    /// Construct a new `ReadBuf` wrapping `buf`, which is uninitialized.
+    #[inline]
+    pub fn uninit(buf: &'buf mut [MaybeUninit<u8>]) -> Self {
+        Self {
+            buf,
+            filled: 0,
+            initialized: 0,
+        }
+    }
+
+    /// Return the total length of the buffer.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.buf.len()
+    }
+
+    /// Return the number of bytes which may still be filled.
+    #[inline]
+    pub fn remaining(&self) -> usize {
+        self.buf.len() - self.filled
+    }
+
+    /// Return the data which has been filled.
+    #[inline]
+    pub fn filled(&self) -> &[u8] {
+        unsafe { slice::from_raw_parts(self.buf.as_ptr().cast::<u8>(), self.filled) }
+    }
+
+    /// Return the number of bytes at the start of the buffer which are
+    /// initialized, including the filled part.
+    #[inline]
+    pub fn initialized_len(&self) -> usize {
+        self.initialized
+    }
+
+    /// Initialize the unfilled part of the buffer, zero-filling any of it
+    /// which isn't initialized yet, and return it.
+    pub fn initialize_unfilled(&mut self) -> &mut [u8] {
+        let len = self.buf.len();
+        if self.initialized < len {
+            unsafe {
+                ptr::write_bytes(
+                    self.buf[self.initialized..].as_mut_ptr(),
+                    0,
+                    len - self.initialized,
+                )
+            };
+            self.initialized = len;
+        }
+        let unfilled = &mut self.buf[self.filled..];
+        unsafe { &mut *(unfilled as *mut [MaybeUninit<u8>] as *mut [u8]) }
+    }
+
+    /// Mark `n` more bytes of the buffer, which must already be initialized,
+    /// as filled.
+    ///
+    /// # Panics
+    ///
+    /// Panics if fewer than `n` bytes following the filled part are
+    /// initialized.
+    #[inline]
+    pub fn add_filled(&mut self, n: usize) {
+        let filled = self.filled.checked_add(n).expect("overflow in add_filled");
+        assert!(
+            filled <= self.initialized,
+            "filled bytes must be initialized"
+        );
+        self.filled = filled;
+    }
+
+    /// Copy `data` into the buffer and mark it as filled.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `data` is longer than the remaining space.
+    #[inline]
+    pub fn put_slice(&mut self, data: &[u8]) {
+        assert!(data.len() <= self.remaining(), "data too long for buffer");
+        unsafe {
+            ptr::copy_nonoverlapping(
+                data.as_ptr(),
+                self.buf[self.filled..].as_mut_ptr().cast::<u8>(),
+                data.len(),
+            )
+        };
+        self.filled += data.len();
+        self.initialized = self.initialized.max(self.filled);
+    }
+
+    /// Return the unfilled part of the buffer, which may be uninitialized.
+    ///
+    /// # Safety
+    ///
+    /// The caller must not write uninitialized bytes into the buffer.
+    #[inline]
+    pub unsafe fn unfilled_mut(&mut self) -> &mut [MaybeUninit<u8>] {
+        &mut self.buf[self.filled..]
+    }
+
+    /// Declare that the first `n` bytes of the unfilled part of the buffer
+    /// have been initialized.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure that those bytes have been initialized.
+    #[inline]
+    pub unsafe fn assume_init(&mut self, n: usize) {
+        self.initialized = self.initialized.max(min(self.filled + n, self.buf.len()));
+    }
+}
+
+#[test]
+fn test_read_buf() {
+    let mut storage = [MaybeUninit::<u8>::uninit(); 8];
+    let mut buf = ReadBuf::uninit(&mut storage);
+    buf.put_slice(b"abc");
+    assert_eq!(buf.filled(), b"abc");
+    assert_eq!(buf.remaining(), 5);
+    let unfilled = buf.initialize_unfilled();
+    assert_eq!(unfilled, &[0; 5]);
+    unfilled[..2].copy_from_slice(b"de");
+    buf.add_filled(2);
+    assert_eq!(buf.filled(), b"abcde");
+    assert_eq!(buf.initialized_len(), 8);
+}
@@ -0,0 +1,171 @@
+use crate::{unicode::MAX_UTF8_SIZE, Chars, Lines, Read, ReadOutcome};
+#[cfg(feature = "text")]
+use crate::{Graphemes, Words};
+use std::{io, str};
+
+/// The size of the buffer used by the default `ReadStr::read_to_string`.
+const CHUNK_SIZE: usize = 1024;
+
+/// A trait for readers which produce valid UTF-8, allowing generic code to
+/// require a reader of text rather than a concrete type.
+///
+/// This is implemented by `Utf8Reader`, `TextReader`, and `TextDuplexer`.
+/// It doesn't have `Read` as a supertrait, so that in generic code its
+/// `read_to_string` isn't ambiguous with `Read::read_to_string`.
+pub trait ReadStr {
+    /// Like [`Read::read_outcome`], but produces the result in a `str`. Be
+    /// sure to check the `size` field of the return value to see how many
+    /// bytes were written. `buf` must be at least 4 bytes long.
+    fn read_str(&mut self, buf: &mut str) -> io::Result<ReadOutcome>;
+
+    /// Like [`Read::read_exact`], but produces the result in a `str`. The
+    /// data read must end at a scalar value boundary at the end of `buf`.
+    fn read_exact_str(&mut self, mut buf: &mut str) -> io::Result<()> {
+        while !buf.is_empty() {
+            match self.read_str(buf) {
+                Ok(ReadOutcome { size, status }) => {
+                    let t = buf;
+                    buf = &mut t[size..];
+                    if status.is_end() {
+                        break;
+                    }
+                }
+                Err(ref e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        }
+
+        if buf.is_empty() {
+            Ok(())
+        } else {
+            Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "failed to fill whole buffer",
+            ))
+        }
+    }
+
+    /// Like [`Read::read_to_string`].
+    fn read_to_string(&mut self, buf: &mut String) -> io::Result<usize> {
+        let start_len = buf.len();
+        let mut chunk = "\0".repeat(CHUNK_SIZE);
+        loop {
+            match self.read_str(&mut chunk) {
+                Ok(ReadOutcome { size, status }) => {
+                    buf.push_str(&chunk[..size]);
+                    if status.is_end() {
+                        return Ok(buf.len() - start_len);
+                    }
+                }
+                Err(ref e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    /// Return an iterator over the scalar values of this stream. See
+    /// [`Chars`] for details.
+    #[inline]
+    fn chars(self) -> Chars<Self>
+    where
+        Self: Sized,
+    {
+        Chars::new(self)
+    }
+
+    /// Return an iterator over the extended grapheme clusters of this
+    /// stream. See [`Graphemes`] for details.
+    #[cfg(feature = "text")]
+    #[inline]
+    fn graphemes(self) -> Graphemes<Self>
+    where
+        Self: Sized,
+    {
+        Graphemes::new(self)
+    }
+
+    /// Return an iterator over the words of this stream. See [`Words`] for
+    /// details.
+    #[cfg(feature = "text")]
+    #[inline]
+    fn words(self) -> Words<Self>
+    where
+        Self: Sized,
+    {
+        Words::new(self)
+    }
+
+    /// Return an iterator over the lines of this stream, which also reports
+    /// lulls. See [`Lines`] for details.
+    #[inline]
+    fn lines(self) -> Lines<Self>
+    where
+        Self: Sized,
+    {
+        Lines::new(self)
+    }
+}
+
+/// Read from `reader`, which must produce valid UTF-8 at scalar value
+/// boundaries, into `buf`.
+pub(crate) fn read_into_str<Inner: Read + ?Sized>(
+    reader: &mut Inner,
+    buf: &mut str,
+) -> io::Result<ReadOutcome> {
+    if buf.len() < MAX_UTF8_SIZE {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "buffer for reading into a str must be at least 4 bytes long",
+        ));
+    }
+
+    let bytes = unsafe { buf.as_bytes_mut() };
+    let result = reader.read_outcome(bytes);
+
+    // Readers may leave arbitrary bytes in the buffer after the output, so
+    // clear them, to ensure that `buf` remains valid UTF-8.
+    let size = result.as_ref().map_or(0, |outcome| outcome.size);
+    bytes[size..].iter_mut().for_each(|b| *b = 0);
+    debug_assert!(str::from_utf8(&bytes[..size]).is_ok());
+
+    result
+}
+
+#[cfg(test)]
+fn read_all<R: ReadStr>(reader: &mut R) -> String {
+    let mut s = String::new();
+    reader.read_to_string(&mut s).unwrap();
+    s
+}
+
+#[test]
+fn test_read_str_utf8_reader() {
+    let mut reader = crate::Utf8Reader::new(crate::SliceReader::new(b"caf\xc3\xa9 \xff"));
+    assert_eq!(read_all(&mut reader), "caf\u{e9} \u{fffd}");
+}
+
+#[cfg(feature = "text")]
+#[test]
+fn test_read_str_text_reader() {
+    let mut reader = crate::TextReader::new(crate::SliceReader::new(b"hello\r\nworld"));
+    assert_eq!(read_all(&mut reader), "hello\nworld\n");
+}
+
+#[cfg(feature = "text")]
+#[test]
+fn test_read_exact_str() {
+    let mut reader = crate::TextReader::new(crate::SliceReader::new(b"\x1b[1mhello\n"));
+    let mut buf = "\0".repeat(6);
+    reader.read_exact_str(&mut buf).unwrap();
+    assert_eq!(buf, "hello\n");
+}
+
+#[test]
+fn test_read_str_small_buffer() {
+    let mut reader = crate::Utf8Reader::new(crate::SliceReader::new(b"hello"));
+    let mut buf = "\0".repeat(3);
+    assert_eq!(
+        reader.read_str(&mut buf).unwrap_err().kind(),
+        io::ErrorKind::InvalidInput
+    );
+}
@@ -0,0 +1,82 @@
+//! Defines `RestrictionPolicy` and `RestrictionChecker`.
+
+use unicode_security::{RestrictionLevel, RestrictionLevelDetection};
+
+/// What a `TextReader` or `TextWriter` does when a word exceeds the
+/// configured [`RestrictionLevel`].
+///
+/// [`RestrictionLevel`]: crate::RestrictionLevel
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RestrictionPolicy {
+    /// Count the word, in `restriction_violations`, and continue.
+    Flag,
+
+    /// Fail with [`Error::RestrictionLevelExceeded`].
+    ///
+    /// [`Error::RestrictionLevelExceeded`]: crate::Error::RestrictionLevelExceeded
+    Reject,
+}
+
+/// The longest word checked as a unit. Longer runs of non-whitespace are
+/// checked in pieces of this size, to bound the memory used.
+const MAX_WORD_LEN: usize = 4096;
+
+/// Checks the words of a text stream, delimited by whitespace, against a
+/// [UTS #39 restriction level]. Words containing characters not allowed in
+/// identifiers are considered `RestrictionLevel::Unrestricted`.
+///
+/// [UTS #39 restriction level]: https://www.unicode.org/reports/tr39/#Restriction_Level_Detection
+pub(crate) struct RestrictionChecker {
+    level: RestrictionLevel,
+    word: String,
+}
+
+impl RestrictionChecker {
+    pub(crate) fn new(level: RestrictionLevel) -> Self {
+        Self {
+            level,
+            word: String::new(),
+        }
+    }
+
+    /// Process the next scalar value, and return true if it completes a
+    /// word which exceeds the restriction level.
+    pub(crate) fn step(&mut self, c: char) -> bool {
+        if !c.is_whitespace() {
+            self.word.push(c);
+            if self.word.len() < MAX_WORD_LEN {
+                return false;
+            }
+        }
+        let exceeded =
+            !self.word.is_empty() && !self.word.as_str().check_restriction_level(self.level);
+        self.word.clear();
+        exceeded
+    }
+
+    /// Forget any word in progress.
+    pub(crate) fn reset(&mut self) {
+        self.word.clear();
+    }
+}
+
+#[test]
+fn test_restriction_checker() {
+    let check = |level, s: &str| {
+        let mut checker = RestrictionChecker::new(level);
+        s.chars().filter(|c| checker.step(*c)).count()
+    };
+    assert_eq!(check(RestrictionLevel::ASCIIOnly, "hello world\n"), 0);
+    assert_eq!(check(RestrictionLevel::ASCIIOnly, "héllo world\n"), 1);
+    assert_eq!(check(RestrictionLevel::SingleScript, "héllo world\n"), 0);
+
+    // "pаypal", with a Cyrillic "а".
+    assert_eq!(
+        check(RestrictionLevel::HighlyRestrictive, "p\u{430}ypal\n"),
+        1
+    );
+    assert_eq!(check(RestrictionLevel::Unrestricted, "p\u{430}ypal\n"), 0);
+
+    // Punctuation isn't allowed in identifiers.
+    assert_eq!(check(RestrictionLevel::SingleScript, "a+b\n"), 1);
+}
@@ -0,0 +1,186 @@
+use crate::{Read, ReadOutcome};
+use std::{io, thread, time::Duration};
+
+/// A `Read` implementation which passes data through from an inner `Read`
+/// unmodified, transparently retrying reads which fail with transient
+/// errors.
+pub struct RetryReader<Inner: Read> {
+    /// The wrapped byte stream.
+    inner: Inner,
+
+    /// When and how to retry.
+    policy: RetryPolicy,
+}
+
+/// A policy describing which errors a `RetryReader` retries, and how.
+#[derive(Clone, Debug)]
+pub struct RetryPolicy {
+    /// The maximum number of attempts for a single read, including the
+    /// first. Once this is reached, the error is returned to the caller.
+    pub max_attempts: u32,
+
+    /// How long to wait before the first retry. The delay doubles with each
+    /// subsequent retry.
+    pub backoff: Duration,
+
+    /// The error kinds which are considered transient.
+    pub retryable: Vec<io::ErrorKind>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            backoff: Duration::from_millis(10),
+            retryable: vec![
+                io::ErrorKind::Interrupted,
+                io::ErrorKind::WouldBlock,
+                io::ErrorKind::TimedOut,
+            ],
+        }
+    }
+}
+
+impl<Inner: Read> RetryReader<Inner> {
+    /// Construct a new instance of `RetryReader` wrapping `inner` with the
+    /// default `RetryPolicy`.
+    #[inline]
+    pub fn new(inner: Inner) -> Self {
+        Self::with_policy(inner, RetryPolicy::default())
+    }
+
+    /// Construct a new instance of `RetryReader` wrapping `inner` with the
+    /// given `RetryPolicy`.
+    #[inline]
+    pub fn with_policy(inner: Inner, policy: RetryPolicy) -> Self {
+        Self { inner, policy }
+    }
+}
+
+impl<Inner: Read> Read for RetryReader<Inner> {
+    fn read_outcome(&mut self, buf: &mut [u8]) -> io::Result<ReadOutcome> {
+        let mut attempts = 1;
+        let mut backoff = self.policy.backoff;
+        loop {
+            match self.inner.read_outcome(buf) {
+                Err(ref e)
+                    if attempts < self.policy.max_attempts
+                        && self.policy.retryable.contains(&e.kind()) =>
+                {
+                    attempts += 1;
+                    thread::sleep(backoff);
+                    backoff *= 2;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    #[inline]
+    fn remaining_hint(&self) -> (u64, Option<u64>) {
+        self.inner.remaining_hint()
+    }
+}
+
+impl<Inner: Read> io::Read for RetryReader<Inner> {
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        Read::read(self, buf)
+    }
+
+    #[inline]
+    fn read_vectored(&mut self, bufs: &mut [io::IoSliceMut<'_>]) -> io::Result<usize> {
+        Read::read_vectored(self, bufs)
+    }
+
+    #[cfg(feature = "nightly")]
+    #[inline]
+    fn is_read_vectored(&self) -> bool {
+        Read::is_read_vectored(self)
+    }
+
+    #[inline]
+    fn read_to_end(&mut self, buf: &mut Vec<u8>) -> io::Result<usize> {
+        Read::read_to_end(self, buf)
+    }
+
+    #[inline]
+    fn read_to_string(&mut self, buf: &mut String) -> io::Result<usize> {
+        Read::read_to_string(self, buf)
+    }
+
+    #[inline]
+    fn read_exact(&mut self, buf: &mut [u8]) -> io::Result<()> {
+        Read::read_exact(self, buf)
+    }
+}
+
+#[cfg(test)]
+struct Flaky<'a> {
+    failures: u32,
+    kind: io::ErrorKind,
+    inner: crate::SliceReader<'a>,
+}
+
+#[cfg(test)]
+impl<'a> Read for Flaky<'a> {
+    fn read_outcome(&mut self, buf: &mut [u8]) -> io::Result<ReadOutcome> {
+        if self.failures != 0 {
+            self.failures -= 1;
+            return Err(io::Error::new(self.kind, "flaky"));
+        }
+        self.inner.read_outcome(buf)
+    }
+}
+
+#[cfg(test)]
+fn flaky_policy() -> RetryPolicy {
+    RetryPolicy {
+        max_attempts: 3,
+        backoff: Duration::from_millis(0),
+        ..RetryPolicy::default()
+    }
+}
+
+#[test]
+fn test_retry_reader() {
+    let mut reader = RetryReader::with_policy(
+        Flaky {
+            failures: 2,
+            kind: io::ErrorKind::TimedOut,
+            inner: crate::SliceReader::new(b"hello world"),
+        },
+        flaky_policy(),
+    );
+    let mut s = String::new();
+    reader.read_to_string(&mut s).unwrap();
+    assert_eq!(s, "hello world");
+}
+
+#[test]
+fn test_retry_reader_gives_up() {
+    let mut reader = RetryReader::with_policy(
+        Flaky {
+            failures: 3,
+            kind: io::ErrorKind::TimedOut,
+            inner: crate::SliceReader::new(b"hello world"),
+        },
+        flaky_policy(),
+    );
+    let mut buf = [0; 16];
+    assert!(reader.read_outcome(&mut buf).is_err());
+}
+
+#[test]
+fn test_retry_reader_non_retryable() {
+    let mut reader = RetryReader::with_policy(
+        Flaky {
+            failures: 1,
+            kind: io::ErrorKind::PermissionDenied,
+            inner: crate::SliceReader::new(b"hello world"),
+        },
+        flaky_policy(),
+    );
+    let mut buf = [0; 16];
+    assert!(reader.read_outcome(&mut buf).is_err());
+}
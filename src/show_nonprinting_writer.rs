@@ -0,0 +1,181 @@
+use crate::{unicode::is_default_ignorable, Close, Status, Write};
+use std::{fmt::Write as _, io, str};
+
+/// A `Write` implementation which renders data written to it with
+/// nonprinting content made visible, in the manner of `cat -v`, and writes
+/// the result to an inner `Write`.
+///
+///  - ASCII control codes other than '\n' and '\t' are rendered in caret
+///    notation, such as `^M` for '\r' and `^?` for DEL.
+///  - Bytes which aren't part of a valid UTF-8 encoding are rendered in
+///    meta notation, such as `M-^?` for 0xff and `M-i` for 0xe9.
+///  - C1 control codes, U+2028 (LINE SEPARATOR), U+2029 (PARAGRAPH
+///    SEPARATOR), and characters which are normally invisible, such as
+///    U+200B (ZERO WIDTH SPACE) and U+FEFF (BOM), are rendered as their
+///    code point, such as `<U+200B>`.
+///
+/// This is useful for inspecting exactly what a "dirty" stream contained
+/// before sanitization. The output is written with `write_all_utf8`, so the
+/// inner stream may be a `TextWriter`.
+pub struct ShowNonprintingWriter<Inner: Write> {
+    /// The wrapped byte stream.
+    inner: Inner,
+
+    /// An incomplete UTF-8 encoding at the end of the last write.
+    partial: Vec<u8>,
+
+    /// Scratch space for rendered output.
+    rendered: String,
+}
+
+impl<Inner: Write> ShowNonprintingWriter<Inner> {
+    /// Construct a new instance of `ShowNonprintingWriter` wrapping `inner`.
+    #[inline]
+    pub fn new(inner: Inner) -> Self {
+        Self {
+            inner,
+            partial: Vec::new(),
+            rendered: String::new(),
+        }
+    }
+
+    /// Flush and close the underlying stream and return the underlying
+    /// stream object.
+    pub fn close_into_inner(mut self) -> io::Result<Inner> {
+        self.flush(Status::End)?;
+        Ok(self.inner)
+    }
+
+    /// Render `bytes` into `self.rendered`, holding back an incomplete UTF-8
+    /// encoding at the end unless `end` is true.
+    fn render(&mut self, mut bytes: &[u8], end: bool) {
+        loop {
+            match str::from_utf8(bytes) {
+                Ok(s) => {
+                    self.render_str(s);
+                    return;
+                }
+                Err(error) => {
+                    let (valid, rest) = bytes.split_at(error.valid_up_to());
+                    self.render_str(str::from_utf8(valid).unwrap());
+                    match error.error_len() {
+                        Some(len) => {
+                            rest[..len].iter().for_each(|b| self.render_byte(*b));
+                            bytes = &rest[len..];
+                        }
+                        None if end => {
+                            rest.iter().for_each(|b| self.render_byte(*b));
+                            return;
+                        }
+                        None => {
+                            self.partial.extend_from_slice(rest);
+                            return;
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    fn render_str(&mut self, s: &str) {
+        for c in s.chars() {
+            match c {
+                '\n' | '\t' => self.rendered.push(c),
+                '\0'..='\x1f' => {
+                    self.rendered.push('^');
+                    self.rendered.push(char::from(c as u8 + b'@'));
+                }
+                '\x7f' => self.rendered.push_str("^?"),
+                '\u{2028}' | '\u{2029}' => write!(self.rendered, "<U+{:04X}>", c as u32).unwrap(),
+                c if c.is_control() || is_default_ignorable(c) => {
+                    write!(self.rendered, "<U+{:04X}>", c as u32).unwrap()
+                }
+                c => self.rendered.push(c),
+            }
+        }
+    }
+
+    /// Render a byte which isn't part of a valid UTF-8 encoding.
+    fn render_byte(&mut self, b: u8) {
+        self.rendered.push_str("M-");
+        match b & 0x7f {
+            b @ 0..=0x1f => {
+                self.rendered.push('^');
+                self.rendered.push(char::from(b + b'@'));
+            }
+            0x7f => self.rendered.push_str("^?"),
+            b => self.rendered.push(char::from(b)),
+        }
+    }
+}
+
+impl<Inner: Write> Close for ShowNonprintingWriter<Inner> {
+    type Output = Inner;
+
+    #[inline]
+    fn close_into_inner(self) -> io::Result<Inner> {
+        ShowNonprintingWriter::close_into_inner(self)
+    }
+}
+
+impl<Inner: Write> Write for ShowNonprintingWriter<Inner> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.rendered.clear();
+        if self.partial.is_empty() {
+            self.render(buf, false);
+        } else {
+            let mut bytes = std::mem::take(&mut self.partial);
+            bytes.extend_from_slice(buf);
+            self.render(&bytes, false);
+        }
+        self.inner.write_all_utf8(&self.rendered)?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self, status: Status) -> io::Result<()> {
+        if status.is_end() && !self.partial.is_empty() {
+            self.rendered.clear();
+            let bytes = std::mem::take(&mut self.partial);
+            self.render(&bytes, true);
+            self.inner.write_all_utf8(&self.rendered)?;
+        }
+        self.inner.flush(status)
+    }
+
+    #[inline]
+    fn abandon(&mut self) {
+        self.partial.clear();
+        self.inner.abandon()
+    }
+}
+
+#[cfg(test)]
+fn show(chunks: &[&[u8]]) -> String {
+    let mut v = Vec::new();
+    let mut writer = ShowNonprintingWriter::new(crate::StdWriter::new(&mut v));
+    for chunk in chunks {
+        writer.write_all(chunk).unwrap();
+    }
+    writer.close_into_inner().unwrap();
+    String::from_utf8(v).unwrap()
+}
+
+#[test]
+fn test_show_nonprinting_writer() {
+    assert_eq!(show(&[b"hello\tworld\n"]), "hello\tworld\n");
+    assert_eq!(show(&[b"hello\r\n"]), "hello^M\n");
+    assert_eq!(show(&[b"\x00\x1b[m\x7f"]), "^@^[[m^?");
+    assert_eq!(show(&[b"\xff\xe9\x80"]), "M-^?M-iM-^@");
+    assert_eq!(show(&["caf\u{e9}".as_bytes()]), "caf\u{e9}");
+    assert_eq!(
+        show(&["a\u{200b}b\u{feff}\u{85}\u{2028}".as_bytes()]),
+        "a<U+200B>b<U+FEFF><U+0085><U+2028>"
+    );
+}
+
+#[test]
+fn test_show_nonprinting_writer_split() {
+    assert_eq!(show(&[b"caf\xc3", b"\xa9"]), "caf\u{e9}");
+    assert_eq!(show(&[b"caf\xc3"]), "cafM-C");
+    assert_eq!(show(&[b"\xe2\x80", b"\x8b!"]), "<U+200B>!");
+}
@@ -1,5 +1,8 @@
-use crate::{Read, ReadOutcome};
-use std::io::{self, IoSliceMut};
+use crate::{Read, ReadBuf, ReadOutcome};
+use std::{
+    cmp::min,
+    io::{self, IoSliceMut},
+};
 
 /// Adapts an `&[u8]` to implement `Read`.
 pub struct SliceReader<'slice> {
@@ -31,6 +34,22 @@ impl<'slice> Read for SliceReader<'slice> {
         ))
     }
 
+    #[inline]
+    fn read_buf_outcome(&mut self, buf: &mut ReadBuf<'_>) -> io::Result<ReadOutcome> {
+        if self.ended {
+            return Ok(ReadOutcome::end(0));
+        }
+
+        let size = min(buf.remaining(), self.slice.len());
+        let (head, tail) = self.slice.split_at(size);
+        buf.put_slice(head);
+        self.slice = tail;
+        Ok(ReadOutcome::ready_or_not(
+            size,
+            buf.capacity() == 0 || !self.slice.is_empty(),
+        ))
+    }
+
     #[inline]
     fn read_vectored_outcome(&mut self, bufs: &mut [IoSliceMut<'_>]) -> io::Result<ReadOutcome> {
         if self.ended {
@@ -79,6 +98,16 @@ impl<'slice> Read for SliceReader<'slice> {
 
         io::Read::read_exact(&mut self.slice, buf)
     }
+
+    #[inline]
+    fn remaining_hint(&self) -> (u64, Option<u64>) {
+        let len = if self.ended {
+            0
+        } else {
+            self.slice.len() as u64
+        };
+        (len, Some(len))
+    }
 }
 
 impl<'slice> io::Read for SliceReader<'slice> {
@@ -113,3 +142,12 @@ impl<'slice> io::Read for SliceReader<'slice> {
         Read::read_exact(self, buf)
     }
 }
+
+#[test]
+fn test_slice_reader_remaining_hint() {
+    let mut reader = SliceReader::new(b"hello world");
+    assert_eq!(reader.remaining_hint(), (11, Some(11)));
+    let mut buf = [0; 6];
+    reader.read_exact(&mut buf).unwrap();
+    assert_eq!(reader.remaining_hint(), (5, Some(5)));
+}
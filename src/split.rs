@@ -0,0 +1,157 @@
+use crate::{Read, ReadOutcome, Status, Write};
+use std::{
+    io,
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+/// Split a duplex stream, such as a socket, into a `ReadHalf` and a
+/// `WriteHalf` which can be used independently, including from different
+/// threads.
+///
+/// The halves share the stream, locking it for the duration of each
+/// operation, so a read which blocks waiting for input also blocks writes
+/// on the other half until it returns. Streams which can be cloned at the
+/// OS level, such as `TcpStream` via `try_clone`, can avoid this by using
+/// separate handles for each direction instead.
+pub fn split<Inner: Read + Write>(inner: Inner) -> (ReadHalf<Inner>, WriteHalf<Inner>) {
+    let shared = Arc::new(Mutex::new(inner));
+    (
+        ReadHalf {
+            shared: Arc::clone(&shared),
+        },
+        WriteHalf { shared },
+    )
+}
+
+/// The input half of a duplex stream, returned from [`split`].
+pub struct ReadHalf<Inner> {
+    /// The shared stream.
+    shared: Arc<Mutex<Inner>>,
+}
+
+/// The output half of a duplex stream, returned from [`split`].
+pub struct WriteHalf<Inner> {
+    /// The shared stream.
+    shared: Arc<Mutex<Inner>>,
+}
+
+impl<Inner> ReadHalf<Inner> {
+    /// Reunite this `ReadHalf` with its `WriteHalf`, returning the
+    /// underlying stream object.
+    ///
+    /// # Panics
+    ///
+    /// This panics if `write` did not come from the same call to `split`.
+    pub fn unsplit(self, write: WriteHalf<Inner>) -> Inner {
+        assert!(
+            Arc::ptr_eq(&self.shared, &write.shared),
+            "unsplit called with halves of different streams"
+        );
+        drop(write);
+        match Arc::try_unwrap(self.shared) {
+            Ok(mutex) => mutex.into_inner().unwrap_or_else(|e| e.into_inner()),
+            Err(_) => unreachable!(),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Inner> {
+        self.shared.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl<Inner> WriteHalf<Inner> {
+    fn lock(&self) -> MutexGuard<'_, Inner> {
+        self.shared.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl<Inner: Read> Read for ReadHalf<Inner> {
+    #[inline]
+    fn read_outcome(&mut self, buf: &mut [u8]) -> io::Result<ReadOutcome> {
+        self.lock().read_outcome(buf)
+    }
+}
+
+impl<Inner: Read> io::Read for ReadHalf<Inner> {
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        Read::read(self, buf)
+    }
+
+    #[inline]
+    fn read_vectored(&mut self, bufs: &mut [io::IoSliceMut<'_>]) -> io::Result<usize> {
+        Read::read_vectored(self, bufs)
+    }
+
+    #[cfg(feature = "nightly")]
+    #[inline]
+    fn is_read_vectored(&self) -> bool {
+        Read::is_read_vectored(self)
+    }
+
+    #[inline]
+    fn read_to_end(&mut self, buf: &mut Vec<u8>) -> io::Result<usize> {
+        Read::read_to_end(self, buf)
+    }
+
+    #[inline]
+    fn read_to_string(&mut self, buf: &mut String) -> io::Result<usize> {
+        Read::read_to_string(self, buf)
+    }
+
+    #[inline]
+    fn read_exact(&mut self, buf: &mut [u8]) -> io::Result<()> {
+        Read::read_exact(self, buf)
+    }
+}
+
+impl<Inner: Write> Write for WriteHalf<Inner> {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.lock().write(buf)
+    }
+
+    #[inline]
+    fn flush(&mut self, status: Status) -> io::Result<()> {
+        self.lock().flush(status)
+    }
+
+    #[inline]
+    fn abandon(&mut self) {
+        self.lock().abandon()
+    }
+
+    #[inline]
+    fn write_all_utf8(&mut self, s: &str) -> io::Result<()> {
+        self.lock().write_all_utf8(s)
+    }
+
+    #[inline]
+    fn write_outcome(&mut self, buf: &[u8], status: Status) -> io::Result<()> {
+        self.lock().write_outcome(buf, status)
+    }
+}
+
+#[test]
+fn test_split() {
+    let (mut read, mut write) = split(crate::Cursor::new(b"hello".to_vec()));
+    let mut buf = [0; 5];
+    crate::Read::read_exact(&mut read, &mut buf).unwrap();
+    assert_eq!(&buf, b"hello");
+
+    let thread = std::thread::spawn(move || {
+        write.write_all(b" world").unwrap();
+        write
+    });
+    let write = thread.join().unwrap();
+    let cursor = read.unsplit(write);
+    assert_eq!(cursor.get_ref(), b"hello world");
+}
+
+#[test]
+#[should_panic]
+fn test_unsplit_mismatch() {
+    let (read, _) = split(crate::Cursor::new(Vec::new()));
+    let (_, write) = split(crate::Cursor::new(Vec::new()));
+    read.unsplit(write);
+}
@@ -31,6 +31,15 @@ impl Status {
     pub fn is_end(&self) -> bool {
         *self == Self::End
     }
+
+    /// Test whether this status marks a boundary in the data, which is to
+    /// say a lull or the end of the stream, where partial sequences must be
+    /// resolved. `Readiness::Pending` isn't a boundary, since it says
+    /// nothing about the data itself.
+    #[inline]
+    pub fn is_boundary(&self) -> bool {
+        matches!(self, Self::Open(Readiness::Lull) | Self::End)
+    }
 }
 
 /// Whether a stream is ready or in a temporary lull. Most users can
@@ -46,4 +55,10 @@ pub enum Readiness {
     /// This is not to be confused with data which waiting to be read but which
     /// will take time to be delivered.
     Lull,
+
+    /// The input source is in non-blocking mode and no bytes could be read
+    /// without blocking. Unlike `Lull`, this says nothing about the data
+    /// itself; the reader should wait for the source to become readable,
+    /// such as by polling it, and read again.
+    Pending,
 }
@@ -0,0 +1,163 @@
+use crate::{Error, Read, ReadOutcome, Readiness, Status, Write};
+use std::{
+    fs::{File, OpenOptions},
+    io::{self, Write as _},
+    os::windows::io::{AsHandle, AsRawHandle, BorrowedHandle, RawHandle},
+    path::Path,
+};
+
+/// Adapts a Windows named pipe, opened as a [`std::fs::File`], to implement
+/// `Read` and `Write`, with semantics suited to local IPC protocols:
+///
+///  - Reads which would block report `Readiness::Pending` instead of
+///    failing with `ErrorKind::WouldBlock`.
+///  - A read which fails because the other end of the pipe has been closed
+///    reports the end of the stream.
+///
+/// Pipes can't be half-closed, so flushing with `Status::End` just flushes
+/// the output and prevents further writes; the peer sees the end of the
+/// stream when the pipe is closed.
+pub struct StdNamedPipe {
+    /// The wrapped pipe handle.
+    inner: File,
+
+    /// The other end of the pipe has been closed.
+    read_ended: bool,
+
+    /// The output stream has been ended.
+    write_ended: bool,
+}
+
+impl StdNamedPipe {
+    /// Construct a new instance of `StdNamedPipe` wrapping `inner`, which
+    /// should be a handle to a named pipe.
+    #[inline]
+    pub fn new(inner: File) -> Self {
+        Self {
+            inner,
+            read_ended: false,
+            write_ended: false,
+        }
+    }
+
+    /// Open the client end of the named pipe at `path`, such as
+    /// `\\.\pipe\name`, for reading and writing.
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let inner = OpenOptions::new().read(true).write(true).open(path)?;
+        Ok(Self::new(inner))
+    }
+
+    /// Gets a reference to the underlying pipe handle.
+    #[inline]
+    pub fn get_ref(&self) -> &File {
+        &self.inner
+    }
+
+    /// Consume this `StdNamedPipe` and return the underlying pipe handle.
+    #[inline]
+    pub fn into_inner(self) -> File {
+        self.inner
+    }
+}
+
+impl Read for StdNamedPipe {
+    fn read_outcome(&mut self, buf: &mut [u8]) -> io::Result<ReadOutcome> {
+        if self.read_ended {
+            return Ok(ReadOutcome::end(0));
+        }
+        match io::Read::read(&mut self.inner, buf) {
+            Ok(0) if !buf.is_empty() => {
+                self.read_ended = true;
+                Ok(ReadOutcome::end(0))
+            }
+            Ok(size) => Ok(ReadOutcome::ready(size)),
+            Err(ref e) if e.kind() == io::ErrorKind::BrokenPipe => {
+                self.read_ended = true;
+                Ok(ReadOutcome::end(0))
+            }
+            Err(ref e) if e.kind() == io::ErrorKind::WouldBlock => Ok(ReadOutcome::pending(0)),
+            Err(ref e) if e.kind() == io::ErrorKind::Interrupted => Ok(ReadOutcome::ready(0)),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+impl io::Read for StdNamedPipe {
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        Read::read(self, buf)
+    }
+
+    #[inline]
+    fn read_vectored(&mut self, bufs: &mut [io::IoSliceMut<'_>]) -> io::Result<usize> {
+        Read::read_vectored(self, bufs)
+    }
+
+    #[cfg(feature = "nightly")]
+    #[inline]
+    fn is_read_vectored(&self) -> bool {
+        Read::is_read_vectored(self)
+    }
+
+    #[inline]
+    fn read_to_end(&mut self, buf: &mut Vec<u8>) -> io::Result<usize> {
+        Read::read_to_end(self, buf)
+    }
+
+    #[inline]
+    fn read_to_string(&mut self, buf: &mut String) -> io::Result<usize> {
+        Read::read_to_string(self, buf)
+    }
+
+    #[inline]
+    fn read_exact(&mut self, buf: &mut [u8]) -> io::Result<()> {
+        Read::read_exact(self, buf)
+    }
+}
+
+impl Write for StdNamedPipe {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.write_ended {
+            return Err(stream_already_ended());
+        }
+        self.inner.write(buf)
+    }
+
+    fn flush(&mut self, status: Status) -> io::Result<()> {
+        if self.write_ended {
+            return Err(stream_already_ended());
+        }
+        match status {
+            Status::Open(Readiness::Ready) | Status::Open(Readiness::Pending) => Ok(()),
+            Status::Open(Readiness::Lull) => self.inner.flush(),
+            Status::End => {
+                self.write_ended = true;
+                self.inner.flush()
+            }
+        }
+    }
+
+    #[inline]
+    fn abandon(&mut self) {
+        self.write_ended = true;
+    }
+}
+
+impl AsRawHandle for StdNamedPipe {
+    #[inline]
+    fn as_raw_handle(&self) -> RawHandle {
+        self.inner.as_raw_handle()
+    }
+}
+
+impl AsHandle for StdNamedPipe {
+    #[inline]
+    fn as_handle(&self) -> BorrowedHandle<'_> {
+        self.inner.as_handle()
+    }
+}
+
+fn stream_already_ended() -> io::Error {
+    Error::StreamEnded.into()
+}
@@ -1,11 +1,17 @@
 use crate::{default_read_exact, default_read_to_end, default_read_to_string, Read, ReadOutcome};
-#[cfg(unix)]
-use std::os::unix::io::AsRawFd;
 #[cfg(windows)]
-use std::os::windows::io::AsRawHandle;
+use crate::{read::default_read_vectored_outcome, windows_console_reader::ConsoleInput};
+#[cfg(windows)]
+use std::os::windows::io::{AsHandle, AsRawHandle, BorrowedHandle, FromRawHandle, RawHandle};
+use std::{
+    fs::File,
+    io::{self, IoSliceMut, Seek, SeekFrom},
+    mem::ManuallyDrop,
+};
+#[cfg(not(windows))]
 use std::{
-    io::{self, IoSliceMut},
     mem::MaybeUninit,
+    os::unix::io::{AsFd, AsRawFd, BorrowedFd, FromRawFd, RawFd},
 };
 
 /// Adapts an `io::Read` to implement `Read`.
@@ -13,86 +19,236 @@ pub struct StdReader<Inner: io::Read> {
     inner: Inner,
     sticky_end: bool,
     line_by_line: bool,
+    nonblocking: bool,
     ended: bool,
+    /// If `inner` is a regular file, its size when the reader was
+    /// constructed, for `remaining_hint`.
+    file_size: Option<u64>,
+    /// The position in the file, if `file_size` is known.
+    position: u64,
+    #[cfg(windows)]
+    console: Option<ConsoleInput>,
 }
 
 #[cfg(not(windows))]
 impl<Inner: io::Read + AsRawFd> StdReader<Inner> {
     /// Construct a new `StdReader` which wraps `inner`, which implements
-    /// `AsRawFd`, and automatically sets the `line_by_line` setting if
-    /// appropriate.
+    /// `AsRawFd`, and automatically selects the mode with
+    /// [`StdReaderMode::detect`].
+    ///
+    /// To select the mode explicitly, or to have reads from a non-blocking
+    /// `inner` which would block report `Readiness::Pending`, use
+    /// [`StdReaderOptions`].
     pub fn new(inner: Inner) -> Self {
-        let line_by_line = unsafe {
-            let mut termios = MaybeUninit::<libc::termios>::uninit();
-            if libc::tcgetattr(inner.as_raw_fd(), termios.as_mut_ptr()) == 0 {
-                (termios.assume_init().c_lflag & libc::ICANON) == libc::ICANON
-            } else {
-                // `tcgetattr` fails when it's not reading from a terminal.
-                false
-            }
-        };
-
-        if line_by_line {
-            StdReader::line_by_line(inner)
-        } else {
-            StdReader::generic(inner)
+        let mode = StdReaderMode::detect(&inner);
+        let mut reader = StdReaderOptions::new().mode(mode).build(inner);
+        if mode == StdReaderMode::Generic {
+            let file = ManuallyDrop::new(unsafe { File::from_raw_fd(reader.inner.as_raw_fd()) });
+            reader.set_file_extent(&file);
         }
+        reader
     }
 }
 
 #[cfg(windows)]
 impl<Inner: io::Read + AsRawHandle> StdReader<Inner> {
     /// Construct a new `StdReader` which wraps `inner`, which implements
-    /// `AsRawHandle`.
-    ///
-    /// TODO: Does Windows have a concept of line-by-line console input?
+    /// `AsRawHandle`. If `inner` is a console, it's read line-by-line using
+    /// `ReadConsoleW`, as [`WindowsConsoleReader`](crate::WindowsConsoleReader)
+    /// does, so that input is decoded independently of the console code page.
     #[cfg(windows)]
     pub fn new(inner: Inner) -> Self {
-        StdReader::generic(inner)
+        let handle = inner.as_raw_handle();
+        if crate::windows_console::is_console(handle) {
+            let mut reader = StdReaderOptions::new()
+                .mode(StdReaderMode::LineByLine)
+                .build(inner);
+            reader.console = Some(ConsoleInput::new(handle));
+            reader
+        } else {
+            let file = ManuallyDrop::new(unsafe { File::from_raw_handle(handle) });
+            let mut reader = StdReaderOptions::new().build(inner);
+            reader.set_file_extent(&file);
+            reader
+        }
     }
 }
 
 impl<Inner: io::Read> StdReader<Inner> {
     /// Construct a new `StdReader` which wraps `inner` with generic settings.
     pub fn generic(inner: Inner) -> Self {
-        Self {
-            inner,
-            sticky_end: true,
-            line_by_line: false,
-            ended: false,
-        }
+        StdReaderOptions::new()
+            .mode(StdReaderMode::Generic)
+            .build(inner)
     }
 
     /// Construct a new `StdReader` which wraps `inner`. When a lull occurs,
     /// don't treat it as the end of the stream, but keep waiting to see if
     /// more data arrives.
     pub fn wait_for_lulls(inner: Inner) -> Self {
-        Self {
-            inner,
-            sticky_end: false,
-            line_by_line: false,
-            ended: false,
-        }
+        StdReaderOptions::new()
+            .mode(StdReaderMode::WaitForLulls)
+            .build(inner)
     }
 
     /// Construct a new `StdReader` which wraps an `inner` which reads its
     /// input line-by-line, such as stdin on a terminal.
     pub fn line_by_line(inner: Inner) -> Self {
+        StdReaderOptions::new()
+            .mode(StdReaderMode::LineByLine)
+            .build(inner)
+    }
+
+    /// Gets a reference to the underlying reader.
+    #[inline]
+    pub fn get_ref(&self) -> &Inner {
+        &self.inner
+    }
+
+    /// Gets a mutable reference to the underlying reader.
+    ///
+    /// It is inadvisable to directly read from the underlying reader.
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut Inner {
+        &mut self.inner
+    }
+
+    /// If `file` is a regular file, record its size and current position so
+    /// that `remaining_hint` can report how much of it remains.
+    fn set_file_extent(&mut self, file: &File) {
+        let mut file = file;
+        if let Ok(metadata) = file.metadata() {
+            if metadata.is_file() {
+                if let Ok(position) = file.stream_position() {
+                    self.file_size = Some(metadata.len());
+                    self.position = position;
+                }
+            }
+        }
+    }
+}
+
+/// How a `StdReader` interprets the reads of its inner stream.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StdReaderMode {
+    /// A zero-length read is the end of the stream.
+    Generic,
+
+    /// The inner stream produces its input line-by-line, such as stdin on a
+    /// terminal, so a read ending in '\n' is reported as a lull.
+    LineByLine,
+
+    /// A zero-length read is reported as a lull, and reading continues to
+    /// see if more data arrives.
+    WaitForLulls,
+}
+
+impl StdReaderMode {
+    /// Select `LineByLine` if `fd` is a terminal in canonical mode, and
+    /// `Generic` otherwise.
+    #[cfg(not(windows))]
+    pub fn detect<Fd: AsRawFd>(fd: &Fd) -> Self {
+        let line_by_line = unsafe {
+            let mut termios = MaybeUninit::<libc::termios>::uninit();
+            if libc::tcgetattr(fd.as_raw_fd(), termios.as_mut_ptr()) == 0 {
+                (termios.assume_init().c_lflag & libc::ICANON) == libc::ICANON
+            } else {
+                // `tcgetattr` fails when it's not reading from a terminal.
+                false
+            }
+        };
+
+        if line_by_line {
+            Self::LineByLine
+        } else {
+            Self::Generic
+        }
+    }
+}
+
+/// A builder for `StdReader`, for selecting its mode explicitly rather than
+/// having `StdReader::new` detect it, and for enabling non-blocking mode.
+#[derive(Clone, Debug)]
+pub struct StdReaderOptions {
+    mode: StdReaderMode,
+    sticky_end: Option<bool>,
+    nonblocking: bool,
+}
+
+impl StdReaderOptions {
+    /// Construct a new `StdReaderOptions` with `StdReaderMode::Generic`.
+    #[inline]
+    pub fn new() -> Self {
         Self {
+            mode: StdReaderMode::Generic,
+            sticky_end: None,
+            nonblocking: false,
+        }
+    }
+
+    /// Set the mode.
+    #[inline]
+    pub fn mode(&mut self, mode: StdReaderMode) -> &mut Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Set whether the end of the stream is sticky, so that once a
+    /// zero-length read occurs, the inner stream isn't read again. By
+    /// default, this is enabled except in `StdReaderMode::WaitForLulls`.
+    #[inline]
+    pub fn sticky_end(&mut self, enable: bool) -> &mut Self {
+        self.sticky_end = Some(enable);
+        self
+    }
+
+    /// Set whether the inner stream is in non-blocking mode, so that reads
+    /// which fail with `io::ErrorKind::WouldBlock` report
+    /// `Readiness::Pending` instead, and the caller should wait for the
+    /// stream to become readable and read again. By default, this is
+    /// disabled, and such errors are returned.
+    #[inline]
+    pub fn nonblocking(&mut self, enable: bool) -> &mut Self {
+        self.nonblocking = enable;
+        self
+    }
+
+    /// Construct a `StdReader` wrapping `inner` with the configured options.
+    pub fn build<Inner: io::Read>(&self, inner: Inner) -> StdReader<Inner> {
+        let sticky_end = self
+            .sticky_end
+            .unwrap_or(self.mode != StdReaderMode::WaitForLulls);
+        StdReader {
             inner,
-            sticky_end: true,
-            line_by_line: true,
+            sticky_end,
+            line_by_line: self.mode == StdReaderMode::LineByLine,
+            nonblocking: self.nonblocking,
             ended: false,
+            file_size: None,
+            position: 0,
+            #[cfg(windows)]
+            console: None,
         }
     }
 }
 
+impl Default for StdReaderOptions {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl<Inner: io::Read> Read for StdReader<Inner> {
     #[inline]
     fn read_outcome(&mut self, buf: &mut [u8]) -> io::Result<ReadOutcome> {
         if self.ended {
             return Ok(ReadOutcome::end(0));
         }
+        #[cfg(windows)]
+        if let Some(console) = &mut self.console {
+            return console.read_outcome(buf);
+        }
         match self.inner.read(buf) {
             Ok(0) if !buf.is_empty() => {
                 if self.sticky_end {
@@ -103,6 +259,7 @@ impl<Inner: io::Read> Read for StdReader<Inner> {
                 }
             }
             Ok(size) => {
+                self.position += size as u64;
                 if self.line_by_line && buf[size - 1] == b'\n' {
                     Ok(ReadOutcome::lull(size))
                 } else {
@@ -110,6 +267,9 @@ impl<Inner: io::Read> Read for StdReader<Inner> {
                 }
             }
             Err(ref e) if e.kind() == io::ErrorKind::Interrupted => Ok(ReadOutcome::ready(0)),
+            Err(ref e) if self.nonblocking && e.kind() == io::ErrorKind::WouldBlock => {
+                Ok(ReadOutcome::pending(0))
+            }
             Err(e) => Err(e),
         }
     }
@@ -119,6 +279,10 @@ impl<Inner: io::Read> Read for StdReader<Inner> {
         if self.ended {
             return Ok(ReadOutcome::end(0));
         }
+        #[cfg(windows)]
+        if self.console.is_some() {
+            return default_read_vectored_outcome(self, bufs);
+        }
         match self.inner.read_vectored(bufs) {
             Ok(0) if !bufs.iter().all(|b| b.is_empty()) => {
                 if self.sticky_end {
@@ -129,6 +293,7 @@ impl<Inner: io::Read> Read for StdReader<Inner> {
                 }
             }
             Ok(size) => {
+                self.position += size as u64;
                 if self.line_by_line {
                     let mut i = size;
                     let mut saw_line = false;
@@ -147,6 +312,9 @@ impl<Inner: io::Read> Read for StdReader<Inner> {
                 Ok(ReadOutcome::ready(size))
             }
             Err(ref e) if e.kind() == io::ErrorKind::Interrupted => Ok(ReadOutcome::ready(0)),
+            Err(ref e) if self.nonblocking && e.kind() == io::ErrorKind::WouldBlock => {
+                Ok(ReadOutcome::pending(0))
+            }
             Err(e) => Err(e),
         }
     }
@@ -186,6 +354,62 @@ impl<Inner: io::Read> Read for StdReader<Inner> {
 
         default_read_exact(self, buf)
     }
+
+    #[inline]
+    fn remaining_hint(&self) -> (u64, Option<u64>) {
+        if self.ended {
+            return (0, Some(0));
+        }
+        // The file may grow, so its size is only a lower bound.
+        match self.file_size {
+            Some(file_size) => (file_size.saturating_sub(self.position), None),
+            None => (0, None),
+        }
+    }
+}
+
+#[cfg(not(windows))]
+impl<Inner: io::Read + AsRawFd> AsRawFd for StdReader<Inner> {
+    #[inline]
+    fn as_raw_fd(&self) -> RawFd {
+        self.inner.as_raw_fd()
+    }
+}
+
+#[cfg(not(windows))]
+impl<Inner: io::Read + AsFd> AsFd for StdReader<Inner> {
+    #[inline]
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.inner.as_fd()
+    }
+}
+
+#[cfg(windows)]
+impl<Inner: io::Read + AsRawHandle> AsRawHandle for StdReader<Inner> {
+    #[inline]
+    fn as_raw_handle(&self) -> RawHandle {
+        self.inner.as_raw_handle()
+    }
+}
+
+#[cfg(windows)]
+impl<Inner: io::Read + AsHandle> AsHandle for StdReader<Inner> {
+    #[inline]
+    fn as_handle(&self) -> BorrowedHandle<'_> {
+        self.inner.as_handle()
+    }
+}
+
+/// Seeking clears any ended state, so that reading may resume at the new
+/// position.
+impl<Inner: io::Read + Seek> Seek for StdReader<Inner> {
+    #[inline]
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let pos = self.inner.seek(pos)?;
+        self.ended = false;
+        self.position = pos;
+        Ok(pos)
+    }
 }
 
 #[test]
@@ -196,3 +420,85 @@ fn test_std_reader() {
     reader.read_to_string(&mut s).unwrap();
     assert_eq!(s, "hello world");
 }
+
+#[test]
+fn test_std_reader_seek() {
+    let mut reader = StdReader::generic(io::Cursor::new(b"hello world"));
+    let mut s = String::new();
+    reader.read_to_string(&mut s).unwrap();
+    reader.seek(SeekFrom::Start(6)).unwrap();
+    s.clear();
+    reader.read_to_string(&mut s).unwrap();
+    assert_eq!(s, "world");
+}
+
+#[test]
+fn test_std_reader_remaining_hint() {
+    let len = std::fs::metadata("Cargo.toml").unwrap().len();
+    let mut reader = StdReader::new(File::open("Cargo.toml").unwrap());
+    assert_eq!(reader.remaining_hint(), (len, None));
+    let mut buf = [0; 6];
+    reader.read_exact(&mut buf).unwrap();
+    assert_eq!(reader.remaining_hint(), (len - 6, None));
+    let mut v = Vec::new();
+    reader.read_to_end(&mut v).unwrap();
+    assert_eq!(reader.remaining_hint(), (0, Some(0)));
+}
+
+#[cfg(not(windows))]
+#[test]
+fn test_std_reader_as_raw_fd() {
+    let file = File::open("Cargo.toml").unwrap();
+    let fd = file.as_raw_fd();
+    let reader = StdReader::new(file);
+    assert_eq!(reader.as_raw_fd(), fd);
+    assert_eq!(reader.as_fd().as_raw_fd(), fd);
+}
+
+#[test]
+fn test_std_reader_options() {
+    use crate::testing::{ScriptedReader, Step};
+
+    let input = ScriptedReader::new(vec![Step::Data(b"hello\n".to_vec())]);
+    let mut reader = StdReaderOptions::new()
+        .mode(StdReaderMode::LineByLine)
+        .sticky_end(false)
+        .build(input);
+    let mut buf = [0; 16];
+    let outcome = reader.read_outcome(&mut buf).unwrap();
+    assert_eq!(outcome.size, 6);
+    assert_eq!(outcome.status, crate::Status::Open(crate::Readiness::Lull));
+    let outcome = reader.read_outcome(&mut buf).unwrap();
+    assert_eq!(outcome.size, 0);
+    assert_eq!(outcome.status, crate::Status::Open(crate::Readiness::Lull));
+}
+
+#[cfg(not(windows))]
+#[test]
+fn test_std_reader_nonblocking() {
+    use crate::{Readiness, Status};
+    use std::{io::Write as _, os::unix::net::UnixStream};
+
+    let (mut client, server) = UnixStream::pair().unwrap();
+    server.set_nonblocking(true).unwrap();
+    let mut reader = StdReader::new(server.try_clone().unwrap());
+    let mut buf = [0; 16];
+    assert_eq!(
+        reader.read_outcome(&mut buf).unwrap_err().kind(),
+        io::ErrorKind::WouldBlock
+    );
+
+    let mut reader = StdReaderOptions::new().nonblocking(true).build(server);
+    let outcome = reader.read_outcome(&mut buf).unwrap();
+    assert_eq!(outcome.size, 0);
+    assert_eq!(outcome.status, Status::Open(Readiness::Pending));
+    client.write_all(b"hello").unwrap();
+    let outcome = reader.read_outcome(&mut buf).unwrap();
+    assert_eq!(outcome.size, 5);
+    assert_eq!(outcome.status, Status::ready());
+    let outcome = reader.read_outcome(&mut buf).unwrap();
+    assert_eq!(outcome.status, Status::Open(Readiness::Pending));
+    drop(client);
+    let outcome = reader.read_outcome(&mut buf).unwrap();
+    assert_eq!(outcome.status, Status::End);
+}
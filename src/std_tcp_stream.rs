@@ -0,0 +1,219 @@
+use crate::{Error, Read, ReadOutcome, Readiness, Status, Write};
+#[cfg(unix)]
+use std::os::unix::io::{AsFd, AsRawFd, BorrowedFd, RawFd};
+#[cfg(windows)]
+use std::os::windows::io::{AsRawSocket, AsSocket, BorrowedSocket, RawSocket};
+use std::{
+    io::{self, Write as _},
+    net::{Shutdown, TcpStream},
+};
+
+/// Adapts a [`std::net::TcpStream`] to implement `Read` and `Write`, with
+/// semantics suited to network protocols:
+///
+///  - When the stream is in non-blocking mode, reads which would block
+///    report `Readiness::Pending` instead of failing with
+///    `ErrorKind::WouldBlock`.
+///  - A read of zero bytes, meaning the peer has shut down its side of the
+///    connection, reports the end of the stream.
+///  - Flushing with `Status::End` shuts down the write side of the
+///    connection, so the peer sees the end of the stream, while reads may
+///    continue.
+pub struct StdTcpStream {
+    /// The wrapped socket.
+    inner: TcpStream,
+
+    /// The peer has shut down its side of the connection.
+    read_ended: bool,
+
+    /// We've shut down our side of the connection.
+    write_ended: bool,
+}
+
+impl StdTcpStream {
+    /// Construct a new instance of `StdTcpStream` wrapping `inner`.
+    #[inline]
+    pub fn new(inner: TcpStream) -> Self {
+        Self {
+            inner,
+            read_ended: false,
+            write_ended: false,
+        }
+    }
+
+    /// Gets a reference to the underlying socket.
+    #[inline]
+    pub fn get_ref(&self) -> &TcpStream {
+        &self.inner
+    }
+
+    /// Consume this `StdTcpStream` and return the underlying socket.
+    #[inline]
+    pub fn into_inner(self) -> TcpStream {
+        self.inner
+    }
+}
+
+impl Read for StdTcpStream {
+    fn read_outcome(&mut self, buf: &mut [u8]) -> io::Result<ReadOutcome> {
+        if self.read_ended {
+            return Ok(ReadOutcome::end(0));
+        }
+        match io::Read::read(&mut self.inner, buf) {
+            Ok(0) if !buf.is_empty() => {
+                self.read_ended = true;
+                Ok(ReadOutcome::end(0))
+            }
+            Ok(size) => Ok(ReadOutcome::ready(size)),
+            Err(ref e) if e.kind() == io::ErrorKind::WouldBlock => Ok(ReadOutcome::pending(0)),
+            Err(ref e) if e.kind() == io::ErrorKind::Interrupted => Ok(ReadOutcome::ready(0)),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+impl io::Read for StdTcpStream {
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        Read::read(self, buf)
+    }
+
+    #[inline]
+    fn read_vectored(&mut self, bufs: &mut [io::IoSliceMut<'_>]) -> io::Result<usize> {
+        Read::read_vectored(self, bufs)
+    }
+
+    #[cfg(feature = "nightly")]
+    #[inline]
+    fn is_read_vectored(&self) -> bool {
+        Read::is_read_vectored(self)
+    }
+
+    #[inline]
+    fn read_to_end(&mut self, buf: &mut Vec<u8>) -> io::Result<usize> {
+        Read::read_to_end(self, buf)
+    }
+
+    #[inline]
+    fn read_to_string(&mut self, buf: &mut String) -> io::Result<usize> {
+        Read::read_to_string(self, buf)
+    }
+
+    #[inline]
+    fn read_exact(&mut self, buf: &mut [u8]) -> io::Result<()> {
+        Read::read_exact(self, buf)
+    }
+}
+
+impl Write for StdTcpStream {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.write_ended {
+            return Err(stream_already_ended());
+        }
+        self.inner.write(buf)
+    }
+
+    fn flush(&mut self, status: Status) -> io::Result<()> {
+        if self.write_ended {
+            return Err(stream_already_ended());
+        }
+        match status {
+            Status::Open(Readiness::Ready) | Status::Open(Readiness::Pending) => Ok(()),
+            Status::Open(Readiness::Lull) => self.inner.flush(),
+            Status::End => {
+                self.write_ended = true;
+                self.inner.flush()?;
+                self.inner.shutdown(Shutdown::Write)
+            }
+        }
+    }
+
+    #[inline]
+    fn abandon(&mut self) {
+        if !self.write_ended {
+            self.write_ended = true;
+            let _ = self.inner.shutdown(Shutdown::Write);
+        }
+    }
+}
+
+#[cfg(unix)]
+impl AsRawFd for StdTcpStream {
+    #[inline]
+    fn as_raw_fd(&self) -> RawFd {
+        self.inner.as_raw_fd()
+    }
+}
+
+#[cfg(unix)]
+impl AsFd for StdTcpStream {
+    #[inline]
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.inner.as_fd()
+    }
+}
+
+#[cfg(windows)]
+impl AsRawSocket for StdTcpStream {
+    #[inline]
+    fn as_raw_socket(&self) -> RawSocket {
+        self.inner.as_raw_socket()
+    }
+}
+
+#[cfg(windows)]
+impl AsSocket for StdTcpStream {
+    #[inline]
+    fn as_socket(&self) -> BorrowedSocket<'_> {
+        self.inner.as_socket()
+    }
+}
+
+fn stream_already_ended() -> io::Error {
+    Error::StreamEnded.into()
+}
+
+#[cfg(test)]
+fn connected_pair() -> (StdTcpStream, StdTcpStream) {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let client = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+    let (server, _) = listener.accept().unwrap();
+    (StdTcpStream::new(client), StdTcpStream::new(server))
+}
+
+#[test]
+fn test_std_tcp_stream_half_close() {
+    let (mut client, mut server) = connected_pair();
+    client.write_all(b"hello\n").unwrap();
+    client.flush(Status::End).unwrap();
+    assert!(client.write_all(b"!").is_err());
+
+    let mut s = String::new();
+    Read::read_to_string(&mut server, &mut s).unwrap();
+    assert_eq!(s, "hello\n");
+
+    // The other direction is still open.
+    server.write_all(b"world\n").unwrap();
+    server.flush(Status::End).unwrap();
+    let mut s = String::new();
+    Read::read_to_string(&mut client, &mut s).unwrap();
+    assert_eq!(s, "world\n");
+}
+
+#[test]
+fn test_std_tcp_stream_would_block() {
+    let (mut client, mut server) = connected_pair();
+    server.get_ref().set_nonblocking(true).unwrap();
+    let mut buf = [0; 16];
+    let outcome = server.read_outcome(&mut buf).unwrap();
+    assert_eq!(outcome.size, 0);
+    assert_eq!(outcome.status, Status::Open(Readiness::Pending));
+
+    client.write_all(b"hello").unwrap();
+    client.flush(Status::End).unwrap();
+    server.get_ref().set_nonblocking(false).unwrap();
+    let mut s = String::new();
+    Read::read_to_string(&mut server, &mut s).unwrap();
+    assert_eq!(s, "hello");
+}
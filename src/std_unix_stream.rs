@@ -0,0 +1,189 @@
+use crate::{Error, Read, ReadOutcome, Readiness, Status, Write};
+use std::{
+    io::{self, Write as _},
+    net::Shutdown,
+    os::unix::{
+        io::{AsFd, AsRawFd, BorrowedFd, RawFd},
+        net::UnixStream,
+    },
+};
+
+/// Adapts a [`std::os::unix::net::UnixStream`] to implement `Read` and
+/// `Write`, with semantics suited to local IPC protocols:
+///
+///  - When the stream is in non-blocking mode, reads which would block
+///    report `Readiness::Pending` instead of failing with
+///    `ErrorKind::WouldBlock`.
+///  - A read of zero bytes, meaning the peer has shut down its side of the
+///    connection, reports the end of the stream.
+///  - Flushing with `Status::End` shuts down the write side of the
+///    connection, so the peer sees the end of the stream, while reads may
+///    continue.
+pub struct StdUnixStream {
+    /// The wrapped socket.
+    inner: UnixStream,
+
+    /// The peer has shut down its side of the connection.
+    read_ended: bool,
+
+    /// We've shut down our side of the connection.
+    write_ended: bool,
+}
+
+impl StdUnixStream {
+    /// Construct a new instance of `StdUnixStream` wrapping `inner`.
+    #[inline]
+    pub fn new(inner: UnixStream) -> Self {
+        Self {
+            inner,
+            read_ended: false,
+            write_ended: false,
+        }
+    }
+
+    /// Gets a reference to the underlying socket.
+    #[inline]
+    pub fn get_ref(&self) -> &UnixStream {
+        &self.inner
+    }
+
+    /// Consume this `StdUnixStream` and return the underlying socket.
+    #[inline]
+    pub fn into_inner(self) -> UnixStream {
+        self.inner
+    }
+}
+
+impl Read for StdUnixStream {
+    fn read_outcome(&mut self, buf: &mut [u8]) -> io::Result<ReadOutcome> {
+        if self.read_ended {
+            return Ok(ReadOutcome::end(0));
+        }
+        match io::Read::read(&mut self.inner, buf) {
+            Ok(0) if !buf.is_empty() => {
+                self.read_ended = true;
+                Ok(ReadOutcome::end(0))
+            }
+            Ok(size) => Ok(ReadOutcome::ready(size)),
+            Err(ref e) if e.kind() == io::ErrorKind::WouldBlock => Ok(ReadOutcome::pending(0)),
+            Err(ref e) if e.kind() == io::ErrorKind::Interrupted => Ok(ReadOutcome::ready(0)),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+impl io::Read for StdUnixStream {
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        Read::read(self, buf)
+    }
+
+    #[inline]
+    fn read_vectored(&mut self, bufs: &mut [io::IoSliceMut<'_>]) -> io::Result<usize> {
+        Read::read_vectored(self, bufs)
+    }
+
+    #[cfg(feature = "nightly")]
+    #[inline]
+    fn is_read_vectored(&self) -> bool {
+        Read::is_read_vectored(self)
+    }
+
+    #[inline]
+    fn read_to_end(&mut self, buf: &mut Vec<u8>) -> io::Result<usize> {
+        Read::read_to_end(self, buf)
+    }
+
+    #[inline]
+    fn read_to_string(&mut self, buf: &mut String) -> io::Result<usize> {
+        Read::read_to_string(self, buf)
+    }
+
+    #[inline]
+    fn read_exact(&mut self, buf: &mut [u8]) -> io::Result<()> {
+        Read::read_exact(self, buf)
+    }
+}
+
+impl Write for StdUnixStream {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.write_ended {
+            return Err(stream_already_ended());
+        }
+        self.inner.write(buf)
+    }
+
+    fn flush(&mut self, status: Status) -> io::Result<()> {
+        if self.write_ended {
+            return Err(stream_already_ended());
+        }
+        match status {
+            Status::Open(Readiness::Ready) | Status::Open(Readiness::Pending) => Ok(()),
+            Status::Open(Readiness::Lull) => self.inner.flush(),
+            Status::End => {
+                self.write_ended = true;
+                self.inner.flush()?;
+                self.inner.shutdown(Shutdown::Write)
+            }
+        }
+    }
+
+    #[inline]
+    fn abandon(&mut self) {
+        if !self.write_ended {
+            self.write_ended = true;
+            let _ = self.inner.shutdown(Shutdown::Write);
+        }
+    }
+}
+
+impl AsRawFd for StdUnixStream {
+    #[inline]
+    fn as_raw_fd(&self) -> RawFd {
+        self.inner.as_raw_fd()
+    }
+}
+
+impl AsFd for StdUnixStream {
+    #[inline]
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.inner.as_fd()
+    }
+}
+
+fn stream_already_ended() -> io::Error {
+    Error::StreamEnded.into()
+}
+
+#[test]
+fn test_std_unix_stream_half_close() {
+    let (client, server) = UnixStream::pair().unwrap();
+    let (mut client, mut server) = (StdUnixStream::new(client), StdUnixStream::new(server));
+    client.write_all(b"hello\n").unwrap();
+    client.flush(Status::End).unwrap();
+    assert!(client.write_all(b"!").is_err());
+
+    let mut s = String::new();
+    Read::read_to_string(&mut server, &mut s).unwrap();
+    assert_eq!(s, "hello\n");
+
+    // The other direction is still open.
+    server.write_all(b"world\n").unwrap();
+    server.flush(Status::End).unwrap();
+    let mut s = String::new();
+    Read::read_to_string(&mut client, &mut s).unwrap();
+    assert_eq!(s, "world\n");
+}
+
+#[test]
+fn test_std_unix_stream_would_block() {
+    let (client, server) = UnixStream::pair().unwrap();
+    let (_client, mut server) = (StdUnixStream::new(client), StdUnixStream::new(server));
+    server.get_ref().set_nonblocking(true).unwrap();
+    let mut buf = [0; 16];
+    let outcome = server.read_outcome(&mut buf).unwrap();
+    assert_eq!(outcome.size, 0);
+    assert_eq!(outcome.status, Status::Open(Readiness::Pending));
+    assert_eq!(server.as_raw_fd(), server.get_ref().as_raw_fd());
+}
@@ -1,7 +1,11 @@
-use crate::{Readiness, Status, Write};
+use crate::{Close, Error, Readiness, Status, Write};
+#[cfg(unix)]
+use std::os::unix::io::{AsFd, AsRawFd, BorrowedFd, RawFd};
+#[cfg(windows)]
+use std::os::windows::io::{AsHandle, AsRawHandle, BorrowedHandle, RawHandle};
 use std::{
     fmt::Arguments,
-    io::{self, IoSlice},
+    io::{self, IoSlice, Seek, SeekFrom},
 };
 
 /// Adapts a [`std::io::Write`] to implement [`Write`].
@@ -30,6 +34,22 @@ impl<Inner: io::Write> StdWriter<Inner> {
     pub fn get_mut(&mut self) -> &mut Inner {
         &mut self.inner
     }
+
+    /// Flush and close the underlying stream and return the underlying
+    /// stream object.
+    pub fn close_into_inner(mut self) -> io::Result<Inner> {
+        self.flush(Status::End)?;
+        Ok(self.inner)
+    }
+}
+
+impl<Inner: io::Write> Close for StdWriter<Inner> {
+    type Output = Inner;
+
+    #[inline]
+    fn close_into_inner(self) -> io::Result<Inner> {
+        StdWriter::close_into_inner(self)
+    }
 }
 
 impl<Inner: io::Write> Write for StdWriter<Inner> {
@@ -47,7 +67,7 @@ impl<Inner: io::Write> Write for StdWriter<Inner> {
             return Err(stream_already_ended());
         }
         match status {
-            Status::Open(Readiness::Ready) => Ok(()),
+            Status::Open(Readiness::Ready) | Status::Open(Readiness::Pending) => Ok(()),
             Status::Open(Readiness::Lull) => self.inner.flush(),
             Status::End => {
                 self.ended = true;
@@ -83,6 +103,32 @@ impl<Inner: io::Write> Write for StdWriter<Inner> {
         self.inner.write_all(buf)
     }
 
+    fn write_all_vectored_utf8(&mut self, bufs: &[&str]) -> io::Result<()> {
+        if self.ended {
+            return Err(stream_already_ended());
+        }
+        let mut slices = bufs
+            .iter()
+            .map(|s| IoSlice::new(s.as_bytes()))
+            .collect::<Vec<_>>();
+        let mut slices = &mut slices[..];
+        IoSlice::advance_slices(&mut slices, 0);
+        while !slices.is_empty() {
+            match self.inner.write_vectored(slices) {
+                Ok(0) => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::WriteZero,
+                        "failed to write whole buffer",
+                    ));
+                }
+                Ok(n) => IoSlice::advance_slices(&mut slices, n),
+                Err(ref e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(())
+    }
+
     #[cfg(feature = "nightly")]
     #[inline]
     fn write_all_vectored(&mut self, bufs: &mut [IoSlice<'_>]) -> io::Result<()> {
@@ -101,6 +147,101 @@ impl<Inner: io::Write> Write for StdWriter<Inner> {
     }
 }
 
+/// `flush` flushes with `Status::Open(Readiness::Lull)`. To declare
+/// the end of the stream, use [`Write::flush`] with `Status::End`, or
+/// `close_into_inner`.
+impl<Inner: io::Write> io::Write for StdWriter<Inner> {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        Write::write(self, buf)
+    }
+
+    #[inline]
+    fn flush(&mut self) -> io::Result<()> {
+        Write::flush(self, Status::Open(Readiness::Lull))
+    }
+
+    #[inline]
+    fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) -> io::Result<usize> {
+        Write::write_vectored(self, bufs)
+    }
+
+    #[cfg(feature = "nightly")]
+    #[inline]
+    fn is_write_vectored(&self) -> bool {
+        Write::is_write_vectored(self)
+    }
+
+    #[inline]
+    fn write_all(&mut self, buf: &[u8]) -> io::Result<()> {
+        Write::write_all(self, buf)
+    }
+
+    #[inline]
+    fn write_fmt(&mut self, fmt: Arguments<'_>) -> io::Result<()> {
+        Write::write_fmt(self, fmt)
+    }
+}
+
+/// Seeking clears any ended state, so that writing may resume at the new
+/// position.
+impl<Inner: io::Write + Seek> Seek for StdWriter<Inner> {
+    #[inline]
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let pos = self.inner.seek(pos)?;
+        self.ended = false;
+        Ok(pos)
+    }
+}
+
+#[cfg(unix)]
+impl<Inner: io::Write + AsRawFd> AsRawFd for StdWriter<Inner> {
+    #[inline]
+    fn as_raw_fd(&self) -> RawFd {
+        self.inner.as_raw_fd()
+    }
+}
+
+#[cfg(unix)]
+impl<Inner: io::Write + AsFd> AsFd for StdWriter<Inner> {
+    #[inline]
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.inner.as_fd()
+    }
+}
+
+#[cfg(windows)]
+impl<Inner: io::Write + AsRawHandle> AsRawHandle for StdWriter<Inner> {
+    #[inline]
+    fn as_raw_handle(&self) -> RawHandle {
+        self.inner.as_raw_handle()
+    }
+}
+
+#[cfg(windows)]
+impl<Inner: io::Write + AsHandle> AsHandle for StdWriter<Inner> {
+    #[inline]
+    fn as_handle(&self) -> BorrowedHandle<'_> {
+        self.inner.as_handle()
+    }
+}
+
 fn stream_already_ended() -> io::Error {
-    io::Error::new(io::ErrorKind::Other, "stream has already ended")
+    Error::StreamEnded.into()
+}
+
+#[test]
+fn test_std_writer_close_into_inner() {
+    let mut writer = StdWriter::new(Vec::new());
+    writer.write_all(b"hello").unwrap();
+    assert_eq!(writer.close_into_inner().unwrap(), b"hello");
+}
+
+#[test]
+fn test_std_writer_write_all_vectored_utf8() {
+    let mut writer = crate::Utf8Writer::new(StdWriter::new(Vec::new()));
+    writer
+        .write_all_vectored_utf8(&["hello", ", ", "", "world\n"])
+        .unwrap();
+    assert_eq!(writer.get_ref().get_ref(), b"hello, world\n");
 }
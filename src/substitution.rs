@@ -0,0 +1,46 @@
+/// A description of a change made to the data by a `Utf8Reader` or
+/// `TextReader`, passed to the hook installed with `set_substitution_hook`.
+#[derive(Debug)]
+pub struct Substitution<'a> {
+    /// What kind of change was made.
+    pub kind: SubstitutionKind,
+
+    /// The original bytes which were replaced or removed.
+    pub original: &'a [u8],
+
+    /// The byte offset of the original bytes in the inner stream.
+    pub offset: u64,
+}
+
+/// The kinds of changes described by [`Substitution`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum SubstitutionKind {
+    /// An invalid UTF-8 sequence was replaced.
+    InvalidUtf8,
+
+    /// A U+FEFF (BOM) was stripped.
+    Bom,
+
+    /// A control code was replaced.
+    ControlCode,
+
+    /// An escape sequence was removed.
+    EscapeSequence,
+
+    /// A Unicode Normalization Form non-starter at the beginning of the
+    /// stream or after a lull was replaced.
+    NonStarter,
+
+    /// A scalar value in the configured `ForbiddenCharacters` was replaced.
+    ForbiddenCharacter,
+
+    /// A bidirectional control was replaced or stripped.
+    BidiControl,
+
+    /// An invisible formatting character was replaced or stripped.
+    InvisibleCharacter,
+}
+
+/// The type of the hook called with each `Substitution`.
+pub(crate) type SubstitutionHook = Box<dyn FnMut(&Substitution<'_>) + Send + Sync>;
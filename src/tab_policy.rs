@@ -0,0 +1,77 @@
+//! Defines `TabPolicy`.
+
+use crate::unicode::is_normalization_form_starter;
+
+/// What a `TextReader` or `TextWriter` does with '\t'.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TabPolicy {
+    /// Pass '\t' through. This is the default.
+    Keep,
+
+    /// Expand '\t' to spaces, up to the next tab stop, with tab stops every
+    /// given number of columns.
+    ///
+    /// Columns are counted in scalar values which are normalization-form
+    /// starters, after normalization, so combining marks don't advance the
+    /// column, however wide characters such as CJK ideographs and emoji
+    /// count as one column.
+    Expand(usize),
+
+    /// Replace '\t' with U+FFFD, or the configured replacement, like other
+    /// control codes.
+    Replace,
+
+    /// Fail with [`Error::ControlCode`].
+    ///
+    /// [`Error::ControlCode`]: crate::Error::ControlCode
+    Reject,
+}
+
+impl Default for TabPolicy {
+    #[inline]
+    fn default() -> Self {
+        Self::Keep
+    }
+}
+
+/// Tracks the output column, for `TabPolicy::Expand`.
+#[derive(Default)]
+pub(crate) struct Columns {
+    column: usize,
+}
+
+impl Columns {
+    /// Process the next scalar value of output, other than a '\t' being
+    /// expanded.
+    #[inline]
+    pub(crate) fn step(&mut self, c: char) {
+        if c == '\n' {
+            self.column = 0;
+        } else if !c.is_control() && is_normalization_form_starter(c) {
+            self.column += 1;
+        }
+    }
+
+    /// Return the number of spaces to expand a '\t' to, with tab stops
+    /// every `stop` columns, and advance past them.
+    #[inline]
+    pub(crate) fn expand_tab(&mut self, stop: usize) -> usize {
+        let spaces = stop - self.column % stop;
+        self.column += spaces;
+        spaces
+    }
+}
+
+#[test]
+fn test_columns() {
+    let mut columns = Columns::default();
+    assert_eq!(columns.expand_tab(8), 8);
+    for c in "abc\u{301}".chars() {
+        columns.step(c);
+    }
+    assert_eq!(columns.expand_tab(8), 5);
+    assert_eq!(columns.expand_tab(4), 4);
+    columns.step('\n');
+    columns.step('x');
+    assert_eq!(columns.expand_tab(4), 3);
+}
@@ -0,0 +1,8 @@
+//! Utilities for testing code which uses this crate's `Read` and `Write`
+//! traits, without real terminals or sockets.
+
+mod chaos_reader;
+mod scripted_reader;
+
+pub use chaos_reader::ChaosReader;
+pub use scripted_reader::{ScriptedReader, Step};
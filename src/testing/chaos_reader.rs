@@ -0,0 +1,136 @@
+use crate::{Read, ReadOutcome};
+use std::io;
+
+/// A `Read` implementation which wraps another `Read`, splitting its data
+/// at arbitrary byte boundaries, including in the middle of scalar value
+/// encodings, and injecting spurious `io::ErrorKind::Interrupted` errors
+/// and lulls.
+///
+/// The choices are made by a pseudo-random number generator initialized
+/// from a seed, so a failure can be reproduced by reusing its seed.
+///
+/// This is useful for stress-testing consumers which should produce the
+/// same results regardless of how their input is chunked.
+pub struct ChaosReader<Inner: Read> {
+    /// The wrapped byte stream.
+    inner: Inner,
+
+    /// The state of the xorshift generator.
+    state: u64,
+}
+
+impl<Inner: Read> ChaosReader<Inner> {
+    /// Construct a new instance of `ChaosReader` wrapping `inner`, with
+    /// choices determined by `seed`.
+    #[inline]
+    pub fn new(inner: Inner, seed: u64) -> Self {
+        Self {
+            inner,
+            // Xorshift requires a non-zero state.
+            state: seed ^ 0x9e37_79b9_7f4a_7c15,
+        }
+    }
+
+    /// Consume this `ChaosReader` and return the underlying stream object.
+    #[inline]
+    pub fn into_inner(self) -> Inner {
+        self.inner
+    }
+
+    fn next(&mut self) -> u64 {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 7;
+        self.state ^= self.state << 17;
+        self.state
+    }
+}
+
+impl<Inner: Read> Read for ChaosReader<Inner> {
+    fn read_outcome(&mut self, buf: &mut [u8]) -> io::Result<ReadOutcome> {
+        match self.next() % 8 {
+            0 => Err(io::Error::new(
+                io::ErrorKind::Interrupted,
+                "spurious interruption",
+            )),
+            1 => Ok(ReadOutcome::lull(0)),
+            _ if buf.is_empty() => self.inner.read_outcome(buf),
+            _ => {
+                let len = 1 + (self.next() % buf.len() as u64) as usize;
+                self.inner.read_outcome(&mut buf[..len])
+            }
+        }
+    }
+}
+
+impl<Inner: Read> io::Read for ChaosReader<Inner> {
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        Read::read(self, buf)
+    }
+
+    #[inline]
+    fn read_vectored(&mut self, bufs: &mut [io::IoSliceMut<'_>]) -> io::Result<usize> {
+        Read::read_vectored(self, bufs)
+    }
+
+    #[cfg(feature = "nightly")]
+    #[inline]
+    fn is_read_vectored(&self) -> bool {
+        Read::is_read_vectored(self)
+    }
+
+    #[inline]
+    fn read_to_end(&mut self, buf: &mut Vec<u8>) -> io::Result<usize> {
+        Read::read_to_end(self, buf)
+    }
+
+    #[inline]
+    fn read_to_string(&mut self, buf: &mut String) -> io::Result<usize> {
+        Read::read_to_string(self, buf)
+    }
+
+    #[inline]
+    fn read_exact(&mut self, buf: &mut [u8]) -> io::Result<()> {
+        Read::read_exact(self, buf)
+    }
+}
+
+#[test]
+fn test_chaos_reader() {
+    let input = b"hello, world\n";
+    for seed in 0..64 {
+        let mut reader = ChaosReader::new(crate::SliceReader::new(input), seed);
+        let mut v = Vec::new();
+        reader.read_to_end(&mut v).unwrap();
+        assert_eq!(v, input, "seed {}", seed);
+    }
+}
+
+#[test]
+fn test_chaos_utf8_reader() {
+    let input = "caf\u{e9} \u{1f600} \u{65e5}\u{672c}\n".as_bytes();
+    for seed in 0..64 {
+        let mut reader =
+            crate::Utf8Reader::new(ChaosReader::new(crate::SliceReader::new(input), seed));
+        let mut s = String::new();
+        reader.read_to_string(&mut s).unwrap();
+        assert_eq!(s.as_bytes(), input, "seed {}", seed);
+    }
+}
+
+#[cfg(feature = "text")]
+#[test]
+fn test_chaos_text_reader() {
+    let input = "caf\u{e9} \u{1f600}\r\n\u{65e5}\u{672c}\n".as_bytes();
+    for seed in 0..64 {
+        let mut reader =
+            crate::TextReader::new(ChaosReader::new(crate::SliceReader::new(input), seed));
+        let mut s = String::new();
+        reader.read_to_string(&mut s).unwrap();
+        assert_eq!(
+            s, "caf\u{e9} \u{1f600}\n\u{65e5}\u{672c}\n",
+            "seed {}",
+            seed
+        );
+    }
+}
@@ -0,0 +1,146 @@
+use crate::{Read, ReadOutcome};
+use std::{collections::VecDeque, io};
+
+/// One step in the script of a `ScriptedReader`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Step {
+    /// Yield the given bytes, with `Readiness::Ready`. If the caller's
+    /// buffer is too small, the remaining bytes are yielded by subsequent
+    /// reads.
+    Data(Vec<u8>),
+
+    /// Yield no bytes, with `Readiness::Lull`.
+    Lull,
+
+    /// Yield no bytes, with `Readiness::Pending`.
+    Pending,
+
+    /// Fail with `io::ErrorKind::Interrupted`.
+    Interrupted,
+}
+
+/// A `Read` implementation which yields a programmed sequence of chunks,
+/// lulls, and interrupted errors, one per read, and then the end of the
+/// stream.
+///
+/// This is useful for testing how consumers handle partial reads and
+/// `Readiness::Lull`.
+pub struct ScriptedReader {
+    /// The steps which haven't been performed yet.
+    steps: VecDeque<Step>,
+}
+
+impl ScriptedReader {
+    /// Construct a new `ScriptedReader` which performs `steps`.
+    #[inline]
+    pub fn new<I: IntoIterator<Item = Step>>(steps: I) -> Self {
+        Self {
+            steps: steps.into_iter().collect(),
+        }
+    }
+
+    /// Test whether all of the steps have been performed.
+    #[inline]
+    pub fn is_finished(&self) -> bool {
+        self.steps.is_empty()
+    }
+}
+
+impl Read for ScriptedReader {
+    fn read_outcome(&mut self, buf: &mut [u8]) -> io::Result<ReadOutcome> {
+        match self.steps.pop_front() {
+            None => Ok(ReadOutcome::end(0)),
+            Some(Step::Lull) => Ok(ReadOutcome::lull(0)),
+            Some(Step::Pending) => Ok(ReadOutcome::pending(0)),
+            Some(Step::Interrupted) => Err(io::Error::new(
+                io::ErrorKind::Interrupted,
+                "scripted interruption",
+            )),
+            Some(Step::Data(mut data)) => {
+                let size = data.len().min(buf.len());
+                buf[..size].copy_from_slice(&data[..size]);
+                if size != data.len() {
+                    data.drain(..size);
+                    self.steps.push_front(Step::Data(data));
+                }
+                Ok(ReadOutcome::ready(size))
+            }
+        }
+    }
+}
+
+impl io::Read for ScriptedReader {
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        Read::read(self, buf)
+    }
+
+    #[inline]
+    fn read_vectored(&mut self, bufs: &mut [io::IoSliceMut<'_>]) -> io::Result<usize> {
+        Read::read_vectored(self, bufs)
+    }
+
+    #[cfg(feature = "nightly")]
+    #[inline]
+    fn is_read_vectored(&self) -> bool {
+        Read::is_read_vectored(self)
+    }
+
+    #[inline]
+    fn read_to_end(&mut self, buf: &mut Vec<u8>) -> io::Result<usize> {
+        Read::read_to_end(self, buf)
+    }
+
+    #[inline]
+    fn read_to_string(&mut self, buf: &mut String) -> io::Result<usize> {
+        Read::read_to_string(self, buf)
+    }
+
+    #[inline]
+    fn read_exact(&mut self, buf: &mut [u8]) -> io::Result<()> {
+        Read::read_exact(self, buf)
+    }
+}
+
+#[test]
+fn test_scripted_reader() {
+    use crate::{Readiness, Status};
+
+    let mut reader = ScriptedReader::new(vec![
+        Step::Data(b"hello".to_vec()),
+        Step::Lull,
+        Step::Interrupted,
+        Step::Data(b"world".to_vec()),
+    ]);
+    let mut buf = [0; 3];
+    let outcome = reader.read_outcome(&mut buf).unwrap();
+    assert_eq!(&buf[..outcome.size], b"hel");
+    assert_eq!(outcome.status, Status::ready());
+    let outcome = reader.read_outcome(&mut buf).unwrap();
+    assert_eq!(&buf[..outcome.size], b"lo");
+    assert_eq!(outcome.status, Status::ready());
+    let outcome = reader.read_outcome(&mut buf).unwrap();
+    assert_eq!(outcome.size, 0);
+    assert_eq!(outcome.status, Status::Open(Readiness::Lull));
+    assert_eq!(
+        reader.read_outcome(&mut buf).unwrap_err().kind(),
+        io::ErrorKind::Interrupted
+    );
+    let mut s = String::new();
+    reader.read_to_string(&mut s).unwrap();
+    assert_eq!(s, "world");
+    assert!(reader.is_finished());
+}
+
+#[test]
+fn test_scripted_reader_utf8() {
+    let mut reader = crate::Utf8Reader::new(ScriptedReader::new(vec![
+        Step::Data(b"caf\xc3".to_vec()),
+        Step::Interrupted,
+        Step::Data(b"\xa9".to_vec()),
+        Step::Lull,
+    ]));
+    let mut s = String::new();
+    reader.read_to_string(&mut s).unwrap();
+    assert_eq!(s, "caf\u{e9}");
+}
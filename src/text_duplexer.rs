@@ -0,0 +1,225 @@
+use crate::{
+    split, Close, Read, ReadHalf, ReadOutcome, Status, TextReader, TextWriter, Write, WriteHalf,
+};
+use std::io;
+
+/// A combination of a `TextReader` and a `TextWriter` over a single duplex
+/// stream, such as a socket or a terminal, for interactive programs.
+///
+/// Before each read, any output written since the last flush is flushed
+/// with a lull, even if it doesn't end with a newline, so that a prompt
+/// written before reading a line of input is displayed.
+pub struct TextDuplexer<Inner: Read + Write> {
+    /// The input side.
+    reader: TextReader<ReadHalf<Inner>>,
+
+    /// The output side.
+    writer: TextWriter<WriteHalf<Inner>>,
+
+    /// Output has been written since the last flush.
+    prompt: bool,
+}
+
+impl<Inner: Read + Write> TextDuplexer<Inner> {
+    /// Construct a new instance of `TextDuplexer` wrapping `inner`.
+    #[inline]
+    pub fn new(inner: Inner) -> Self {
+        let (read, write) = split(inner);
+        Self {
+            reader: TextReader::new(read),
+            writer: TextWriter::new(write),
+            prompt: false,
+        }
+    }
+
+    /// Like `read` but produces the result in a `str`. Be sure to check
+    /// the `size` field of the return value to see how many bytes were written.
+    pub fn read_utf8(&mut self, buf: &mut str) -> io::Result<ReadOutcome> {
+        self.flush_prompt()?;
+        self.reader.read_utf8(buf)
+    }
+
+    /// Split this `TextDuplexer` into a `TextReader` and a `TextWriter`
+    /// which can be used independently, including from different threads.
+    /// See [`split`](crate::split) for details.
+    ///
+    /// After splitting, prompts are no longer flushed automatically before
+    /// reads.
+    pub fn split(self) -> (TextReader<ReadHalf<Inner>>, TextWriter<WriteHalf<Inner>>) {
+        (self.reader, self.writer)
+    }
+
+    /// Flush and close the output side of the underlying stream and return
+    /// the underlying stream object.
+    pub fn close_into_inner(self) -> io::Result<Inner> {
+        let write = self.writer.close_into_inner()?;
+        let read = self.reader.into_inner();
+        Ok(read.unsplit(write))
+    }
+
+    fn flush_prompt(&mut self) -> io::Result<()> {
+        if self.prompt {
+            self.prompt = false;
+            self.writer.flush_prompt()?;
+        }
+        Ok(())
+    }
+}
+
+impl<Inner: Read + Write> Close for TextDuplexer<Inner> {
+    type Output = Inner;
+
+    #[inline]
+    fn close_into_inner(self) -> io::Result<Inner> {
+        TextDuplexer::close_into_inner(self)
+    }
+}
+
+impl<Inner: Read + Write> Read for TextDuplexer<Inner> {
+    #[inline]
+    fn read_outcome(&mut self, buf: &mut [u8]) -> io::Result<ReadOutcome> {
+        self.flush_prompt()?;
+        self.reader.read_outcome(buf)
+    }
+}
+
+impl<Inner: Read + Write> crate::ReadStr for TextDuplexer<Inner> {
+    #[inline]
+    fn read_str(&mut self, buf: &mut str) -> io::Result<ReadOutcome> {
+        self.read_utf8(buf)
+    }
+
+    #[inline]
+    fn read_to_string(&mut self, buf: &mut String) -> io::Result<usize> {
+        Read::read_to_string(self, buf)
+    }
+}
+
+impl<Inner: Read + Write> io::Read for TextDuplexer<Inner> {
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        Read::read(self, buf)
+    }
+
+    #[inline]
+    fn read_vectored(&mut self, bufs: &mut [io::IoSliceMut<'_>]) -> io::Result<usize> {
+        Read::read_vectored(self, bufs)
+    }
+
+    #[cfg(feature = "nightly")]
+    #[inline]
+    fn is_read_vectored(&self) -> bool {
+        Read::is_read_vectored(self)
+    }
+
+    #[inline]
+    fn read_to_end(&mut self, buf: &mut Vec<u8>) -> io::Result<usize> {
+        Read::read_to_end(self, buf)
+    }
+
+    #[inline]
+    fn read_to_string(&mut self, buf: &mut String) -> io::Result<usize> {
+        Read::read_to_string(self, buf)
+    }
+
+    #[inline]
+    fn read_exact(&mut self, buf: &mut [u8]) -> io::Result<()> {
+        Read::read_exact(self, buf)
+    }
+}
+
+impl<Inner: Read + Write> Write for TextDuplexer<Inner> {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.prompt = true;
+        self.writer.write(buf)
+    }
+
+    #[inline]
+    fn flush(&mut self, status: Status) -> io::Result<()> {
+        self.prompt = false;
+        self.writer.flush(status)
+    }
+
+    #[inline]
+    fn abandon(&mut self) {
+        self.prompt = false;
+        self.writer.abandon()
+    }
+
+    #[inline]
+    fn write_all_utf8(&mut self, s: &str) -> io::Result<()> {
+        self.prompt = true;
+        self.writer.write_all_utf8(s)
+    }
+}
+
+/// A duplex stream for testing, which reads from a fixed input and records
+/// its output, along with the flushes.
+#[cfg(test)]
+struct TestDuplex {
+    input: crate::SliceReader<'static>,
+    output: Vec<u8>,
+}
+
+#[cfg(test)]
+impl Read for TestDuplex {
+    fn read_outcome(&mut self, buf: &mut [u8]) -> io::Result<ReadOutcome> {
+        self.input.read_outcome(buf)
+    }
+}
+
+#[cfg(test)]
+impl Write for TestDuplex {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.output.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self, status: Status) -> io::Result<()> {
+        match status {
+            Status::Open(crate::Readiness::Ready) | Status::Open(crate::Readiness::Pending) => {}
+            Status::Open(crate::Readiness::Lull) => self.output.extend_from_slice(b"<lull>"),
+            Status::End => self.output.extend_from_slice(b"<end>"),
+        }
+        Ok(())
+    }
+
+    fn abandon(&mut self) {}
+}
+
+#[test]
+fn test_text_duplexer() {
+    let mut duplexer = TextDuplexer::new(TestDuplex {
+        input: crate::SliceReader::new(b"world\r\n"),
+        output: Vec::new(),
+    });
+    duplexer.write_all_utf8("name? ").unwrap();
+    let mut s = String::new();
+    duplexer.read_to_string(&mut s).unwrap();
+    assert_eq!(s, "world\n");
+    duplexer.write_all_utf8("hello, world\n").unwrap();
+    let inner = duplexer.close_into_inner().unwrap();
+    assert_eq!(inner.output, b"name? <lull>hello, world\n<end>");
+}
+
+#[test]
+fn test_text_duplexer_split() {
+    let duplexer = TextDuplexer::new(TestDuplex {
+        input: crate::SliceReader::new(b"hello\n"),
+        output: Vec::new(),
+    });
+    let (mut reader, mut writer) = duplexer.split();
+    let thread = std::thread::spawn(move || {
+        writer.write_all_utf8("world\n").unwrap();
+        writer
+    });
+    let mut s = String::new();
+    reader.read_to_string(&mut s).unwrap();
+    assert_eq!(s, "hello\n");
+    let writer = thread.join().unwrap();
+    let inner = reader
+        .into_inner()
+        .unsplit(writer.close_into_inner().unwrap());
+    assert_eq!(inner.output, b"world\n<end>");
+}
@@ -1,47 +1,96 @@
 use crate::{
-    no_forbidden_characters::NoForbiddenCharacters,
+    bidi::{is_bidi_control, BidiBalance},
+    escape_state::EscapeState,
+    invisible::is_invisible,
+    line_terminator_policy::is_unicode_line_terminator,
+    normalization_form::Normalize,
+    problem_sequences::ProblemSequences,
     rc_char_queue::{RcCharQueue, RcCharQueueIter},
+    read_str::read_into_str,
+    tab_policy::Columns,
     unicode::{
-        is_normalization_form_starter, BOM, DEL, ESC, FF, MAX_UTF8_SIZE, NORMALIZATION_BUFFER_LEN,
-        NORMALIZATION_BUFFER_SIZE, REPL,
+        is_normalization_form_starter, is_valid_replacement, printable_ascii_len, BOM, ESC, FF,
+        MAX_UTF8_SIZE, NORMALIZATION_BUFFER_LEN, NORMALIZATION_BUFFER_SIZE, REPL,
     },
-    Read, ReadOutcome, Status, Utf8Reader,
+    BidiPolicy, BomPolicy, Error, ForbiddenCharacterPolicy, ForbiddenCharacters, FormFeedPolicy,
+    InvisiblePolicy, LineTerminatorPolicy, NewlinePolicy, NonStarterPolicy, NormalizationForm,
+    NulPolicy, Read, ReadOutcome, Readiness, Status, Substitution, SubstitutionKind, TabPolicy,
+    Utf8Reader, Utf8ReaderBuilder,
 };
-use std::{io, mem, str};
-use unicode_normalization::{Recompositions, StreamSafe, UnicodeNormalization};
+#[cfg(feature = "security")]
+use crate::{restriction::RestrictionChecker, RestrictionLevel, RestrictionPolicy};
+#[cfg(unix)]
+use std::os::unix::io::{AsFd, AsRawFd, BorrowedFd, RawFd};
+#[cfg(windows)]
+use std::os::windows::io::{AsHandle, AsRawHandle, BorrowedHandle, RawHandle};
+use std::{
+    cmp::min,
+    io::{self, Seek, SeekFrom},
+    mem, str,
+};
+use unicode_segmentation::UnicodeSegmentation;
 
 /// A `Read` implementation which translates from an input `Read` producing
 /// an arbitrary byte sequence into a valid plain text stream.
 ///
 /// In addition to the transforms performed by `Utf8Reader`, an input text
 /// stream ensures the following properties:
-///  - U+FEFF (BOM) scalar values are stripped
+///  - U+FEFF (BOM) scalar values are stripped, or handled as configured
+///    with `TextReaderBuilder::bom_policy`.
 ///  - A '\n' is appended at the end of the stream if it doesn't already
 ///    have one.
-///  - '\r' followed by '\n' is replaced by '\n'.
-///  - U+000C (FF) is replaced by ' '.
+///  - '\r' followed by '\n' is replaced by '\n'. Optionally, a '\r' not
+///    followed by '\n' is also replaced by '\n', or "\r\n" is preserved,
+///    as configured with `TextReaderBuilder::newline_policy`.
+///  - Optionally, U+0085 (NEL), U+2028 (LS), and U+2029 (PS) are replaced
+///    by '\n', as configured with `TextReaderBuilder::line_terminator_policy`.
+///  - U+000C (FF) is replaced by ' ', unless configured otherwise with
+///    `TextReaderBuilder::form_feed_policy`.
+///  - '\t' is passed through, unless configured otherwise with
+///    `TextReaderBuilder::tab_policy`.
+///  - U+0000 (NUL) is replaced like other control codes, unless configured
+///    otherwise with `TextReaderBuilder::nul_policy`.
 ///  - All other control codes other than '\n' and '\t' are replaced
-///    by U+FFFD (REPLACEMENT CHARACTER).
-///  - Text is transformed to Normalization Form C (NFC).
-///  - The Stream-Safe Text Process (UAX15-D4) is applied.
+///    by U+FFFD (REPLACEMENT CHARACTER), or the replacement configured with
+///    `TextReaderBuilder`.
+///  - [`ForbiddenCharacters`] are replaced similarly, or rejected, as
+///    configured with `TextReaderBuilder::forbidden_character_policy`.
+///  - Bidirectional controls are passed through, unless configured
+///    otherwise with `TextReaderBuilder::bidi_policy`.
+///  - Invisible formatting characters are passed through, unless configured
+///    otherwise with `TextReaderBuilder::invisible_policy`.
+///  - Text is transformed to Normalization Form C (NFC), or the form
+///    configured with `TextReaderBuilder::normalization_form`.
+///  - The Stream-Safe Text Process (UAX15-D4) is applied, unless
+///    normalization is disabled with `NormalizationForm::Passthrough`.
 ///  - Streams never start or resume after a lull with a normalization-form
-///    non-starter.
+///    non-starter. By default such non-starters are replaced; this can be
+///    configured with `TextReaderBuilder::non_starter_policy`.
 ///
-/// TODO: use `from_utf8_unchecked` and `as_mut_vec` to optimize this.
+/// Reads into buffers of at least [`NORMALIZATION_BUFFER_SIZE`] bytes are
+/// performed directly. Smaller buffers are supported by staging the output
+/// internally; in this case, if the buffer is smaller than 4 bytes, scalar
+/// value encodings may straddle `read` calls.
 ///
-/// TODO: canonical_combining_class doesn't know about the astral
-/// compositions like U+11099 U+110BA => U+1109A. Restrict non-starters
-/// of that form too? Or use unicode-segmentation to detect grapheme boundaries.
+/// With `TextReaderBuilder::grapheme_atomic`, reads into buffers of at least
+/// `NORMALIZATION_BUFFER_SIZE` bytes never split an extended grapheme
+/// cluster, unless the cluster is longer than half that size.
 ///
-/// TODO: support security restrictions? Or have a mode where they are supported?
-///   - [Unicode Restriction Levels](https://www.unicode.org/reports/tr39/#Restriction_Level_Detection)
-///   - [unicode-security crate](https://crates.io/crates/unicode-security)
+/// Over a non-blocking source, such as a `StdTcpStream` in non-blocking
+/// mode, reads report `Readiness::Pending` when no data is available, and
+/// partial sequences are held until more data arrives, so an event loop
+/// can read whenever the source is readable, and the output doesn't depend
+/// on how the input is split up.
 ///
-/// TODO: Forbidden characters?
-///   - [11.4 Forbidden Characters](https://unicode.org/reports/tr15/#Forbidding_Characters)
+/// With the `rayon` feature, `read_to_string` on a large input reads all of
+/// it and translates pieces of it in parallel, splitting at line boundaries
+/// where a new reader's output is the same as the serial output. The result
+/// is identical to the serial path. Readers with a substitution hook or a
+/// restriction level always use the serial path.
 ///
-/// TODO: Problem sequences?
-///   - [11.5 Problem Sequences](https://unicode.org/reports/tr15/#Corrigendum_5_Sequences)
+/// TODO: canonical_combining_class doesn't know about the astral
+/// compositions like U+11099 U+110BA => U+1109A. Restrict non-starters
+/// of that form too? Or use unicode-segmentation to detect grapheme boundaries.
 ///
 /// TODO: Implement Stablized Strings
 ///   - [12.1 Stablized Strings](https://unicode.org/reports/tr15/#Normalization_Process_for_Stabilized_Strings)
@@ -51,8 +100,9 @@ pub struct TextReader<Inner: Read> {
     /// The wrapped byte stream.
     inner: Utf8Reader<Inner>,
 
-    /// Temporary storage for reading scalar values from the underlying stream.
-    raw_string: String,
+    /// Storage for reading scalar values from the underlying stream, which
+    /// is reused for each read.
+    raw_bytes: Box<[u8]>,
 
     /// A queue of scalar values which have been translated but not written to
     /// the output yet.
@@ -61,7 +111,13 @@ pub struct TextReader<Inner: Read> {
     queue: RcCharQueue,
 
     /// An iterator over the chars in `self.queue`.
-    queue_iter: Option<NoForbiddenCharacters<Recompositions<StreamSafe<RcCharQueueIter>>>>,
+    queue_iter: Option<Normalize<RcCharQueueIter>>,
+
+    /// Detects Corrigendum #5 problem sequences in the normalized output.
+    problem_sequences: ProblemSequences,
+
+    /// The Normalization Form to transform to.
+    normalization_form: NormalizationForm,
 
     /// When we can't fit all the data from an underlying read in our buffer,
     /// we buffer it up. Remember the status value so we can replay that too.
@@ -71,33 +127,304 @@ pub struct TextReader<Inner: Read> {
     /// normalization-form starter.
     expect_starter: bool,
 
-    /// Control-code and escape-sequence state machine.
+    /// What to do with a non-starter when `expect_starter` is set.
+    non_starter_policy: NonStarterPolicy,
+
+    /// With `NonStarterPolicy::Buffer`, the last normalization segment
+    /// before a lull, held back until more data arrives.
+    held: Vec<char>,
+
+    /// What to do with U+FEFF.
+    bom_policy: BomPolicy,
+
+    /// True until the first scalar value of the stream has been processed.
+    at_start: bool,
+
+    /// Control-code state machine.
     state: State,
+
+    /// Escape-sequence state machine.
+    escape: EscapeState,
+
+    /// Pass through escape sequences which are safe to display.
+    safe_escapes: bool,
+
+    /// Scalar values which aren't permitted.
+    forbidden: ForbiddenCharacters,
+
+    /// What to do with scalar values in `forbidden`.
+    forbidden_policy: ForbiddenCharacterPolicy,
+
+    /// What to do with bidirectional controls.
+    bidi_policy: BidiPolicy,
+
+    /// With `BidiPolicy::RequireBalanced`, the nesting of bidirectional
+    /// controls in the current line.
+    bidi_balance: BidiBalance,
+
+    /// What to do with invisible formatting characters.
+    invisible_policy: InvisiblePolicy,
+
+    /// What to do with U+0085, U+2028, and U+2029.
+    line_terminator_policy: LineTerminatorPolicy,
+
+    /// How to translate line endings involving '\r'.
+    newline_policy: NewlinePolicy,
+
+    /// What to do with '\t'.
+    tab_policy: TabPolicy,
+
+    /// What to do with U+0000.
+    nul_policy: NulPolicy,
+
+    /// What to do with U+000C.
+    form_feed_policy: FormFeedPolicy,
+
+    /// With `TabPolicy::Expand`, the output column.
+    columns: Columns,
+
+    /// With `TabPolicy::Expand`, the number of spaces remaining in the
+    /// expansion of a '\t'.
+    pending_spaces: usize,
+
+    /// The string used in place of invalid or disallowed scalar values.
+    replacement: String,
+
+    /// The escape sequence in progress, when `safe_escapes` is enabled or
+    /// a substitution hook is installed.
+    sequence: String,
+
+    /// The offset in the inner stream of the escape sequence in progress.
+    sequence_offset: u64,
+
+    /// The offset in the inner stream of the '\r' in `State::Cr`.
+    cr_offset: u64,
+
+    /// Counts of the sanitizations performed so far.
+    stats: Stats,
+
+    /// Output which has been translated but not yet delivered, because the
+    /// caller's buffer was smaller than `NORMALIZATION_BUFFER_SIZE`.
+    staged: Vec<u8>,
+
+    /// The status to report once `staged` is drained.
+    staged_status: Status,
+
+    /// Never split extended grapheme clusters across reads.
+    grapheme_atomic: bool,
+
+    /// Checks words against a UTS #39 restriction level.
+    #[cfg(feature = "security")]
+    restriction: Option<(RestrictionChecker, RestrictionPolicy)>,
+
+    /// A word exceeding the restriction level was found with
+    /// `RestrictionPolicy::Reject`.
+    #[cfg(feature = "security")]
+    restriction_rejected: bool,
+
+    /// With `grapheme_atomic`, output held back from the previous read
+    /// because it may be the beginning of an incomplete grapheme cluster.
+    carry: Vec<u8>,
 }
 
 impl<Inner: Read> TextReader<Inner> {
     /// Construct a new instance of `TextReader` wrapping `inner`.
     #[inline]
     pub fn new(inner: Inner) -> Self {
-        let queue = RcCharQueue::new();
-        Self {
-            inner: Utf8Reader::new(inner),
-            raw_string: String::new(),
-            queue,
-            queue_iter: None,
-            pending_status: Status::ready(),
-            expect_starter: true,
-            state: State::Ground(true),
+        TextReaderBuilder::new().build(inner)
+    }
+
+    /// Like `new`, but instead of stripping all escape sequences, passes
+    /// through sequences which only affect the appearance of the text:
+    /// SGR sequences, which set colors and styles, and OSC 8 sequences,
+    /// which delimit hyperlinks. All other escape sequences, including
+    /// cursor movement and title-setting sequences, are still stripped.
+    ///
+    /// With this option, the output is no longer plain text, but it is
+    /// suitable for displaying on a terminal.
+    #[inline]
+    pub fn with_safe_escapes(inner: Inner) -> Self {
+        TextReaderBuilder::new().safe_escapes(true).build(inner)
+    }
+
+    /// Consume this `TextReader` and return the underlying stream object.
+    /// Any data which has been read from the underlying stream but not yet
+    /// consumed is discarded.
+    #[inline]
+    pub fn into_inner(self) -> Inner {
+        self.inner.into_inner()
+    }
+
+    /// Consume this `TextReader` and return the underlying stream object,
+    /// along with any data which has been read from the underlying stream
+    /// but not yet consumed. This allows a stream to switch from text to
+    /// another format mid-stream, such as after a text handshake.
+    ///
+    /// Data which has already been translated is returned in its translated
+    /// form, followed by any bytes which have not yet been decoded. A
+    /// partially translated sequence, such as an incomplete escape sequence,
+    /// is discarded. To avoid translating data which follows the text,
+    /// use an inner stream which reports a lull at the end of the text.
+    pub fn into_parts(mut self) -> (Inner, Vec<u8>) {
+        let mut data = mem::take(&mut self.staged);
+        data.append(&mut self.carry);
+        let mut tmp = [0; MAX_UTF8_SIZE];
+        while let Some(c) = self.queue_next(true) {
+            data.extend_from_slice(c.encode_utf8(&mut tmp).as_bytes());
         }
+        for c in mem::take(&mut self.held) {
+            data.extend_from_slice(c.encode_utf8(&mut tmp).as_bytes());
+        }
+        let (inner, overflow) = self.inner.into_parts();
+        data.extend_from_slice(&overflow);
+        (inner, data)
+    }
+
+    /// Gets a reference to the underlying reader.
+    #[inline]
+    pub fn get_ref(&self) -> &Inner {
+        self.inner.get_ref()
+    }
+
+    /// Gets a mutable reference to the underlying reader.
+    ///
+    /// It is inadvisable to directly read from the underlying reader, as
+    /// data buffered by this reader would then be out of order with it.
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut Inner {
+        self.inner.get_mut()
     }
 
     /// Like `read` but produces the result in a `str`. Be sure to check
     /// the `size` field of the return value to see how many bytes were written.
     pub fn read_utf8(&mut self, buf: &mut str) -> io::Result<ReadOutcome> {
-        self.inner.read_utf8(buf)
+        read_into_str(self, buf)
+    }
+
+    /// Return the number of invalid UTF-8 sequences, control codes, and
+    /// non-starters replaced so far.
+    #[inline]
+    pub fn replacements(&self) -> u64 {
+        self.inner.replacements() + self.stats.replacements
+    }
+
+    /// Return the number of U+FEFF (BOM) scalar values stripped so far.
+    #[inline]
+    pub fn stripped_boms(&self) -> u64 {
+        self.stats.stripped_boms
+    }
+
+    /// Return the number of bidirectional controls, invisible characters,
+    /// and NULs stripped so far by `BidiPolicy::Strip`,
+    /// `InvisiblePolicy::Strip`, and `NulPolicy::Strip`.
+    #[inline]
+    pub fn stripped_characters(&self) -> u64 {
+        self.stats.stripped_characters
+    }
+
+    /// Return the number of "\r\n" sequences converted to "\n" so far.
+    #[inline]
+    pub fn converted_crlfs(&self) -> u64 {
+        self.stats.converted_crlfs
+    }
+
+    /// Return the number of '\r's not followed by '\n' converted to "\n" so
+    /// far, with `NewlinePolicy::ConvertCr`.
+    #[inline]
+    pub fn converted_crs(&self) -> u64 {
+        self.stats.converted_crs
+    }
+
+    /// Return the number of U+0085 (NEL), U+2028 (LS), and U+2029 (PS)
+    /// scalar values converted to "\n" so far, with
+    /// `LineTerminatorPolicy::Normalize`.
+    #[inline]
+    pub fn converted_line_terminators(&self) -> u64 {
+        self.stats.converted_line_terminators
+    }
+
+    /// Return the number of escape sequences removed so far.
+    #[inline]
+    pub fn dropped_escapes(&self) -> u64 {
+        self.stats.dropped_escapes
+    }
+
+    /// Return the number of [Corrigendum #5 problem sequences] seen so far.
+    /// These are normalized according to current versions of Unicode, but
+    /// implementations predating Corrigendum #5 may normalize them
+    /// differently.
+    ///
+    /// [Corrigendum #5 problem sequences]: https://unicode.org/reports/tr15/#Corrigendum_5_Sequences
+    #[inline]
+    pub fn problem_sequences(&self) -> u64 {
+        self.stats.problem_sequences
+    }
+
+    /// Return the number of words found so far which exceed the restriction
+    /// level configured with `TextReaderBuilder::restriction_level`.
+    #[cfg(feature = "security")]
+    #[inline]
+    pub fn restriction_violations(&self) -> u64 {
+        self.stats.restriction_violations
+    }
+
+    /// Install a hook which is called for each substitution made: invalid
+    /// UTF-8 sequences, BOMs, control codes, and non-starters replaced or
+    /// stripped, and escape sequences removed. It's called with the
+    /// original bytes and their offset in the inner stream, so that tools
+    /// can report precisely what was altered.
+    ///
+    /// Escape sequences longer than 2048 bytes are reported truncated.
+    pub fn set_substitution_hook<F: FnMut(&Substitution<'_>) + Send + Sync + 'static>(
+        &mut self,
+        hook: F,
+    ) {
+        self.inner.set_substitution_hook(hook)
     }
 
     fn queue_next(&mut self, sequence_end: bool) -> Option<char> {
+        if self.pending_spaces != 0 {
+            self.pending_spaces -= 1;
+            return Some(' ');
+        }
+        let c = self.normalized_next(sequence_end)?;
+        if self.problem_sequences.step(c) {
+            self.stats.problem_sequences += 1;
+        }
+        #[cfg(feature = "security")]
+        if let Some((checker, policy)) = &mut self.restriction {
+            if checker.step(c) {
+                self.stats.restriction_violations += 1;
+                if *policy == RestrictionPolicy::Reject {
+                    self.restriction_rejected = true;
+                }
+            }
+        }
+        if let TabPolicy::Expand(stop) = self.tab_policy {
+            if c == '\t' {
+                self.pending_spaces = self.columns.expand_tab(stop) - 1;
+                return Some(' ');
+            }
+            self.columns.step(c);
+        }
+        Some(c)
+    }
+
+    /// Test whether translated output remains to be delivered.
+    fn has_queued_output(&self) -> bool {
+        if self.pending_spaces != 0 {
+            true
+        } else if self.normalization_form == NormalizationForm::Passthrough {
+            !self.queue.is_empty()
+        } else {
+            self.queue_iter.is_some()
+        }
+    }
+
+    fn normalized_next(&mut self, sequence_end: bool) -> Option<char> {
+        if self.normalization_form == NormalizationForm::Passthrough {
+            return self.queue.pop();
+        }
         if !sequence_end && self.queue.len() < NORMALIZATION_BUFFER_LEN {
             return None;
         }
@@ -105,160 +432,531 @@ impl<Inner: Read> TextReader<Inner> {
             if self.queue.is_empty() {
                 return None;
             }
-            self.queue_iter = Some(NoForbiddenCharacters::new(
-                self.queue.iter().stream_safe().nfc(),
-            ));
+            self.queue_iter = Some(Normalize::new(self.queue.iter(), self.normalization_form));
         }
         if let Some(c) = self.queue_iter.as_mut().unwrap().next() {
-            return Some(c.unwrap_or(REPL));
+            return Some(c);
         }
         self.queue_iter = None;
         None
     }
 
-    fn process_raw_string(&mut self) {
-        for c in self.raw_string.chars() {
+    /// Translate the first `len` bytes of `self.raw_bytes`.
+    fn process_raw_bytes(&mut self, len: usize) -> io::Result<()> {
+        // Release a segment held back at a lull; anything that follows can
+        // combine with it.
+        if !self.held.is_empty() {
+            for c in mem::take(&mut self.held) {
+                self.queue.push(c);
+            }
+            self.expect_starter = false;
+        }
+
+        let track_sequence = self.safe_escapes || self.inner.has_substitution_hook();
+        let skip_ascii = !self.forbidden.contains_printable_ascii();
+        let raw_bytes = mem::take(&mut self.raw_bytes);
+        // Safety: The bytes come from a `Utf8Reader`, which only produces
+        // valid UTF-8, or from a `str` in `translate_serially`, and they're
+        // only ever truncated at char boundaries.
+        debug_assert!(str::from_utf8(&raw_bytes[..len]).is_ok());
+        let raw_string = unsafe { str::from_utf8_unchecked(&raw_bytes[..len]) };
+        let mut i = 0;
+        while i < raw_string.len() {
+            // Printable ASCII needs no translation in the ground state, so
+            // pass runs of it through without stepping the state machine.
+            if skip_ascii && matches!(self.state, State::Ground(_)) {
+                let run = printable_ascii_len(&raw_string.as_bytes()[i..]);
+                if run != 0 {
+                    self.queue.push_ascii(&raw_string.as_bytes()[i..i + run]);
+                    self.at_start = false;
+                    self.expect_starter = false;
+                    self.state = State::Ground(false);
+                    i += run;
+                    continue;
+                }
+            }
+
+            let c = raw_string[i..].chars().next().unwrap();
+            let leading = mem::replace(&mut self.at_start, false);
             loop {
                 match (self.state, c) {
-                    (State::Ground(_), BOM) => self.state = State::Ground(false),
+                    (State::Ground(_), BOM)
+                        if self.bom_policy == BomPolicy::StripAll
+                            || (leading && self.bom_policy != BomPolicy::Preserve) =>
+                    {
+                        let mut tmp = [0; MAX_UTF8_SIZE];
+                        let offset = self.inner.offset_in_last_read(i);
+                        self.inner.report_substitution(
+                            SubstitutionKind::Bom,
+                            c.encode_utf8(&mut tmp).as_bytes(),
+                            offset,
+                        );
+                        self.stats.stripped_boms += 1;
+                        self.state = State::Ground(false)
+                    }
+                    (State::Ground(_), BOM) if self.bom_policy == BomPolicy::RejectInterior => {
+                        self.raw_bytes = raw_bytes;
+                        return Err(Error::InteriorBom.into());
+                    }
                     (State::Ground(_), '\n') => {
+                        if !self.end_bidi_line() {
+                            self.raw_bytes = raw_bytes;
+                            return Err(Error::BidiControl.into());
+                        }
+                        self.queue.push('\n');
+                        self.state = State::Ground(true)
+                    }
+                    (State::Ground(_), c)
+                        if is_unicode_line_terminator(c)
+                            && self.line_terminator_policy != LineTerminatorPolicy::Keep =>
+                    {
+                        if self.line_terminator_policy == LineTerminatorPolicy::Reject {
+                            self.raw_bytes = raw_bytes;
+                            return Err(Error::LineTerminator { scalar: c }.into());
+                        }
+                        if !self.end_bidi_line() {
+                            self.raw_bytes = raw_bytes;
+                            return Err(Error::BidiControl.into());
+                        }
+                        self.stats.converted_line_terminators += 1;
                         self.queue.push('\n');
                         self.state = State::Ground(true)
                     }
                     (State::Ground(_), '\t') => {
-                        self.queue.push('\t');
+                        match self.tab_policy {
+                            TabPolicy::Keep | TabPolicy::Expand(_) => self.queue.push('\t'),
+                            TabPolicy::Replace => {
+                                let offset = self.inner.offset_in_last_read(i);
+                                self.inner.report_substitution(
+                                    SubstitutionKind::ControlCode,
+                                    b"\t",
+                                    offset,
+                                );
+                                self.stats.replacements += 1;
+                                self.push_replacement();
+                            }
+                            TabPolicy::Reject => {
+                                self.raw_bytes = raw_bytes;
+                                return Err(Error::ControlCode { scalar: c }.into());
+                            }
+                        }
                         self.state = State::Ground(false)
                     }
                     (State::Ground(_), FF) => {
-                        self.queue.push(' ');
+                        if self.form_feed_policy == FormFeedPolicy::Preserve {
+                            self.queue.push(FF);
+                        } else {
+                            if self.form_feed_policy == FormFeedPolicy::Reject {
+                                self.raw_bytes = raw_bytes;
+                                return Err(Error::ControlCode { scalar: c }.into());
+                            }
+                            let offset = self.inner.offset_in_last_read(i);
+                            self.inner.report_substitution(
+                                SubstitutionKind::ControlCode,
+                                b"\x0c",
+                                offset,
+                            );
+                            self.stats.replacements += 1;
+                            if self.form_feed_policy == FormFeedPolicy::Space {
+                                self.queue.push(' ');
+                            } else {
+                                self.push_replacement();
+                            }
+                        }
                         self.state = State::Ground(false)
                     }
-                    (State::Ground(_), '\r') => self.state = State::Cr,
-                    (State::Ground(_), ESC) => self.state = State::Esc,
+                    (State::Ground(_), '\r') if self.newline_policy == NewlinePolicy::ConvertCr => {
+                        if !self.end_bidi_line() {
+                            self.raw_bytes = raw_bytes;
+                            return Err(Error::BidiControl.into());
+                        }
+                        self.queue.push('\n');
+                        self.state = State::CrNewline;
+                    }
+                    (State::Ground(_), '\r') => {
+                        self.cr_offset = self.inner.offset_in_last_read(i);
+                        self.state = State::Cr
+                    }
+                    (State::Ground(_), ESC) => {
+                        self.escape.step(c);
+                        self.state = State::Escape;
+                        if track_sequence {
+                            self.sequence.clear();
+                            self.sequence.push(c);
+                            self.sequence_offset = self.inner.offset_in_last_read(i);
+                        }
+                    }
+                    (State::Ground(_), '\0') if self.nul_policy != NulPolicy::Replace => {
+                        if self.nul_policy == NulPolicy::Reject {
+                            self.raw_bytes = raw_bytes;
+                            return Err(Error::ControlCode { scalar: c }.into());
+                        }
+                        let offset = self.inner.offset_in_last_read(i);
+                        self.inner.report_substitution(
+                            SubstitutionKind::ControlCode,
+                            b"\0",
+                            offset,
+                        );
+                        self.stats.stripped_characters += 1;
+                        self.state = State::Ground(false);
+                    }
                     (State::Ground(_), c) if c.is_control() => {
-                        self.queue.push(REPL);
+                        let mut tmp = [0; MAX_UTF8_SIZE];
+                        let offset = self.inner.offset_in_last_read(i);
+                        self.inner.report_substitution(
+                            SubstitutionKind::ControlCode,
+                            c.encode_utf8(&mut tmp).as_bytes(),
+                            offset,
+                        );
+                        self.stats.replacements += 1;
+                        self.push_replacement();
                         self.state = State::Ground(false);
                     }
-                    (State::Ground(_), mut c) => {
-                        if self.expect_starter {
-                            self.expect_starter = false;
-                            if !is_normalization_form_starter(c) {
-                                c = REPL;
+                    (State::Ground(_), c) if self.forbidden.contains(c) => {
+                        if self.forbidden_policy == ForbiddenCharacterPolicy::Reject {
+                            self.raw_bytes = raw_bytes;
+                            return Err(Error::ForbiddenCharacter { scalar: c }.into());
+                        }
+                        let mut tmp = [0; MAX_UTF8_SIZE];
+                        let offset = self.inner.offset_in_last_read(i);
+                        self.inner.report_substitution(
+                            SubstitutionKind::ForbiddenCharacter,
+                            c.encode_utf8(&mut tmp).as_bytes(),
+                            offset,
+                        );
+                        self.stats.replacements += 1;
+                        self.push_replacement();
+                        self.state = State::Ground(false);
+                    }
+                    (State::Ground(_), c)
+                        if is_bidi_control(c) && self.bidi_policy != BidiPolicy::Allow =>
+                    {
+                        match self.bidi_policy {
+                            BidiPolicy::Strip | BidiPolicy::Replace => {
+                                let mut tmp = [0; MAX_UTF8_SIZE];
+                                let offset = self.inner.offset_in_last_read(i);
+                                self.inner.report_substitution(
+                                    SubstitutionKind::BidiControl,
+                                    c.encode_utf8(&mut tmp).as_bytes(),
+                                    offset,
+                                );
+                                if self.bidi_policy == BidiPolicy::Replace {
+                                    self.stats.replacements += 1;
+                                    self.push_replacement();
+                                    self.expect_starter = self.replacement.is_empty();
+                                } else {
+                                    self.stats.stripped_characters += 1;
+                                }
+                            }
+                            BidiPolicy::RequireBalanced if self.bidi_balance.step(c) => {
+                                self.expect_starter = false;
+                                self.queue.push(c);
+                            }
+                            _ => {
+                                self.raw_bytes = raw_bytes;
+                                return Err(Error::BidiControl.into());
                             }
                         }
-                        self.queue.push(c);
+                        self.state = State::Ground(false);
+                    }
+                    (State::Ground(_), c)
+                        if is_invisible(c) && self.invisible_policy != InvisiblePolicy::Keep =>
+                    {
+                        if self.invisible_policy == InvisiblePolicy::Reject {
+                            self.raw_bytes = raw_bytes;
+                            return Err(Error::InvisibleCharacter { scalar: c }.into());
+                        }
+                        let mut tmp = [0; MAX_UTF8_SIZE];
+                        let offset = self.inner.offset_in_last_read(i);
+                        self.inner.report_substitution(
+                            SubstitutionKind::InvisibleCharacter,
+                            c.encode_utf8(&mut tmp).as_bytes(),
+                            offset,
+                        );
+                        if self.invisible_policy == InvisiblePolicy::Replace {
+                            self.stats.replacements += 1;
+                            self.push_replacement();
+                            self.expect_starter = self.replacement.is_empty();
+                        } else {
+                            self.stats.stripped_characters += 1;
+                        }
+                        self.state = State::Ground(false);
+                    }
+                    (State::Ground(_), c) => {
+                        if self.expect_starter && !is_normalization_form_starter(c) {
+                            if self.non_starter_policy == NonStarterPolicy::Reject {
+                                self.raw_bytes = raw_bytes;
+                                return Err(Error::NonStarter.into());
+                            }
+                            let mut tmp = [0; MAX_UTF8_SIZE];
+                            let offset = self.inner.offset_in_last_read(i);
+                            self.inner.report_substitution(
+                                SubstitutionKind::NonStarter,
+                                c.encode_utf8(&mut tmp).as_bytes(),
+                                offset,
+                            );
+                            self.stats.replacements += 1;
+                            self.push_replacement();
+                            // If the replacement is empty, the next scalar
+                            // value must still be a starter.
+                            self.expect_starter = self.replacement.is_empty();
+                        } else {
+                            self.expect_starter = false;
+                            self.queue.push(c);
+                        }
                         self.state = State::Ground(false)
                     }
 
                     (State::Cr, '\n') => {
+                        if !self.end_bidi_line() {
+                            self.raw_bytes = raw_bytes;
+                            return Err(Error::BidiControl.into());
+                        }
+                        if self.newline_policy == NewlinePolicy::PreserveCrlf {
+                            self.queue.push('\r');
+                        } else {
+                            self.stats.converted_crlfs += 1;
+                        }
                         self.queue.push('\n');
                         self.state = State::Ground(true);
                     }
                     (State::Cr, _) => {
-                        self.queue.push(REPL);
-                        self.state = State::Ground(false);
+                        self.replace_cr();
                         continue;
                     }
 
-                    (State::Esc, '[') => self.state = State::CsiStart,
-                    (State::Esc, ']') => self.state = State::Osc,
-                    (State::Esc, c) if ('@'..='~').contains(&c) => {
-                        self.state = State::Ground(false)
-                    }
-                    (State::Esc, _) => {
-                        self.state = State::Ground(false);
-                        continue;
-                    }
-
-                    (State::CsiStart, '[') => self.state = State::Linux,
-                    (State::CsiStart, c) | (State::Csi, c) if (' '..='?').contains(&c) => {
-                        self.state = State::Csi
-                    }
-                    (State::CsiStart, c) | (State::Csi, c) if ('@'..='~').contains(&c) => {
-                        self.state = State::Ground(false)
+                    (State::CrNewline, '\n') => {
+                        self.stats.converted_crlfs += 1;
+                        self.state = State::Ground(true);
                     }
-                    (State::CsiStart, _) | (State::Csi, _) => {
-                        self.state = State::Ground(false);
+                    (State::CrNewline, _) => {
+                        self.stats.converted_crs += 1;
+                        self.state = State::Ground(true);
                         continue;
                     }
 
-                    (State::Osc, c) if !c.is_control() || c == '\n' || c == '\t' => (),
-                    (State::Osc, _) => self.state = State::Ground(false),
-
-                    (State::Linux, c) if ('\0'..=DEL).contains(&c) => {
-                        self.state = State::Ground(false)
-                    }
-                    (State::Linux, _) => {
-                        self.state = State::Ground(false);
-                        continue;
+                    (State::Escape, c) => {
+                        if !self.escape.step(c) {
+                            self.drop_sequence();
+                            self.state = State::Ground(false);
+                            continue;
+                        }
+                        if track_sequence && self.sequence.len() <= MAX_SAFE_ESCAPE_LEN {
+                            self.sequence.push(c);
+                        }
+                        if self.escape == EscapeState::Ground {
+                            self.state = State::Ground(false);
+                            if self.safe_escapes && is_safe_escape(&self.sequence) {
+                                for c in self.sequence.chars() {
+                                    self.queue.push(c);
+                                }
+                            } else {
+                                self.drop_sequence();
+                            }
+                        }
                     }
                 }
                 break;
             }
+            i += c.len_utf8();
         }
+        self.raw_bytes = raw_bytes;
+        Ok(())
     }
-}
 
-impl<Inner: Read> Read for TextReader<Inner> {
-    fn read_outcome(&mut self, buf: &mut [u8]) -> io::Result<ReadOutcome> {
-        if buf.len() < NORMALIZATION_BUFFER_SIZE {
-            return Err(io::Error::new(
-                io::ErrorKind::InvalidInput,
-                "buffer for text input must be at least NORMALIZATION_BUFFER_SIZE bytes",
-            ));
+    /// Declare the end of a line, and return false if it leaves
+    /// bidirectional controls open with `BidiPolicy::RequireBalanced`.
+    #[inline]
+    fn end_bidi_line(&mut self) -> bool {
+        self.bidi_policy != BidiPolicy::RequireBalanced || self.bidi_balance.end_line()
+    }
+
+    /// Replace a '\r' which isn't followed by a '\n'.
+    fn replace_cr(&mut self) {
+        self.inner
+            .report_substitution(SubstitutionKind::ControlCode, b"\r", self.cr_offset);
+        self.stats.replacements += 1;
+        self.push_replacement();
+        self.state = State::Ground(false);
+    }
+
+    /// Push the replacement string onto the queue.
+    fn push_replacement(&mut self) {
+        for c in self.replacement.chars() {
+            self.queue.push(c);
         }
+    }
 
-        let mut nread = 0;
+    /// Report the removal of the escape sequence in progress.
+    fn drop_sequence(&mut self) {
+        self.stats.dropped_escapes += 1;
+        if self.inner.has_substitution_hook() {
+            self.inner.report_substitution(
+                SubstitutionKind::EscapeSequence,
+                self.sequence.as_bytes(),
+                self.sequence_offset,
+            );
+        }
+    }
+}
 
-        loop {
-            match self.queue_next(false) {
-                Some(c) => nread += c.encode_utf8(&mut buf[nread..]).len(),
-                None => break,
+impl<Inner: Read> TextReader<Inner> {
+    /// Deliver output from `self.staged`, reading more into it first if it's
+    /// empty.
+    #[cold]
+    fn read_staged(&mut self, buf: &mut [u8]) -> io::Result<ReadOutcome> {
+        if self.staged.is_empty() {
+            let mut staging = [0_u8; NORMALIZATION_BUFFER_SIZE];
+            let outcome = self.read_translated(&mut staging)?;
+            self.staged.extend_from_slice(&staging[..outcome.size]);
+            self.staged_status = outcome.status;
+        }
+
+        // Avoid splitting a scalar value encoding if the buffer has room for
+        // at least one.
+        let mut size = min(buf.len(), self.staged.len());
+        if buf.len() >= MAX_UTF8_SIZE {
+            while size > 0 && size < self.staged.len() && (self.staged[size] & 0xc0) == 0x80 {
+                size -= 1;
             }
-            if buf.len() - nread < MAX_UTF8_SIZE {
-                return Ok(ReadOutcome::ready(nread));
+            if size == 0 {
+                size = min(buf.len(), self.staged.len());
             }
         }
-        if self.pending_status != Status::ready() {
-            self.pending_status = Status::ready();
-            self.expect_starter = true;
-            return Ok(ReadOutcome {
-                size: nread,
-                status: self.pending_status,
-            });
+
+        buf[..size].copy_from_slice(&self.staged[..size]);
+        self.staged.drain(..size);
+
+        Ok(if self.staged.is_empty() {
+            ReadOutcome {
+                size,
+                status: mem::replace(&mut self.staged_status, Status::ready()),
+            }
+        } else {
+            ReadOutcome::ready(size)
+        })
+    }
+
+    fn read_translated(&mut self, buf: &mut [u8]) -> io::Result<ReadOutcome> {
+        let outcome = if self.grapheme_atomic {
+            self.read_graphemes(buf)?
+        } else {
+            self.read_direct(buf)?
+        };
+        #[cfg(feature = "security")]
+        if mem::take(&mut self.restriction_rejected) {
+            return Err(Error::RestrictionLevelExceeded.into());
         }
+        Ok(outcome)
+    }
 
-        let mut raw_bytes = mem::replace(&mut self.raw_string, String::new()).into_bytes();
-        raw_bytes.resize(4096, 0_u8);
-        let outcome = self.inner.read_outcome(&mut raw_bytes)?;
-        raw_bytes.resize(outcome.size, 0);
-        self.raw_string = String::from_utf8(raw_bytes).unwrap();
+    /// Like `read_direct`, but hold back the last extended grapheme cluster
+    /// when more data may follow, since it may be extended.
+    fn read_graphemes(&mut self, buf: &mut [u8]) -> io::Result<ReadOutcome> {
+        let carried = self.carry.len();
+        buf[..carried].copy_from_slice(&self.carry);
+        self.carry.clear();
 
-        self.process_raw_string();
+        let mut size = carried;
+        loop {
+            let outcome = self.read_direct(&mut buf[size..])?;
+            size += outcome.size;
+            if outcome.status.is_boundary() {
+                return Ok(ReadOutcome {
+                    size,
+                    status: outcome.status,
+                });
+            }
 
-        if outcome.status != Status::ready() {
+            // Boundaries before the last cluster are final, because they only
+            // depend on the text before them and the scalar value after them.
+            let s = str::from_utf8(&buf[..size]).unwrap();
+            let last = s.grapheme_indices(true).next_back().map_or(0, |(i, _)| i);
+            if size - last > MAX_GRAPHEME_CARRY {
+                return Ok(ReadOutcome {
+                    size,
+                    status: outcome.status,
+                });
+            }
+
+            // If there's nothing before the last cluster, read more rather
+            // than report an empty read on a stream which is ready.
+            if last != 0 || outcome.status != Status::ready() {
+                self.carry.extend_from_slice(&buf[last..size]);
+                return Ok(ReadOutcome {
+                    size: last,
+                    status: outcome.status,
+                });
+            }
+        }
+    }
+
+    /// Handle the status of a read from the inner stream, once its data
+    /// has been processed.
+    fn end_read(&mut self, status: Status) -> io::Result<()> {
+        if status.is_boundary() {
             match self.state {
-                State::Ground(_) => {}
-                State::Cr => {
-                    self.queue.push(REPL);
-                    self.state = State::Ground(false);
-                }
-                State::Esc | State::CsiStart | State::Csi | State::Osc | State::Linux => {
+                State::Ground(_) | State::CrNewline => {}
+                State::Cr => self.replace_cr(),
+                State::Escape => {
+                    self.drop_sequence();
+                    self.escape = EscapeState::Ground;
                     self.state = State::Ground(false);
                 }
             }
 
-            if outcome.status.is_end() && self.state != State::Ground(true) {
-                self.queue.push('\n');
-                self.state = State::Ground(true);
+            if status.is_end() {
+                if !self.end_bidi_line() {
+                    return Err(Error::BidiControl.into());
+                }
+                if self.state == State::CrNewline {
+                    self.stats.converted_crs += 1;
+                    self.state = State::Ground(true);
+                }
+                if self.state != State::Ground(true) {
+                    self.queue.push('\n');
+                    self.state = State::Ground(true);
+                }
+            } else if self.non_starter_policy == NonStarterPolicy::Buffer {
+                self.held = self.queue.split_off_last_segment();
             }
+        } else if status == Status::Open(Readiness::Pending) {
+            // Release everything up to the last segment, which may still
+            // combine with what follows, and hold that until more data
+            // arrives.
+            self.held = self.queue.split_off_last_segment();
         }
+        Ok(())
+    }
 
-        loop {
-            match self.queue_next(outcome.status != Status::ready()) {
-                Some(c) => nread += c.encode_utf8(&mut buf[nread..]).len(),
-                None => break,
+    fn read_direct(&mut self, buf: &mut [u8]) -> io::Result<ReadOutcome> {
+        let mut nread = 0;
+
+        // If a status is pending, the input up to it has all been queued, so
+        // drain the queue completely before reporting it.
+        while let Some(c) = self.queue_next(self.pending_status != Status::ready()) {
+            nread += c.encode_utf8(&mut buf[nread..]).len();
+            if buf.len() - nread < MAX_UTF8_SIZE {
+                return Ok(ReadOutcome::ready(nread));
+            }
+        }
+        if self.pending_status != Status::ready() {
+            let status = mem::replace(&mut self.pending_status, Status::ready());
+            if status.is_boundary() {
+                self.expect_starter = true;
             }
+            return Ok(ReadOutcome {
+                size: nread,
+                status,
+            });
+        }
+
+        let outcome = self.inner.read_outcome(&mut self.raw_bytes)?;
+        self.process_raw_bytes(outcome.size)?;
+        self.end_read(outcome.status)?;
+
+        while let Some(c) = self.queue_next(outcome.status != Status::ready()) {
+            nread += c.encode_utf8(&mut buf[nread..]).len();
             if buf.len() - nread < MAX_UTF8_SIZE {
                 break;
             }
@@ -266,8 +964,8 @@ impl<Inner: Read> Read for TextReader<Inner> {
 
         Ok(ReadOutcome {
             size: nread,
-            status: if self.queue_iter.is_none() {
-                if outcome.status != Status::ready() {
+            status: if !self.has_queued_output() {
+                if outcome.status.is_boundary() {
                     self.expect_starter = true;
                 }
                 outcome.status
@@ -279,6 +977,321 @@ impl<Inner: Read> Read for TextReader<Inner> {
     }
 }
 
+impl<Inner: Read> Read for TextReader<Inner> {
+    fn read_outcome(&mut self, buf: &mut [u8]) -> io::Result<ReadOutcome> {
+        if buf.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "buffer for text input must not be empty",
+            ));
+        }
+
+        if buf.len() < NORMALIZATION_BUFFER_SIZE || !self.staged.is_empty() {
+            self.read_staged(buf)
+        } else {
+            self.read_translated(buf)
+        }
+    }
+
+    /// With the `rayon` feature, large inputs are translated in parallel.
+    #[cfg(feature = "rayon")]
+    fn read_to_string(&mut self, buf: &mut String) -> io::Result<usize> {
+        self.read_to_string_parallel(buf, rayon::current_num_threads(), MIN_PARALLEL_CHUNK)
+    }
+}
+
+/// Seeking discards all buffered and partially translated data, and resets
+/// the stream to the state it has at the beginning, so the new position
+/// should be at a line boundary. Positions are in terms of the inner stream.
+///
+/// Since the amount of buffered data doesn't correspond to a number of bytes
+/// in the inner stream, seeking with `SeekFrom::Current` is not supported.
+impl<Inner: Read + Seek> Seek for TextReader<Inner> {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        if let SeekFrom::Current(_) = pos {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "TextReader does not support seeking relative to the current position",
+            ));
+        }
+        let pos = self.inner.seek(pos)?;
+        self.queue.clear();
+        self.queue_iter = None;
+        self.problem_sequences = ProblemSequences::default();
+        self.bidi_balance = BidiBalance::default();
+        self.columns = Columns::default();
+        self.pending_spaces = 0;
+        self.held.clear();
+        self.pending_status = Status::ready();
+        self.expect_starter = true;
+        self.at_start = true;
+        self.state = State::Ground(true);
+        self.escape = EscapeState::Ground;
+        self.staged.clear();
+        self.staged_status = Status::ready();
+        self.carry.clear();
+        #[cfg(feature = "security")]
+        if let Some((checker, _)) = &mut self.restriction {
+            checker.reset();
+        }
+        Ok(pos)
+    }
+}
+
+#[cfg(feature = "rayon")]
+impl<Inner: Read> TextReader<Inner> {
+    /// Read the rest of the stream into `buf`, translating up to about
+    /// `pieces` pieces of at least `min_chunk` bytes in parallel.
+    ///
+    /// The input is split only after a '\n' which isn't within an escape
+    /// sequence and which is followed by a printable ASCII character. At
+    /// such a point, a `TextReader` is at the beginning of a line with
+    /// nothing pending, so a fresh `TextReader` with the same options
+    /// produces the same output from there on.
+    fn read_to_string_parallel(
+        &mut self,
+        buf: &mut String,
+        pieces: usize,
+        min_chunk: usize,
+    ) -> io::Result<usize> {
+        use rayon::prelude::*;
+
+        if !self.can_split() {
+            return crate::default_read_to_string(self, buf);
+        }
+
+        // Read all the input, noting where the lulls are, since they
+        // affect the translation.
+        let mut raw = String::new();
+        let mut lulls = Vec::new();
+        let mut chunk = vec![0_u8; 1 << 16];
+        loop {
+            let outcome = match self.inner.read_outcome(&mut chunk) {
+                Ok(outcome) => outcome,
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+                Err(err) => return Err(err),
+            };
+            let data = str::from_utf8(&chunk[..outcome.size])
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+            raw.push_str(data);
+            match outcome.status {
+                Status::Open(Readiness::Ready) => {}
+                Status::Open(Readiness::Lull) => lulls.push(raw.len()),
+                Status::Open(Readiness::Pending) => {
+                    // The input read so far can't be given back to the inner
+                    // stream, so translate it here before reporting that
+                    // more isn't available yet.
+                    self.translate_serially(&raw, &lulls, buf)?;
+                    return Err(io::Error::new(
+                        io::ErrorKind::WouldBlock,
+                        "read would block",
+                    ));
+                }
+                Status::End => break,
+            }
+        }
+
+        let target = (raw.len() / pieces).max(min_chunk);
+        let splits = self.split_points(&raw, &lulls, target);
+        let builder = self.builder();
+        let (at_start, expect_starter) = (self.at_start, self.expect_starter);
+        let results = splits
+            .par_windows(2)
+            .map(|window| {
+                let (start, end) = (window[0], window[1]);
+                let first = lulls.partition_point(|&lull| lull < start);
+                let count = lulls[first..].partition_point(|&lull| lull <= end);
+                let mut reader = builder.build(Segments {
+                    data: &raw[start..end],
+                    lulls: lulls[first..first + count]
+                        .iter()
+                        .map(|lull| lull - start)
+                        .collect(),
+                    pos: 0,
+                });
+                if start == 0 {
+                    reader.at_start = at_start;
+                    reader.expect_starter = expect_starter;
+                }
+                let mut output = String::new();
+                crate::default_read_to_string(&mut reader, &mut output)?;
+                Ok((output, reader.stats))
+            })
+            .collect::<io::Result<Vec<(String, Stats)>>>()?;
+
+        let start_len = buf.len();
+        buf.reserve(results.iter().map(|(output, _)| output.len()).sum());
+        for (output, stats) in results {
+            buf.push_str(&output);
+            self.stats.add(&stats);
+        }
+        if !raw.is_empty() {
+            self.at_start = false;
+        }
+        self.expect_starter = true;
+        Ok(buf.len() - start_len)
+    }
+
+    /// Translate `raw`, which was read from the inner stream with lulls at
+    /// the offsets in `lulls` and ended with `Readiness::Pending`, in order
+    /// using this reader's own state, and append the output to `buf`.
+    fn translate_serially(
+        &mut self,
+        raw: &str,
+        lulls: &[usize],
+        buf: &mut String,
+    ) -> io::Result<()> {
+        let mut pos = 0;
+        let ends = lulls
+            .iter()
+            .map(|&lull| (lull, Status::Open(Readiness::Lull)))
+            .chain(std::iter::once((
+                raw.len(),
+                Status::Open(Readiness::Pending),
+            )));
+        for (end, end_status) in ends {
+            loop {
+                let mut len = (end - pos).min(self.raw_bytes.len());
+                while !raw.is_char_boundary(pos + len) {
+                    len -= 1;
+                }
+                self.raw_bytes[..len].copy_from_slice(&raw.as_bytes()[pos..pos + len]);
+                self.process_raw_bytes(len)?;
+                pos += len;
+                let status = if pos == end {
+                    end_status
+                } else {
+                    Status::ready()
+                };
+                self.end_read(status)?;
+                while let Some(c) = self.queue_next(status != Status::ready()) {
+                    buf.push(c);
+                }
+                if pos == end {
+                    if status.is_boundary() {
+                        self.expect_starter = true;
+                    }
+                    break;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Test whether this reader is at the beginning of a line with nothing
+    /// pending, and has no state which depends on the input being
+    /// processed in order.
+    fn can_split(&self) -> bool {
+        #[cfg(feature = "security")]
+        if self.restriction.is_some() {
+            return false;
+        }
+        self.state == State::Ground(true)
+            && self.escape == EscapeState::Ground
+            && self.queue.is_empty()
+            && self.queue_iter.is_none()
+            && self.held.is_empty()
+            && self.pending_status == Status::ready()
+            && self.pending_spaces == 0
+            && self.staged.is_empty()
+            && self.carry.is_empty()
+            && !self.inner.has_substitution_hook()
+    }
+
+    /// Return the offsets at which to split `raw` into pieces of about
+    /// `target` bytes, including its start and end.
+    fn split_points(&self, raw: &str, lulls: &[usize], target: usize) -> Vec<usize> {
+        let mut splits = vec![0];
+        let mut escape = EscapeState::Ground;
+        for (i, c) in raw.char_indices() {
+            // Track escape sequences the way `process_raw_bytes` does.
+            if escape != EscapeState::Ground && escape.step(c) {
+                continue;
+            }
+            if c == ESC {
+                escape.step(c);
+                continue;
+            }
+            let next = i + 1;
+            if c == '\n'
+                && next - splits.last().unwrap() >= target
+                && raw.len() - next >= target / 2
+                && matches!(raw.as_bytes().get(next), Some(&b @ b' '..=b'~')
+                    if !self.forbidden.contains(char::from(b)))
+                && lulls.binary_search(&next).is_err()
+            {
+                splits.push(next);
+            }
+        }
+        splits.push(raw.len());
+        splits
+    }
+
+    /// Return a builder with the options of this reader.
+    fn builder(&self) -> TextReaderBuilder {
+        TextReaderBuilder {
+            safe_escapes: self.safe_escapes,
+            replacement: self.replacement.clone(),
+            bom_policy: self.bom_policy,
+            non_starter_policy: self.non_starter_policy,
+            normalization_form: self.normalization_form,
+            grapheme_atomic: self.grapheme_atomic,
+            forbidden: self.forbidden.clone(),
+            forbidden_policy: self.forbidden_policy,
+            bidi_policy: self.bidi_policy,
+            invisible_policy: self.invisible_policy,
+            line_terminator_policy: self.line_terminator_policy,
+            newline_policy: self.newline_policy,
+            tab_policy: self.tab_policy,
+            nul_policy: self.nul_policy,
+            form_feed_policy: self.form_feed_policy,
+            #[cfg(feature = "security")]
+            restriction: None,
+        }
+    }
+}
+
+/// A `Read` implementation which replays a piece of the input read by
+/// `TextReader::read_to_string_parallel`, with its lulls.
+#[cfg(feature = "rayon")]
+struct Segments<'a> {
+    data: &'a str,
+
+    /// The offsets of the lulls which haven't been reported yet.
+    lulls: std::collections::VecDeque<usize>,
+
+    pos: usize,
+}
+
+#[cfg(feature = "rayon")]
+impl Read for Segments<'_> {
+    fn read_outcome(&mut self, buf: &mut [u8]) -> io::Result<ReadOutcome> {
+        let stop = self.lulls.front().copied().unwrap_or(self.data.len());
+        let size = min(buf.len(), stop - self.pos);
+        buf[..size].copy_from_slice(&self.data.as_bytes()[self.pos..self.pos + size]);
+        self.pos += size;
+        Ok(if self.pos != stop {
+            ReadOutcome::ready(size)
+        } else if self.lulls.pop_front().is_some() {
+            ReadOutcome::lull(size)
+        } else {
+            ReadOutcome::end(size)
+        })
+    }
+}
+
+impl<Inner: Read> crate::ReadStr for TextReader<Inner> {
+    #[inline]
+    fn read_str(&mut self, buf: &mut str) -> io::Result<ReadOutcome> {
+        self.read_utf8(buf)
+    }
+
+    #[inline]
+    fn read_to_string(&mut self, buf: &mut String) -> io::Result<usize> {
+        Read::read_to_string(self, buf)
+    }
+}
+
 impl<Inner: Read> io::Read for TextReader<Inner> {
     #[inline]
     fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
@@ -312,6 +1325,377 @@ impl<Inner: Read> io::Read for TextReader<Inner> {
     }
 }
 
+/// A builder for `TextReader`, for combining options.
+#[derive(Clone, Debug)]
+pub struct TextReaderBuilder {
+    safe_escapes: bool,
+    replacement: String,
+    bom_policy: BomPolicy,
+    non_starter_policy: NonStarterPolicy,
+    normalization_form: NormalizationForm,
+    grapheme_atomic: bool,
+    forbidden: ForbiddenCharacters,
+    forbidden_policy: ForbiddenCharacterPolicy,
+    bidi_policy: BidiPolicy,
+    invisible_policy: InvisiblePolicy,
+    line_terminator_policy: LineTerminatorPolicy,
+    newline_policy: NewlinePolicy,
+    tab_policy: TabPolicy,
+    nul_policy: NulPolicy,
+    form_feed_policy: FormFeedPolicy,
+    #[cfg(feature = "security")]
+    restriction: Option<(RestrictionLevel, RestrictionPolicy)>,
+}
+
+impl TextReaderBuilder {
+    /// Construct a new `TextReaderBuilder` with the default options.
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            safe_escapes: false,
+            replacement: REPL.to_string(),
+            bom_policy: BomPolicy::StripAll,
+            non_starter_policy: NonStarterPolicy::Replace,
+            normalization_form: NormalizationForm::Nfc,
+            grapheme_atomic: false,
+            forbidden: ForbiddenCharacters::tr15(),
+            forbidden_policy: ForbiddenCharacterPolicy::Replace,
+            bidi_policy: BidiPolicy::Allow,
+            invisible_policy: InvisiblePolicy::Keep,
+            line_terminator_policy: LineTerminatorPolicy::Keep,
+            newline_policy: NewlinePolicy::ConvertCrlf,
+            tab_policy: TabPolicy::Keep,
+            nul_policy: NulPolicy::Replace,
+            form_feed_policy: FormFeedPolicy::Space,
+            #[cfg(feature = "security")]
+            restriction: None,
+        }
+    }
+
+    /// Pass through escape sequences which only affect the appearance of
+    /// the text, as `TextReader::with_safe_escapes` does.
+    #[inline]
+    pub fn safe_escapes(&mut self, enable: bool) -> &mut Self {
+        self.safe_escapes = enable;
+        self
+    }
+
+    /// Set the string which invalid UTF-8 sequences and disallowed scalar
+    /// values are replaced with, such as "?" for ASCII-only environments,
+    /// or "" to delete them.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `replacement` is longer than 4 bytes, or contains
+    /// Unicode Normalization Form non-starters, control codes, or U+FEFF.
+    #[inline]
+    pub fn replacement(&mut self, replacement: &str) -> &mut Self {
+        assert!(
+            is_valid_replacement(replacement),
+            "invalid replacement string"
+        );
+        self.replacement = replacement.to_owned();
+        self
+    }
+
+    /// Set what to do with U+FEFF (BOM). The default is
+    /// `BomPolicy::StripAll`.
+    #[inline]
+    pub fn bom_policy(&mut self, policy: BomPolicy) -> &mut Self {
+        self.bom_policy = policy;
+        self
+    }
+
+    /// Set what to do with a non-starter at the beginning of the stream or
+    /// after a lull. The default is `NonStarterPolicy::Replace`.
+    #[inline]
+    pub fn non_starter_policy(&mut self, policy: NonStarterPolicy) -> &mut Self {
+        self.non_starter_policy = policy;
+        self
+    }
+
+    /// Set the Normalization Form to transform text to. The default is
+    /// `NormalizationForm::Nfc`.
+    #[inline]
+    pub fn normalization_form(&mut self, form: NormalizationForm) -> &mut Self {
+        self.normalization_form = form;
+        self
+    }
+
+    /// Never split an extended grapheme cluster across two reads, so that
+    /// consumers which process each chunk independently, for example to
+    /// compute display widths, see whole clusters. The last cluster of each
+    /// read is held back until more data arrives, a lull, or the end of the
+    /// stream. This applies to reads into buffers of at least
+    /// [`NORMALIZATION_BUFFER_SIZE`] bytes, and clusters no longer than half
+    /// that size.
+    #[inline]
+    pub fn grapheme_atomic(&mut self, enable: bool) -> &mut Self {
+        self.grapheme_atomic = enable;
+        self
+    }
+
+    /// Set the scalar values which aren't permitted. The default is
+    /// `ForbiddenCharacters::tr15()`.
+    #[inline]
+    pub fn forbidden_characters(&mut self, forbidden: ForbiddenCharacters) -> &mut Self {
+        self.forbidden = forbidden;
+        self
+    }
+
+    /// Add `c` to the scalar values which aren't permitted.
+    #[inline]
+    pub fn forbid(&mut self, c: char) -> &mut Self {
+        self.forbidden.insert(c);
+        self
+    }
+
+    /// Set what to do with forbidden scalar values. The default is
+    /// `ForbiddenCharacterPolicy::Replace`.
+    #[inline]
+    pub fn forbidden_character_policy(&mut self, policy: ForbiddenCharacterPolicy) -> &mut Self {
+        self.forbidden_policy = policy;
+        self
+    }
+
+    /// Set what to do with bidirectional embedding, override, and isolate
+    /// controls, which can disguise text as in [Trojan Source] attacks. The
+    /// default is `BidiPolicy::Allow`.
+    ///
+    /// With `BidiPolicy::RequireBalanced`, controls left open at the end of
+    /// a line are only detected at its '\n', so the error may be reported
+    /// after part of the line has been read.
+    ///
+    /// [Trojan Source]: https://trojansource.codes/
+    #[inline]
+    pub fn bidi_policy(&mut self, policy: BidiPolicy) -> &mut Self {
+        self.bidi_policy = policy;
+        self
+    }
+
+    /// Set what to do with invisible formatting characters, such as
+    /// zero-width spaces and joiners and soft hyphens. The default is
+    /// `InvisiblePolicy::Keep`.
+    ///
+    /// Characters are checked as they appear in the input, before
+    /// normalization.
+    #[inline]
+    pub fn invisible_policy(&mut self, policy: InvisiblePolicy) -> &mut Self {
+        self.invisible_policy = policy;
+        self
+    }
+
+    /// Set how to translate line endings involving '\r'. The default is
+    /// `NewlinePolicy::ConvertCrlf`.
+    #[inline]
+    pub fn newline_policy(&mut self, policy: NewlinePolicy) -> &mut Self {
+        self.newline_policy = policy;
+        self
+    }
+
+    /// Set what to do with '\t'. The default is `TabPolicy::Keep`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `policy` is `TabPolicy::Expand(0)`.
+    #[inline]
+    pub fn tab_policy(&mut self, policy: TabPolicy) -> &mut Self {
+        assert_ne!(policy, TabPolicy::Expand(0), "tab stops must be nonzero");
+        self.tab_policy = policy;
+        self
+    }
+
+    /// Set what to do with U+0000 (NUL). The default is
+    /// `NulPolicy::Replace`. With `NulPolicy::Reject`, reading stops at the
+    /// first NUL, which is useful for detecting binary data early.
+    #[inline]
+    pub fn nul_policy(&mut self, policy: NulPolicy) -> &mut Self {
+        self.nul_policy = policy;
+        self
+    }
+
+    /// Set what to do with U+000C (FF). The default is
+    /// `FormFeedPolicy::Space`.
+    #[inline]
+    pub fn form_feed_policy(&mut self, policy: FormFeedPolicy) -> &mut Self {
+        self.form_feed_policy = policy;
+        self
+    }
+
+    /// Set what to do with U+0085 (NEL), U+2028 (LINE SEPARATOR), and
+    /// U+2029 (PARAGRAPH SEPARATOR). The default is
+    /// `LineTerminatorPolicy::Keep`.
+    #[inline]
+    pub fn line_terminator_policy(&mut self, policy: LineTerminatorPolicy) -> &mut Self {
+        self.line_terminator_policy = policy;
+        self
+    }
+
+    /// Check each word, delimited by whitespace, against the UTS #39
+    /// restriction `level`, for programs ingesting untrusted identifiers.
+    /// Words containing characters not allowed in identifiers, including
+    /// punctuation, exceed all levels below `RestrictionLevel::Unrestricted`.
+    ///
+    /// Words are checked after normalization. Since a word is checked once
+    /// it's complete, with `RestrictionPolicy::Reject` the error may be
+    /// reported after part of the word has been read.
+    #[cfg(feature = "security")]
+    #[inline]
+    pub fn restriction_level(
+        &mut self,
+        level: RestrictionLevel,
+        policy: RestrictionPolicy,
+    ) -> &mut Self {
+        self.restriction = Some((level, policy));
+        self
+    }
+
+    /// Construct a `TextReader` wrapping `inner` with the configured
+    /// options.
+    pub fn build<Inner: Read>(&self, inner: Inner) -> TextReader<Inner> {
+        TextReader {
+            inner: Utf8ReaderBuilder::new()
+                .replacement(&self.replacement)
+                .build(inner),
+            raw_bytes: vec![0; RAW_BUFFER_SIZE].into_boxed_slice(),
+            queue: RcCharQueue::new(),
+            queue_iter: None,
+            problem_sequences: ProblemSequences::default(),
+            normalization_form: self.normalization_form,
+            pending_status: Status::ready(),
+            expect_starter: true,
+            non_starter_policy: self.non_starter_policy,
+            held: Vec::new(),
+            bom_policy: self.bom_policy,
+            at_start: true,
+            state: State::Ground(true),
+            escape: EscapeState::Ground,
+            safe_escapes: self.safe_escapes,
+            forbidden: self.forbidden.clone(),
+            forbidden_policy: self.forbidden_policy,
+            bidi_policy: self.bidi_policy,
+            bidi_balance: BidiBalance::default(),
+            invisible_policy: self.invisible_policy,
+            line_terminator_policy: self.line_terminator_policy,
+            newline_policy: self.newline_policy,
+            tab_policy: self.tab_policy,
+            nul_policy: self.nul_policy,
+            form_feed_policy: self.form_feed_policy,
+            columns: Columns::default(),
+            pending_spaces: 0,
+            replacement: self.replacement.clone(),
+            sequence: String::new(),
+            sequence_offset: 0,
+            cr_offset: 0,
+            stats: Stats::default(),
+            staged: Vec::new(),
+            staged_status: Status::ready(),
+            grapheme_atomic: self.grapheme_atomic,
+            carry: Vec::new(),
+            #[cfg(feature = "security")]
+            restriction: self
+                .restriction
+                .map(|(level, policy)| (RestrictionChecker::new(level), policy)),
+            #[cfg(feature = "security")]
+            restriction_rejected: false,
+        }
+    }
+
+    /// Construct an `AsyncTextReader` wrapping `inner` with the configured
+    /// options.
+    #[cfg(feature = "futures-io")]
+    #[inline]
+    pub fn build_async<Inner: futures_io::AsyncRead + Unpin>(
+        &self,
+        inner: Inner,
+    ) -> crate::AsyncTextReader<Inner> {
+        crate::AsyncTextReader::from_text_reader(self.build(crate::FromAsyncRead::new(inner)))
+    }
+}
+
+impl Default for TextReaderBuilder {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Counts of the sanitizations performed by a `TextReader`.
+#[derive(Default)]
+struct Stats {
+    replacements: u64,
+    stripped_boms: u64,
+    stripped_characters: u64,
+    converted_crlfs: u64,
+    converted_crs: u64,
+    converted_line_terminators: u64,
+    dropped_escapes: u64,
+    problem_sequences: u64,
+    #[cfg(feature = "security")]
+    restriction_violations: u64,
+}
+
+#[cfg(feature = "rayon")]
+impl Stats {
+    /// Add the counts in `other` to these.
+    fn add(&mut self, other: &Self) {
+        self.replacements += other.replacements;
+        self.stripped_boms += other.stripped_boms;
+        self.stripped_characters += other.stripped_characters;
+        self.converted_crlfs += other.converted_crlfs;
+        self.converted_crs += other.converted_crs;
+        self.converted_line_terminators += other.converted_line_terminators;
+        self.dropped_escapes += other.dropped_escapes;
+        self.problem_sequences += other.problem_sequences;
+        #[cfg(feature = "security")]
+        {
+            self.restriction_violations += other.restriction_violations;
+        }
+    }
+}
+
+/// The size of the buffer `TextReader` reads from its inner stream into.
+const RAW_BUFFER_SIZE: usize = 4096;
+
+/// The smallest piece of input which `read_to_string` translates on its own
+/// thread.
+#[cfg(feature = "rayon")]
+const MIN_PARALLEL_CHUNK: usize = 256 * 1024;
+
+/// The longest grapheme cluster tail held back by `read_graphemes`. This
+/// leaves room in the minimum direct-read buffer for `read_direct`.
+const MAX_GRAPHEME_CARRY: usize = NORMALIZATION_BUFFER_SIZE / 2;
+
+/// The maximum length of an escape sequence passed through by
+/// `TextReader::with_safe_escapes`. This is long enough for typical
+/// hyperlinks, while bounding the amount of data buffered.
+const MAX_SAFE_ESCAPE_LEN: usize = 2048;
+
+/// Test whether `sequence` is an SGR sequence or an OSC 8 hyperlink
+/// sequence, with nothing unexpected in its parameters.
+fn is_safe_escape(sequence: &str) -> bool {
+    if sequence.len() > MAX_SAFE_ESCAPE_LEN {
+        return false;
+    }
+    if let Some(params) = sequence
+        .strip_prefix("\x1b[")
+        .and_then(|rest| rest.strip_suffix('m'))
+    {
+        return params
+            .chars()
+            .all(|c| c.is_ascii_digit() || c == ';' || c == ':');
+    }
+    if let Some(rest) = sequence.strip_prefix("\x1b]8;") {
+        if let Some(params) = rest
+            .strip_suffix('\x07')
+            .or_else(|| rest.strip_suffix("\x1b\\"))
+        {
+            return params.chars().all(|c| (' '..='~').contains(&c));
+        }
+    }
+    false
+}
+
 #[derive(Clone, Copy, PartialEq, Eq)]
 enum State {
     // Default state. Boolean is true iff we just saw a '\n'.
@@ -320,20 +1704,44 @@ enum State {
     // After a '\r'.
     Cr,
 
-    // After a '\x1b'.
-    Esc,
+    // After a '\r' which has been converted to '\n', with
+    // `NewlinePolicy::ConvertCr`.
+    CrNewline,
+
+    // Within an escape sequence, tracked by `TextReader::escape`.
+    Escape,
+}
 
-    // Immediately after a "\x1b[".
-    CsiStart,
+#[cfg(unix)]
+impl<Inner: Read + AsRawFd> AsRawFd for TextReader<Inner> {
+    #[inline]
+    fn as_raw_fd(&self) -> RawFd {
+        self.inner.as_raw_fd()
+    }
+}
 
-    // Within a sequence started by "\x1b[".
-    Csi,
+#[cfg(unix)]
+impl<Inner: Read + AsFd> AsFd for TextReader<Inner> {
+    #[inline]
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.inner.as_fd()
+    }
+}
 
-    // Within a sequence started by "\x1b]".
-    Osc,
+#[cfg(windows)]
+impl<Inner: Read + AsRawHandle> AsRawHandle for TextReader<Inner> {
+    #[inline]
+    fn as_raw_handle(&self) -> RawHandle {
+        self.inner.as_raw_handle()
+    }
+}
 
-    // After a "\x1b[[".
-    Linux,
+#[cfg(windows)]
+impl<Inner: Read + AsHandle> AsHandle for TextReader<Inner> {
+    #[inline]
+    fn as_handle(&self) -> BorrowedHandle<'_> {
+        self.inner.as_handle()
+    }
 }
 
 #[cfg(test)]
@@ -367,11 +1775,42 @@ fn translate_with_small_buffer(bytes: &[u8]) -> String {
     String::from_utf8(v).unwrap()
 }
 
+#[cfg(test)]
+fn translate_with_tiny_buffer(bytes: &[u8], size: usize) -> String {
+    let mut reader = TextReader::new(crate::SliceReader::new(bytes));
+    let mut v = Vec::new();
+    let mut buf = vec![0; size];
+    loop {
+        let ReadOutcome { size, status } = reader.read_outcome(&mut buf).unwrap();
+        if buf.len() >= MAX_UTF8_SIZE {
+            str::from_utf8(&buf[..size]).unwrap();
+        }
+        v.extend_from_slice(&buf[..size]);
+        if status.is_end() {
+            break;
+        }
+    }
+    String::from_utf8(v).unwrap()
+}
+
+#[cfg(all(test, feature = "rayon"))]
+fn translate_in_parallel(bytes: &[u8]) -> String {
+    let mut reader = TextReader::new(crate::SliceReader::new(bytes));
+    let mut s = String::new();
+    reader.read_to_string_parallel(&mut s, 64, 1).unwrap();
+    s
+}
+
 #[cfg(test)]
 fn test(bytes: &[u8], s: &str) {
     assert_eq!(translate_via_std_reader(bytes), s);
     assert_eq!(translate_via_slice_reader(bytes), s);
+    #[cfg(feature = "rayon")]
+    assert_eq!(translate_in_parallel(bytes), s);
     assert_eq!(translate_with_small_buffer(bytes), s);
+    for size in 1..=MAX_UTF8_SIZE + 1 {
+        assert_eq!(translate_with_tiny_buffer(bytes, size), s);
+    }
 }
 
 #[test]
@@ -379,6 +1818,31 @@ fn test_empty_string() {
     test(b"", "");
 }
 
+#[test]
+fn test_long_line() {
+    let input = "a".repeat(1000) + "\n";
+    test(input.as_bytes(), &input);
+}
+
+#[test]
+fn test_ascii_runs() {
+    test(
+        b"plain text with\ttabs,\r\ncrlfs, \x1b[1mescapes\x1b[0m, e\xcc\x81 and \x07\n",
+        "plain text with\ttabs,\ncrlfs, escapes, \u{e9} and \u{fffd}\n",
+    );
+
+    // A forbidden character in the middle of a run is still caught.
+    let mut forbidden = ForbiddenCharacters::empty();
+    forbidden.insert('#');
+    let mut reader = TextReaderBuilder::new()
+        .forbidden_characters(forbidden)
+        .forbidden_character_policy(ForbiddenCharacterPolicy::Replace)
+        .build(crate::SliceReader::new(b"plain # text\n"));
+    let mut s = String::new();
+    reader.read_to_string(&mut s).unwrap();
+    assert_eq!(s, "plain \u{fffd} text\n");
+}
+
 #[test]
 fn test_nl() {
     test(b"\n", "\n");
@@ -394,6 +1858,38 @@ fn test_bom() {
     );
 }
 
+#[test]
+fn test_bom_policy() {
+    let translate = |policy, bytes: &[u8]| {
+        let mut reader = TextReaderBuilder::new()
+            .bom_policy(policy)
+            .build(crate::SliceReader::new(bytes));
+        let mut s = String::new();
+        reader
+            .read_to_string(&mut s)
+            .map(|_| (s, reader.stripped_boms()))
+    };
+    let input = "\u{feff}hello\n\u{feff}world\n".as_bytes();
+    assert_eq!(
+        translate(BomPolicy::StripAll, input).unwrap(),
+        ("hello\nworld\n".to_owned(), 2)
+    );
+    assert_eq!(
+        translate(BomPolicy::StripLeading, input).unwrap(),
+        ("hello\n\u{feff}world\n".to_owned(), 1)
+    );
+    assert_eq!(
+        translate(BomPolicy::Preserve, input).unwrap(),
+        ("\u{feff}hello\n\u{feff}world\n".to_owned(), 0)
+    );
+    assert_eq!(
+        translate(BomPolicy::RejectInterior, "\u{feff}hello\n".as_bytes()).unwrap(),
+        ("hello\n".to_owned(), 1)
+    );
+    let err = translate(BomPolicy::RejectInterior, input).unwrap_err();
+    assert_eq!(Error::from_io_error(&err), Some(&Error::InteriorBom));
+}
+
 #[test]
 fn test_crlf() {
     test(b"\r\n", "\n");
@@ -406,12 +1902,144 @@ fn test_cr_plain() {
     test(b"\rhello\rworld\r", "\u{fffd}hello\u{fffd}world\u{fffd}\n");
 }
 
+#[test]
+fn test_convert_cr() {
+    use crate::testing::{ScriptedReader, Step};
+
+    let translate = |steps: Vec<Step>| {
+        let mut reader = TextReaderBuilder::new()
+            .newline_policy(NewlinePolicy::ConvertCr)
+            .build(ScriptedReader::new(steps));
+        let mut s = String::new();
+        reader.read_to_string(&mut s).unwrap();
+        (s, reader.converted_crs(), reader.converted_crlfs())
+    };
+    assert_eq!(
+        translate(vec![Step::Data(b"\rhello\rworld\r".to_vec())]),
+        ("\nhello\nworld\n".to_owned(), 3, 0)
+    );
+    assert_eq!(
+        translate(vec![Step::Data(b"a\r\nb\r\rc".to_vec())]),
+        ("a\nb\n\nc\n".to_owned(), 2, 1)
+    );
+
+    // A "\r\n" split by a lull is still one newline.
+    assert_eq!(
+        translate(vec![
+            Step::Data(b"a\r".to_vec()),
+            Step::Lull,
+            Step::Data(b"\nb\n".to_vec()),
+        ]),
+        ("a\nb\n".to_owned(), 0, 1)
+    );
+}
+
+#[test]
+fn test_preserve_crlf() {
+    let mut reader = TextReaderBuilder::new()
+        .newline_policy(NewlinePolicy::PreserveCrlf)
+        .build(crate::SliceReader::new(b"a\r\nb\rc\x07\ne\xcc\x81\r\n"));
+    let mut s = String::new();
+    reader.read_to_string(&mut s).unwrap();
+    assert_eq!(s, "a\r\nb\u{fffd}c\u{fffd}\n\u{e9}\r\n");
+    assert_eq!(reader.converted_crlfs(), 0);
+}
+
+#[test]
+fn test_tab_policy() {
+    let translate = |policy, bytes: &[u8]| {
+        let mut reader = TextReaderBuilder::new()
+            .tab_policy(policy)
+            .build(crate::SliceReader::new(bytes));
+        let mut s = String::new();
+        reader.read_to_string(&mut s).map(|_| s)
+    };
+    let input = "\ta\tbc\u{301}\td\ne\t\tf\n".as_bytes();
+    assert_eq!(
+        translate(TabPolicy::Keep, input).unwrap(),
+        "\ta\tb\u{107}\td\ne\t\tf\n"
+    );
+    assert_eq!(
+        translate(TabPolicy::Expand(4), input).unwrap(),
+        "    a   b\u{107}  d\ne       f\n"
+    );
+    assert_eq!(
+        translate(TabPolicy::Replace, input).unwrap(),
+        "\u{fffd}a\u{fffd}b\u{107}\u{fffd}d\ne\u{fffd}\u{fffd}f\n"
+    );
+    let err = translate(TabPolicy::Reject, input).unwrap_err();
+    assert_eq!(
+        Error::from_io_error(&err),
+        Some(&Error::ControlCode { scalar: '\t' })
+    );
+}
+
+#[test]
+fn test_nul_policy() {
+    let translate = |policy, bytes: &[u8]| {
+        let mut reader = TextReaderBuilder::new()
+            .nul_policy(policy)
+            .build(crate::SliceReader::new(bytes));
+        let mut s = String::new();
+        reader.read_to_string(&mut s).map(|_| s)
+    };
+    let input = b"a\0b\x01c\n";
+    assert_eq!(
+        translate(NulPolicy::Replace, input).unwrap(),
+        "a\u{fffd}b\u{fffd}c\n"
+    );
+    assert_eq!(translate(NulPolicy::Strip, input).unwrap(), "ab\u{fffd}c\n");
+    let err = translate(NulPolicy::Reject, input).unwrap_err();
+    assert_eq!(
+        Error::from_io_error(&err),
+        Some(&Error::ControlCode { scalar: '\0' })
+    );
+
+    let mut reader = TextReaderBuilder::new()
+        .nul_policy(NulPolicy::Strip)
+        .build(crate::SliceReader::new(input));
+    let mut s = String::new();
+    reader.read_to_string(&mut s).unwrap();
+    assert_eq!(reader.stripped_characters(), 1);
+    assert_eq!(reader.replacements(), 1);
+}
+
 #[test]
 fn test_ff() {
     test(b"\x0c", " \n");
     test(b"\x0chello\x0cworld\x0c", " hello world \n");
 }
 
+#[test]
+fn test_form_feed_policy() {
+    let translate = |policy, bytes: &[u8]| {
+        let mut reader = TextReaderBuilder::new()
+            .form_feed_policy(policy)
+            .replacement("?")
+            .build(crate::SliceReader::new(bytes));
+        let mut s = String::new();
+        reader.read_to_string(&mut s).map(|_| s)
+    };
+    let input = b"page 1\n\x0cpage 2\n";
+    assert_eq!(
+        translate(FormFeedPolicy::Space, input).unwrap(),
+        "page 1\n page 2\n"
+    );
+    assert_eq!(
+        translate(FormFeedPolicy::Preserve, input).unwrap(),
+        "page 1\n\x0cpage 2\n"
+    );
+    assert_eq!(
+        translate(FormFeedPolicy::Replace, input).unwrap(),
+        "page 1\n?page 2\n"
+    );
+    let err = translate(FormFeedPolicy::Reject, input).unwrap_err();
+    assert_eq!(
+        Error::from_io_error(&err),
+        Some(&Error::ControlCode { scalar: '\x0c' })
+    );
+}
+
 #[test]
 fn test_del() {
     test(b"\x7f", "\u{fffd}\n");
@@ -496,6 +2124,32 @@ fn test_osc() {
         b"\x1b]mes\ns\tage\x07hello\x1b]mes\ns\tage\x07world\x1b]mes\ns\tage\x07",
         "helloworld\n",
     );
+    test(
+        b"\x1b]8;;http://example.com\x1b\\hello\x1b]8;;\x1b\\world",
+        "helloworld\n",
+    );
+}
+
+#[test]
+fn test_safe_escapes() {
+    fn test_safe(bytes: &[u8], s: &str) {
+        let mut reader = TextReader::with_safe_escapes(crate::SliceReader::new(bytes));
+        let mut result = String::new();
+        reader.read_to_string(&mut result).unwrap();
+        assert_eq!(result, s);
+    }
+
+    test_safe(b"\x1b[1;31mred\x1b[0m", "\x1b[1;31mred\x1b[0m\n");
+    test_safe(b"\x1b[38:5:208mhello\x1b[m", "\x1b[38:5:208mhello\x1b[m\n");
+    test_safe(
+        b"\x1b]8;;http://example.com\x1b\\link\x1b]8;;\x07",
+        "\x1b]8;;http://example.com\x1b\\link\x1b]8;;\x07\n",
+    );
+    test_safe(b"\x1b[2Jclear\x1b[H", "clear\n");
+    test_safe(b"\x1b]0;title\x07hello", "hello\n");
+    test_safe(b"\x1b[?25lhello", "hello\n");
+    test_safe(b"\x1b]8;;http://exa\tmple.com\x07hello", "hello\n");
+    test_safe(b"\x1b[1;31", "\n");
 }
 
 #[test]
@@ -504,5 +2158,630 @@ fn test_linux() {
     test(b"\x1b[[Ahello\x1b[[Aworld\x1b[[A", "helloworld\n");
 }
 
+#[test]
+fn test_seek() {
+    let mut reader = TextReader::new(crate::Cursor::new(b"hello\r\nworld"));
+    let mut s = String::new();
+    reader.read_to_string(&mut s).unwrap();
+    assert_eq!(s, "hello\nworld\n");
+    reader.seek(SeekFrom::Start(7)).unwrap();
+    s.clear();
+    reader.read_to_string(&mut s).unwrap();
+    assert_eq!(s, "world\n");
+}
+
+#[test]
+fn test_into_parts() {
+    use crate::testing::{ScriptedReader, Step};
+
+    let mut reader = TextReader::new(ScriptedReader::new(vec![
+        Step::Data(b"HELLO\r\n".to_vec()),
+        Step::Lull,
+        Step::Data(b"\x00\x01\x02".to_vec()),
+    ]));
+    let mut buf = [0; 4];
+    reader.read_exact(&mut buf).unwrap();
+    assert_eq!(&buf, b"HELL");
+    let (mut inner, data) = reader.into_parts();
+    assert_eq!(data, b"O\n");
+    let mut rest = Vec::new();
+    inner.read_to_end(&mut rest).unwrap();
+    assert_eq!(rest, b"\x00\x01\x02");
+}
+
 // TODO: Test Stream-Safe
 // TODO: test for nonstarter after lull
+
+#[test]
+fn test_substitution_hook() {
+    use std::sync::{Arc, Mutex};
+
+    let log = Arc::new(Mutex::new(Vec::new()));
+    let mut reader = TextReader::new(crate::SliceReader::new(
+        b"\xef\xbb\xbfa\xffb\x01c\x1b[2Jd\re\n",
+    ));
+    let hook_log = Arc::clone(&log);
+    reader.set_substitution_hook(move |sub| {
+        hook_log
+            .lock()
+            .unwrap()
+            .push((sub.kind, sub.original.to_vec(), sub.offset))
+    });
+    let mut s = String::new();
+    reader.read_to_string(&mut s).unwrap();
+    assert_eq!(s, "a\u{fffd}b\u{fffd}cd\u{fffd}e\n");
+    assert_eq!(
+        *log.lock().unwrap(),
+        vec![
+            (SubstitutionKind::InvalidUtf8, b"\xff".to_vec(), 4),
+            (SubstitutionKind::Bom, "\u{feff}".as_bytes().to_vec(), 0),
+            (SubstitutionKind::ControlCode, b"\x01".to_vec(), 6),
+            (SubstitutionKind::EscapeSequence, b"\x1b[2J".to_vec(), 8),
+            (SubstitutionKind::ControlCode, b"\r".to_vec(), 13),
+        ]
+    );
+}
+
+#[test]
+fn test_stats() {
+    let mut reader = TextReader::new(crate::SliceReader::new(
+        b"\xef\xbb\xbfa\xff\x01b\r\nc\x1b[2J\x1b[1md\n",
+    ));
+    let mut s = String::new();
+    reader.read_to_string(&mut s).unwrap();
+    assert_eq!(s, "a\u{fffd}\u{fffd}b\ncd\n");
+    assert_eq!(reader.replacements(), 2);
+    assert_eq!(reader.stripped_boms(), 1);
+    assert_eq!(reader.converted_crlfs(), 1);
+    assert_eq!(reader.dropped_escapes(), 2);
+}
+
+#[test]
+fn test_builder_replacement() {
+    let translate = |replacement: &str, bytes: &[u8]| {
+        let mut reader = TextReaderBuilder::new()
+            .replacement(replacement)
+            .build(crate::SliceReader::new(bytes));
+        let mut s = String::new();
+        reader.read_to_string(&mut s).unwrap();
+        s
+    };
+    assert_eq!(translate("?", b"a\xffb\x01c\rd\n"), "a?b?c?d\n");
+    assert_eq!(translate("", b"a\xffb\x01c\rd\n"), "abcd\n");
+    assert_eq!(translate("", "\u{301}\u{301}e\n".as_bytes()), "e\n");
+    assert_eq!(
+        translate("?", "\u{301}\u{301}e\n".as_bytes()),
+        "?\u{301}e\n"
+    );
+}
+
+#[test]
+fn test_non_starter_policy() {
+    use crate::testing::{ScriptedReader, Step};
+
+    let reader = |policy| {
+        TextReaderBuilder::new()
+            .non_starter_policy(policy)
+            .build(ScriptedReader::new(vec![
+                Step::Data(b"hi\ne".to_vec()),
+                Step::Lull,
+                Step::Data("\u{301}\n".as_bytes().to_vec()),
+                Step::Lull,
+                Step::Data("\u{301}\n".as_bytes().to_vec()),
+            ]))
+    };
+
+    let mut s = String::new();
+    reader(NonStarterPolicy::Replace)
+        .read_to_string(&mut s)
+        .unwrap();
+    assert_eq!(s, "hi\ne\u{fffd}\n\u{fffd}\n");
+
+    let mut s = String::new();
+    let err = reader(NonStarterPolicy::Reject)
+        .read_to_string(&mut s)
+        .unwrap_err();
+    assert_eq!(Error::from_io_error(&err), Some(&Error::NonStarter));
+
+    let mut reader = reader(NonStarterPolicy::Buffer);
+    let mut buf = [0; NORMALIZATION_BUFFER_SIZE];
+    let mut v = Vec::new();
+    loop {
+        let outcome = reader.read_outcome(&mut buf).unwrap();
+        v.extend_from_slice(&buf[..outcome.size]);
+        if outcome.status != Status::ready() {
+            assert_eq!(outcome.status, Status::Open(crate::Readiness::Lull));
+            break;
+        }
+    }
+    assert_eq!(v, b"hi\n");
+    let mut s = String::new();
+    reader.read_to_string(&mut s).unwrap();
+    assert_eq!(s, "\u{e9}\n\u{fffd}\n");
+}
+
+#[test]
+fn test_normalization_form() {
+    let translate = |form, bytes: &[u8]| {
+        let mut reader = TextReaderBuilder::new()
+            .normalization_form(form)
+            .build(crate::SliceReader::new(bytes));
+        let mut s = String::new();
+        reader.read_to_string(&mut s).unwrap();
+        s
+    };
+    let input = "\u{212b}\u{fb01}\n".as_bytes();
+    assert_eq!(translate(NormalizationForm::Nfc, input), "\u{c5}\u{fb01}\n");
+    assert_eq!(
+        translate(NormalizationForm::Nfd, input),
+        "A\u{30a}\u{fb01}\n"
+    );
+    assert_eq!(translate(NormalizationForm::Nfkc, input), "\u{c5}fi\n");
+    assert_eq!(translate(NormalizationForm::Nfkd, input), "A\u{30a}fi\n");
+}
+
+#[test]
+fn test_passthrough() {
+    let mut reader = TextReaderBuilder::new()
+        .normalization_form(NormalizationForm::Passthrough)
+        .build(crate::SliceReader::new(
+            "\u{212b}A\u{30a}\u{f951}\r\n\x1b[2J\x01".as_bytes(),
+        ));
+    let mut s = String::new();
+    reader.read_to_string(&mut s).unwrap();
+    assert_eq!(s, "\u{212b}A\u{30a}\u{fffd}\n\u{fffd}\n");
+}
+
+#[test]
+fn test_grapheme_atomic() {
+    use crate::testing::{ScriptedReader, Step};
+
+    let input = "x\u{1f468}\u{200d}\u{1f469}\u{200d}\u{1f467}\u{1f1fa}\u{1f1f8}".repeat(40) + "\n";
+    let steps = input
+        .as_bytes()
+        .chunks(7)
+        .map(|chunk| Step::Data(chunk.to_vec()))
+        .collect::<Vec<_>>();
+    let mut reader = TextReaderBuilder::new()
+        .grapheme_atomic(true)
+        .build(ScriptedReader::new(steps));
+    let boundaries = input
+        .grapheme_indices(true)
+        .map(|(i, _)| i)
+        .collect::<Vec<_>>();
+    let mut output = Vec::new();
+    let mut buf = [0; NORMALIZATION_BUFFER_SIZE];
+    loop {
+        let outcome = reader.read_outcome(&mut buf).unwrap();
+        output.extend_from_slice(&buf[..outcome.size]);
+        if outcome.status.is_end() {
+            break;
+        }
+        assert!(boundaries.contains(&output.len()));
+    }
+    assert_eq!(output, input.as_bytes());
+}
+
+#[test]
+fn test_grapheme_atomic_single_cluster() {
+    use crate::testing::{ScriptedReader, Step};
+
+    // The first read produces only a cluster which may still be extended,
+    // which mustn't be reported as an empty read.
+    let mut reader = TextReaderBuilder::new()
+        .grapheme_atomic(true)
+        .build(ScriptedReader::new(vec![
+            Step::Data(b"a".to_vec()),
+            Step::Data("\u{301}".as_bytes().to_vec()),
+            Step::Data(b"b\n".to_vec()),
+        ]));
+    let mut buf = [0; NORMALIZATION_BUFFER_SIZE];
+    let mut output = Vec::new();
+    loop {
+        let outcome = reader.read_outcome(&mut buf).unwrap();
+        assert!(outcome.size != 0 || outcome.status.is_end());
+        output.extend_from_slice(&buf[..outcome.size]);
+        if outcome.status.is_end() {
+            break;
+        }
+    }
+    assert_eq!(output, "\u{e1}b\n".as_bytes());
+}
+
+#[test]
+fn test_passthrough_small_reads() {
+    let input = "abcdefghij".repeat(100) + "\n";
+    let mut reader = TextReaderBuilder::new()
+        .normalization_form(NormalizationForm::Passthrough)
+        .build(crate::SliceReader::new(input.as_bytes()));
+    let mut buf = [0; NORMALIZATION_BUFFER_SIZE];
+    let mut s = String::new();
+    loop {
+        let outcome = reader.read_outcome(&mut buf).unwrap();
+        s.push_str(str::from_utf8(&buf[..outcome.size]).unwrap());
+        if outcome.status.is_end() {
+            break;
+        }
+    }
+    assert_eq!(s, input);
+}
+
+#[test]
+fn test_forbidden_characters() {
+    let translate = |builder: &TextReaderBuilder, bytes: &[u8]| {
+        let mut reader = builder.build(crate::SliceReader::new(bytes));
+        let mut s = String::new();
+        reader.read_to_string(&mut s).map(|_| s)
+    };
+    let input = "a\u{f951}b!\n".as_bytes();
+    assert_eq!(
+        translate(&TextReaderBuilder::new(), input).unwrap(),
+        "a\u{fffd}b!\n"
+    );
+    assert_eq!(
+        translate(
+            TextReaderBuilder::new().forbidden_characters(ForbiddenCharacters::empty()),
+            input
+        )
+        .unwrap(),
+        "a\u{964b}b!\n"
+    );
+    assert_eq!(
+        translate(TextReaderBuilder::new().forbid('!'), input).unwrap(),
+        "a\u{fffd}b\u{fffd}\n"
+    );
+    let err = translate(
+        TextReaderBuilder::new().forbidden_character_policy(ForbiddenCharacterPolicy::Reject),
+        input,
+    )
+    .unwrap_err();
+    assert_eq!(
+        Error::from_io_error(&err),
+        Some(&Error::ForbiddenCharacter { scalar: '\u{f951}' })
+    );
+}
+
+#[test]
+fn test_bidi_policy() {
+    let translate = |policy, bytes: &[u8]| {
+        let mut reader = TextReaderBuilder::new()
+            .bidi_policy(policy)
+            .build(crate::SliceReader::new(bytes));
+        let mut s = String::new();
+        reader.read_to_string(&mut s).map(|_| s)
+    };
+    let input = "a\u{202e}b\u{202c}c\u{200f}\n".as_bytes();
+    assert_eq!(
+        translate(BidiPolicy::Allow, input).unwrap(),
+        "a\u{202e}b\u{202c}c\u{200f}\n"
+    );
+    assert_eq!(
+        translate(BidiPolicy::Strip, input).unwrap(),
+        "abc\u{200f}\n"
+    );
+    assert_eq!(
+        translate(BidiPolicy::Replace, input).unwrap(),
+        "a\u{fffd}b\u{fffd}c\u{200f}\n"
+    );
+    assert_eq!(
+        translate(BidiPolicy::RequireBalanced, input).unwrap(),
+        "a\u{202e}b\u{202c}c\u{200f}\n"
+    );
+    let err = translate(BidiPolicy::Reject, input).unwrap_err();
+    assert_eq!(Error::from_io_error(&err), Some(&Error::BidiControl));
+
+    let mut reader = TextReaderBuilder::new()
+        .bidi_policy(BidiPolicy::Strip)
+        .build(crate::SliceReader::new(input));
+    let mut s = String::new();
+    reader.read_to_string(&mut s).unwrap();
+    assert_eq!(reader.stripped_characters(), 2);
+    assert_eq!(reader.replacements(), 0);
+
+    // An override left open at the end of a line, with either line ending,
+    // or at the end of the stream.
+    for input in ["a\u{202e}b\nc\n", "a\u{202e}b\r\nc\n", "a\u{2067}b"] {
+        let err = translate(BidiPolicy::RequireBalanced, input.as_bytes()).unwrap_err();
+        assert_eq!(Error::from_io_error(&err), Some(&Error::BidiControl));
+    }
+}
+
+#[test]
+fn test_invisible_policy() {
+    let translate = |policy, bytes: &[u8]| {
+        let mut reader = TextReaderBuilder::new()
+            .invisible_policy(policy)
+            .build(crate::SliceReader::new(bytes));
+        let mut s = String::new();
+        reader.read_to_string(&mut s).map(|_| s)
+    };
+    let input = "a\u{200b}b\u{ad}c\n".as_bytes();
+    assert_eq!(
+        translate(InvisiblePolicy::Keep, input).unwrap(),
+        "a\u{200b}b\u{ad}c\n"
+    );
+    assert_eq!(translate(InvisiblePolicy::Strip, input).unwrap(), "abc\n");
+    assert_eq!(
+        translate(InvisiblePolicy::Replace, input).unwrap(),
+        "a\u{fffd}b\u{fffd}c\n"
+    );
+    let err = translate(InvisiblePolicy::Reject, input).unwrap_err();
+    assert_eq!(
+        Error::from_io_error(&err),
+        Some(&Error::InvisibleCharacter { scalar: '\u{200b}' })
+    );
+
+    let mut reader = TextReaderBuilder::new()
+        .invisible_policy(InvisiblePolicy::Strip)
+        .build(crate::SliceReader::new(input));
+    let mut s = String::new();
+    reader.read_to_string(&mut s).unwrap();
+    assert_eq!(reader.stripped_characters(), 2);
+    assert_eq!(reader.replacements(), 0);
+}
+
+#[test]
+fn test_line_terminator_policy() {
+    let input = "a\u{85}b\u{2028}c\u{2029}d".as_bytes();
+    let mut reader = TextReaderBuilder::new().build(crate::SliceReader::new(input));
+    let mut s = String::new();
+    reader.read_to_string(&mut s).unwrap();
+    assert_eq!(s, "a\u{fffd}b\u{2028}c\u{2029}d\n");
+
+    let mut reader = TextReaderBuilder::new()
+        .line_terminator_policy(LineTerminatorPolicy::Normalize)
+        .build(crate::SliceReader::new(input));
+    let mut s = String::new();
+    reader.read_to_string(&mut s).unwrap();
+    assert_eq!(s, "a\nb\nc\nd\n");
+    assert_eq!(reader.converted_line_terminators(), 3);
+
+    let mut reader = TextReaderBuilder::new()
+        .line_terminator_policy(LineTerminatorPolicy::Reject)
+        .build(crate::SliceReader::new(input));
+    let err = reader.read_to_string(&mut String::new()).unwrap_err();
+    assert_eq!(
+        Error::from_io_error(&err),
+        Some(&Error::LineTerminator { scalar: '\u{85}' })
+    );
+}
+
+#[test]
+fn test_problem_sequences() {
+    use crate::testing::{ScriptedReader, Step};
+
+    let sequences = [
+        ('\u{9c7}', '\u{9be}'),
+        ('\u{9c7}', '\u{9d7}'),
+        ('\u{b47}', '\u{b3e}'),
+        ('\u{b47}', '\u{b56}'),
+        ('\u{b47}', '\u{b57}'),
+        ('\u{bc6}', '\u{bbe}'),
+        ('\u{bc6}', '\u{bd7}'),
+        ('\u{bc7}', '\u{bbe}'),
+        ('\u{b92}', '\u{bd7}'),
+        ('\u{cc6}', '\u{cc2}'),
+        ('\u{cc6}', '\u{cd5}'),
+        ('\u{cc6}', '\u{cd6}'),
+        ('\u{cbf}', '\u{cd5}'),
+        ('\u{cca}', '\u{cd5}'),
+        ('\u{d47}', '\u{d3e}'),
+        ('\u{d46}', '\u{d3e}'),
+        ('\u{d46}', '\u{d57}'),
+        ('\u{1025}', '\u{102e}'),
+        ('\u{dd9}', '\u{dcf}'),
+        ('\u{dd9}', '\u{ddf}'),
+        ('\u{1100}', '\u{1161}'),
+        ('\u{1112}', '\u{1175}'),
+        ('\u{ac00}', '\u{11a8}'),
+        ('\u{d788}', '\u{11c2}'),
+    ];
+    for (first, last) in sequences.iter() {
+        // The intervening non-starter blocks composition, so the sequence
+        // is already in NFC. Deliver it one byte at a time, so that it
+        // straddles reads.
+        let input = format!("x{}\u{334}{}y\n", first, last);
+        let steps = input
+            .as_bytes()
+            .iter()
+            .map(|b| Step::Data(vec![*b]))
+            .collect::<Vec<_>>();
+        let mut reader = TextReader::new(ScriptedReader::new(steps));
+        let mut s = String::new();
+        reader.read_to_string(&mut s).unwrap();
+        assert_eq!(s, input);
+        assert_eq!(reader.problem_sequences(), 1, "{:?}", input);
+    }
+
+    // Without an intervening non-starter, these compose, and there's no
+    // problem sequence.
+    let mut reader = TextReader::new(crate::SliceReader::new("\u{9c7}\u{9be}\n".as_bytes()));
+    let mut s = String::new();
+    reader.read_to_string(&mut s).unwrap();
+    assert_eq!(s, "\u{9cb}\n");
+    assert_eq!(reader.problem_sequences(), 0);
+
+    // In NFD, the Hangul LV syllable is decomposed.
+    let mut reader = TextReaderBuilder::new()
+        .normalization_form(NormalizationForm::Nfd)
+        .build(crate::SliceReader::new(
+            "\u{ac00}\u{334}\u{11a8}\n".as_bytes(),
+        ));
+    let mut s = String::new();
+    reader.read_to_string(&mut s).unwrap();
+    assert_eq!(s, "\u{1100}\u{1161}\u{334}\u{11a8}\n");
+    assert_eq!(reader.problem_sequences(), 1);
+}
+
+#[cfg(feature = "security")]
+#[test]
+fn test_restriction_level() {
+    let translate = |policy, bytes: &[u8]| {
+        let mut reader = TextReaderBuilder::new()
+            .restriction_level(RestrictionLevel::HighlyRestrictive, policy)
+            .build(crate::SliceReader::new(bytes));
+        let mut s = String::new();
+        reader
+            .read_to_string(&mut s)
+            .map(|_| (s, reader.restriction_violations()))
+    };
+    let input = "login p\u{430}ypal paypal\n".as_bytes();
+    assert_eq!(
+        translate(RestrictionPolicy::Flag, input).unwrap(),
+        ("login p\u{430}ypal paypal\n".to_owned(), 1)
+    );
+    let err = translate(RestrictionPolicy::Reject, input).unwrap_err();
+    assert_eq!(
+        Error::from_io_error(&err),
+        Some(&Error::RestrictionLevelExceeded)
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn test_nonblocking_partial_sequences() -> io::Result<()> {
+    use crate::{Readiness, StdUnixStream};
+    use std::{io::Write as _, os::unix::net::UnixStream};
+
+    let (mut client, server) = UnixStream::pair()?;
+    server.set_nonblocking(true)?;
+    let mut reader = TextReader::new(StdUnixStream::new(server));
+    let mut buf = [0_u8; NORMALIZATION_BUFFER_SIZE];
+    let mut output = String::new();
+    for chunk in [&b"a\r"[..], b"\nb\x1b[", b"1mce", b"\xcc\x81\n"] {
+        client.write_all(chunk)?;
+        loop {
+            let outcome = reader.read_outcome(&mut buf)?;
+            output.push_str(str::from_utf8(&buf[..outcome.size]).unwrap());
+            if outcome.status == Status::Open(Readiness::Pending) {
+                break;
+            }
+        }
+    }
+    assert_eq!(output, "a\nbc\u{e9}\n");
+    assert_eq!(reader.converted_crlfs(), 1);
+    assert_eq!(reader.replacements(), 0);
+    Ok(())
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn test_parallel_read_to_string() {
+    use crate::testing::{ScriptedReader, Step};
+
+    let mut input = Vec::new();
+    for i in 0..2000 {
+        input.extend_from_slice(format!("line {}\r\n", i).as_bytes());
+        input.extend_from_slice(match i % 7 {
+            0 => &b"e\xcc\x81\tx\n"[..],
+            1 => b"\x1b]0;title\nstill title\x07after\n",
+            2 => b"\xcc\x81 combining\n",
+            3 => b"\x1b[1mbold\x1b[0m\n\xef\xbb\xbf\n",
+            4 => b"\xff invalid\n\x01 control\n",
+            5 => b"\x1b[\nbroken\r",
+            _ => b"\xe2\x80\xa8separator\n",
+        });
+    }
+
+    let builders = [
+        TextReaderBuilder::new(),
+        TextReaderBuilder::new()
+            .tab_policy(TabPolicy::Expand(8))
+            .newline_policy(NewlinePolicy::ConvertCr)
+            .clone(),
+        TextReaderBuilder::new()
+            .safe_escapes(true)
+            .grapheme_atomic(true)
+            .normalization_form(NormalizationForm::Nfd)
+            .clone(),
+    ];
+    for builder in &builders {
+        let mut serial = builder.build(crate::SliceReader::new(&input));
+        let mut expected = String::new();
+        crate::default_read_to_string(&mut serial, &mut expected).unwrap();
+
+        // Interleave lulls into the input, since they affect the
+        // translation.
+        let steps = input
+            .chunks(997)
+            .flat_map(|chunk| [Step::Data(chunk.to_vec()), Step::Lull]);
+        let mut serial_with_lulls = builder.build(ScriptedReader::new(steps.clone()));
+        let mut expected_with_lulls = String::new();
+        crate::default_read_to_string(&mut serial_with_lulls, &mut expected_with_lulls).unwrap();
+
+        for min_chunk in [1, 100, 10000, MIN_PARALLEL_CHUNK] {
+            let mut reader = builder.build(crate::SliceReader::new(&input));
+            let mut output = String::new();
+            reader
+                .read_to_string_parallel(&mut output, 64, min_chunk)
+                .unwrap();
+            assert_eq!(output, expected);
+            assert_eq!(output, expected);
+            assert_eq!(reader.replacements(), serial.replacements());
+            assert_eq!(reader.converted_crlfs(), serial.converted_crlfs());
+            assert_eq!(reader.converted_crs(), serial.converted_crs());
+            assert_eq!(reader.dropped_escapes(), serial.dropped_escapes());
+            assert_eq!(reader.stripped_boms(), serial.stripped_boms());
+            assert_eq!(
+                reader.converted_line_terminators(),
+                serial.converted_line_terminators()
+            );
+
+            let mut reader = builder.build(ScriptedReader::new(steps.clone()));
+            let mut output = String::new();
+            reader
+                .read_to_string_parallel(&mut output, 64, min_chunk)
+                .unwrap();
+            assert_eq!(output, expected_with_lulls);
+            assert_eq!(reader.replacements(), serial_with_lulls.replacements());
+        }
+    }
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn test_parallel_read_to_string_pending() {
+    use crate::testing::{ScriptedReader, Step};
+
+    let steps = vec![
+        Step::Data(b"first\r\nline\n".to_vec()),
+        Step::Lull,
+        Step::Data(b"e\xcc\x81\x1b[1m".to_vec()),
+        Step::Pending,
+        Step::Data(b"bold\x1b[0m\xcc\x81\nlast".to_vec()),
+    ];
+    let mut serial = TextReader::new(ScriptedReader::new(steps.clone()));
+    let mut expected = String::new();
+    loop {
+        match crate::default_read_to_string(&mut serial, &mut expected) {
+            Ok(_) => break,
+            Err(err) => assert_eq!(err.kind(), io::ErrorKind::WouldBlock),
+        }
+    }
+
+    let mut reader = TextReader::new(ScriptedReader::new(steps));
+    let mut output = String::new();
+    let err = reader
+        .read_to_string_parallel(&mut output, 64, 1)
+        .unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::WouldBlock);
+    assert_eq!(output, "first\nline\n");
+    reader.read_to_string_parallel(&mut output, 64, 1).unwrap();
+    assert_eq!(output, expected);
+    assert_eq!(reader.converted_crlfs(), serial.converted_crlfs());
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn test_parallel_read_to_string_error() {
+    let input = "ok\n".repeat(1000) + "\u{202e}unbalanced\n" + &"ok\n".repeat(1000);
+    let mut reader = TextReaderBuilder::new()
+        .bidi_policy(BidiPolicy::RequireBalanced)
+        .build(crate::SliceReader::new(input.as_bytes()));
+    let mut output = String::new();
+    let err = reader
+        .read_to_string_parallel(&mut output, 64, 16)
+        .unwrap_err();
+    assert_eq!(Error::from_io_error(&err), Some(&Error::BidiControl));
+    assert!(output.is_empty());
+}
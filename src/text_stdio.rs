@@ -0,0 +1,38 @@
+use crate::{StdReader, StdWriter, TextReader, TextWriter};
+use std::io::{self, StdinLock, StdoutLock};
+
+/// A `TextReader` reading from the locked standard input.
+pub type TextStdin = TextReader<StdReader<StdinLock<'static>>>;
+
+/// A `TextWriter` writing to the locked standard output.
+pub type TextStdout = TextWriter<StdWriter<StdoutLock<'static>>>;
+
+/// Lock the standard input and return a `TextReader` reading from it, using
+/// `StdReader::new` so that input from a terminal is read line by line.
+///
+/// The lock is held for as long as the returned stream lives, so this is
+/// meant to be called once, such as at the beginning of `main`, with the
+/// result used for the rest of the program.
+#[inline]
+pub fn text_stdin() -> TextStdin {
+    TextReader::new(StdReader::new(io::stdin().lock()))
+}
+
+/// Lock the standard output and return a `TextWriter` writing to it.
+///
+/// The lock is held for as long as the returned stream lives, so this is
+/// meant to be called once, such as at the beginning of `main`, with the
+/// result used for the rest of the program. As with any `TextWriter`, the
+/// stream should be closed with `close_into_inner` when the program is done
+/// writing.
+#[inline]
+pub fn text_stdout() -> TextStdout {
+    TextWriter::new(StdWriter::new(io::stdout().lock()))
+}
+
+#[test]
+fn test_text_stdio() {
+    let _stdin = text_stdin();
+    let mut stdout = text_stdout();
+    crate::Write::abandon(&mut stdout);
+}
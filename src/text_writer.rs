@@ -1,9 +1,27 @@
 use crate::{
-    unicode::{is_normalization_form_starter, BOM, MAX_UTF8_SIZE},
-    Readiness, Status, Utf8Writer, Write,
+    bidi::{is_bidi_control, BidiBalance},
+    invisible::is_invisible,
+    line_terminator_policy::is_unicode_line_terminator,
+    normalization_form::Normalize,
+    tab_policy::Columns,
+    unicode::{is_normalization_form_starter, is_valid_replacement, BOM, FF, MAX_UTF8_SIZE, REPL},
+    write::write_vectored_utf8,
+    BidiPolicy, Close, Error, ForbiddenCharacterPolicy, ForbiddenCharacters, FormFeedPolicy,
+    InvisiblePolicy, LineTerminatorPolicy, NonStarterPolicy, NormalizationForm, NulPolicy,
+    Readiness, Status, TabPolicy, Utf8Writer, Write,
+};
+#[cfg(feature = "security")]
+use crate::{restriction::RestrictionChecker, RestrictionLevel, RestrictionPolicy};
+#[cfg(unix)]
+use std::os::unix::io::{AsFd, AsRawFd, BorrowedFd, RawFd};
+#[cfg(windows)]
+use std::os::windows::io::{AsHandle, AsRawHandle, BorrowedHandle, RawHandle};
+use std::{
+    borrow::Cow,
+    fmt,
+    io::{self, IoSlice},
+    mem, str, thread,
 };
-use std::{io, str};
-use unicode_normalization::UnicodeNormalization;
 
 /// A `Write` implementation which translates to an output `Write` producing
 /// a valid plain text stream from an arbitrary byte sequence.
@@ -11,62 +29,137 @@ use unicode_normalization::UnicodeNormalization;
 /// An output text stream enforces the following restrictions:
 ///  - Data must be valid UTF-8.
 ///  - U+FEFF (BOM) scalar values must not be present.
-///  - A '\n' is required at the end of the stream.
-///  - Control codes other than '\n' and '\t' most not be present.
+///  - A '\n' is required at the end of the stream, unless
+///    `TextWriterBuilder::append_newline` is enabled, in which case one is
+///    appended.
+///  - Control codes other than '\n' and '\t' most not be present, and
+///    '\t' may be disallowed with `TextWriterBuilder::tab_policy`. U+0000
+///    (NUL) may be replaced or stripped instead, with
+///    `TextWriterBuilder::nul_policy`, and U+000C (FF) may be permitted or
+///    replaced, with `TextWriterBuilder::form_feed_policy`.
+///  - [`ForbiddenCharacters`] must not be present, unless configured to be
+///    replaced with `TextWriterBuilder::forbidden_character_policy`.
+///  - Bidirectional controls are permitted, unless configured otherwise with
+///    `TextWriterBuilder::bidi_policy`.
+///  - Invisible formatting characters are permitted, unless configured
+///    otherwise with `TextWriterBuilder::invisible_policy`.
+///  - U+0085 (NEL) must not be present, as a control code, and U+2028 (LS)
+///    and U+2029 (PS) are permitted, unless configured otherwise with
+///    `TextWriterBuilder::line_terminator_policy`.
+///  - Data written at the beginning of the stream or after a lull must not
+///    begin with a normalization-form non-starter, unless configured
+///    otherwise with `TextWriterBuilder::non_starter_policy`.
 ///
 /// An output text stream implicitly applies the following transformations:
-///  - Text is transformed to Normalization Form C (NFC).
-///  - The Stream-Safe Text Process (UAX15-D4) is applied.
+///  - Text is transformed to Normalization Form C (NFC), or the form
+///    configured with `TextWriterBuilder::normalization_form`.
+///  - The Stream-Safe Text Process (UAX15-D4) is applied, unless
+///    normalization is disabled with `NormalizationForm::Passthrough`.
+///  - Optionally, '\t' is expanded to spaces.
 ///  - Optionally, "\n" is translated to "\r\n".
 ///
+/// NFC is not closed under concatenation, so to keep the output normalized
+/// when a write begins with a non-starter, the last normalization segment
+/// of each write is held back until the next write, or until a flush with a
+/// status other than `Readiness::Ready`.
+///
 /// `write` is not guaranteed to perform a single operation, because short
 /// writes could produce invalid UTF-8, so `write` will retry as needed.
 pub struct TextWriter<Inner: Write> {
-    /// The wrapped byte stream.
-    inner: Utf8Writer<Inner>,
+    /// The wrapped byte stream, or `None` once it has been taken by
+    /// `close_into_inner` or `abandon_into_inner`.
+    inner: Option<Utf8Writer<Inner>>,
 
     /// Temporary staging buffer.
     buffer: String,
 
     /// True if the last byte written was a '\n'.
-    nl: NlGuard,
+    nl: bool,
+
+    /// What to do if this writer is dropped without a trailing newline.
+    drop_policy: DropPolicy,
 
     /// When enabled, "\n" is replaced by "\r\n".
     crlf_compatibility: bool,
 
+    /// The Normalization Form to transform to.
+    normalization_form: NormalizationForm,
+
+    /// Scalar values which aren't permitted.
+    forbidden: ForbiddenCharacters,
+
+    /// What to do with scalar values in `forbidden`.
+    forbidden_policy: ForbiddenCharacterPolicy,
+
+    /// What to do with bidirectional controls.
+    bidi_policy: BidiPolicy,
+
+    /// With `BidiPolicy::RequireBalanced`, the nesting of bidirectional
+    /// controls in the current line.
+    bidi_balance: BidiBalance,
+
+    /// What to do with invisible formatting characters.
+    invisible_policy: InvisiblePolicy,
+
+    /// What to do with U+0085, U+2028, and U+2029.
+    line_terminator_policy: LineTerminatorPolicy,
+
+    /// What to do with '\t'.
+    tab_policy: TabPolicy,
+
+    /// What to do with U+0000.
+    nul_policy: NulPolicy,
+
+    /// What to do with U+000C.
+    form_feed_policy: FormFeedPolicy,
+
+    /// The string which disallowed scalar values are replaced with.
+    replacement: String,
+
+    /// With `TabPolicy::Expand`, the output column.
+    columns: Columns,
+
+    /// Checks words against a UTS #39 restriction level.
+    #[cfg(feature = "security")]
+    restriction: Option<(RestrictionChecker, RestrictionPolicy)>,
+
+    /// The number of words written which exceed the restriction level.
+    #[cfg(feature = "security")]
+    restriction_violations: u64,
+
     /// At the beginning of a stream or after a lull, expect a
     /// normalization-form starter.
     expect_starter: bool,
+
+    /// What to do with a non-starter when `expect_starter` is set.
+    non_starter_policy: NonStarterPolicy,
+
+    /// The last normalization segment written, held back until the next
+    /// write or flush.
+    held: String,
+
+    /// When enabled, a missing final '\n' is appended at the end.
+    append_newline: bool,
+
+    /// True if any data has been written.
+    started: bool,
 }
 
 impl<Inner: Write> TextWriter<Inner> {
     /// Construct a new instance of `TextWriter` wrapping `inner`.
     #[inline]
     pub fn new(inner: Inner) -> Self {
-        Self {
-            inner: Utf8Writer::new(inner),
-            buffer: String::new(),
-            nl: NlGuard(false),
-            crlf_compatibility: false,
-            expect_starter: true,
-        }
+        Self::from_builder(inner, &TextWriterBuilder::new())
     }
 
     /// Like `new`, but writes a U+FEFF (BOM) to the beginning of the output
     /// stream for compatibility with consumers that require that to determine
     /// the text encoding.
     #[inline]
-    pub fn with_bom_compatibility(mut inner: Inner) -> io::Result<Self> {
-        let mut bom_bytes = [0_u8; MAX_UTF8_SIZE];
-        let bom_len = BOM.encode_utf8(&mut bom_bytes).len();
-        inner.write(&bom_bytes[..bom_len])?;
-        Ok(Self {
-            inner: Utf8Writer::new(inner),
-            buffer: String::new(),
-            nl: NlGuard(false),
-            crlf_compatibility: false,
-            expect_starter: true,
-        })
+    pub fn with_bom_compatibility(inner: Inner) -> io::Result<Self> {
+        TextWriterBuilder::new()
+            .bom_compatibility(true)
+            .build(inner)
     }
 
     /// Like `new`, but enables CRLF output mode, which translates "\n" to
@@ -81,79 +174,204 @@ impl<Inner: Write> TextWriter<Inner> {
     /// [RFC-5198]: https://tools.ietf.org/html/rfc5198#appendix-C
     #[inline]
     pub fn with_crlf_compatibility(inner: Inner) -> Self {
+        Self::from_builder(inner, TextWriterBuilder::new().crlf_compatibility(true))
+    }
+
+    fn from_builder(inner: Inner, builder: &TextWriterBuilder) -> Self {
         Self {
-            inner: Utf8Writer::new(inner),
+            inner: Some(Utf8Writer::new(inner)),
             buffer: String::new(),
-            nl: NlGuard(false),
-            crlf_compatibility: true,
+            nl: false,
+            drop_policy: builder.drop_policy,
+            crlf_compatibility: builder.crlf_compatibility,
+            normalization_form: builder.normalization_form,
+            forbidden: builder.forbidden.clone(),
+            forbidden_policy: builder.forbidden_policy,
+            bidi_policy: builder.bidi_policy,
+            bidi_balance: BidiBalance::default(),
+            invisible_policy: builder.invisible_policy,
+            line_terminator_policy: builder.line_terminator_policy,
+            tab_policy: builder.tab_policy,
+            nul_policy: builder.nul_policy,
+            form_feed_policy: builder.form_feed_policy,
+            replacement: builder.replacement.clone(),
+            columns: Columns::default(),
+            #[cfg(feature = "security")]
+            restriction: builder
+                .restriction
+                .map(|(level, policy)| (RestrictionChecker::new(level), policy)),
+            #[cfg(feature = "security")]
+            restriction_violations: 0,
             expect_starter: true,
+            non_starter_policy: builder.non_starter_policy,
+            held: String::new(),
+            append_newline: builder.append_newline,
+            started: false,
         }
     }
 
+    /// Gets a reference to the underlying writer.
+    #[inline]
+    pub fn get_ref(&self) -> &Inner {
+        self.inner.as_ref().unwrap().get_ref()
+    }
+
+    /// Gets a mutable reference to the underlying writer.
+    ///
+    /// It is inadvisable to directly write to the underlying writer, as
+    /// that bypasses the validation this writer performs.
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut Inner {
+        self.inner.as_mut().unwrap().get_mut()
+    }
+
+    /// Return the number of words written so far which exceed the
+    /// restriction level configured with `TextWriterBuilder::restriction_level`.
+    #[cfg(feature = "security")]
+    #[inline]
+    pub fn restriction_violations(&self) -> u64 {
+        self.restriction_violations
+    }
+
     /// Flush and close the underlying stream and return the underlying
     /// stream object.
     pub fn close_into_inner(mut self) -> io::Result<Inner> {
         self.check_nl(Status::End)?;
-        self.inner.close_into_inner()
+        self.into_utf8_writer().close_into_inner()
     }
 
     /// Discard and close the underlying stream and return the underlying
     /// stream object.
     pub fn abandon_into_inner(mut self) -> io::Result<Inner> {
         self.abandon();
-        self.inner.close_into_inner()
+        self.into_utf8_writer().close_into_inner()
+    }
+
+    /// Take the `Utf8Writer` out of this `TextWriter`, so that its `Drop`
+    /// implementation does nothing.
+    fn into_utf8_writer(mut self) -> Utf8Writer<Inner> {
+        self.inner.take().unwrap()
+    }
+
+    /// Flush the output with a lull, without requiring it to end with a
+    /// newline, so that an interactive prompt is displayed before input is
+    /// read.
+    pub(crate) fn flush_prompt(&mut self) -> io::Result<()> {
+        self.write_held()?;
+        self.inner
+            .as_mut()
+            .unwrap()
+            .flush(Status::Open(Readiness::Lull))
     }
 
     fn normal_write_all_utf8(&mut self, s: &str) -> io::Result<()> {
-        self.buffer.extend(s.chars().stream_safe().nfc());
+        let held = mem::take(&mut self.held);
+        self.buffer.extend(Normalize::new(
+            held.chars().chain(s.chars()),
+            self.normalization_form,
+        ));
+        self.hold_last_segment();
 
         // Write to the underlying stream.
         self.write_buffer()
     }
 
     fn crlf_write_all_utf8(&mut self, s: &str) -> io::Result<()> {
+        // Reject '\r' in the input here, because the translated output
+        // contains '\r's that `write_buffer` must accept.
+        if s.contains('\r') {
+            self.abandon();
+            return Err(Error::InvalidScalarValue.into());
+        }
+
         // Translate "\n" into "\r\n".
-        let mut first = true;
-        for slice in s.split('\n') {
-            if first {
-                first = false;
+        let held = mem::take(&mut self.held);
+        for (i, slice) in s.split('\n').enumerate() {
+            if i == 0 {
+                self.buffer.extend(Normalize::new(
+                    held.chars().chain(slice.chars()),
+                    self.normalization_form,
+                ));
             } else {
                 self.buffer.push_str("\r\n");
+                self.buffer
+                    .extend(Normalize::new(slice.chars(), self.normalization_form));
             }
-            self.buffer.extend(slice.chars().stream_safe().nfc());
         }
+        self.hold_last_segment();
 
         // Write to the underlying stream.
         self.write_buffer()
     }
 
+    /// Move the last normalization segment in `self.buffer` into
+    /// `self.held`, unless it's a control code, which nothing can combine
+    /// with.
+    fn hold_last_segment(&mut self) {
+        if self.normalization_form == NormalizationForm::Passthrough {
+            return;
+        }
+        if let Some((index, c)) = self
+            .buffer
+            .char_indices()
+            .rev()
+            .find(|(_, c)| is_normalization_form_starter(*c))
+        {
+            if !c.is_control() {
+                self.held = self.buffer.split_off(index);
+            }
+        }
+    }
+
+    /// Write out the segment held back by `hold_last_segment`, if any.
+    fn write_held(&mut self) -> io::Result<()> {
+        if self.held.is_empty() {
+            return Ok(());
+        }
+        self.buffer = mem::take(&mut self.held);
+        self.write_buffer()
+    }
+
     fn write_buffer(&mut self) -> io::Result<()> {
-        if self.expect_starter {
-            self.expect_starter = false;
-            if let Some(c) = self.buffer.chars().next() {
-                if !is_normalization_form_starter(c) {
-                    self.abandon();
-                    return Err(io::Error::new(
-                        io::ErrorKind::Other,
-                        "write data must begin with a Unicode Normalization Form starter",
-                    ));
-                }
+        // With an empty replacement, a non-starter is deleted, and the next
+        // scalar value must be a starter in its place.
+        while self.expect_starter {
+            let c = match self.buffer.chars().next() {
+                Some(c) => c,
+                None => break,
+            };
+            if is_normalization_form_starter(c) {
+                self.expect_starter = false;
+                break;
             }
+            if self.non_starter_policy == NonStarterPolicy::Reject {
+                self.abandon();
+                return Err(Error::NonStarter.into());
+            }
+            self.buffer.replace_range(..c.len_utf8(), &self.replacement);
+            self.expect_starter = self.replacement.is_empty();
         }
 
-        if self
-            .buffer
-            .chars()
-            .any(|c| (c.is_control() && c != '\n' && c != '\t') || c == BOM)
-        {
+        if self.buffer.chars().any(|c| {
+            (c.is_control()
+                && c != '\n'
+                && c != '\t'
+                && !(c == '\r' && self.crlf_compatibility)
+                && !(c == FF && self.form_feed_policy == FormFeedPolicy::Preserve))
+                || c == BOM
+        }) {
             self.abandon();
-            return Err(io::Error::new(
-                io::ErrorKind::Other,
-                "invalid Unicode scalar value written to text stream",
-            ));
+            return Err(Error::InvalidScalarValue.into());
+        }
+
+        if let TabPolicy::Expand(stop) = self.tab_policy {
+            self.expand_tabs(stop);
         }
 
-        match self.inner.write_all_utf8(&self.buffer) {
+        #[cfg(feature = "security")]
+        self.check_restriction()?;
+
+        match self.inner.as_mut().unwrap().write_all_utf8(&self.buffer) {
             Ok(()) => (),
             Err(e) => {
                 self.abandon();
@@ -162,7 +380,12 @@ impl<Inner: Write> TextWriter<Inner> {
         }
 
         if let Some(last) = self.buffer.as_bytes().last() {
-            self.nl.0 = *last == b'\n';
+            self.nl = *last == b'\n';
+            self.started = true;
+        }
+        if !self.held.is_empty() {
+            self.nl = false;
+            self.started = true;
         }
 
         // Reset the temporary buffer.
@@ -171,32 +394,409 @@ impl<Inner: Write> TextWriter<Inner> {
         Ok(())
     }
 
+    /// Check the words in `self.buffer` against the restriction level.
+    #[cfg(feature = "security")]
+    fn check_restriction(&mut self) -> io::Result<()> {
+        if let Some((checker, policy)) = &mut self.restriction {
+            let violations = self.buffer.chars().filter(|c| checker.step(*c)).count();
+            if violations != 0 {
+                self.restriction_violations += violations as u64;
+                if *policy == RestrictionPolicy::Reject {
+                    self.abandon();
+                    return Err(Error::RestrictionLevelExceeded.into());
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Apply the `ForbiddenCharacterPolicy`, `LineTerminatorPolicy`,
+    /// `BidiPolicy`, `InvisiblePolicy`, `TabPolicy`, `NulPolicy`, and
+    /// `FormFeedPolicy` to `s`, except for tab expansion, which
+    /// `write_buffer` does.
+    fn apply_policies<'a>(&mut self, s: &'a str) -> io::Result<Cow<'a, str>> {
+        let mut s = Cow::Borrowed(s);
+
+        if let Some(scalar) = s.chars().find(|c| self.forbidden.contains(*c)) {
+            if self.forbidden_policy == ForbiddenCharacterPolicy::Reject {
+                return Err(Error::ForbiddenCharacter { scalar }.into());
+            }
+            s = Cow::Owned(s.replace(|c| self.forbidden.contains(c), &self.replacement));
+        }
+
+        if self.line_terminator_policy != LineTerminatorPolicy::Keep {
+            if let Some(scalar) = s.chars().find(|c| is_unicode_line_terminator(*c)) {
+                if self.line_terminator_policy == LineTerminatorPolicy::Reject {
+                    return Err(Error::LineTerminator { scalar }.into());
+                }
+                s = Cow::Owned(
+                    s.chars()
+                        .map(|c| {
+                            if is_unicode_line_terminator(c) {
+                                '\n'
+                            } else {
+                                c
+                            }
+                        })
+                        .collect(),
+                );
+            }
+        }
+
+        match self.bidi_policy {
+            BidiPolicy::Allow => {}
+            BidiPolicy::RequireBalanced => {
+                if !s.chars().all(|c| self.bidi_balance.step(c)) {
+                    return Err(Error::BidiControl.into());
+                }
+            }
+            policy => {
+                if s.chars().any(is_bidi_control) {
+                    s = Cow::Owned(match policy {
+                        BidiPolicy::Strip => s.chars().filter(|c| !is_bidi_control(*c)).collect(),
+                        BidiPolicy::Replace => s.replace(is_bidi_control, &self.replacement),
+                        _ => return Err(Error::BidiControl.into()),
+                    });
+                }
+            }
+        }
+
+        if self.invisible_policy != InvisiblePolicy::Keep {
+            if let Some(scalar) = s.chars().find(|c| is_invisible(*c)) {
+                s = Cow::Owned(match self.invisible_policy {
+                    InvisiblePolicy::Strip => s.chars().filter(|c| !is_invisible(*c)).collect(),
+                    InvisiblePolicy::Replace => s.replace(is_invisible, &self.replacement),
+                    _ => return Err(Error::InvisibleCharacter { scalar }.into()),
+                });
+            }
+        }
+
+        match self.tab_policy {
+            TabPolicy::Keep | TabPolicy::Expand(_) => {}
+            TabPolicy::Replace => {
+                if s.contains('\t') {
+                    s = Cow::Owned(s.replace('\t', &self.replacement));
+                }
+            }
+            TabPolicy::Reject => {
+                if s.contains('\t') {
+                    return Err(Error::ControlCode { scalar: '\t' }.into());
+                }
+            }
+        }
+
+        if s.contains('\0') {
+            s = match self.nul_policy {
+                NulPolicy::Reject => return Err(Error::ControlCode { scalar: '\0' }.into()),
+                NulPolicy::Replace => Cow::Owned(s.replace('\0', &self.replacement)),
+                NulPolicy::Strip => Cow::Owned(s.replace('\0', "")),
+            };
+        }
+
+        if s.contains(FF) {
+            s = match self.form_feed_policy {
+                FormFeedPolicy::Preserve => s,
+                FormFeedPolicy::Space => Cow::Owned(s.replace(FF, " ")),
+                FormFeedPolicy::Replace => Cow::Owned(s.replace(FF, &self.replacement)),
+                FormFeedPolicy::Reject => return Err(Error::ControlCode { scalar: FF }.into()),
+            };
+        }
+
+        Ok(s)
+    }
+
+    /// Expand the '\t's in `self.buffer`, and track the output column.
+    fn expand_tabs(&mut self, stop: usize) {
+        if !self.buffer.contains('\t') {
+            for c in self.buffer.chars() {
+                self.columns.step(c);
+            }
+            return;
+        }
+        let mut expanded = String::with_capacity(self.buffer.len());
+        for c in self.buffer.chars() {
+            if c == '\t' {
+                let spaces = self.columns.expand_tab(stop);
+                expanded.extend((0..spaces).map(|_| ' '));
+            } else {
+                self.columns.step(c);
+                expanded.push(c);
+            }
+        }
+        self.buffer = expanded;
+    }
+
     fn check_nl(&mut self, status: Status) -> io::Result<()> {
         match status {
             Status::End => {
-                if !self.nl.0 {
+                self.write_held()?;
+                if !self.nl && self.append_newline {
+                    if self.started {
+                        self.write_all_utf8("\n")?;
+                    } else {
+                        self.nl = true;
+                    }
+                }
+                if !self.nl {
                     self.abandon();
-                    return Err(io::Error::new(
-                        io::ErrorKind::Other,
-                        "output text stream must end with newline",
-                    ));
+                    return Err(Error::MissingNewline.into());
                 }
             }
             Status::Open(Readiness::Lull) => {
-                if !self.nl.0 {
+                self.write_held()?;
+                if !self.nl {
                     self.abandon();
-                    return Err(io::Error::new(
-                        io::ErrorKind::Other,
-                        "output text stream lull must be preceded by newline",
-                    ));
+                    return Err(Error::LullWithoutNewline.into());
                 }
             }
-            Status::Open(Readiness::Ready) => (),
+            Status::Open(Readiness::Ready) | Status::Open(Readiness::Pending) => (),
         }
         Ok(())
     }
 }
 
+/// A builder for `TextWriter`, for combining options such as BOM
+/// compatibility and CRLF compatibility.
+#[derive(Clone, Debug)]
+pub struct TextWriterBuilder {
+    bom_compatibility: bool,
+    crlf_compatibility: bool,
+    append_newline: bool,
+    drop_policy: DropPolicy,
+    non_starter_policy: NonStarterPolicy,
+    normalization_form: NormalizationForm,
+    forbidden: ForbiddenCharacters,
+    forbidden_policy: ForbiddenCharacterPolicy,
+    bidi_policy: BidiPolicy,
+    invisible_policy: InvisiblePolicy,
+    line_terminator_policy: LineTerminatorPolicy,
+    tab_policy: TabPolicy,
+    nul_policy: NulPolicy,
+    form_feed_policy: FormFeedPolicy,
+    replacement: String,
+    #[cfg(feature = "security")]
+    restriction: Option<(RestrictionLevel, RestrictionPolicy)>,
+}
+
+impl TextWriterBuilder {
+    /// Construct a new `TextWriterBuilder` with the default options.
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            bom_compatibility: false,
+            crlf_compatibility: false,
+            append_newline: false,
+            drop_policy: DropPolicy::default(),
+            non_starter_policy: NonStarterPolicy::Reject,
+            normalization_form: NormalizationForm::Nfc,
+            forbidden: ForbiddenCharacters::tr15(),
+            forbidden_policy: ForbiddenCharacterPolicy::Reject,
+            bidi_policy: BidiPolicy::Allow,
+            invisible_policy: InvisiblePolicy::Keep,
+            line_terminator_policy: LineTerminatorPolicy::Keep,
+            tab_policy: TabPolicy::Keep,
+            nul_policy: NulPolicy::Reject,
+            form_feed_policy: FormFeedPolicy::Reject,
+            replacement: REPL.to_string(),
+            #[cfg(feature = "security")]
+            restriction: None,
+        }
+    }
+
+    /// Write a U+FEFF (BOM) to the beginning of the output stream, as
+    /// `TextWriter::with_bom_compatibility` does.
+    #[inline]
+    pub fn bom_compatibility(&mut self, enable: bool) -> &mut Self {
+        self.bom_compatibility = enable;
+        self
+    }
+
+    /// Translate "\n" to "\r\n", as `TextWriter::with_crlf_compatibility`
+    /// does.
+    #[inline]
+    pub fn crlf_compatibility(&mut self, enable: bool) -> &mut Self {
+        self.crlf_compatibility = enable;
+        self
+    }
+
+    /// At the end of the stream, if the data written doesn't end with a
+    /// '\n', append one instead of failing, as `TextReader` does on input.
+    #[inline]
+    pub fn append_newline(&mut self, enable: bool) -> &mut Self {
+        self.append_newline = enable;
+        self
+    }
+
+    /// Set what to do if the `TextWriter` is dropped without a trailing
+    /// newline. The default is `DropPolicy::Abandon`.
+    #[inline]
+    pub fn drop_policy(&mut self, policy: DropPolicy) -> &mut Self {
+        self.drop_policy = policy;
+        self
+    }
+
+    /// Set what to do with a non-starter at the beginning of the stream or
+    /// after a lull. The default is `NonStarterPolicy::Reject`.
+    #[inline]
+    pub fn non_starter_policy(&mut self, policy: NonStarterPolicy) -> &mut Self {
+        self.non_starter_policy = policy;
+        self
+    }
+
+    /// Set the Normalization Form to transform text to. The default is
+    /// `NormalizationForm::Nfc`.
+    #[inline]
+    pub fn normalization_form(&mut self, form: NormalizationForm) -> &mut Self {
+        self.normalization_form = form;
+        self
+    }
+
+    /// Set the scalar values which aren't permitted. The default is
+    /// `ForbiddenCharacters::tr15()`.
+    #[inline]
+    pub fn forbidden_characters(&mut self, forbidden: ForbiddenCharacters) -> &mut Self {
+        self.forbidden = forbidden;
+        self
+    }
+
+    /// Add `c` to the scalar values which aren't permitted.
+    #[inline]
+    pub fn forbid(&mut self, c: char) -> &mut Self {
+        self.forbidden.insert(c);
+        self
+    }
+
+    /// Set what to do with forbidden scalar values. The default is
+    /// `ForbiddenCharacterPolicy::Reject`.
+    #[inline]
+    pub fn forbidden_character_policy(&mut self, policy: ForbiddenCharacterPolicy) -> &mut Self {
+        self.forbidden_policy = policy;
+        self
+    }
+
+    /// Set what to do with bidirectional embedding, override, and isolate
+    /// controls. The default is `BidiPolicy::Allow`.
+    ///
+    /// With `BidiPolicy::RequireBalanced`, controls left open at the end of
+    /// a line are only detected once its '\n' is written, so the error may
+    /// be reported after part of the line has been written to the
+    /// underlying stream.
+    #[inline]
+    pub fn bidi_policy(&mut self, policy: BidiPolicy) -> &mut Self {
+        self.bidi_policy = policy;
+        self
+    }
+
+    /// Set what to do with invisible formatting characters. The default is
+    /// `InvisiblePolicy::Keep`.
+    #[inline]
+    pub fn invisible_policy(&mut self, policy: InvisiblePolicy) -> &mut Self {
+        self.invisible_policy = policy;
+        self
+    }
+
+    /// Set what to do with '\t'. The default is `TabPolicy::Keep`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `policy` is `TabPolicy::Expand(0)`.
+    #[inline]
+    pub fn tab_policy(&mut self, policy: TabPolicy) -> &mut Self {
+        assert_ne!(policy, TabPolicy::Expand(0), "tab stops must be nonzero");
+        self.tab_policy = policy;
+        self
+    }
+
+    /// Set what to do with U+0000 (NUL). The default is
+    /// `NulPolicy::Reject`.
+    #[inline]
+    pub fn nul_policy(&mut self, policy: NulPolicy) -> &mut Self {
+        self.nul_policy = policy;
+        self
+    }
+
+    /// Set what to do with U+000C (FF). The default is
+    /// `FormFeedPolicy::Reject`.
+    #[inline]
+    pub fn form_feed_policy(&mut self, policy: FormFeedPolicy) -> &mut Self {
+        self.form_feed_policy = policy;
+        self
+    }
+
+    /// Set the string which disallowed scalar values are replaced with,
+    /// as `TextReaderBuilder::replacement` does for the reader.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `replacement` is longer than 4 bytes, or contains
+    /// Unicode Normalization Form non-starters, control codes, or U+FEFF.
+    #[inline]
+    pub fn replacement(&mut self, replacement: &str) -> &mut Self {
+        assert!(
+            is_valid_replacement(replacement),
+            "invalid replacement string"
+        );
+        self.replacement = replacement.to_owned();
+        self
+    }
+
+    /// Set what to do with U+0085 (NEL), U+2028 (LINE SEPARATOR), and
+    /// U+2029 (PARAGRAPH SEPARATOR). The default is
+    /// `LineTerminatorPolicy::Keep`.
+    ///
+    /// With `LineTerminatorPolicy::Normalize`, they're converted to '\n'
+    /// before any CRLF translation, so they satisfy the trailing newline
+    /// requirement.
+    #[inline]
+    pub fn line_terminator_policy(&mut self, policy: LineTerminatorPolicy) -> &mut Self {
+        self.line_terminator_policy = policy;
+        self
+    }
+
+    /// Check each word, delimited by whitespace, against the UTS #39
+    /// restriction `level`, as `TextReaderBuilder::restriction_level` does.
+    ///
+    /// Since a word is checked once it's complete, with
+    /// `RestrictionPolicy::Reject` the error may be reported after part of
+    /// the word has been written to the underlying stream.
+    #[cfg(feature = "security")]
+    #[inline]
+    pub fn restriction_level(
+        &mut self,
+        level: RestrictionLevel,
+        policy: RestrictionPolicy,
+    ) -> &mut Self {
+        self.restriction = Some((level, policy));
+        self
+    }
+
+    /// Construct a `TextWriter` wrapping `inner` with the configured
+    /// options.
+    pub fn build<Inner: Write>(&self, mut inner: Inner) -> io::Result<TextWriter<Inner>> {
+        if self.bom_compatibility {
+            let mut bom_bytes = [0_u8; MAX_UTF8_SIZE];
+            inner.write_all(BOM.encode_utf8(&mut bom_bytes).as_bytes())?;
+        }
+        Ok(TextWriter::from_builder(inner, self))
+    }
+}
+
+impl Default for TextWriterBuilder {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<Inner: Write> Close for TextWriter<Inner> {
+    type Output = Inner;
+
+    #[inline]
+    fn close_into_inner(self) -> io::Result<Inner> {
+        TextWriter::close_into_inner(self)
+    }
+}
+
 impl<Inner: Write> Write for TextWriter<Inner> {
     fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
         match str::from_utf8(buf) {
@@ -204,44 +804,180 @@ impl<Inner: Write> Write for TextWriter<Inner> {
             Err(error) if error.valid_up_to() != 0 => self
                 .write_all(&buf[..error.valid_up_to()])
                 .map(|_| buf.len()),
-            Err(error) => {
+            Err(_) => {
                 self.abandon();
-                Err(io::Error::new(io::ErrorKind::Other, error))
+                Err(Error::InvalidUtf8 { offset: None }.into())
             }
         }
     }
 
     fn flush(&mut self, status: Status) -> io::Result<()> {
+        if status == Status::Open(Readiness::Pending)
+            && self.non_starter_policy != NonStarterPolicy::Buffer
+        {
+            self.write_held()?;
+        }
+        self.check_nl(status)?;
         if status != Status::ready() {
             self.expect_starter = true;
         }
-        self.check_nl(status)?;
-        self.inner.flush(status)
+        self.inner.as_mut().unwrap().flush(status)
     }
 
     fn abandon(&mut self) {
-        self.inner.abandon();
+        self.inner.as_mut().unwrap().abandon();
+        self.held.clear();
 
         // Don't enforce a trailing newline.
-        self.nl.0 = true;
+        self.nl = true;
+    }
+
+    #[inline]
+    fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) -> io::Result<usize> {
+        write_vectored_utf8(self, bufs)
     }
 
     fn write_all_utf8(&mut self, s: &str) -> io::Result<()> {
+        let s = match self.apply_policies(s) {
+            Ok(s) => s,
+            Err(e) => {
+                self.abandon();
+                return Err(e);
+            }
+        };
+
         if self.crlf_compatibility {
-            self.crlf_write_all_utf8(s)
+            self.crlf_write_all_utf8(&s)
         } else {
-            self.normal_write_all_utf8(s)
+            self.normal_write_all_utf8(&s)
         }
     }
 }
 
-struct NlGuard(bool);
+/// `flush` flushes with `Status::Open(Readiness::Lull)`. As with
+/// [`Write::flush`], the data written so far must end with a newline. To declare
+/// the end of the stream, use [`Write::flush`] with `Status::End`, or
+/// `close_into_inner`.
+impl<Inner: Write> io::Write for TextWriter<Inner> {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        Write::write(self, buf)
+    }
+
+    #[inline]
+    fn flush(&mut self) -> io::Result<()> {
+        Write::flush(self, Status::Open(Readiness::Lull))
+    }
+
+    #[inline]
+    fn write_vectored(&mut self, bufs: &[io::IoSlice<'_>]) -> io::Result<usize> {
+        Write::write_vectored(self, bufs)
+    }
 
-impl Drop for NlGuard {
+    #[cfg(feature = "nightly")]
+    #[inline]
+    fn is_write_vectored(&self) -> bool {
+        Write::is_write_vectored(self)
+    }
+
+    #[inline]
+    fn write_all(&mut self, buf: &[u8]) -> io::Result<()> {
+        Write::write_all(self, buf)
+    }
+
+    #[inline]
+    fn write_fmt(&mut self, fmt: fmt::Arguments<'_>) -> io::Result<()> {
+        Write::write_fmt(self, fmt)
+    }
+}
+
+/// Formatting errors are reported as `fmt::Error`; the underlying
+/// `io::Error` isn't available through this interface.
+impl<Inner: Write> fmt::Write for TextWriter<Inner> {
+    #[inline]
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.write_all_utf8(s).map_err(|_| fmt::Error)
+    }
+}
+
+/// What a `TextWriter` does if it's dropped while the data written doesn't
+/// end with a newline, which is usually a bug, but which also happens when a
+/// writer is dropped during unwinding or early returns.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DropPolicy {
+    /// Panic, unless the thread is already panicking.
+    Panic,
+
+    /// Silently abandon the underlying stream.
+    Abandon,
+
+    /// Append a newline and end the stream, ignoring any errors.
+    AppendNewline,
+}
+
+impl Default for DropPolicy {
+    #[inline]
+    fn default() -> Self {
+        Self::Abandon
+    }
+}
+
+impl<Inner: Write> Drop for TextWriter<Inner> {
     fn drop(&mut self) {
-        if !self.0 {
-            panic!("output text stream not ended with newline");
+        if self.nl || self.inner.is_none() {
+            return;
         }
+        match self.drop_policy {
+            DropPolicy::Panic => {
+                if !thread::panicking() {
+                    panic!("output text stream not ended with newline");
+                }
+            }
+            DropPolicy::Abandon => {
+                // Write out any held-back data first, so that everything
+                // written reaches the underlying stream.
+                let _ = self.write_held();
+                self.abandon()
+            }
+            DropPolicy::AppendNewline => {
+                self.append_newline = true;
+                if Write::flush(self, Status::End).is_err() {
+                    self.abandon();
+                }
+            }
+        }
+    }
+}
+
+#[cfg(unix)]
+impl<Inner: Write + AsRawFd> AsRawFd for TextWriter<Inner> {
+    #[inline]
+    fn as_raw_fd(&self) -> RawFd {
+        self.inner.as_ref().unwrap().as_raw_fd()
+    }
+}
+
+#[cfg(unix)]
+impl<Inner: Write + AsFd> AsFd for TextWriter<Inner> {
+    #[inline]
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.inner.as_ref().unwrap().as_fd()
+    }
+}
+
+#[cfg(windows)]
+impl<Inner: Write + AsRawHandle> AsRawHandle for TextWriter<Inner> {
+    #[inline]
+    fn as_raw_handle(&self) -> RawHandle {
+        self.inner.as_ref().unwrap().as_raw_handle()
+    }
+}
+
+#[cfg(windows)]
+impl<Inner: Write + AsHandle> AsHandle for TextWriter<Inner> {
+    #[inline]
+    fn as_handle(&self) -> BorrowedHandle<'_> {
+        self.inner.as_ref().unwrap().as_handle()
     }
 }
 
@@ -310,6 +1046,34 @@ fn test_ff() {
     test_error(b"hello world\x0c");
 }
 
+#[test]
+fn test_form_feed_policy() {
+    let translate = |policy, s: &str| {
+        let mut writer = TextWriterBuilder::new()
+            .form_feed_policy(policy)
+            .build(crate::StdWriter::new(Vec::new()))
+            .unwrap();
+        writer.write_all_utf8(s)?;
+        let inner = writer.close_into_inner()?;
+        Ok::<_, io::Error>(String::from_utf8(inner.get_ref().clone()).unwrap())
+    };
+    let input = "page 1\n\x0cpage 2\n";
+    let err = translate(FormFeedPolicy::Reject, input).unwrap_err();
+    assert_eq!(
+        Error::from_io_error(&err),
+        Some(&Error::ControlCode { scalar: '\x0c' })
+    );
+    assert_eq!(translate(FormFeedPolicy::Preserve, input).unwrap(), input);
+    assert_eq!(
+        translate(FormFeedPolicy::Space, input).unwrap(),
+        "page 1\n page 2\n"
+    );
+    assert_eq!(
+        translate(FormFeedPolicy::Replace, input).unwrap(),
+        "page 1\n\u{fffd}page 2\n"
+    );
+}
+
 #[test]
 fn test_del() {
     test_error(b"\x7f");
@@ -427,5 +1191,469 @@ fn test_linux() {
     test_error(b"\x1b[[Ahello\x1b[[Aworld\x1b[[A");
 }
 
+#[test]
+fn test_get_ref() {
+    let mut writer = TextWriter::new(crate::StdWriter::new(Vec::new()));
+    writer.write_all_utf8("hello\n").unwrap();
+    writer.flush(Status::Open(crate::Readiness::Lull)).unwrap();
+    assert_eq!(writer.get_ref().get_ref(), b"hello\n");
+    writer.get_mut().get_mut().clear();
+    writer.write_all_utf8("world\n").unwrap();
+    let inner = writer.close_into_inner().unwrap();
+    assert_eq!(inner.get_ref(), b"world\n");
+}
+
+#[cfg(test)]
+fn format_into<W: fmt::Write>(writer: &mut W) -> fmt::Result {
+    writeln!(writer, "{} + {} = {}", 1, 2, 1 + 2)
+}
+
+#[test]
+fn test_fmt_write() {
+    let mut writer = TextWriter::new(crate::StdWriter::new(Vec::new()));
+    format_into(&mut writer).unwrap();
+    let inner = writer.close_into_inner().unwrap();
+    assert_eq!(inner.get_ref(), b"1 + 2 = 3\n");
+
+    let mut writer = crate::Utf8Writer::new(crate::StdWriter::new(Vec::new()));
+    format_into(&mut writer).unwrap();
+    assert_eq!(writer.get_ref().get_ref(), b"1 + 2 = 3\n");
+}
+
+#[test]
+fn test_io_write() {
+    let mut writer = TextWriter::new(crate::StdWriter::new(Vec::new()));
+    io::copy(&mut &b"hello\n"[..], &mut writer).unwrap();
+    io::Write::flush(&mut writer).unwrap();
+    assert_eq!(writer.get_ref().get_ref(), b"hello\n");
+    io::copy(&mut &b"world"[..], &mut writer).unwrap();
+    assert!(io::Write::flush(&mut writer).is_err());
+}
+
+#[test]
+fn test_write_vectored() {
+    let mut writer = TextWriter::new(crate::StdWriter::new(Vec::new()));
+    let bufs = [
+        IoSlice::new(b"hello "),
+        IoSlice::new(b"world\n"),
+        IoSlice::new(b"\xff"),
+    ];
+    assert_eq!(writer.write_vectored(&bufs).unwrap(), 12);
+    assert!(writer.write_vectored(&bufs[2..]).is_err());
+    assert_eq!(writer.get_ref().get_ref(), b"hello world\n");
+}
+
 // TODO: Test Stream-Safe
-// TODO: test for nonstarter after lull
+
+#[test]
+fn test_builder_bom_and_crlf() {
+    let mut writer = TextWriterBuilder::new()
+        .bom_compatibility(true)
+        .crlf_compatibility(true)
+        .build(crate::StdWriter::new(Vec::new()))
+        .unwrap();
+    writer.write_all_utf8("hello\nworld\n").unwrap();
+    let inner = writer.close_into_inner().unwrap();
+    assert_eq!(inner.get_ref(), "\u{feff}hello\r\nworld\r\n".as_bytes());
+}
+
+#[test]
+fn test_crlf_compatibility_rejects_cr() {
+    let mut writer = TextWriter::with_crlf_compatibility(crate::StdWriter::new(Vec::new()));
+    assert!(writer.write_all_utf8("hello\r\n").is_err());
+}
+
+#[test]
+fn test_error_kind() {
+    let mut writer = TextWriter::new(crate::StdWriter::new(Vec::new()));
+    writer.write_all_utf8("hello").unwrap();
+    let err = writer.flush(Status::End).unwrap_err();
+    assert_eq!(Error::from_io_error(&err), Some(&Error::MissingNewline));
+}
+
+#[test]
+fn test_append_newline() {
+    let translate = |s: &str, crlf: bool| {
+        let mut writer = TextWriterBuilder::new()
+            .append_newline(true)
+            .crlf_compatibility(crlf)
+            .build(crate::StdWriter::new(Vec::new()))
+            .unwrap();
+        writer.write_all_utf8(s).unwrap();
+        let inner = writer.close_into_inner().unwrap();
+        String::from_utf8(inner.get_ref().clone()).unwrap()
+    };
+    assert_eq!(translate("hello", false), "hello\n");
+    assert_eq!(translate("hello\n", false), "hello\n");
+    assert_eq!(translate("hello", true), "hello\r\n");
+    assert_eq!(translate("", false), "");
+}
+
+#[test]
+fn test_drop_policy() {
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Clone, Default)]
+    struct Shared(Arc<Mutex<Vec<u8>>>);
+
+    impl io::Write for Shared {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    let output = Shared::default();
+    let mut writer = TextWriter::new(crate::StdWriter::new(output.clone()));
+    writer.write_all_utf8("hello").unwrap();
+    drop(writer);
+    assert_eq!(*output.0.lock().unwrap(), b"hello");
+
+    let output = Shared::default();
+    let mut writer = TextWriterBuilder::new()
+        .drop_policy(DropPolicy::AppendNewline)
+        .build(crate::StdWriter::new(output.clone()))
+        .unwrap();
+    writer.write_all_utf8("hello").unwrap();
+    drop(writer);
+    assert_eq!(*output.0.lock().unwrap(), b"hello\n");
+}
+
+#[test]
+#[should_panic(expected = "output text stream not ended with newline")]
+fn test_drop_policy_panic() {
+    let mut writer = TextWriterBuilder::new()
+        .drop_policy(DropPolicy::Panic)
+        .build(crate::StdWriter::new(Vec::new()))
+        .unwrap();
+    writer.write_all_utf8("hello").unwrap();
+}
+
+#[test]
+fn test_non_starter_policy() {
+    let translate = |policy, first: &str, second: &str| {
+        let mut writer = TextWriterBuilder::new()
+            .non_starter_policy(policy)
+            .build(crate::StdWriter::new(Vec::new()))
+            .unwrap();
+        writer.write_all_utf8(first)?;
+        writer.flush(Status::Open(Readiness::Pending))?;
+        writer.write_all_utf8(second)?;
+        let inner = writer.close_into_inner()?;
+        Ok::<_, io::Error>(String::from_utf8(inner.get_ref().clone()).unwrap())
+    };
+
+    let err = translate(NonStarterPolicy::Reject, "e", "\u{301}\n").unwrap_err();
+    assert_eq!(Error::from_io_error(&err), Some(&Error::NonStarter));
+    assert_eq!(
+        translate(NonStarterPolicy::Replace, "e", "\u{301}\n").unwrap(),
+        "e\u{fffd}\n"
+    );
+    assert_eq!(
+        translate(NonStarterPolicy::Buffer, "e", "\u{301}\n").unwrap(),
+        "\u{e9}\n"
+    );
+    assert_eq!(
+        translate(NonStarterPolicy::Buffer, "\n", "\u{301}\n").unwrap(),
+        "\n\u{fffd}\n"
+    );
+}
+
+#[test]
+fn test_replacement() {
+    let translate = |replacement: &str, first: &str, second: &str| {
+        let mut writer = TextWriterBuilder::new()
+            .replacement(replacement)
+            .non_starter_policy(NonStarterPolicy::Replace)
+            .bidi_policy(BidiPolicy::Replace)
+            .invisible_policy(InvisiblePolicy::Replace)
+            .nul_policy(NulPolicy::Replace)
+            .form_feed_policy(FormFeedPolicy::Replace)
+            .tab_policy(TabPolicy::Replace)
+            .build(crate::StdWriter::new(Vec::new()))
+            .unwrap();
+        writer.write_all_utf8(first).unwrap();
+        writer.flush(Status::Open(Readiness::Pending)).unwrap();
+        writer.write_all_utf8(second).unwrap();
+        let inner = writer.close_into_inner().unwrap();
+        String::from_utf8(inner.get_ref().clone()).unwrap()
+    };
+    let second = "\u{301}a\u{202e}b\u{200b}c\0d\u{c}e\tf\n";
+    assert_eq!(translate("?", "x", second), "x?a?b?c?d?e?f\n");
+    assert_eq!(translate("", "x", second), "xabcdef\n");
+    // An empty replacement deletes a whole run of non-starters.
+    assert_eq!(translate("", "x", "\u{301}\u{301}a\n"), "xa\n");
+    assert_eq!(
+        translate("\u{fffd}", "x", second),
+        "x\u{fffd}a\u{fffd}b\u{fffd}c\u{fffd}d\u{fffd}e\u{fffd}f\n"
+    );
+}
+
+#[test]
+#[should_panic(expected = "invalid replacement string")]
+fn test_invalid_replacement() {
+    TextWriterBuilder::new().replacement("\u{301}");
+}
+
+#[test]
+fn test_nfc_across_writes() {
+    let mut writer = TextWriter::new(crate::StdWriter::new(Vec::new()));
+    writer.write_all_utf8("A").unwrap();
+    writer.write_all_utf8("\u{30a}").unwrap();
+    writer.write_all_utf8("\n").unwrap();
+    writer.write_all_utf8("\u{1100}").unwrap();
+    writer.write_all_utf8("\u{1161}\n").unwrap();
+    let inner = writer.close_into_inner().unwrap();
+    assert_eq!(inner.get_ref(), "\u{c5}\n\u{ac00}\n".as_bytes());
+}
+
+#[test]
+fn test_buffer_policy_holds_last_segment() {
+    let mut writer = TextWriterBuilder::new()
+        .non_starter_policy(NonStarterPolicy::Buffer)
+        .build(crate::StdWriter::new(Vec::new()))
+        .unwrap();
+    writer.write_all_utf8("hello").unwrap();
+    assert_eq!(writer.get_ref().get_ref(), b"hell");
+    writer.write_all_utf8("\n").unwrap();
+    writer.flush(Status::Open(Readiness::Lull)).unwrap();
+    assert_eq!(writer.get_ref().get_ref(), b"hello\n");
+    writer.write_all_utf8("bye").unwrap();
+    assert!(writer.flush(Status::Open(Readiness::Lull)).is_err());
+}
+
+#[test]
+fn test_normalization_form() {
+    let translate = |form, s: &str| {
+        let mut writer = TextWriterBuilder::new()
+            .normalization_form(form)
+            .build(crate::StdWriter::new(Vec::new()))
+            .unwrap();
+        writer.write_all_utf8(s).unwrap();
+        let inner = writer.close_into_inner().unwrap();
+        String::from_utf8(inner.get_ref().clone()).unwrap()
+    };
+    let input = "\u{212b}\u{fb01}\n";
+    assert_eq!(translate(NormalizationForm::Nfc, input), "\u{c5}\u{fb01}\n");
+    assert_eq!(
+        translate(NormalizationForm::Nfd, input),
+        "A\u{30a}\u{fb01}\n"
+    );
+    assert_eq!(translate(NormalizationForm::Nfkc, input), "\u{c5}fi\n");
+    assert_eq!(translate(NormalizationForm::Nfkd, input), "A\u{30a}fi\n");
+}
+
+#[test]
+fn test_passthrough() {
+    let mut writer = TextWriterBuilder::new()
+        .normalization_form(NormalizationForm::Passthrough)
+        .build(crate::StdWriter::new(Vec::new()))
+        .unwrap();
+    writer.write_all_utf8("\u{212b}A\u{30a}\n").unwrap();
+    assert!(writer.write_all_utf8("\x1b[2J").is_err());
+    assert_eq!(writer.get_ref().get_ref(), "\u{212b}A\u{30a}\n".as_bytes());
+}
+
+#[test]
+fn test_forbidden_characters() {
+    let translate = |builder: &TextWriterBuilder, s: &str| {
+        let mut writer = builder.build(crate::StdWriter::new(Vec::new())).unwrap();
+        writer.write_all_utf8(s)?;
+        let inner = writer.close_into_inner()?;
+        Ok::<_, io::Error>(String::from_utf8(inner.get_ref().clone()).unwrap())
+    };
+    let err = translate(&TextWriterBuilder::new(), "a\u{f951}b\n").unwrap_err();
+    assert_eq!(
+        Error::from_io_error(&err),
+        Some(&Error::ForbiddenCharacter { scalar: '\u{f951}' })
+    );
+    assert_eq!(
+        translate(
+            TextWriterBuilder::new()
+                .forbid('!')
+                .forbidden_character_policy(ForbiddenCharacterPolicy::Replace),
+            "a\u{f951}b!\n"
+        )
+        .unwrap(),
+        "a\u{fffd}b\u{fffd}\n"
+    );
+    assert_eq!(
+        translate(
+            TextWriterBuilder::new().forbidden_characters(ForbiddenCharacters::empty()),
+            "a\u{f951}b\n"
+        )
+        .unwrap(),
+        "a\u{964b}b\n"
+    );
+}
+
+#[test]
+fn test_bidi_policy() {
+    let translate = |policy, s: &str| {
+        let mut writer = TextWriterBuilder::new()
+            .bidi_policy(policy)
+            .build(crate::StdWriter::new(Vec::new()))
+            .unwrap();
+        writer.write_all_utf8(s)?;
+        let inner = writer.close_into_inner()?;
+        Ok::<_, io::Error>(String::from_utf8(inner.get_ref().clone()).unwrap())
+    };
+    let input = "a\u{2067}b\u{2069}c\u{200e}\n";
+    assert_eq!(translate(BidiPolicy::Allow, input).unwrap(), input);
+    assert_eq!(
+        translate(BidiPolicy::Strip, input).unwrap(),
+        "abc\u{200e}\n"
+    );
+    assert_eq!(
+        translate(BidiPolicy::Replace, input).unwrap(),
+        "a\u{fffd}b\u{fffd}c\u{200e}\n"
+    );
+    assert_eq!(
+        translate(BidiPolicy::RequireBalanced, input).unwrap(),
+        input
+    );
+    let err = translate(BidiPolicy::Reject, input).unwrap_err();
+    assert_eq!(Error::from_io_error(&err), Some(&Error::BidiControl));
+    let err = translate(BidiPolicy::RequireBalanced, "a\u{202e}b\n").unwrap_err();
+    assert_eq!(Error::from_io_error(&err), Some(&Error::BidiControl));
+}
+
+#[test]
+fn test_invisible_policy() {
+    let translate = |policy, s: &str| {
+        let mut writer = TextWriterBuilder::new()
+            .invisible_policy(policy)
+            .build(crate::StdWriter::new(Vec::new()))
+            .unwrap();
+        writer.write_all_utf8(s)?;
+        let inner = writer.close_into_inner()?;
+        Ok::<_, io::Error>(String::from_utf8(inner.get_ref().clone()).unwrap())
+    };
+    let input = "a\u{2060}b\u{200d}c\n";
+    assert_eq!(translate(InvisiblePolicy::Keep, input).unwrap(), input);
+    assert_eq!(translate(InvisiblePolicy::Strip, input).unwrap(), "abc\n");
+    assert_eq!(
+        translate(InvisiblePolicy::Replace, input).unwrap(),
+        "a\u{fffd}b\u{fffd}c\n"
+    );
+    let err = translate(InvisiblePolicy::Reject, input).unwrap_err();
+    assert_eq!(
+        Error::from_io_error(&err),
+        Some(&Error::InvisibleCharacter { scalar: '\u{2060}' })
+    );
+}
+
+#[test]
+fn test_line_terminator_policy() {
+    let translate = |policy, s: &str| {
+        let mut writer = TextWriterBuilder::new()
+            .line_terminator_policy(policy)
+            .build(crate::StdWriter::new(Vec::new()))
+            .unwrap();
+        writer.write_all_utf8(s)?;
+        let inner = writer.close_into_inner()?;
+        Ok::<_, io::Error>(String::from_utf8(inner.get_ref().clone()).unwrap())
+    };
+    assert_eq!(
+        translate(LineTerminatorPolicy::Keep, "a\u{2028}b\u{2029}c\n").unwrap(),
+        "a\u{2028}b\u{2029}c\n"
+    );
+    let err = translate(LineTerminatorPolicy::Keep, "a\u{85}b\n").unwrap_err();
+    assert_eq!(Error::from_io_error(&err), Some(&Error::InvalidScalarValue));
+    assert_eq!(
+        translate(LineTerminatorPolicy::Normalize, "a\u{85}b\u{2028}c\u{2029}").unwrap(),
+        "a\nb\nc\n"
+    );
+    let err = translate(LineTerminatorPolicy::Reject, "a\u{2029}").unwrap_err();
+    assert_eq!(
+        Error::from_io_error(&err),
+        Some(&Error::LineTerminator { scalar: '\u{2029}' })
+    );
+}
+
+#[test]
+fn test_tab_policy() {
+    let translate = |policy, writes: &[&str]| {
+        let mut writer = TextWriterBuilder::new()
+            .tab_policy(policy)
+            .build(crate::StdWriter::new(Vec::new()))
+            .unwrap();
+        for s in writes {
+            writer.write_all_utf8(s)?;
+        }
+        let inner = writer.close_into_inner()?;
+        Ok::<_, io::Error>(String::from_utf8(inner.get_ref().clone()).unwrap())
+    };
+    let input = ["\ta\tb", "c\u{301}\td\ne\t", "\tf\n"];
+    assert_eq!(
+        translate(TabPolicy::Keep, &input).unwrap(),
+        "\ta\tb\u{107}\td\ne\t\tf\n"
+    );
+    assert_eq!(
+        translate(TabPolicy::Expand(4), &input).unwrap(),
+        "    a   b\u{107}  d\ne       f\n"
+    );
+    assert_eq!(
+        translate(TabPolicy::Replace, &input).unwrap(),
+        "\u{fffd}a\u{fffd}b\u{107}\u{fffd}d\ne\u{fffd}\u{fffd}f\n"
+    );
+    let err = translate(TabPolicy::Reject, &input).unwrap_err();
+    assert_eq!(
+        Error::from_io_error(&err),
+        Some(&Error::ControlCode { scalar: '\t' })
+    );
+}
+
+#[test]
+fn test_nul_policy() {
+    let translate = |policy, s: &str| {
+        let mut writer = TextWriterBuilder::new()
+            .nul_policy(policy)
+            .build(crate::StdWriter::new(Vec::new()))
+            .unwrap();
+        writer.write_all_utf8(s)?;
+        let inner = writer.close_into_inner()?;
+        Ok::<_, io::Error>(String::from_utf8(inner.get_ref().clone()).unwrap())
+    };
+    let err = translate(NulPolicy::Reject, "a\0b\n").unwrap_err();
+    assert_eq!(
+        Error::from_io_error(&err),
+        Some(&Error::ControlCode { scalar: '\0' })
+    );
+    assert_eq!(
+        translate(NulPolicy::Replace, "a\0b\n").unwrap(),
+        "a\u{fffd}b\n"
+    );
+    assert_eq!(translate(NulPolicy::Strip, "a\0b\n").unwrap(), "ab\n");
+}
+
+#[cfg(feature = "security")]
+#[test]
+fn test_restriction_level() {
+    let mut writer = TextWriterBuilder::new()
+        .restriction_level(RestrictionLevel::HighlyRestrictive, RestrictionPolicy::Flag)
+        .build(crate::StdWriter::new(Vec::new()))
+        .unwrap();
+    writer
+        .write_all_utf8("login p\u{430}ypal paypal\n")
+        .unwrap();
+    assert_eq!(writer.restriction_violations(), 1);
+    writer.close_into_inner().unwrap();
+
+    let mut writer = TextWriterBuilder::new()
+        .restriction_level(
+            RestrictionLevel::HighlyRestrictive,
+            RestrictionPolicy::Reject,
+        )
+        .build(crate::StdWriter::new(Vec::new()))
+        .unwrap();
+    let err = writer.write_all_utf8("p\u{430}ypal\n").unwrap_err();
+    assert_eq!(
+        Error::from_io_error(&err),
+        Some(&Error::RestrictionLevelExceeded)
+    );
+}
@@ -0,0 +1,198 @@
+use crate::{unicode::MAX_UTF8_SIZE, Read, ReadOutcome};
+use std::{
+    cmp::min,
+    io, thread,
+    time::{Duration, Instant},
+};
+
+/// A `Read` implementation which passes data through from an inner `Read`
+/// unmodified, limiting the throughput to a given number of bytes per
+/// second.
+///
+/// This is useful for simulating slow terminals and networks when testing
+/// interactive pipelines.
+pub struct ThrottleReader<Inner: Read> {
+    /// The wrapped byte stream.
+    inner: Inner,
+
+    /// The maximum number of bytes to read per second.
+    bytes_per_second: u64,
+
+    /// The start of the current one-second window.
+    window_start: Instant,
+
+    /// The number of bytes read in the current window.
+    window_bytes: u64,
+
+    /// When the budget is exhausted, report `Readiness::Pending` instead of
+    /// sleeping.
+    non_blocking: bool,
+}
+
+impl<Inner: Read> ThrottleReader<Inner> {
+    /// Construct a new instance of `ThrottleReader` wrapping `inner`, which
+    /// sleeps as needed to read no more than `bytes_per_second` bytes per
+    /// second.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bytes_per_second` is zero.
+    #[inline]
+    pub fn new(inner: Inner, bytes_per_second: u64) -> Self {
+        assert_ne!(bytes_per_second, 0, "throttle rate must be non-zero");
+        Self {
+            inner,
+            bytes_per_second,
+            window_start: Instant::now(),
+            window_bytes: 0,
+            non_blocking: false,
+        }
+    }
+
+    /// Like `new`, but when the budget for the current second is exhausted,
+    /// rather than sleeping, report `Readiness::Pending`.
+    ///
+    /// No wake-up is scheduled when this happens; the inner stream may have
+    /// data ready, so readiness notifications from it can't be relied on.
+    /// Callers should retry on a timer, using `time_until_refill` to find
+    /// how long to wait.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bytes_per_second` is zero.
+    #[inline]
+    pub fn non_blocking(inner: Inner, bytes_per_second: u64) -> Self {
+        Self {
+            non_blocking: true,
+            ..Self::new(inner, bytes_per_second)
+        }
+    }
+
+    /// Return how long until the budget is refilled, or `Duration::ZERO` if
+    /// some budget remains for the current second.
+    pub fn time_until_refill(&self) -> Duration {
+        if self.window_bytes < self.bytes_per_second {
+            return Duration::ZERO;
+        }
+        Duration::from_secs(1).saturating_sub(self.window_start.elapsed())
+    }
+
+    /// Return the number of bytes which may be read now, or `None` if the
+    /// budget is exhausted and we're in non-blocking mode.
+    fn budget(&mut self) -> Option<u64> {
+        let elapsed = self.window_start.elapsed();
+        if elapsed >= Duration::from_secs(1) {
+            self.window_start = Instant::now();
+            self.window_bytes = 0;
+        } else if self.window_bytes >= self.bytes_per_second {
+            if self.non_blocking {
+                return None;
+            }
+            thread::sleep(Duration::from_secs(1) - elapsed);
+            self.window_start = Instant::now();
+            self.window_bytes = 0;
+        }
+        Some(self.bytes_per_second - self.window_bytes)
+    }
+}
+
+impl<Inner: Read> Read for ThrottleReader<Inner> {
+    fn read_outcome(&mut self, buf: &mut [u8]) -> io::Result<ReadOutcome> {
+        if buf.is_empty() {
+            return self.inner.read_outcome(buf);
+        }
+        let budget = match self.budget() {
+            Some(budget) => budget,
+            None => return Ok(ReadOutcome::pending(0)),
+        };
+        // Layers such as `Utf8Reader` need room for a whole scalar value, so
+        // don't pass them less than that, even if it exceeds the budget.
+        let len = min(buf.len() as u64, budget.max(MAX_UTF8_SIZE as u64)) as usize;
+        let outcome = self.inner.read_outcome(&mut buf[..len])?;
+        self.window_bytes += outcome.size as u64;
+        Ok(outcome)
+    }
+
+    #[inline]
+    fn remaining_hint(&self) -> (u64, Option<u64>) {
+        self.inner.remaining_hint()
+    }
+}
+
+impl<Inner: Read> io::Read for ThrottleReader<Inner> {
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        Read::read(self, buf)
+    }
+
+    #[inline]
+    fn read_vectored(&mut self, bufs: &mut [io::IoSliceMut<'_>]) -> io::Result<usize> {
+        Read::read_vectored(self, bufs)
+    }
+
+    #[cfg(feature = "nightly")]
+    #[inline]
+    fn is_read_vectored(&self) -> bool {
+        Read::is_read_vectored(self)
+    }
+
+    #[inline]
+    fn read_to_end(&mut self, buf: &mut Vec<u8>) -> io::Result<usize> {
+        Read::read_to_end(self, buf)
+    }
+
+    #[inline]
+    fn read_to_string(&mut self, buf: &mut String) -> io::Result<usize> {
+        Read::read_to_string(self, buf)
+    }
+
+    #[inline]
+    fn read_exact(&mut self, buf: &mut [u8]) -> io::Result<()> {
+        Read::read_exact(self, buf)
+    }
+}
+
+#[test]
+fn test_throttle_reader() {
+    let mut reader = ThrottleReader::non_blocking(crate::SliceReader::new(b"hello world"), 4);
+    let mut buf = [0; 16];
+    let outcome = reader.read_outcome(&mut buf).unwrap();
+    assert_eq!(outcome.size, 4);
+    assert_eq!(&buf[..4], b"hell");
+    let outcome = reader.read_outcome(&mut buf).unwrap();
+    assert_eq!(outcome.size, 0);
+    assert_eq!(
+        outcome.status,
+        crate::Status::Open(crate::Readiness::Pending)
+    );
+}
+
+#[test]
+fn test_throttle_reader_time_until_refill() {
+    let mut reader = ThrottleReader::non_blocking(crate::SliceReader::new(b"hello world"), 4);
+    assert_eq!(reader.time_until_refill(), Duration::ZERO);
+    let mut buf = [0; 16];
+    assert_eq!(reader.read_outcome(&mut buf).unwrap().size, 4);
+    let wait = reader.time_until_refill();
+    assert!(wait > Duration::ZERO && wait <= Duration::from_secs(1));
+    assert_eq!(reader.read_outcome(&mut buf).unwrap().size, 0);
+    thread::sleep(wait);
+    assert_eq!(reader.time_until_refill(), Duration::ZERO);
+    let outcome = reader.read_outcome(&mut buf).unwrap();
+    assert_eq!(&buf[..outcome.size], b"o wo");
+}
+
+#[test]
+fn test_throttle_reader_small_budget() {
+    let mut reader = ThrottleReader::non_blocking(
+        crate::Utf8Reader::new(crate::SliceReader::new("h\u{e9}llo".as_bytes())),
+        6,
+    );
+    let mut buf = [0; 16];
+    let outcome = reader.read_outcome(&mut buf[..4]).unwrap();
+    assert_eq!(&buf[..outcome.size], "h\u{e9}l".as_bytes());
+    // Only 2 bytes of the budget remain, but the `Utf8Reader` is still
+    // given room for a scalar value.
+    let outcome = reader.read_outcome(&mut buf).unwrap();
+    assert_eq!(&buf[..outcome.size], b"lo");
+}
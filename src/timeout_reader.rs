@@ -0,0 +1,314 @@
+use crate::{Read, ReadOutcome, Readiness, Status};
+#[cfg(not(windows))]
+use std::os::unix::io::{AsRawFd, RawFd};
+use std::{
+    io,
+    time::{Duration, Instant},
+};
+
+/// What a `TimeoutReader` does when no data arrives within its idle window.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TimeoutAction {
+    /// Report the end of the stream. This is the default.
+    End,
+
+    /// Fail with `io::ErrorKind::TimedOut`, and then report the end of the
+    /// stream.
+    Fail,
+}
+
+impl Default for TimeoutAction {
+    #[inline]
+    fn default() -> Self {
+        Self::End
+    }
+}
+
+/// A `Read` implementation which passes data through from an inner `Read`
+/// unmodified, and ends the stream if no data arrives within an idle window
+/// after a lull.
+///
+/// This is useful for interactive capture tools, so that they finish when
+/// the program they're capturing goes silent, rather than hanging.
+///
+/// The window is only checked between reads of the inner stream, so with
+/// `new`, reads after a lull must return promptly, such as by reporting
+/// `Readiness::Pending` or further lulls. To wait on a blocking file
+/// descriptor, use `polling`.
+pub struct TimeoutReader<Inner: Read> {
+    /// The wrapped byte stream.
+    inner: Inner,
+
+    /// How long to wait for data after a lull.
+    idle: Duration,
+
+    /// What to do when the window passes.
+    action: TimeoutAction,
+
+    /// When the current lull started, if we're in one.
+    lull_start: Option<Instant>,
+
+    /// The file descriptor to wait on before reading during a lull.
+    #[cfg(not(windows))]
+    fd: Option<RawFd>,
+
+    /// The window has passed.
+    timed_out: bool,
+}
+
+impl<Inner: Read> TimeoutReader<Inner> {
+    /// Construct a new instance of `TimeoutReader` wrapping `inner`, which
+    /// ends the stream if no data arrives within `idle` after a lull.
+    #[inline]
+    pub fn new(inner: Inner, idle: Duration) -> Self {
+        Self {
+            inner,
+            idle,
+            action: TimeoutAction::End,
+            lull_start: None,
+            #[cfg(not(windows))]
+            fd: None,
+            timed_out: false,
+        }
+    }
+
+    /// Set what to do when no data arrives within the window. The default
+    /// is `TimeoutAction::End`.
+    #[inline]
+    pub fn set_timeout_action(&mut self, action: TimeoutAction) {
+        self.action = action;
+    }
+
+    /// Gets a reference to the underlying reader.
+    #[inline]
+    pub fn get_ref(&self) -> &Inner {
+        &self.inner
+    }
+
+    /// Gets a mutable reference to the underlying reader.
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut Inner {
+        &mut self.inner
+    }
+
+    /// Consume this `TimeoutReader` and return the underlying stream.
+    #[inline]
+    pub fn into_inner(self) -> Inner {
+        self.inner
+    }
+
+    /// Test whether the window has passed, waiting on the file descriptor,
+    /// if there is one, for up to the rest of the window.
+    fn expired(&self, lull_start: Instant) -> io::Result<bool> {
+        let remaining = match self.idle.checked_sub(lull_start.elapsed()) {
+            Some(remaining) if !remaining.is_zero() => remaining,
+            _ => return Ok(true),
+        };
+        #[cfg(not(windows))]
+        if let Some(fd) = self.fd {
+            return Ok(!wait_readable(fd, remaining)?);
+        }
+        #[cfg(windows)]
+        let _ = remaining;
+        Ok(false)
+    }
+
+    fn time_out(&mut self) -> io::Result<ReadOutcome> {
+        self.timed_out = true;
+        match self.action {
+            TimeoutAction::End => Ok(ReadOutcome::end(0)),
+            TimeoutAction::Fail => Err(io::Error::new(
+                io::ErrorKind::TimedOut,
+                "no data arrived within the idle window after a lull",
+            )),
+        }
+    }
+}
+
+#[cfg(not(windows))]
+impl<Inner: Read + AsRawFd> TimeoutReader<Inner> {
+    /// Like `new`, but during a lull, wait for `inner`'s file descriptor to
+    /// become readable before reading, for up to the rest of the window, so
+    /// that a blocking read can't hang past it.
+    #[inline]
+    pub fn polling(inner: Inner, idle: Duration) -> Self {
+        let fd = inner.as_raw_fd();
+        Self {
+            fd: Some(fd),
+            ..Self::new(inner, idle)
+        }
+    }
+}
+
+/// Wait for up to `timeout` for `fd` to become readable, and return whether
+/// it did.
+#[cfg(not(windows))]
+fn wait_readable(fd: RawFd, timeout: Duration) -> io::Result<bool> {
+    let deadline = Instant::now() + timeout;
+    loop {
+        let mut pollfd = libc::pollfd {
+            fd,
+            events: libc::POLLIN,
+            revents: 0,
+        };
+        // Round up, so that we don't wake before the deadline.
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        let millis = remaining.as_nanos().div_ceil(1_000_000);
+        let millis = millis.min(libc::c_int::MAX as u128) as libc::c_int;
+        match unsafe { libc::poll(&mut pollfd, 1, millis) } {
+            -1 => {
+                let err = io::Error::last_os_error();
+                if err.kind() != io::ErrorKind::Interrupted {
+                    return Err(err);
+                }
+            }
+            0 => return Ok(false),
+            _ => return Ok(true),
+        }
+    }
+}
+
+impl<Inner: Read> Read for TimeoutReader<Inner> {
+    fn read_outcome(&mut self, buf: &mut [u8]) -> io::Result<ReadOutcome> {
+        if self.timed_out {
+            return Ok(ReadOutcome::end(0));
+        }
+        if let Some(lull_start) = self.lull_start {
+            if self.expired(lull_start)? {
+                return self.time_out();
+            }
+        }
+
+        let outcome = self.inner.read_outcome(buf)?;
+        if outcome.size != 0 {
+            self.lull_start = None;
+        }
+        match outcome.status {
+            Status::Open(Readiness::Lull) => {
+                if self.lull_start.is_none() {
+                    self.lull_start = Some(Instant::now());
+                }
+            }
+            Status::Open(Readiness::Ready) | Status::Open(Readiness::Pending) => {}
+            Status::End => self.lull_start = None,
+        }
+        Ok(outcome)
+    }
+
+    #[inline]
+    fn remaining_hint(&self) -> (u64, Option<u64>) {
+        if self.timed_out {
+            return (0, Some(0));
+        }
+        // Timing out only ends the stream early, so the upper bound holds.
+        let (_, upper) = self.inner.remaining_hint();
+        (0, upper)
+    }
+}
+
+impl<Inner: Read> io::Read for TimeoutReader<Inner> {
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        Read::read(self, buf)
+    }
+
+    #[inline]
+    fn read_vectored(&mut self, bufs: &mut [io::IoSliceMut<'_>]) -> io::Result<usize> {
+        Read::read_vectored(self, bufs)
+    }
+
+    #[cfg(feature = "nightly")]
+    #[inline]
+    fn is_read_vectored(&self) -> bool {
+        Read::is_read_vectored(self)
+    }
+
+    #[inline]
+    fn read_to_end(&mut self, buf: &mut Vec<u8>) -> io::Result<usize> {
+        Read::read_to_end(self, buf)
+    }
+
+    #[inline]
+    fn read_to_string(&mut self, buf: &mut String) -> io::Result<usize> {
+        Read::read_to_string(self, buf)
+    }
+
+    #[inline]
+    fn read_exact(&mut self, buf: &mut [u8]) -> io::Result<()> {
+        Read::read_exact(self, buf)
+    }
+}
+
+#[test]
+fn test_timeout_reader() {
+    use crate::testing::{ScriptedReader, Step};
+
+    let input = ScriptedReader::new(vec![
+        Step::Data(b"hello\n".to_vec()),
+        Step::Lull,
+        Step::Data(b"world\n".to_vec()),
+        Step::Lull,
+        Step::Pending,
+        Step::Data(b"never\n".to_vec()),
+    ]);
+    let mut reader = TimeoutReader::new(input, Duration::ZERO);
+    let mut buf = [0; 16];
+    assert_eq!(reader.read_outcome(&mut buf).unwrap().size, 6);
+    assert_eq!(
+        reader.read_outcome(&mut buf).unwrap().status,
+        Status::Open(Readiness::Lull)
+    );
+    let outcome = reader.read_outcome(&mut buf).unwrap();
+    assert_eq!(outcome.status, Status::End);
+    assert_eq!(outcome.size, 0);
+    assert_eq!(reader.read_outcome(&mut buf).unwrap().status, Status::End);
+}
+
+#[test]
+fn test_timeout_reader_data_resets() {
+    use crate::testing::{ScriptedReader, Step};
+
+    let input = ScriptedReader::new(vec![
+        Step::Lull,
+        Step::Data(b"hello\n".to_vec()),
+        Step::Lull,
+        Step::Pending,
+        Step::Data(b"world\n".to_vec()),
+    ]);
+    let mut reader = TimeoutReader::new(input, Duration::from_secs(3600));
+    reader.set_timeout_action(TimeoutAction::Fail);
+    let mut s = String::new();
+    loop {
+        let mut buf = [0; 16];
+        let outcome = reader.read_outcome(&mut buf).unwrap();
+        s.push_str(std::str::from_utf8(&buf[..outcome.size]).unwrap());
+        if outcome.status.is_end() {
+            break;
+        }
+    }
+    assert_eq!(s, "hello\nworld\n");
+}
+
+#[cfg(not(windows))]
+#[test]
+fn test_timeout_reader_polling() {
+    use crate::StdReader;
+    use std::{io::Write as _, os::unix::net::UnixStream};
+
+    let (mut client, server) = UnixStream::pair().unwrap();
+    let mut reader =
+        TimeoutReader::polling(StdReader::line_by_line(server), Duration::from_millis(50));
+    reader.set_timeout_action(TimeoutAction::Fail);
+    client.write_all(b"hello\n").unwrap();
+    let mut buf = [0; 16];
+    let outcome = reader.read_outcome(&mut buf).unwrap();
+    assert_eq!(&buf[..outcome.size], b"hello\n");
+    assert_eq!(outcome.status, Status::Open(Readiness::Lull));
+
+    // The client is silent, but hasn't closed the stream.
+    let start = Instant::now();
+    let err = reader.read_outcome(&mut buf).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::TimedOut);
+    assert!(start.elapsed() >= Duration::from_millis(50));
+    assert_eq!(reader.read_outcome(&mut buf).unwrap().status, Status::End);
+}
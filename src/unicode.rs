@@ -1,3 +1,5 @@
+#[cfg(feature = "text")]
+use std::{convert::TryInto, mem};
 use unicode_normalization::char::canonical_combining_class;
 
 /// The size of the longest UTF-8 scalar value encoding. Note that even though
@@ -11,15 +13,19 @@ pub(crate) const MAX_UTF8_SIZE: usize = 4;
 const MAX_NONSTARTERS: usize = 30;
 
 // Enough for a composed start, a long sequence of nonstarters, followed by a
-// composed end.
+// composed end. The Stream-Safe Text Process counts nonstarters in their
+// compatibility decompositions, so this suffices for all the normalization
+// forms.
 pub(crate) const NORMALIZATION_BUFFER_LEN: usize = 2 + MAX_NONSTARTERS + 2;
 
-/// The minimum size of a buffer needed to perform NFC normalization,
+/// The minimum size of a buffer needed to perform normalization,
 /// and thus the minimum size needed to pass to
-/// [`TextReader::read`](crate::TextReader::read).
+/// [`TextReader::read`](crate::TextReader::read) to avoid having the output
+/// staged in an internal buffer.
 pub const NORMALIZATION_BUFFER_SIZE: usize = MAX_UTF8_SIZE * NORMALIZATION_BUFFER_LEN;
 
 /// ASCII FF, known as '\f' in some contexts.
+#[cfg(feature = "text")]
 pub(crate) const FF: char = '\u{c}';
 
 /// ASCII ESC, known as '\e' in some contexts.
@@ -37,3 +43,86 @@ pub(crate) const REPL: char = '\u{fffd}';
 pub(crate) fn is_normalization_form_starter(c: char) -> bool {
     canonical_combining_class(c) == 0
 }
+
+/// Test whether `s` may be used in place of U+FFFD as a replacement: it must
+/// fit in the minimum read buffer, and consist of normalization-form starters
+/// which are neither control codes nor U+FEFF, so that the output still
+/// satisfies the invariants of `Utf8Reader` and `TextReader`.
+pub(crate) fn is_valid_replacement(s: &str) -> bool {
+    s.len() <= MAX_UTF8_SIZE
+        && s.chars()
+            .all(|c| is_normalization_form_starter(c) && !c.is_control() && c != BOM)
+}
+
+/// Test whether `c` has the [Default_Ignorable_Code_Point] property, meaning
+/// that it's normally rendered invisibly, if it's supported at all.
+///
+/// [Default_Ignorable_Code_Point]: https://www.unicode.org/reports/tr44/#Default_Ignorable_Code_Point
+pub(crate) fn is_default_ignorable(c: char) -> bool {
+    matches!(c,
+        '\u{ad}'
+        | '\u{34f}'
+        | '\u{61c}'
+        | '\u{115f}'..='\u{1160}'
+        | '\u{17b4}'..='\u{17b5}'
+        | '\u{180b}'..='\u{180f}'
+        | '\u{200b}'..='\u{200f}'
+        | '\u{202a}'..='\u{202e}'
+        | '\u{2060}'..='\u{206f}'
+        | '\u{3164}'
+        | '\u{fe00}'..='\u{fe0f}'
+        | '\u{feff}'
+        | '\u{ffa0}'
+        | '\u{fff0}'..='\u{fff8}'
+        | '\u{1bca0}'..='\u{1bca3}'
+        | '\u{1d173}'..='\u{1d17a}'
+        | '\u{e0000}'..='\u{e0fff}'
+    )
+}
+
+/// Return the length of the run of printable ASCII, ' ' through '~', at the
+/// start of `bytes`.
+///
+/// This checks a word at a time, in the manner of `memchr`, since plain
+/// ASCII text is typically mostly such runs.
+#[cfg(feature = "text")]
+pub(crate) fn printable_ascii_len(bytes: &[u8]) -> usize {
+    const WORD: usize = mem::size_of::<usize>();
+    const ONES: usize = usize::MAX / 0xff;
+    const HIGHS: usize = ONES * 0x80;
+
+    let mut len = 0;
+    while let Some(chunk) = bytes.get(len..len + WORD) {
+        let word = usize::from_ne_bytes(chunk.try_into().unwrap());
+        // Bytes at or above 0x80, bytes below ' ', and DEL.
+        let non_ascii = word & HIGHS;
+        let below_space = word.wrapping_sub(ONES * 0x20) & !word & HIGHS;
+        let del = (word ^ (ONES * 0x7f)).wrapping_sub(ONES) & !(word ^ (ONES * 0x7f)) & HIGHS;
+        if non_ascii | below_space | del != 0 {
+            break;
+        }
+        len += WORD;
+    }
+    len + bytes[len..]
+        .iter()
+        .take_while(|b| matches!(b, b' '..=b'~'))
+        .count()
+}
+
+#[cfg(feature = "text")]
+#[test]
+fn test_printable_ascii_len() {
+    assert_eq!(printable_ascii_len(b""), 0);
+    assert_eq!(printable_ascii_len(b"hello"), 5);
+    assert_eq!(printable_ascii_len(b"hello world, this is plain"), 26);
+    for special in [
+        0_u8, b'\t', b'\n', b'\r', 0x1b, 0x1f, 0x7f, 0x80, 0xc3, 0xff,
+    ] {
+        for pos in 0..20 {
+            let mut bytes = vec![b'~'; 20];
+            bytes[pos] = special;
+            bytes[0] = if pos == 0 { special } else { b' ' };
+            assert_eq!(printable_ascii_len(&bytes), pos);
+        }
+    }
+}
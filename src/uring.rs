@@ -0,0 +1,149 @@
+//! A minimal io_uring wrapper which keeps at most one read or write in
+//! flight, shared by `UringReader` and `UringWriter`.
+
+use io_uring::{opcode, squeue, types, IoUring};
+use std::{
+    convert::TryFrom,
+    io, mem,
+    os::unix::io::{AsRawFd, RawFd},
+};
+
+/// The `user_data` of the read or write in flight.
+const OPERATION: u64 = 0;
+
+/// The `user_data` of a cancellation request.
+const CANCEL: u64 = 1;
+
+/// An offset which tells the kernel to use, and advance, the file position,
+/// as `read(2)` and `write(2)` do.
+const CURRENT_POSITION: u64 = u64::MAX;
+
+/// An io_uring instance for operations on a single file descriptor.
+pub(crate) struct Uring {
+    ring: IoUring,
+
+    /// The file descriptor to read from or write to.
+    fd: RawFd,
+
+    /// The file descriptor is in non-blocking mode.
+    nowait: bool,
+
+    /// The buffer of the operation in flight, which the kernel may be
+    /// accessing until its completion is reaped.
+    in_flight: Option<Vec<u8>>,
+}
+
+impl Uring {
+    /// Set up a ring for operations on `fd`.
+    pub(crate) fn new<Fd: AsRawFd>(fd: &Fd) -> io::Result<Self> {
+        Ok(Self {
+            // One entry for the operation, and one for cancelling it.
+            ring: IoUring::new(2)?,
+            fd: fd.as_raw_fd(),
+            nowait: is_nonblocking(fd),
+            in_flight: None,
+        })
+    }
+
+    /// Test whether an operation is in flight.
+    #[inline]
+    pub(crate) fn is_in_flight(&self) -> bool {
+        self.in_flight.is_some()
+    }
+
+    /// Submit a read which fills `buf`, taking ownership of it until the
+    /// read completes. If the file descriptor is in non-blocking mode, a
+    /// read which would block fails with `io::ErrorKind::WouldBlock`, as
+    /// `read(2)` would, rather than waiting in the kernel.
+    pub(crate) fn read(&mut self, mut buf: Vec<u8>) -> io::Result<()> {
+        let len = u32::try_from(buf.len()).unwrap_or(u32::MAX);
+        let entry = opcode::Read::new(types::Fd(self.fd), buf.as_mut_ptr(), len)
+            .offset(CURRENT_POSITION)
+            .rw_flags(if self.nowait { libc::RWF_NOWAIT } else { 0 })
+            .build();
+        self.submit(entry, buf)
+    }
+
+    /// Submit a write of `buf[pos..]`, taking ownership of `buf` until the
+    /// write completes.
+    pub(crate) fn write(&mut self, buf: Vec<u8>, pos: usize) -> io::Result<()> {
+        let len = u32::try_from(buf.len() - pos).unwrap_or(u32::MAX);
+        let entry = opcode::Write::new(types::Fd(self.fd), buf[pos..].as_ptr(), len)
+            .offset(CURRENT_POSITION)
+            .build();
+        self.submit(entry, buf)
+    }
+
+    fn submit(&mut self, entry: squeue::Entry, buf: Vec<u8>) -> io::Result<()> {
+        assert!(!self.is_in_flight());
+        unsafe { self.ring.submission().push(&entry.user_data(OPERATION)) }
+            .map_err(|_| io::Error::other("submission queue is full"))?;
+        // The entry is queued, so the buffer must outlive it even if the
+        // submission below fails; waiting submits it again.
+        self.in_flight = Some(buf);
+        self.ring.submit()?;
+        Ok(())
+    }
+
+    /// Return the buffer and result of the operation in flight, if it has
+    /// completed, without waiting.
+    pub(crate) fn poll(&mut self) -> Option<(Vec<u8>, io::Result<usize>)> {
+        for entry in self.ring.completion() {
+            if entry.user_data() == OPERATION {
+                let buf = self.in_flight.take().unwrap();
+                return Some((buf, completion_result(entry.result())));
+            }
+        }
+        None
+    }
+
+    /// Wait for the operation in flight to complete, and return its buffer
+    /// and result.
+    pub(crate) fn wait(&mut self) -> io::Result<(Vec<u8>, io::Result<usize>)> {
+        assert!(self.is_in_flight());
+        loop {
+            if let Some(completion) = self.poll() {
+                return Ok(completion);
+            }
+            self.ring.submit_and_wait(1)?;
+        }
+    }
+}
+
+impl Drop for Uring {
+    fn drop(&mut self) {
+        if !self.is_in_flight() {
+            return;
+        }
+        let cancel = opcode::AsyncCancel::new(OPERATION)
+            .build()
+            .user_data(CANCEL);
+        let _ = unsafe { self.ring.submission().push(&cancel) };
+        loop {
+            match self.wait() {
+                Ok(_) => return,
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+                Err(_) => break,
+            }
+        }
+        // We couldn't confirm that the kernel is done with the buffer, so
+        // leak it rather than free it.
+        mem::forget(self.in_flight.take());
+    }
+}
+
+/// Test whether `fd` is in non-blocking mode.
+fn is_nonblocking<Fd: AsRawFd>(fd: &Fd) -> bool {
+    let flags = unsafe { libc::fcntl(fd.as_raw_fd(), libc::F_GETFL) };
+    flags != -1 && (flags & libc::O_NONBLOCK) == libc::O_NONBLOCK
+}
+
+/// Convert a completion-queue result into the `io::Result` the
+/// corresponding system call would produce.
+fn completion_result(result: i32) -> io::Result<usize> {
+    if result < 0 {
+        Err(io::Error::from_raw_os_error(-result))
+    } else {
+        Ok(result as usize)
+    }
+}
@@ -0,0 +1,241 @@
+use crate::{uring::Uring, Read, ReadOutcome};
+use std::{
+    io, mem,
+    os::unix::io::{AsFd, AsRawFd, BorrowedFd, RawFd},
+};
+
+/// The size of each read submitted to the ring.
+const BUFFER_SIZE: usize = 64 * 1024;
+
+/// A `Read` implementation which reads from a file descriptor using
+/// io_uring.
+///
+/// After each read, the next one is submitted to the kernel ahead of time,
+/// so that it can proceed while the consumer processes the data. Completion
+/// results are mapped as a `read` system call's would be: a zero-length
+/// read is the end of the stream, and if the file descriptor is in
+/// non-blocking mode, a read which would block is reported as
+/// `Readiness::Pending`.
+///
+/// Since data is read ahead, the file descriptor shouldn't be read from by
+/// other means while the `UringReader` is in use, and data which has been
+/// read ahead but not consumed is discarded by `into_inner`.
+pub struct UringReader<Inner: AsRawFd> {
+    /// The ring, with the read ahead in flight.
+    uring: Uring,
+
+    /// The wrapped file descriptor.
+    inner: Inner,
+
+    /// The most recently completed read.
+    buf: Vec<u8>,
+
+    /// The range of `buf` which hasn't been consumed yet.
+    pos: usize,
+    end: usize,
+
+    /// The buffer for the next read, when one isn't in flight.
+    spare: Vec<u8>,
+
+    /// A read has reported the end of the stream.
+    ended: bool,
+}
+
+impl<Inner: AsRawFd> UringReader<Inner> {
+    /// Construct a new instance of `UringReader` wrapping `inner`. This
+    /// fails if io_uring isn't available, such as on kernels older than
+    /// 5.6 or where it's disabled.
+    pub fn new(inner: Inner) -> io::Result<Self> {
+        Ok(Self {
+            uring: Uring::new(&inner)?,
+            inner,
+            buf: vec![0; BUFFER_SIZE],
+            pos: 0,
+            end: 0,
+            spare: vec![0; BUFFER_SIZE],
+            ended: false,
+        })
+    }
+
+    /// Gets a reference to the underlying reader.
+    #[inline]
+    pub fn get_ref(&self) -> &Inner {
+        &self.inner
+    }
+
+    /// Gets a mutable reference to the underlying reader.
+    ///
+    /// It is inadvisable to directly read from the underlying reader.
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut Inner {
+        &mut self.inner
+    }
+
+    /// Consume this `UringReader`, cancelling any read in flight, and return
+    /// the underlying stream.
+    #[inline]
+    pub fn into_inner(self) -> Inner {
+        self.inner
+    }
+
+    /// Submit a read into the spare buffer.
+    fn submit(&mut self) -> io::Result<()> {
+        self.uring.read(mem::take(&mut self.spare))
+    }
+}
+
+impl<Inner: AsRawFd> Read for UringReader<Inner> {
+    fn read_outcome(&mut self, buf: &mut [u8]) -> io::Result<ReadOutcome> {
+        if self.pos == self.end {
+            if self.ended {
+                return Ok(ReadOutcome::end(0));
+            }
+            if !self.uring.is_in_flight() {
+                self.submit()?;
+            }
+            let (completed, result) = self.uring.wait()?;
+            match result {
+                Ok(0) => {
+                    self.spare = completed;
+                    self.ended = true;
+                    return Ok(ReadOutcome::end(0));
+                }
+                Ok(size) => {
+                    self.spare = mem::replace(&mut self.buf, completed);
+                    self.pos = 0;
+                    self.end = size;
+                }
+                Err(err) => {
+                    self.spare = completed;
+                    if err.kind() == io::ErrorKind::WouldBlock {
+                        return Ok(ReadOutcome::pending(0));
+                    }
+                    return Err(err);
+                }
+            }
+        }
+
+        // Read ahead while the consumer processes what we have.
+        if !self.uring.is_in_flight() {
+            self.submit()?;
+        }
+
+        let size = buf.len().min(self.end - self.pos);
+        buf[..size].copy_from_slice(&self.buf[self.pos..self.pos + size]);
+        self.pos += size;
+        Ok(ReadOutcome::ready(size))
+    }
+}
+
+impl<Inner: AsRawFd> io::Read for UringReader<Inner> {
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        Read::read(self, buf)
+    }
+
+    #[inline]
+    fn read_vectored(&mut self, bufs: &mut [io::IoSliceMut<'_>]) -> io::Result<usize> {
+        Read::read_vectored(self, bufs)
+    }
+
+    #[cfg(feature = "nightly")]
+    #[inline]
+    fn is_read_vectored(&self) -> bool {
+        Read::is_read_vectored(self)
+    }
+
+    #[inline]
+    fn read_to_end(&mut self, buf: &mut Vec<u8>) -> io::Result<usize> {
+        Read::read_to_end(self, buf)
+    }
+
+    #[inline]
+    fn read_to_string(&mut self, buf: &mut String) -> io::Result<usize> {
+        Read::read_to_string(self, buf)
+    }
+
+    #[inline]
+    fn read_exact(&mut self, buf: &mut [u8]) -> io::Result<()> {
+        Read::read_exact(self, buf)
+    }
+}
+
+impl<Inner: AsRawFd> AsRawFd for UringReader<Inner> {
+    #[inline]
+    fn as_raw_fd(&self) -> RawFd {
+        self.inner.as_raw_fd()
+    }
+}
+
+impl<Inner: AsRawFd + AsFd> AsFd for UringReader<Inner> {
+    #[inline]
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.inner.as_fd()
+    }
+}
+
+#[test]
+fn test_uring_reader() {
+    use crate::Status;
+    use std::{io::Write as _, os::unix::net::UnixStream, thread};
+
+    let (mut client, server) = UnixStream::pair().unwrap();
+    let mut reader = match UringReader::new(server) {
+        Ok(reader) => reader,
+        // io_uring isn't available here.
+        Err(_) => return,
+    };
+    let writer = thread::spawn(move || {
+        for _ in 0..1000 {
+            client.write_all(b"hello world\n").unwrap();
+        }
+    });
+    let mut s = String::new();
+    reader.read_to_string(&mut s).unwrap();
+    writer.join().unwrap();
+    assert_eq!(s, "hello world\n".repeat(1000));
+    assert_eq!(
+        reader.read_outcome(&mut [0; 4]).unwrap().status,
+        Status::End
+    );
+}
+
+#[test]
+fn test_uring_reader_file() {
+    let file = std::fs::File::open("src/uring_reader.rs").unwrap();
+    let mut reader = match UringReader::new(file) {
+        Ok(reader) => reader,
+        Err(_) => return,
+    };
+    let mut v = Vec::new();
+    reader.read_to_end(&mut v).unwrap();
+    assert_eq!(v, include_bytes!("uring_reader.rs"));
+}
+
+#[test]
+fn test_uring_reader_pending() {
+    use crate::{Readiness, Status};
+    use std::{io::Write as _, os::unix::net::UnixStream};
+
+    let (mut client, server) = UnixStream::pair().unwrap();
+    server.set_nonblocking(true).unwrap();
+    let mut reader = match UringReader::new(server) {
+        Ok(reader) => reader,
+        Err(_) => return,
+    };
+    let mut buf = [0; 16];
+    let outcome = reader.read_outcome(&mut buf).unwrap();
+    assert_eq!(outcome.size, 0);
+    assert_eq!(outcome.status, Status::Open(Readiness::Pending));
+    client.write_all(b"hello\n").unwrap();
+    let outcome = reader.read_outcome(&mut buf).unwrap();
+    assert_eq!(&buf[..outcome.size], b"hello\n");
+    assert_eq!(outcome.status, Status::Open(Readiness::Ready));
+    drop(client);
+    let mut outcome = reader.read_outcome(&mut buf).unwrap();
+    while outcome.status == Status::Open(Readiness::Pending) {
+        outcome = reader.read_outcome(&mut buf).unwrap();
+    }
+    assert_eq!(outcome.size, 0);
+    assert_eq!(outcome.status, Status::End);
+}
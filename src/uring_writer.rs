@@ -0,0 +1,260 @@
+use crate::{uring::Uring, Close, Error, Readiness, Status, Write};
+use std::{
+    io, mem,
+    os::unix::io::{AsFd, AsRawFd, BorrowedFd, RawFd},
+};
+
+/// The amount of data which may be buffered while a write is in flight
+/// before writes wait for it to complete.
+const BUFFER_SIZE: usize = 64 * 1024;
+
+/// A `Write` implementation which writes to a file descriptor using
+/// io_uring.
+///
+/// Written data is submitted to the kernel without waiting for it to be
+/// written, and data written in the meantime is buffered and submitted when
+/// the write in flight completes, so that the producer can proceed while
+/// the kernel writes. Short writes are resubmitted, and a flush with
+/// `Status::Open(Readiness::Lull)` or `Status::End` waits for everything to
+/// be written.
+///
+/// Since writes complete asynchronously, errors are reported by a later
+/// write or flush, and by the flush with `Status::End` at the latest. After
+/// an error, the stream is ended.
+///
+/// Data which hasn't been flushed when a `UringWriter` is dropped is
+/// discarded, and a write in flight is cancelled; use `flush` or
+/// `close_into_inner` to write it.
+pub struct UringWriter<Inner: AsRawFd> {
+    /// The ring, with the write in flight.
+    uring: Uring,
+
+    /// The wrapped file descriptor.
+    inner: Inner,
+
+    /// Data which has been written but not yet submitted.
+    buf: Vec<u8>,
+
+    /// An empty buffer for reuse, when it isn't in flight.
+    spare: Vec<u8>,
+
+    /// How much of the buffer in flight has been written by previous
+    /// submissions of it.
+    written: usize,
+
+    /// The stream has ended, or an error has occurred.
+    ended: bool,
+}
+
+impl<Inner: AsRawFd> UringWriter<Inner> {
+    /// Construct a new instance of `UringWriter` wrapping `inner`. This
+    /// fails if io_uring isn't available, such as on kernels older than
+    /// 5.6 or where it's disabled.
+    pub fn new(inner: Inner) -> io::Result<Self> {
+        Ok(Self {
+            uring: Uring::new(&inner)?,
+            inner,
+            buf: Vec::new(),
+            spare: Vec::new(),
+            written: 0,
+            ended: false,
+        })
+    }
+
+    /// Gets a reference to the underlying writer.
+    #[inline]
+    pub fn get_ref(&self) -> &Inner {
+        &self.inner
+    }
+
+    /// Gets a mutable reference to the underlying writer.
+    ///
+    /// It is inadvisable to directly write to the underlying writer.
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut Inner {
+        &mut self.inner
+    }
+
+    /// Flush and close the underlying stream and return the underlying
+    /// stream object.
+    pub fn close_into_inner(mut self) -> io::Result<Inner> {
+        self.flush(Status::End)?;
+        Ok(self.inner)
+    }
+
+    /// Submit the buffered data, if there is any and nothing is in flight.
+    fn submit(&mut self) -> io::Result<()> {
+        if !self.uring.is_in_flight() && !self.buf.is_empty() {
+            let data = mem::replace(&mut self.buf, mem::take(&mut self.spare));
+            self.written = 0;
+            self.uring.write(data, 0)?;
+        }
+        Ok(())
+    }
+
+    /// Handle the completion of the write in flight, resubmitting the rest
+    /// of the buffer after a short write. If `wait` is true, wait until
+    /// the buffer has been written completely.
+    fn reap(&mut self, wait: bool) -> io::Result<()> {
+        while self.uring.is_in_flight() {
+            let (mut data, result) = if wait {
+                self.uring.wait()?
+            } else {
+                match self.uring.poll() {
+                    Some(completion) => completion,
+                    None => return Ok(()),
+                }
+            };
+            match result {
+                Ok(0) => {
+                    return Err(self.fail(io::Error::new(
+                        io::ErrorKind::WriteZero,
+                        "failed to write whole buffer",
+                    )))
+                }
+                Ok(size) => {
+                    self.written += size;
+                    if self.written < data.len() {
+                        self.uring.write(data, self.written)?;
+                    } else {
+                        data.clear();
+                        self.spare = data;
+                    }
+                }
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => {
+                    self.uring.write(data, self.written)?;
+                }
+                Err(err) => return Err(self.fail(err)),
+            }
+        }
+        Ok(())
+    }
+
+    /// End the stream after an error, and return the error.
+    fn fail(&mut self, err: io::Error) -> io::Error {
+        self.ended = true;
+        self.buf.clear();
+        err
+    }
+
+    /// Wait until all the buffered data has been written.
+    fn drain(&mut self) -> io::Result<()> {
+        loop {
+            self.submit()?;
+            if !self.uring.is_in_flight() {
+                return Ok(());
+            }
+            self.reap(true)?;
+        }
+    }
+}
+
+impl<Inner: AsRawFd> Close for UringWriter<Inner> {
+    type Output = Inner;
+
+    #[inline]
+    fn close_into_inner(self) -> io::Result<Inner> {
+        UringWriter::close_into_inner(self)
+    }
+}
+
+impl<Inner: AsRawFd> Write for UringWriter<Inner> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.ended {
+            return Err(stream_already_ended());
+        }
+        self.reap(false)?;
+        if self.buf.len() >= BUFFER_SIZE {
+            self.reap(true)?;
+        }
+        self.buf.extend_from_slice(buf);
+        self.submit()?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self, status: Status) -> io::Result<()> {
+        if self.ended {
+            return Err(stream_already_ended());
+        }
+        match status {
+            Status::Open(Readiness::Ready) | Status::Open(Readiness::Pending) => {
+                self.reap(false)?;
+                self.submit()
+            }
+            Status::Open(Readiness::Lull) => self.drain(),
+            Status::End => {
+                self.drain()?;
+                self.ended = true;
+                Ok(())
+            }
+        }
+    }
+
+    #[inline]
+    fn abandon(&mut self) {
+        self.ended = true;
+        self.buf.clear();
+    }
+}
+
+impl<Inner: AsRawFd> AsRawFd for UringWriter<Inner> {
+    #[inline]
+    fn as_raw_fd(&self) -> RawFd {
+        self.inner.as_raw_fd()
+    }
+}
+
+impl<Inner: AsRawFd + AsFd> AsFd for UringWriter<Inner> {
+    #[inline]
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.inner.as_fd()
+    }
+}
+
+fn stream_already_ended() -> io::Error {
+    Error::StreamEnded.into()
+}
+
+#[test]
+fn test_uring_writer() {
+    use std::{io::Read as _, os::unix::net::UnixStream, thread};
+
+    let (mut client, server) = UnixStream::pair().unwrap();
+    let mut writer = match UringWriter::new(server) {
+        Ok(writer) => writer,
+        // io_uring isn't available here.
+        Err(_) => return,
+    };
+    let reader = thread::spawn(move || {
+        let mut s = String::new();
+        client.read_to_string(&mut s).unwrap();
+        s
+    });
+    for _ in 0..10000 {
+        writer.write_all(b"hello world\n").unwrap();
+    }
+    writer.flush(Status::Open(Readiness::Lull)).unwrap();
+    writer.write_all(b"goodbye\n").unwrap();
+    let server = writer.close_into_inner().unwrap();
+    drop(server);
+    assert_eq!(
+        reader.join().unwrap(),
+        format!("{}goodbye\n", "hello world\n".repeat(10000))
+    );
+}
+
+#[test]
+fn test_uring_writer_error() {
+    use std::os::unix::net::UnixStream;
+
+    let (client, server) = UnixStream::pair().unwrap();
+    let mut writer = match UringWriter::new(server) {
+        Ok(writer) => writer,
+        Err(_) => return,
+    };
+    drop(client);
+    writer.write_all(b"hello\n").unwrap();
+    let err = writer.flush(Status::End).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::BrokenPipe);
+    assert!(writer.write_all(b"more\n").is_err());
+}
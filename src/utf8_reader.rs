@@ -1,11 +1,27 @@
-use crate::{unicode::REPL, Read, ReadOutcome};
-use std::{cmp::min, io, str};
+use crate::{
+    read_str::read_into_str,
+    substitution::SubstitutionHook,
+    unicode::{is_valid_replacement, REPL},
+    Error, Read, ReadOutcome, Substitution, SubstitutionKind,
+};
+#[cfg(unix)]
+use std::os::unix::io::{AsFd, AsRawFd, BorrowedFd, RawFd};
+#[cfg(windows)]
+use std::os::windows::io::{AsHandle, AsRawHandle, BorrowedHandle, RawHandle};
+use std::{
+    cmp::min,
+    io::{self, Seek, SeekFrom},
+    str,
+};
 
 /// A `Read` implementation which translates from an input `Read` producing
 /// an arbitrary byte sequence into a valid UTF-8 sequence with invalid
 /// sequences replaced by U+FFFD (REPLACEMENT CHARACTER) in the manner of
 /// `String::from_utf8_lossy`, where scalar value encodings never straddle `read`
 /// calls (callers can do `str::from_utf8` and it will always succeed).
+///
+/// Use `Utf8ReaderBuilder` to fail on invalid sequences instead, or to
+/// configure the replacement.
 pub struct Utf8Reader<Inner: Read> {
     /// The wrapped byte stream.
     inner: Inner,
@@ -13,26 +29,121 @@ pub struct Utf8Reader<Inner: Read> {
     /// A queue of bytes which have not been read but which have not been
     /// translated into the output yet.
     overflow: Vec<u8>,
+
+    /// The total number of bytes read from the inner stream.
+    inner_pos: u64,
+
+    /// The number of invalid sequences replaced so far.
+    replacements: u64,
+
+    /// The options this reader was configured with.
+    options: Utf8ReaderBuilder,
+
+    /// The hook to call with each substitution.
+    hook: Option<SubstitutionHook>,
+
+    /// The offset in the inner stream of the data output by the most
+    /// recent read.
+    read_start: u64,
+
+    /// Pairs of an index in the output of the most recent read, following
+    /// a replacement, and the corresponding offset in the inner stream.
+    read_anchors: Vec<(usize, u64)>,
 }
 
 impl<Inner: Read> Utf8Reader<Inner> {
     /// Construct a new instance of `Utf8Reader` wrapping `inner`.
     #[inline]
     pub fn new(inner: Inner) -> Self {
-        Self {
-            inner,
-            overflow: Vec::new(),
-        }
+        Utf8ReaderBuilder::new().build(inner)
+    }
+
+    /// Consume this `Utf8Reader` and return the underlying stream object.
+    /// Any data which has been read from the underlying stream but not yet
+    /// consumed is discarded.
+    #[inline]
+    pub fn into_inner(self) -> Inner {
+        self.inner
+    }
+
+    /// Consume this `Utf8Reader` and return the underlying stream object,
+    /// along with any bytes which have been read from the underlying stream
+    /// but not yet consumed, untranslated. This allows a stream to switch
+    /// from UTF-8 to another format mid-stream.
+    #[inline]
+    pub fn into_parts(self) -> (Inner, Vec<u8>) {
+        (self.inner, self.overflow)
+    }
+
+    /// Gets a reference to the underlying reader.
+    #[inline]
+    pub fn get_ref(&self) -> &Inner {
+        &self.inner
+    }
+
+    /// Gets a mutable reference to the underlying reader.
+    ///
+    /// It is inadvisable to directly read from the underlying reader, as
+    /// data buffered by this reader would then be out of order with it.
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut Inner {
+        &mut self.inner
     }
 
     /// Like `read` but produces the result in a `str`. Be sure to check
     /// the `size` field of the return value to see how many bytes were written.
     pub fn read_utf8(&mut self, buf: &mut str) -> io::Result<ReadOutcome> {
-        let outcome = unsafe { self.read_outcome(buf.as_bytes_mut()) }?;
+        read_into_str(self, buf)
+    }
+
+    /// Return the number of invalid sequences replaced so far.
+    #[inline]
+    pub fn replacements(&self) -> u64 {
+        self.replacements
+    }
+
+    /// Install a hook which is called for each invalid sequence replaced,
+    /// with the original bytes and their offset in the inner stream, so that
+    /// tools can report precisely what was altered.
+    pub fn set_substitution_hook<F: FnMut(&Substitution<'_>) + Send + Sync + 'static>(
+        &mut self,
+        hook: F,
+    ) {
+        self.hook = Some(Box::new(hook));
+    }
 
-        debug_assert!(buf.is_char_boundary(outcome.size));
+    /// Test whether a substitution hook is installed.
+    #[cfg(feature = "text")]
+    #[inline]
+    pub(crate) fn has_substitution_hook(&self) -> bool {
+        self.hook.is_some()
+    }
+
+    /// Call the substitution hook, if there is one.
+    #[cfg(feature = "text")]
+    pub(crate) fn report_substitution(
+        &mut self,
+        kind: SubstitutionKind,
+        original: &[u8],
+        offset: u64,
+    ) {
+        if let Some(hook) = &mut self.hook {
+            hook(&Substitution {
+                kind,
+                original,
+                offset,
+            });
+        }
+    }
 
-        Ok(outcome)
+    /// Return the offset in the inner stream of the byte at `index` in the
+    /// output of the most recent read.
+    #[cfg(feature = "text")]
+    pub(crate) fn offset_in_last_read(&self, index: usize) -> u64 {
+        match self.read_anchors.iter().rev().find(|(i, _)| *i <= index) {
+            Some((i, offset)) => offset + (index - i) as u64,
+            None => self.read_start + index as u64,
+        }
     }
 }
 
@@ -48,18 +159,27 @@ impl<Inner: Read> Read for Utf8Reader<Inner> {
         }
 
         let mut nread = 0;
+        self.read_start = self.inner_pos - self.overflow.len() as u64;
+        self.read_anchors.clear();
 
         if !self.overflow.is_empty() {
-            nread += self
-                .process_overflow(&mut buf[nread..], IncompleteHow::Include)
-                .unwrap();
+            nread = self.process_overflow(buf, nread, IncompleteHow::Include)?;
             if !self.overflow.is_empty() {
                 return Ok(ReadOutcome::ready(nread));
             }
         }
 
-        let outcome = self.inner.read_outcome(&mut buf[nread..])?;
+        let outcome = match self.inner.read_outcome(&mut buf[nread..]) {
+            Ok(outcome) => outcome,
+            Err(e) => {
+                // Put back any incomplete sequence taken from the overflow,
+                // so that it isn't lost if the caller retries.
+                self.overflow.extend_from_slice(&buf[..nread]);
+                return Err(e);
+            }
+        };
         nread += outcome.size;
+        self.inner_pos += outcome.size as u64;
 
         match str::from_utf8(&buf[..nread]) {
             Ok(_) => Ok(ReadOutcome {
@@ -78,9 +198,7 @@ impl<Inner: Read> Read for Utf8Reader<Inner> {
                 } else {
                     IncompleteHow::Exclude
                 };
-                nread += self
-                    .process_overflow(&mut buf[nread..], incomplete_how)
-                    .ok_or_else(|| io::Error::new(io::ErrorKind::Other, "invalid UTF-8"))?;
+                nread = self.process_overflow(buf, nread, incomplete_how)?;
                 if self.overflow.is_empty() {
                     Ok(ReadOutcome {
                         size: nread,
@@ -94,6 +212,18 @@ impl<Inner: Read> Read for Utf8Reader<Inner> {
     }
 }
 
+impl<Inner: Read> crate::ReadStr for Utf8Reader<Inner> {
+    #[inline]
+    fn read_str(&mut self, buf: &mut str) -> io::Result<ReadOutcome> {
+        self.read_utf8(buf)
+    }
+
+    #[inline]
+    fn read_to_string(&mut self, buf: &mut String) -> io::Result<usize> {
+        Read::read_to_string(self, buf)
+    }
+}
+
 impl<Inner: Read> io::Read for Utf8Reader<Inner> {
     #[inline]
     fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
@@ -127,15 +257,38 @@ impl<Inner: Read> io::Read for Utf8Reader<Inner> {
     }
 }
 
+/// Seeking discards any bytes which have been read from the inner stream but
+/// not yet translated. Positions are in terms of the inner stream.
+impl<Inner: Read + Seek> Seek for Utf8Reader<Inner> {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        // Account for bytes we've read ahead of the caller.
+        let pos = match pos {
+            SeekFrom::Current(offset) => SeekFrom::Current(offset - self.overflow.len() as i64),
+            pos => pos,
+        };
+        let pos = self.inner.seek(pos)?;
+        self.overflow.clear();
+        self.inner_pos = pos;
+        Ok(pos)
+    }
+}
+
 impl<Inner: Read> Utf8Reader<Inner> {
     /// If normal reading encounters invalid bytes, the data is copied into
-    /// `self.overflow` as it may need to expand to make room for the U+FFFD's,
-    /// and we may need to hold on to some of it until the next `read` call.
+    /// `self.overflow` as it may need to expand to make room for the
+    /// replacements, and we may need to hold on to some of it until the next
+    /// `read` call. Output is appended to `buf` starting at `nread`, and the
+    /// new total is returned.
     ///
     /// TODO: This code could be significantly optimized.
     #[cold]
-    fn process_overflow(&mut self, buf: &mut [u8], incomplete_how: IncompleteHow) -> Option<usize> {
-        let mut nread = 0;
+    fn process_overflow(
+        &mut self,
+        buf: &mut [u8],
+        mut nread: usize,
+        incomplete_how: IncompleteHow,
+    ) -> io::Result<usize> {
+        let repl_len = self.options.replacement.len();
 
         loop {
             let num = min(buf[nread..].len(), self.overflow.len());
@@ -156,31 +309,45 @@ impl<Inner: Read> Utf8Reader<Inner> {
                     nread += valid_len;
 
                     if let Some(invalid_sequence_length) = error.error_len() {
-                        if REPL.len_utf8() <= buf[nread..].len() {
-                            nread += REPL.encode_utf8(&mut buf[nread..]).len();
+                        if repl_len <= buf[nread..].len() {
+                            if !self.replace_invalid(nread, invalid_sequence_length)? {
+                                break;
+                            }
+                            buf[nread..nread + repl_len]
+                                .copy_from_slice(self.options.replacement.as_bytes());
+                            nread += repl_len;
                             self.overflow.copy_within(invalid_sequence_length.., 0);
                             self.overflow
                                 .resize(self.overflow.len() - invalid_sequence_length, 0);
+                            self.read_anchors
+                                .push((nread, self.inner_pos - self.overflow.len() as u64));
                             continue;
                         }
                     } else {
                         match incomplete_how {
                             IncompleteHow::Replace => {
-                                if REPL.len_utf8() <= buf[nread..].len() {
-                                    nread += REPL.encode_utf8(&mut buf[nread..]).len();
+                                if repl_len <= buf[nread..].len() {
+                                    if !self.replace_invalid(nread, self.overflow.len())? {
+                                        break;
+                                    }
+                                    buf[nread..nread + repl_len]
+                                        .copy_from_slice(self.options.replacement.as_bytes());
+                                    nread += repl_len;
                                     self.overflow.clear();
+                                    self.read_anchors.push((nread, self.inner_pos));
                                 } else if self.overflow.is_empty() {
-                                    return None;
+                                    return Err(Error::InvalidUtf8 { offset: None }.into());
                                 }
                             }
-                            IncompleteHow::Include if after_valid_len == self.overflow.len() => {
-                                if !buf[nread..].is_empty() {
-                                    let num = min(buf[nread..].len(), after_valid_len);
-                                    buf[nread..nread + num].copy_from_slice(&self.overflow[..num]);
-                                    nread += num;
-                                    self.overflow.copy_within(num.., 0);
-                                    self.overflow.resize(self.overflow.len() - num, 0);
-                                }
+                            IncompleteHow::Include
+                                if after_valid_len == self.overflow.len()
+                                    && !buf[nread..].is_empty() =>
+                            {
+                                let num = min(buf[nread..].len(), after_valid_len);
+                                buf[nread..nread + num].copy_from_slice(&self.overflow[..num]);
+                                nread += num;
+                                self.overflow.copy_within(num.., 0);
+                                self.overflow.resize(self.overflow.len() - num, 0);
                             }
                             _ => {}
                         }
@@ -190,7 +357,133 @@ impl<Inner: Read> Utf8Reader<Inner> {
             break;
         }
 
-        Some(nread)
+        Ok(nread)
+    }
+
+    /// Called when an invalid sequence of `len` bytes is at the front of
+    /// `self.overflow`. Returns `true` if it should be replaced, or `false`
+    /// if output should stop before it so that the error is reported on the
+    /// next read, or fails if nothing has been output yet and the error
+    /// should be reported now.
+    fn replace_invalid(&mut self, nread: usize, len: usize) -> io::Result<bool> {
+        let offset = self.inner_pos - self.overflow.len() as u64;
+        if self.options.strict {
+            if nread != 0 {
+                return Ok(false);
+            }
+            return Err(Error::InvalidUtf8 {
+                offset: Some(offset),
+            }
+            .into());
+        }
+        if let Some(max) = self.options.max_replacements {
+            if self.replacements >= max {
+                if nread != 0 {
+                    return Ok(false);
+                }
+                return Err(Error::TooManyReplacements { offset }.into());
+            }
+        }
+        self.replacements += 1;
+        if let Some(hook) = &mut self.hook {
+            hook(&Substitution {
+                kind: SubstitutionKind::InvalidUtf8,
+                original: &self.overflow[..len],
+                offset,
+            });
+        }
+        Ok(true)
+    }
+}
+
+/// A builder for `Utf8Reader`, for selecting how invalid UTF-8 is handled.
+#[derive(Clone, Debug)]
+pub struct Utf8ReaderBuilder {
+    strict: bool,
+    replacement: String,
+    max_replacements: Option<u64>,
+}
+
+impl Utf8ReaderBuilder {
+    /// Construct a new `Utf8ReaderBuilder` with the default options, which
+    /// replace invalid sequences with U+FFFD in the manner of
+    /// `String::from_utf8_lossy`.
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            strict: false,
+            replacement: REPL.to_string(),
+            max_replacements: None,
+        }
+    }
+
+    /// When enabled, invalid sequences are not replaced; reading fails with
+    /// `io::ErrorKind::InvalidData` and a message including the byte offset
+    /// of the invalid sequence in the inner stream. Data preceding the
+    /// invalid sequence is returned before the error is reported.
+    #[inline]
+    pub fn strict(&mut self, enable: bool) -> &mut Self {
+        self.strict = enable;
+        self
+    }
+
+    /// Set the string which invalid sequences are replaced with, such as
+    /// "?" for ASCII-only environments, or "" to delete them.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `replacement` is longer than 4 bytes, or contains
+    /// Unicode Normalization Form non-starters, control codes, or U+FEFF.
+    #[inline]
+    pub fn replacement(&mut self, replacement: &str) -> &mut Self {
+        assert!(
+            is_valid_replacement(replacement),
+            "invalid replacement string"
+        );
+        self.replacement = replacement.to_owned();
+        self
+    }
+
+    /// Set the maximum number of invalid sequences to replace. Once it's
+    /// exceeded, reading fails as it does in strict mode.
+    #[inline]
+    pub fn max_replacements(&mut self, max: Option<u64>) -> &mut Self {
+        self.max_replacements = max;
+        self
+    }
+
+    /// Construct a `Utf8Reader` wrapping `inner` with the configured
+    /// options.
+    #[inline]
+    pub fn build<Inner: Read>(&self, inner: Inner) -> Utf8Reader<Inner> {
+        Utf8Reader {
+            inner,
+            overflow: Vec::new(),
+            inner_pos: 0,
+            replacements: 0,
+            options: self.clone(),
+            hook: None,
+            read_start: 0,
+            read_anchors: Vec::new(),
+        }
+    }
+
+    /// Construct an `AsyncUtf8Reader` wrapping `inner` with the configured
+    /// options.
+    #[cfg(feature = "futures-io")]
+    #[inline]
+    pub fn build_async<Inner: futures_io::AsyncRead + Unpin>(
+        &self,
+        inner: Inner,
+    ) -> crate::AsyncUtf8Reader<Inner> {
+        crate::AsyncUtf8Reader::from_utf8_reader(self.build(crate::FromAsyncRead::new(inner)))
+    }
+}
+
+impl Default for Utf8ReaderBuilder {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
     }
 }
 
@@ -205,6 +498,38 @@ enum IncompleteHow {
     Replace,
 }
 
+#[cfg(unix)]
+impl<Inner: Read + AsRawFd> AsRawFd for Utf8Reader<Inner> {
+    #[inline]
+    fn as_raw_fd(&self) -> RawFd {
+        self.inner.as_raw_fd()
+    }
+}
+
+#[cfg(unix)]
+impl<Inner: Read + AsFd> AsFd for Utf8Reader<Inner> {
+    #[inline]
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.inner.as_fd()
+    }
+}
+
+#[cfg(windows)]
+impl<Inner: Read + AsRawHandle> AsRawHandle for Utf8Reader<Inner> {
+    #[inline]
+    fn as_raw_handle(&self) -> RawHandle {
+        self.inner.as_raw_handle()
+    }
+}
+
+#[cfg(windows)]
+impl<Inner: Read + AsHandle> AsHandle for Utf8Reader<Inner> {
+    #[inline]
+    fn as_handle(&self) -> BorrowedHandle<'_> {
+        self.inner.as_handle()
+    }
+}
+
 #[cfg(test)]
 fn translate_via_std_reader(bytes: &[u8]) -> String {
     let mut reader = Utf8Reader::new(crate::StdReader::generic(bytes));
@@ -260,6 +585,118 @@ fn test(bytes: &[u8], s: &str) {
     }
 }
 
+#[test]
+fn test_seek() {
+    let mut reader = Utf8Reader::new(crate::Cursor::new(b"hello\xff world"));
+    let mut buf = [0; 4];
+    reader.read_outcome(&mut buf).unwrap();
+    reader.seek(SeekFrom::Current(1)).unwrap();
+    let mut s = String::new();
+    reader.read_to_string(&mut s).unwrap();
+    assert_eq!(s, "\u{fffd} world");
+    reader.seek(SeekFrom::Start(6)).unwrap();
+    s.clear();
+    reader.read_to_string(&mut s).unwrap();
+    assert_eq!(s, " world");
+}
+
+#[test]
+fn test_into_parts() {
+    use crate::testing::{ScriptedReader, Step};
+
+    let mut reader = Utf8Reader::new(ScriptedReader::new(vec![
+        Step::Data(b"ab\xc3".to_vec()),
+        Step::Data(b"\xa9cd".to_vec()),
+    ]));
+    let mut buf = [0; 8];
+    let outcome = reader.read_outcome(&mut buf).unwrap();
+    assert_eq!(&buf[..outcome.size], b"ab");
+    let (mut inner, data) = reader.into_parts();
+    assert_eq!(data, b"\xc3");
+    let mut rest = Vec::new();
+    inner.read_to_end(&mut rest).unwrap();
+    assert_eq!(rest, b"\xa9cd");
+}
+
+#[test]
+fn test_builder_strict() {
+    let mut reader = Utf8ReaderBuilder::new()
+        .strict(true)
+        .build(crate::SliceReader::new(b"hello\xffworld"));
+    let mut buf = [0; 16];
+    let outcome = reader.read_outcome(&mut buf).unwrap();
+    assert_eq!(&buf[..outcome.size], b"hello");
+    let err = reader.read_outcome(&mut buf).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    assert_eq!(err.to_string(), "invalid UTF-8 at byte offset 5");
+
+    let mut reader = Utf8ReaderBuilder::new()
+        .strict(true)
+        .build(crate::SliceReader::new(b"hello\xc3"));
+    let mut s = String::new();
+    let err = reader.read_to_string(&mut s).unwrap_err();
+    assert_eq!(err.to_string(), "invalid UTF-8 at byte offset 5");
+}
+
+#[test]
+fn test_builder_replacement() {
+    let mut reader = Utf8ReaderBuilder::new()
+        .replacement("?")
+        .max_replacements(Some(2))
+        .build(crate::SliceReader::new(b"a\xffb\xffc\xffd"));
+    let mut buf = [0; 16];
+    let outcome = reader.read_outcome(&mut buf).unwrap();
+    assert_eq!(&buf[..outcome.size], b"a?b?c");
+    let err = reader.read_outcome(&mut buf).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "too many invalid UTF-8 sequences, at byte offset 5"
+    );
+}
+
+#[test]
+fn test_builder_delete() {
+    let mut reader = Utf8ReaderBuilder::new()
+        .replacement("")
+        .build(crate::SliceReader::new(b"a\xffb\xc3"));
+    let mut s = String::new();
+    reader.read_to_string(&mut s).unwrap();
+    assert_eq!(s, "ab");
+    assert_eq!(reader.replacements(), 2);
+}
+
+#[test]
+#[should_panic(expected = "invalid replacement string")]
+fn test_builder_invalid_replacement() {
+    Utf8ReaderBuilder::new().replacement("\u{301}");
+}
+
+#[test]
+fn test_substitution_hook() {
+    use std::sync::{Arc, Mutex};
+
+    let log = Arc::new(Mutex::new(Vec::new()));
+    let mut reader = Utf8Reader::new(crate::SliceReader::new(b"ab\xffcd\xe2\x82"));
+    let hook_log = Arc::clone(&log);
+    reader.set_substitution_hook(move |sub| {
+        hook_log
+            .lock()
+            .unwrap()
+            .push((sub.kind, sub.original.to_vec(), sub.offset))
+    });
+    let mut s = String::new();
+    reader.read_to_string(&mut s).unwrap();
+    assert_eq!(s, "ab\u{fffd}cd\u{fffd}");
+    assert_eq!(reader.replacements(), 2);
+    assert_eq!(
+        *log.lock().unwrap(),
+        vec![
+            (SubstitutionKind::InvalidUtf8, b"\xff".to_vec(), 2),
+            (SubstitutionKind::InvalidUtf8, b"\xe2\x82".to_vec(), 5),
+        ]
+    );
+}
+
 #[test]
 fn test_empty_string() {
     test(b"", "");
@@ -1,5 +1,13 @@
-use crate::{Status, Write};
-use std::{io, str};
+use crate::{write::write_vectored_utf8, Close, Error, Readiness, Status, Write};
+#[cfg(unix)]
+use std::os::unix::io::{AsFd, AsRawFd, BorrowedFd, RawFd};
+#[cfg(windows)]
+use std::os::windows::io::{AsHandle, AsRawHandle, BorrowedHandle, RawHandle};
+use std::{
+    fmt,
+    io::{self, IoSlice},
+    str,
+};
 
 /// A `Write` implementation which translates into an output `Write` producing
 /// a valid UTF-8 sequence from an arbitrary byte sequence from an arbitrary
@@ -19,6 +27,21 @@ impl<Inner: Write> Utf8Writer<Inner> {
         Self { inner }
     }
 
+    /// Gets a reference to the underlying writer.
+    #[inline]
+    pub fn get_ref(&self) -> &Inner {
+        &self.inner
+    }
+
+    /// Gets a mutable reference to the underlying writer.
+    ///
+    /// It is inadvisable to directly write to the underlying writer, as
+    /// that bypasses the validation this writer performs.
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut Inner {
+        &mut self.inner
+    }
+
     /// Flush and close the underlying stream and return the underlying
     /// stream object.
     pub fn close_into_inner(mut self) -> io::Result<Inner> {
@@ -27,6 +50,15 @@ impl<Inner: Write> Utf8Writer<Inner> {
     }
 }
 
+impl<Inner: Write> Close for Utf8Writer<Inner> {
+    type Output = Inner;
+
+    #[inline]
+    fn close_into_inner(self) -> io::Result<Inner> {
+        Utf8Writer::close_into_inner(self)
+    }
+}
+
 impl<Inner: Write> Write for Utf8Writer<Inner> {
     fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
         match str::from_utf8(buf) {
@@ -34,9 +66,9 @@ impl<Inner: Write> Write for Utf8Writer<Inner> {
             Err(error) if error.valid_up_to() != 0 => self
                 .write_all(&buf[..error.valid_up_to()])
                 .map(|_| error.valid_up_to()),
-            Err(error) => {
+            Err(_) => {
                 self.inner.abandon();
-                Err(io::Error::new(io::ErrorKind::Other, error))
+                Err(Error::InvalidUtf8 { offset: None }.into())
             }
         }
     }
@@ -51,8 +83,95 @@ impl<Inner: Write> Write for Utf8Writer<Inner> {
         self.inner.abandon()
     }
 
+    #[inline]
+    fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) -> io::Result<usize> {
+        write_vectored_utf8(self, bufs)
+    }
+
     #[inline]
     fn write_all_utf8(&mut self, s: &str) -> io::Result<()> {
         self.inner.write_all_utf8(s)
     }
+
+    #[inline]
+    fn write_all_vectored_utf8(&mut self, bufs: &[&str]) -> io::Result<()> {
+        self.inner.write_all_vectored_utf8(bufs)
+    }
+}
+
+/// `flush` flushes with `Status::Open(Readiness::Lull)`. To declare
+/// the end of the stream, use [`Write::flush`] with `Status::End`, or
+/// `close_into_inner`.
+impl<Inner: Write> io::Write for Utf8Writer<Inner> {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        Write::write(self, buf)
+    }
+
+    #[inline]
+    fn flush(&mut self) -> io::Result<()> {
+        Write::flush(self, Status::Open(Readiness::Lull))
+    }
+
+    #[inline]
+    fn write_vectored(&mut self, bufs: &[io::IoSlice<'_>]) -> io::Result<usize> {
+        Write::write_vectored(self, bufs)
+    }
+
+    #[cfg(feature = "nightly")]
+    #[inline]
+    fn is_write_vectored(&self) -> bool {
+        Write::is_write_vectored(self)
+    }
+
+    #[inline]
+    fn write_all(&mut self, buf: &[u8]) -> io::Result<()> {
+        Write::write_all(self, buf)
+    }
+
+    #[inline]
+    fn write_fmt(&mut self, fmt: fmt::Arguments<'_>) -> io::Result<()> {
+        Write::write_fmt(self, fmt)
+    }
+}
+
+/// Formatting errors are reported as `fmt::Error`; the underlying
+/// `io::Error` isn't available through this interface.
+impl<Inner: Write> fmt::Write for Utf8Writer<Inner> {
+    #[inline]
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.write_all_utf8(s).map_err(|_| fmt::Error)
+    }
+}
+
+#[cfg(unix)]
+impl<Inner: Write + AsRawFd> AsRawFd for Utf8Writer<Inner> {
+    #[inline]
+    fn as_raw_fd(&self) -> RawFd {
+        self.inner.as_raw_fd()
+    }
+}
+
+#[cfg(unix)]
+impl<Inner: Write + AsFd> AsFd for Utf8Writer<Inner> {
+    #[inline]
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.inner.as_fd()
+    }
+}
+
+#[cfg(windows)]
+impl<Inner: Write + AsRawHandle> AsRawHandle for Utf8Writer<Inner> {
+    #[inline]
+    fn as_raw_handle(&self) -> RawHandle {
+        self.inner.as_raw_handle()
+    }
+}
+
+#[cfg(windows)]
+impl<Inner: Write + AsHandle> AsHandle for Utf8Writer<Inner> {
+    #[inline]
+    fn as_handle(&self) -> BorrowedHandle<'_> {
+        self.inner.as_handle()
+    }
 }
@@ -0,0 +1,29 @@
+use std::{ffi::c_void, os::windows::io::RawHandle};
+
+#[link(name = "kernel32")]
+extern "system" {
+    fn GetConsoleMode(console_handle: RawHandle, mode: *mut u32) -> i32;
+
+    pub(crate) fn ReadConsoleW(
+        console_input: RawHandle,
+        buffer: *mut c_void,
+        number_of_chars_to_read: u32,
+        number_of_chars_read: *mut u32,
+        input_control: *mut c_void,
+    ) -> i32;
+
+    pub(crate) fn WriteConsoleW(
+        console_output: RawHandle,
+        buffer: *const c_void,
+        number_of_chars_to_write: u32,
+        number_of_chars_written: *mut u32,
+        reserved: *mut c_void,
+    ) -> i32;
+}
+
+/// Test whether `handle` refers to a console.
+pub(crate) fn is_console(handle: RawHandle) -> bool {
+    let mut mode = 0;
+    // `GetConsoleMode` fails when the handle isn't a console.
+    unsafe { GetConsoleMode(handle, &mut mode) != 0 }
+}
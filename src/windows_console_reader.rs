@@ -0,0 +1,209 @@
+use crate::{unicode::REPL, windows_console::ReadConsoleW, Read, ReadOutcome};
+use std::{
+    char, io,
+    os::windows::io::{AsRawHandle, RawHandle},
+    ptr,
+};
+
+/// The number of UTF-16 code units to request from the console at a time.
+const UTF16_BUFFER_LEN: usize = 1024;
+
+/// The character which, typed at the beginning of a line, signals the end
+/// of console input (Ctrl-Z).
+const CTRL_Z: u16 = 0x1a;
+
+/// Reads from a Windows console using `ReadConsoleW`, which produces UTF-16
+/// independently of the console code page, and decodes it to UTF-8.
+///
+/// Console input is line-buffered, so a lull is reported at the end of each
+/// line. Ctrl-Z at the beginning of a line ends the stream.
+///
+/// [`StdReader::new`](crate::StdReader::new) uses this automatically when
+/// its handle is a console.
+pub struct WindowsConsoleReader<Inner: AsRawHandle> {
+    /// The wrapped console handle.
+    inner: Inner,
+
+    /// The decoding state.
+    input: ConsoleInput,
+}
+
+impl<Inner: AsRawHandle> WindowsConsoleReader<Inner> {
+    /// Construct a new instance of `WindowsConsoleReader` wrapping `inner`,
+    /// which should be a console input handle.
+    #[inline]
+    pub fn new(inner: Inner) -> Self {
+        let input = ConsoleInput::new(inner.as_raw_handle());
+        Self { inner, input }
+    }
+
+    /// Gets a reference to the underlying console handle.
+    #[inline]
+    pub fn get_ref(&self) -> &Inner {
+        &self.inner
+    }
+
+    /// Consume this `WindowsConsoleReader` and return the underlying console
+    /// handle.
+    #[inline]
+    pub fn into_inner(self) -> Inner {
+        self.inner
+    }
+}
+
+impl<Inner: AsRawHandle> Read for WindowsConsoleReader<Inner> {
+    #[inline]
+    fn read_outcome(&mut self, buf: &mut [u8]) -> io::Result<ReadOutcome> {
+        self.input.read_outcome(buf)
+    }
+}
+
+impl<Inner: AsRawHandle> io::Read for WindowsConsoleReader<Inner> {
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        Read::read(self, buf)
+    }
+
+    #[inline]
+    fn read_vectored(&mut self, bufs: &mut [io::IoSliceMut<'_>]) -> io::Result<usize> {
+        Read::read_vectored(self, bufs)
+    }
+
+    #[cfg(feature = "nightly")]
+    #[inline]
+    fn is_read_vectored(&self) -> bool {
+        Read::is_read_vectored(self)
+    }
+
+    #[inline]
+    fn read_to_end(&mut self, buf: &mut Vec<u8>) -> io::Result<usize> {
+        Read::read_to_end(self, buf)
+    }
+
+    #[inline]
+    fn read_to_string(&mut self, buf: &mut String) -> io::Result<usize> {
+        Read::read_to_string(self, buf)
+    }
+
+    #[inline]
+    fn read_exact(&mut self, buf: &mut [u8]) -> io::Result<()> {
+        Read::read_exact(self, buf)
+    }
+}
+
+/// The state for reading from a console handle, shared with `StdReader`.
+pub(crate) struct ConsoleInput {
+    /// The console handle, owned by whoever owns this `ConsoleInput`.
+    handle: RawHandle,
+
+    /// A high surrogate at the end of the previous read, awaiting its low
+    /// surrogate.
+    surrogate: Option<u16>,
+
+    /// Decoded UTF-8 not yet returned to the caller.
+    pending: Vec<u8>,
+
+    /// The position of the next byte in `pending` to return.
+    pos: usize,
+
+    /// The contents of `pending` end with a newline.
+    lull: bool,
+
+    /// Ctrl-Z has been read.
+    ended: bool,
+}
+
+// The handle is only used while its owner is alive, and console handles may
+// be used from any thread.
+unsafe impl Send for ConsoleInput {}
+unsafe impl Sync for ConsoleInput {}
+
+impl ConsoleInput {
+    pub(crate) fn new(handle: RawHandle) -> Self {
+        Self {
+            handle,
+            surrogate: None,
+            pending: Vec::new(),
+            pos: 0,
+            lull: false,
+            ended: false,
+        }
+    }
+
+    pub(crate) fn read_outcome(&mut self, buf: &mut [u8]) -> io::Result<ReadOutcome> {
+        if self.pos == self.pending.len() {
+            if self.ended {
+                return Ok(ReadOutcome::end(0));
+            }
+            if buf.is_empty() {
+                return Ok(ReadOutcome::ready(0));
+            }
+            match self.fill() {
+                Ok(()) => {}
+                Err(ref e) if e.kind() == io::ErrorKind::Interrupted => {
+                    return Ok(ReadOutcome::ready(0))
+                }
+                Err(e) => return Err(e),
+            }
+        }
+
+        let size = buf.len().min(self.pending.len() - self.pos);
+        buf[..size].copy_from_slice(&self.pending[self.pos..self.pos + size]);
+        self.pos += size;
+
+        if self.pos == self.pending.len() {
+            if self.ended {
+                return Ok(ReadOutcome::end(size));
+            }
+            if self.lull {
+                return Ok(ReadOutcome::lull(size));
+            }
+        }
+        Ok(ReadOutcome::ready(size))
+    }
+
+    fn fill(&mut self) -> io::Result<()> {
+        self.pending.clear();
+        self.pos = 0;
+
+        let mut utf16 = [0_u16; UTF16_BUFFER_LEN];
+        let start = match self.surrogate.take() {
+            Some(high) => {
+                utf16[0] = high;
+                1
+            }
+            None => 0,
+        };
+        let mut num_read = 0;
+        if unsafe {
+            ReadConsoleW(
+                self.handle,
+                utf16[start..].as_mut_ptr().cast(),
+                (utf16.len() - start) as u32,
+                &mut num_read,
+                ptr::null_mut(),
+            )
+        } == 0
+        {
+            return Err(io::Error::last_os_error());
+        }
+
+        let mut len = start + num_read as usize;
+        if num_read != 0 && utf16[start] == CTRL_Z {
+            self.ended = true;
+            len = start;
+        } else if len != 0 && (0xd800..0xdc00).contains(&utf16[len - 1]) {
+            self.surrogate = Some(utf16[len - 1]);
+            len -= 1;
+        }
+
+        let mut bytes = [0_u8; 4];
+        for c in char::decode_utf16(utf16[..len].iter().copied()) {
+            let c = c.unwrap_or(REPL);
+            self.pending
+                .extend_from_slice(c.encode_utf8(&mut bytes).as_bytes());
+        }
+        self.lull = self.pending.last() == Some(&b'\n');
+        Ok(())
+    }
+}
@@ -0,0 +1,196 @@
+use crate::{
+    unicode::MAX_UTF8_SIZE,
+    windows_console::{is_console, WriteConsoleW},
+    Close, Error, Readiness, Status, Write,
+};
+use std::{io, os::windows::io::AsRawHandle, ptr, str};
+
+/// Writes to a Windows console using `WriteConsoleW`, converting UTF-8 to
+/// UTF-16, so that non-ASCII text displays correctly independently of the
+/// console code page.
+///
+/// When the wrapped handle isn't a console, such as when output is
+/// redirected to a file or a pipe, bytes are written through to it
+/// unmodified.
+///
+/// When writing to a console, data must be valid UTF-8. A scalar value may
+/// be split across calls to `write`.
+pub struct WindowsConsoleWriter<Inner: io::Write + AsRawHandle> {
+    /// The wrapped console handle.
+    inner: Inner,
+
+    /// The wrapped handle is a console.
+    console: bool,
+
+    /// An incomplete UTF-8 encoding from the end of the previous write.
+    pending: Vec<u8>,
+
+    /// The output stream has been ended.
+    ended: bool,
+}
+
+impl<Inner: io::Write + AsRawHandle> WindowsConsoleWriter<Inner> {
+    /// Construct a new instance of `WindowsConsoleWriter` wrapping `inner`.
+    #[inline]
+    pub fn new(inner: Inner) -> Self {
+        let console = is_console(inner.as_raw_handle());
+        Self {
+            inner,
+            console,
+            pending: Vec::new(),
+            ended: false,
+        }
+    }
+
+    /// Gets a reference to the underlying handle.
+    #[inline]
+    pub fn get_ref(&self) -> &Inner {
+        &self.inner
+    }
+
+    /// Flush and close the underlying stream and return the underlying
+    /// stream object.
+    pub fn close_into_inner(mut self) -> io::Result<Inner> {
+        self.flush(Status::End)?;
+        Ok(self.inner)
+    }
+
+    fn write_console(&mut self, s: &str) -> io::Result<()> {
+        let utf16 = s.encode_utf16().collect::<Vec<u16>>();
+        let mut written = 0;
+        while written < utf16.len() {
+            let mut num_written = 0;
+            if unsafe {
+                WriteConsoleW(
+                    self.inner.as_raw_handle(),
+                    utf16[written..].as_ptr().cast(),
+                    (utf16.len() - written) as u32,
+                    &mut num_written,
+                    ptr::null_mut(),
+                )
+            } == 0
+            {
+                return Err(io::Error::last_os_error());
+            }
+            written += num_written as usize;
+        }
+        Ok(())
+    }
+
+    /// Add bytes from `buf` to the incomplete encoding in `pending` until
+    /// it's complete, and write it. Returns the number of bytes consumed.
+    fn write_pending(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let mut consumed = 0;
+        for &b in buf {
+            self.pending.push(b);
+            consumed += 1;
+            match str::from_utf8(&self.pending) {
+                Ok(s) => {
+                    let s = s.to_owned();
+                    self.pending.clear();
+                    self.write_console(&s)?;
+                    return Ok(consumed);
+                }
+                Err(error) if error.error_len().is_some() => {
+                    self.pending.clear();
+                    return Err(invalid_utf8());
+                }
+                Err(_) if self.pending.len() == MAX_UTF8_SIZE => {
+                    self.pending.clear();
+                    return Err(invalid_utf8());
+                }
+                Err(_) => {}
+            }
+        }
+        Ok(consumed)
+    }
+}
+
+impl<Inner: io::Write + AsRawHandle> Close for WindowsConsoleWriter<Inner> {
+    type Output = Inner;
+
+    #[inline]
+    fn close_into_inner(self) -> io::Result<Inner> {
+        WindowsConsoleWriter::close_into_inner(self)
+    }
+}
+
+impl<Inner: io::Write + AsRawHandle> Write for WindowsConsoleWriter<Inner> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.ended {
+            return Err(stream_already_ended());
+        }
+        if !self.console {
+            return self.inner.write(buf);
+        }
+        if !self.pending.is_empty() {
+            return self.write_pending(buf);
+        }
+        match str::from_utf8(buf) {
+            Ok(s) => {
+                self.write_console(s)?;
+                Ok(buf.len())
+            }
+            Err(error) if error.valid_up_to() != 0 => {
+                let valid = error.valid_up_to();
+                self.write_console(unsafe { str::from_utf8_unchecked(&buf[..valid]) })?;
+                Ok(valid)
+            }
+            Err(error) if error.error_len().is_none() => {
+                self.pending.extend_from_slice(buf);
+                Ok(buf.len())
+            }
+            Err(_) => Err(invalid_utf8()),
+        }
+    }
+
+    fn flush(&mut self, status: Status) -> io::Result<()> {
+        if self.ended {
+            return Err(stream_already_ended());
+        }
+        match status {
+            Status::Open(Readiness::Ready) | Status::Open(Readiness::Pending) => Ok(()),
+            Status::Open(Readiness::Lull) => self.inner.flush(),
+            Status::End => {
+                self.ended = true;
+                if !self.pending.is_empty() {
+                    self.pending.clear();
+                    return Err(invalid_utf8());
+                }
+                self.inner.flush()
+            }
+        }
+    }
+
+    #[inline]
+    fn abandon(&mut self) {
+        self.pending.clear();
+        self.ended = true;
+    }
+
+    fn write_all_utf8(&mut self, s: &str) -> io::Result<()> {
+        if self.ended {
+            return Err(stream_already_ended());
+        }
+        if !self.pending.is_empty() {
+            self.pending.clear();
+            return Err(invalid_utf8());
+        }
+        if self.console {
+            self.write_console(s)
+        } else {
+            self.inner.write_all(s.as_bytes())
+        }
+    }
+}
+
+fn invalid_utf8() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        "console output is not valid UTF-8",
+    )
+}
+
+fn stream_already_ended() -> io::Error {
+    Error::StreamEnded.into()
+}
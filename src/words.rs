@@ -0,0 +1,121 @@
+use crate::{ReadOutcome, ReadStr};
+use std::io;
+use unicode_segmentation::UnicodeSegmentation;
+
+/// The size of the buffer used for reading from the underlying stream.
+const CHUNK_SIZE: usize = 1024;
+
+/// An iterator over the words of a [`ReadStr`], returned from
+/// [`ReadStr::words`].
+///
+/// Words are found using the [UAX #29] word boundary rules, in the manner
+/// of `UnicodeSegmentation::unicode_words`: segments which don't contain
+/// any alphanumeric characters, such as whitespace and punctuation, are
+/// skipped.
+///
+/// Since a word boundary can depend on the character after the next one,
+/// the last two segments in each read are held back until more data
+/// arrives. At a lull or the end of the stream, all words read so far are
+/// produced.
+///
+/// [UAX #29]: https://www.unicode.org/reports/tr29/#Word_Boundaries
+pub struct Words<Inner: ReadStr> {
+    /// The wrapped text stream.
+    inner: Inner,
+
+    /// Text which has been read but not yet produced.
+    buffer: String,
+
+    /// The position within `buffer` of the next segment.
+    pos: usize,
+
+    /// The end of the prefix of `buffer` which contains complete segments.
+    boundary: usize,
+
+    /// Temporary storage for reading from the underlying stream.
+    chunk: String,
+
+    /// The underlying stream has ended.
+    ended: bool,
+}
+
+impl<Inner: ReadStr> Words<Inner> {
+    #[inline]
+    pub(crate) fn new(inner: Inner) -> Self {
+        Self {
+            inner,
+            buffer: String::new(),
+            pos: 0,
+            boundary: 0,
+            chunk: "\0".repeat(CHUNK_SIZE),
+            ended: false,
+        }
+    }
+
+    /// Consume this `Words` and return the underlying stream object. Any
+    /// data which has been read from the underlying stream but not yet
+    /// produced is discarded.
+    #[inline]
+    pub fn into_inner(self) -> Inner {
+        self.inner
+    }
+}
+
+impl<Inner: ReadStr> Iterator for Words<Inner> {
+    type Item = io::Result<String>;
+
+    fn next(&mut self) -> Option<io::Result<String>> {
+        loop {
+            while self.pos < self.boundary {
+                let segment = self.buffer[self.pos..self.boundary]
+                    .split_word_bounds()
+                    .next()
+                    .unwrap();
+                self.pos += segment.len();
+                if segment.chars().any(char::is_alphanumeric) {
+                    return Some(Ok(segment.to_owned()));
+                }
+            }
+
+            if self.ended {
+                return None;
+            }
+
+            match self.inner.read_str(&mut self.chunk) {
+                Ok(ReadOutcome { size, status }) => {
+                    self.buffer.drain(..self.pos);
+                    self.pos = 0;
+                    self.buffer.push_str(&self.chunk[..size]);
+                    self.boundary = if !status.is_boundary() {
+                        let mut segments = self.buffer.split_word_bound_indices();
+                        segments.next_back();
+                        segments.next_back().map_or(0, |(index, _)| index)
+                    } else {
+                        self.buffer.len()
+                    };
+                    self.ended = status.is_end();
+                }
+                Err(ref e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Some(Err(e)),
+            }
+        }
+    }
+}
+
+#[test]
+fn test_words() {
+    use crate::testing::{ScriptedReader, Step};
+
+    let reader = crate::TextReader::new(ScriptedReader::new(vec![
+        Step::Data(b"Don".to_vec()),
+        Step::Data(b"'".to_vec()),
+        Step::Data(b"t stop, pi is 3".to_vec()),
+        Step::Data(b".".to_vec()),
+        Step::Data(b"14 ".to_vec()),
+        Step::Data("caf\u{e9}".as_bytes().to_vec()),
+    ]));
+    let words = Words::new(reader)
+        .collect::<io::Result<Vec<String>>>()
+        .unwrap();
+    assert_eq!(words, ["Don't", "stop", "pi", "is", "3.14", "caf\u{e9}"]);
+}
@@ -1,7 +1,8 @@
-use crate::Status;
+use crate::{Readiness, Status};
 use std::{
     fmt::Arguments,
     io::{self, IoSlice},
+    str,
 };
 
 /// A superset of [`std::io::Write`], but has extra parameters for declaring
@@ -13,10 +14,25 @@ pub trait Write {
     /// Like [`std::io::Write::flush`], but has a status parameter describing
     /// the future of the stream:
     ///  - `Status::Ok(Readiness::Ready)`: do nothing
+    ///  - `Status::Ok(Readiness::Pending)`: do nothing
     ///  - `Status::Ok(Readiness::Lull)`: flush the underlying stream
     ///  - `Status::End`: flush the underlying stream and declare the end
     fn flush(&mut self, status: Status) -> io::Result<()>;
 
+    /// Flush and declare the end of the stream. Equivalent to
+    /// `flush(Status::End)`.
+    #[inline]
+    fn end(&mut self) -> io::Result<()> {
+        self.flush(Status::End)
+    }
+
+    /// Flush and declare a lull. Equivalent to
+    /// `flush(Status::Open(Readiness::Lull))`.
+    #[inline]
+    fn lull(&mut self) -> io::Result<()> {
+        self.flush(Status::Open(Readiness::Lull))
+    }
+
     /// Discard any buffered bytes and declare an intention to cease using
     /// this stream. Use after an unrecoverable error.
     fn abandon(&mut self);
@@ -26,15 +42,29 @@ pub trait Write {
         default_write_vectored(self, bufs)
     }
 
-    /// Like [`std::io::Write::is_write_Vectored`].
+    /// Like [`std::io::Write::is_write_vectored`].
     #[cfg(feature = "nightly")]
-    fn is_write_vectored(&self) -> bool;
+    fn is_write_vectored(&self) -> bool {
+        false
+    }
 
     /// Like [`std::io::Write::write_all`].
     fn write_all(&mut self, buf: &[u8]) -> io::Result<()> {
         default_write_all(self, buf)
     }
 
+    /// Write all of `buf`, and then flush with `status`, as a single
+    /// operation, so that a consumer sees the data and the status together,
+    /// such as a lull marking the end of a logical message.
+    ///
+    /// The default implementation calls `write_all` and then `flush`;
+    /// implementations where other parties may observe the stream between
+    /// those calls override it to perform them atomically.
+    fn write_outcome(&mut self, buf: &[u8], status: Status) -> io::Result<()> {
+        self.write_all(buf)?;
+        self.flush(status)
+    }
+
     /// Like `write_all`, but takes a `&str`.
     fn write_all_utf8(&mut self, buf: &str) -> io::Result<()> {
         // Default to just writing it as bytes, however implementors of this
@@ -43,9 +73,23 @@ pub trait Write {
         self.write_all(buf.as_bytes())
     }
 
+    /// Like `write_all_utf8`, but writes a sequence of `&str`s, so that
+    /// data produced in several segments, such as by formatting machinery,
+    /// can be written without concatenating it into a temporary `String`.
+    fn write_all_vectored_utf8(&mut self, bufs: &[&str]) -> io::Result<()> {
+        // Default to writing each segment in turn, however implementors of
+        // this trait can override this to perform vectored writes.
+        for buf in bufs {
+            self.write_all_utf8(buf)?;
+        }
+        Ok(())
+    }
+
     /// Like [`std::io::Write::write_all_vectored`].
     #[cfg(feature = "nightly")]
-    fn write_all_vectored(&mut self, bufs: &mut [IoSlice<'_>]) -> io::Result<()>;
+    fn write_all_vectored(&mut self, bufs: &mut [IoSlice<'_>]) -> io::Result<()> {
+        default_write_all_vectored(self, bufs)
+    }
 
     /// Like [`std::io::Write::write_fmt`].
     fn write_fmt(&mut self, fmt: Arguments<'_>) -> io::Result<()> {
@@ -54,6 +98,124 @@ pub trait Write {
     }
 }
 
+impl<W: Write + ?Sized> Write for &mut W {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        (**self).write(buf)
+    }
+
+    #[inline]
+    fn flush(&mut self, status: Status) -> io::Result<()> {
+        (**self).flush(status)
+    }
+
+    #[inline]
+    fn abandon(&mut self) {
+        (**self).abandon()
+    }
+
+    #[inline]
+    fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) -> io::Result<usize> {
+        (**self).write_vectored(bufs)
+    }
+
+    #[cfg(feature = "nightly")]
+    #[inline]
+    fn is_write_vectored(&self) -> bool {
+        (**self).is_write_vectored()
+    }
+
+    #[inline]
+    fn write_all(&mut self, buf: &[u8]) -> io::Result<()> {
+        (**self).write_all(buf)
+    }
+
+    #[inline]
+    fn write_outcome(&mut self, buf: &[u8], status: Status) -> io::Result<()> {
+        (**self).write_outcome(buf, status)
+    }
+
+    #[inline]
+    fn write_all_utf8(&mut self, buf: &str) -> io::Result<()> {
+        (**self).write_all_utf8(buf)
+    }
+
+    #[inline]
+    fn write_all_vectored_utf8(&mut self, bufs: &[&str]) -> io::Result<()> {
+        (**self).write_all_vectored_utf8(bufs)
+    }
+
+    #[cfg(feature = "nightly")]
+    #[inline]
+    fn write_all_vectored(&mut self, bufs: &mut [IoSlice<'_>]) -> io::Result<()> {
+        (**self).write_all_vectored(bufs)
+    }
+
+    #[inline]
+    fn write_fmt(&mut self, fmt: Arguments<'_>) -> io::Result<()> {
+        (**self).write_fmt(fmt)
+    }
+}
+
+impl<W: Write + ?Sized> Write for Box<W> {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        (**self).write(buf)
+    }
+
+    #[inline]
+    fn flush(&mut self, status: Status) -> io::Result<()> {
+        (**self).flush(status)
+    }
+
+    #[inline]
+    fn abandon(&mut self) {
+        (**self).abandon()
+    }
+
+    #[inline]
+    fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) -> io::Result<usize> {
+        (**self).write_vectored(bufs)
+    }
+
+    #[cfg(feature = "nightly")]
+    #[inline]
+    fn is_write_vectored(&self) -> bool {
+        (**self).is_write_vectored()
+    }
+
+    #[inline]
+    fn write_all(&mut self, buf: &[u8]) -> io::Result<()> {
+        (**self).write_all(buf)
+    }
+
+    #[inline]
+    fn write_outcome(&mut self, buf: &[u8], status: Status) -> io::Result<()> {
+        (**self).write_outcome(buf, status)
+    }
+
+    #[inline]
+    fn write_all_utf8(&mut self, buf: &str) -> io::Result<()> {
+        (**self).write_all_utf8(buf)
+    }
+
+    #[inline]
+    fn write_all_vectored_utf8(&mut self, bufs: &[&str]) -> io::Result<()> {
+        (**self).write_all_vectored_utf8(bufs)
+    }
+
+    #[cfg(feature = "nightly")]
+    #[inline]
+    fn write_all_vectored(&mut self, bufs: &mut [IoSlice<'_>]) -> io::Result<()> {
+        (**self).write_all_vectored(bufs)
+    }
+
+    #[inline]
+    fn write_fmt(&mut self, fmt: Arguments<'_>) -> io::Result<()> {
+        (**self).write_fmt(fmt)
+    }
+}
+
 /// Default implementation of `Write::write_vectored`.
 pub fn default_write_vectored<Inner: Write + ?Sized>(
     inner: &mut Inner,
@@ -66,6 +228,48 @@ pub fn default_write_vectored<Inner: Write + ?Sized>(
     inner.write(buf)
 }
 
+/// Default implementation of `Write::write_all_vectored`.
+#[cfg(feature = "nightly")]
+pub fn default_write_all_vectored<Inner: Write + ?Sized>(
+    inner: &mut Inner,
+    mut bufs: &mut [IoSlice<'_>],
+) -> io::Result<()> {
+    IoSlice::advance_slices(&mut bufs, 0);
+    while !bufs.is_empty() {
+        match inner.write_vectored(bufs) {
+            Ok(0) => {
+                return Err(io::Error::new(
+                    io::ErrorKind::WriteZero,
+                    "failed to write whole buffer",
+                ));
+            }
+            Ok(n) => IoSlice::advance_slices(&mut bufs, n),
+            Err(ref e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(())
+}
+
+/// Implement `Write::write_vectored` for writers which require valid UTF-8,
+/// writing as many whole buffers as are valid UTF-8 with `write_all_utf8`.
+/// If the first buffer isn't valid UTF-8, it's passed to `write` instead.
+pub(crate) fn write_vectored_utf8<Inner: Write + ?Sized>(
+    inner: &mut Inner,
+    bufs: &[IoSlice<'_>],
+) -> io::Result<usize> {
+    let mut total = 0;
+    for buf in bufs {
+        match str::from_utf8(buf) {
+            Ok(s) => inner.write_all_utf8(s)?,
+            Err(_) if total == 0 => return inner.write(buf),
+            Err(_) => break,
+        }
+        total += buf.len();
+    }
+    Ok(total)
+}
+
 /// Default implementation of `Write::write_all`.
 pub fn default_write_all<Inner: Write + ?Sized>(
     inner: &mut Inner,
@@ -86,3 +290,32 @@ pub fn default_write_all<Inner: Write + ?Sized>(
     }
     Ok(())
 }
+
+#[cfg(test)]
+fn write_hello<W: Write>(mut writer: W) {
+    writer.write_all_utf8("hello\n").unwrap();
+    writer.flush(Status::End).unwrap();
+}
+
+#[test]
+fn test_write_by_reference() {
+    let mut writer = crate::Utf8Writer::new(crate::StdWriter::new(Vec::new()));
+    write_hello(&mut writer);
+    assert_eq!(writer.get_ref().get_ref(), b"hello\n");
+
+    let mut writer = Box::new(crate::StdWriter::new(Vec::new()));
+    write_hello(&mut writer);
+    assert_eq!(writer.get_ref(), b"hello\n");
+}
+
+#[test]
+fn test_dyn_write() {
+    let mut writers: Vec<Box<dyn Write>> = vec![
+        Box::new(crate::StdWriter::new(Vec::new())),
+        Box::new(crate::Utf8Writer::new(crate::StdWriter::new(Vec::new()))),
+    ];
+    for writer in &mut writers {
+        write_hello(&mut **writer);
+        assert!(writer.write_all_utf8("world\n").is_err());
+    }
+}
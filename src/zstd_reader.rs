@@ -0,0 +1,179 @@
+use crate::{Read, ReadOutcome, Readiness, Status};
+use std::io;
+use zstd::stream::raw::{Decoder, Operation};
+
+/// The size of the buffer used to hold compressed input.
+const INPUT_SIZE: usize = 8192;
+
+/// A `Read` implementation which decompresses a zstd stream read from an
+/// inner `Read`.
+///
+/// The end of each zstd frame is reported as `Status::Open(Readiness::Lull)`,
+/// so that consumers can flush per frame, and the end of the inner stream,
+/// at a frame boundary, is reported as `Status::End`. Lulls in the
+/// compressed input are also reported as lulls when no more output can be
+/// produced until more input arrives.
+pub struct ZstdReader<Inner: Read> {
+    /// The wrapped byte stream.
+    inner: Inner,
+
+    /// The zstd decompression state.
+    decoder: Decoder<'static>,
+
+    /// Compressed data which has been read but not yet consumed.
+    input: Vec<u8>,
+
+    /// The position within `input` of the next byte to consume.
+    pos: usize,
+
+    /// The status reported by the last read from `inner`.
+    inner_status: Status,
+
+    /// We've consumed part of a frame, and not yet reached its end.
+    in_frame: bool,
+}
+
+impl<Inner: Read> ZstdReader<Inner> {
+    /// Construct a new instance of `ZstdReader` wrapping `inner`.
+    #[inline]
+    pub fn new(inner: Inner) -> io::Result<Self> {
+        Ok(Self {
+            inner,
+            decoder: Decoder::new()?,
+            input: Vec::new(),
+            pos: 0,
+            inner_status: Status::ready(),
+            in_frame: false,
+        })
+    }
+
+    /// Read more compressed data from `inner`, replacing the consumed
+    /// contents of `input`.
+    fn fill_input(&mut self) -> io::Result<()> {
+        self.input.resize(INPUT_SIZE, 0);
+        self.pos = 0;
+        let outcome = self.inner.read_outcome(&mut self.input);
+        let size = outcome.as_ref().map_or(0, |outcome| outcome.size);
+        self.input.truncate(size);
+        self.inner_status = outcome?.status;
+        Ok(())
+    }
+}
+
+impl<Inner: Read> Read for ZstdReader<Inner> {
+    fn read_outcome(&mut self, buf: &mut [u8]) -> io::Result<ReadOutcome> {
+        loop {
+            if self.pos < self.input.len() || self.in_frame {
+                let status = self.decoder.run_on_buffers(&self.input[self.pos..], buf)?;
+                self.pos += status.bytes_read;
+                if status.bytes_read != 0 {
+                    self.in_frame = true;
+                }
+
+                if self.in_frame && status.remaining == 0 {
+                    // We've reached the end of a frame.
+                    self.in_frame = false;
+                    if self.pos == self.input.len() && self.inner_status.is_end() {
+                        return Ok(ReadOutcome::end(status.bytes_written));
+                    }
+                    return Ok(ReadOutcome::lull(status.bytes_written));
+                }
+                if status.bytes_written != 0 || buf.is_empty() {
+                    return Ok(ReadOutcome::ready(status.bytes_written));
+                }
+                if self.pos < self.input.len() {
+                    continue;
+                }
+            }
+
+            // We need more input.
+            match self.inner_status {
+                Status::Open(Readiness::Ready) => self.fill_input()?,
+                Status::Open(Readiness::Lull) => {
+                    self.inner_status = Status::ready();
+                    return Ok(ReadOutcome::lull(0));
+                }
+                Status::Open(Readiness::Pending) => {
+                    self.inner_status = Status::ready();
+                    return Ok(ReadOutcome::pending(0));
+                }
+                Status::End if self.in_frame => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::UnexpectedEof,
+                        "zstd stream ended within a frame",
+                    ))
+                }
+                Status::End => return Ok(ReadOutcome::end(0)),
+            }
+        }
+    }
+}
+
+impl<Inner: Read> io::Read for ZstdReader<Inner> {
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        Read::read(self, buf)
+    }
+
+    #[inline]
+    fn read_vectored(&mut self, bufs: &mut [io::IoSliceMut<'_>]) -> io::Result<usize> {
+        Read::read_vectored(self, bufs)
+    }
+
+    #[cfg(feature = "nightly")]
+    #[inline]
+    fn is_read_vectored(&self) -> bool {
+        Read::is_read_vectored(self)
+    }
+
+    #[inline]
+    fn read_to_end(&mut self, buf: &mut Vec<u8>) -> io::Result<usize> {
+        Read::read_to_end(self, buf)
+    }
+
+    #[inline]
+    fn read_to_string(&mut self, buf: &mut String) -> io::Result<usize> {
+        Read::read_to_string(self, buf)
+    }
+
+    #[inline]
+    fn read_exact(&mut self, buf: &mut [u8]) -> io::Result<()> {
+        Read::read_exact(self, buf)
+    }
+}
+
+#[test]
+fn test_zstd_reader() {
+    let data = "hello world\n".repeat(1000);
+    let compressed = zstd::encode_all(data.as_bytes(), 0).unwrap();
+    let mut reader = ZstdReader::new(crate::SliceReader::new(&compressed)).unwrap();
+    let mut s = String::new();
+    reader.read_to_string(&mut s).unwrap();
+    assert_eq!(s, data);
+}
+
+#[test]
+fn test_zstd_reader_frames() {
+    let mut compressed = zstd::encode_all(&b"hello "[..], 0).unwrap();
+    compressed.extend(zstd::encode_all(&b"world\n"[..], 0).unwrap());
+    let mut reader = ZstdReader::new(crate::SliceReader::new(&compressed)).unwrap();
+    let mut buf = [0; 64];
+    let outcome = reader.read_outcome(&mut buf).unwrap();
+    assert_eq!(&buf[..outcome.size], b"hello ");
+    assert_eq!(outcome.status, Status::Open(Readiness::Lull));
+    let outcome = reader.read_outcome(&mut buf).unwrap();
+    assert_eq!(&buf[..outcome.size], b"world\n");
+    assert!(outcome.status.is_end());
+}
+
+#[test]
+fn test_zstd_reader_truncated() {
+    let compressed = zstd::encode_all(&b"hello world\n"[..], 0).unwrap();
+    let mut reader =
+        ZstdReader::new(crate::SliceReader::new(&compressed[..compressed.len() - 2])).unwrap();
+    let mut s = String::new();
+    assert_eq!(
+        reader.read_to_string(&mut s).unwrap_err().kind(),
+        io::ErrorKind::UnexpectedEof
+    );
+}
@@ -0,0 +1,140 @@
+use crate::{Close, Readiness, Status, Write};
+use std::io;
+use zstd::stream::raw::{Encoder, Operation, OutBuffer};
+
+/// The size of the buffer used to hold compressed output.
+const OUTPUT_SIZE: usize = 8192;
+
+/// A `Write` implementation which compresses data written to it into a
+/// zstd stream, and writes it to an inner `Write`.
+///
+/// Flushing with `Status::Open(Readiness::Lull)` ends the current zstd
+/// frame, so that a `ZstdReader` reports the lull at the same point in the
+/// stream. Flushing with `Status::End` ends the current frame and the
+/// stream.
+pub struct ZstdWriter<Inner: Write> {
+    /// The wrapped byte stream.
+    inner: Inner,
+
+    /// The zstd compression state.
+    encoder: Encoder<'static>,
+
+    /// A buffer for compressed output.
+    output: Box<[u8]>,
+
+    /// Data has been written since the start of the current frame.
+    in_frame: bool,
+}
+
+impl<Inner: Write> ZstdWriter<Inner> {
+    /// Construct a new instance of `ZstdWriter` wrapping `inner`, with the
+    /// default compression level.
+    #[inline]
+    pub fn new(inner: Inner) -> io::Result<Self> {
+        Self::with_level(inner, 0)
+    }
+
+    /// Construct a new instance of `ZstdWriter` wrapping `inner`, with the
+    /// given compression `level`.
+    #[inline]
+    pub fn with_level(inner: Inner, level: i32) -> io::Result<Self> {
+        Ok(Self {
+            inner,
+            encoder: Encoder::new(level)?,
+            output: vec![0; OUTPUT_SIZE].into_boxed_slice(),
+            in_frame: false,
+        })
+    }
+
+    /// Flush and close the underlying stream and return the underlying
+    /// stream object.
+    pub fn close_into_inner(mut self) -> io::Result<Inner> {
+        self.flush(Status::End)?;
+        Ok(self.inner)
+    }
+
+    /// End the current frame, if there is one, and prepare for a new one.
+    fn end_frame(&mut self) -> io::Result<()> {
+        if !self.in_frame {
+            return Ok(());
+        }
+        loop {
+            let mut output = OutBuffer::around(&mut self.output[..]);
+            let remaining = self.encoder.finish(&mut output, true)?;
+            let pos = output.pos();
+            self.inner.write_all(&self.output[..pos])?;
+            if remaining == 0 {
+                break;
+            }
+        }
+        self.encoder.reinit()?;
+        self.in_frame = false;
+        Ok(())
+    }
+}
+
+impl<Inner: Write> Close for ZstdWriter<Inner> {
+    type Output = Inner;
+
+    #[inline]
+    fn close_into_inner(self) -> io::Result<Inner> {
+        ZstdWriter::close_into_inner(self)
+    }
+}
+
+impl<Inner: Write> Write for ZstdWriter<Inner> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let mut input = buf;
+        while !input.is_empty() {
+            self.in_frame = true;
+            let status = self.encoder.run_on_buffers(input, &mut self.output)?;
+            input = &input[status.bytes_read..];
+            self.inner.write_all(&self.output[..status.bytes_written])?;
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self, status: Status) -> io::Result<()> {
+        match status {
+            Status::Open(Readiness::Ready) | Status::Open(Readiness::Pending) => {}
+            Status::Open(Readiness::Lull) | Status::End => self.end_frame()?,
+        }
+        self.inner.flush(status)
+    }
+
+    #[inline]
+    fn abandon(&mut self) {
+        self.inner.abandon()
+    }
+}
+
+#[test]
+fn test_zstd_writer() {
+    let data = "hello world\n".repeat(1000);
+    let mut v = Vec::new();
+    let mut writer = ZstdWriter::new(crate::StdWriter::new(&mut v)).unwrap();
+    writer.write_all(data.as_bytes()).unwrap();
+    writer.close_into_inner().unwrap();
+    assert!(v.len() < data.len());
+    assert_eq!(zstd::decode_all(&v[..]).unwrap(), data.as_bytes());
+}
+
+#[test]
+fn test_zstd_writer_to_reader() {
+    let (reader, writer) = crate::pipe();
+    let mut writer = ZstdWriter::new(writer).unwrap();
+    let mut reader = crate::ZstdReader::new(reader).unwrap();
+
+    writer.write_all(b"hello ").unwrap();
+    writer.flush(Status::Open(Readiness::Lull)).unwrap();
+    let mut buf = [0; 64];
+    let outcome = crate::Read::read_outcome(&mut reader, &mut buf).unwrap();
+    assert_eq!(&buf[..outcome.size], b"hello ");
+    assert_eq!(outcome.status, Status::Open(Readiness::Lull));
+
+    writer.write_all(b"world\n").unwrap();
+    writer.close_into_inner().unwrap();
+    let mut s = String::new();
+    crate::Read::read_to_string(&mut reader, &mut s).unwrap();
+    assert_eq!(s, "world\n");
+}